@@ -2431,6 +2431,24 @@ impl AcpThread {
         self.connection.truncate(&self.session_id, cx).is_some()
     }
 
+    pub fn supports_fork(&self, cx: &App) -> bool {
+        self.connection.fork(&self.session_id, cx).is_some()
+    }
+
+    /// Forks this thread's session at the given user message. See
+    /// [`AgentSessionFork::run`].
+    pub fn fork(
+        &self,
+        client_user_message_id: ClientUserMessageId,
+        work_dirs: Option<PathList>,
+        cx: &mut App,
+    ) -> Task<Result<acp::SessionId>> {
+        let Some(fork) = self.connection.fork(&self.session_id, cx) else {
+            return Task::ready(Err(anyhow!("Forking threads is not supported")));
+        };
+        fork.run(client_user_message_id, work_dirs, cx)
+    }
+
     pub fn work_dirs(&self) -> Option<&PathList> {
         self.work_dirs.as_ref()
     }
@@ -3979,6 +3997,20 @@ impl AcpThread {
         }
     }
 
+    /// The git checkpoint taken before the given user message was sent, if any.
+    pub fn user_message_checkpoint(
+        &self,
+        client_id: &ClientUserMessageId,
+    ) -> Option<GitStoreCheckpoint> {
+        self.entries.iter().find_map(|entry| {
+            let message = entry.user_message()?;
+            if message.client_id.as_ref() != Some(client_id) {
+                return None;
+            }
+            Some(message.checkpoint.as_ref()?.git_checkpoint.clone())
+        })
+    }
+
     /// Restores the git working tree to the state at the given checkpoint (if one exists)
     pub fn restore_checkpoint(
         &mut self,
@@ -216,6 +216,10 @@ pub trait AgentConnection {
         None
     }
 
+    fn fork(&self, _session_id: &acp::SessionId, _cx: &App) -> Option<Rc<dyn AgentSessionFork>> {
+        None
+    }
+
     fn set_title(
         &self,
         _session_id: &acp::SessionId,
@@ -269,6 +273,19 @@ pub trait AgentSessionTruncate {
     fn run(&self, client_user_message_id: ClientUserMessageId, cx: &mut App) -> Task<Result<()>>;
 }
 
+pub trait AgentSessionFork {
+    /// Creates a new session holding the history before the given user
+    /// message, with that message as its draft prompt, and returns its ID.
+    /// The session is saved under `work_dirs` when given, and under the
+    /// original session's folders otherwise.
+    fn run(
+        &self,
+        client_user_message_id: ClientUserMessageId,
+        work_dirs: Option<PathList>,
+        cx: &mut App,
+    ) -> Task<Result<acp::SessionId>>;
+}
+
 pub trait AgentSessionClientUserMessageIds {
     fn new_id(&self) -> ClientUserMessageId {
         ClientUserMessageId::new()
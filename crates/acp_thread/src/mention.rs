@@ -74,6 +74,11 @@ pub enum MentionUri {
         source: String,
         skill_file_path: PathBuf,
     },
+    McpResource {
+        server_id: String,
+        uri: String,
+        name: String,
+    },
 }
 
 impl MentionUri {
@@ -273,6 +278,17 @@ impl MentionUri {
                         source: source.context("missing skill source")?,
                         skill_file_path: skill_file_path.context("missing skill file path")?,
                     })
+                } else if path.starts_with("/agent/mcp-resource") {
+                    validate_query_params(&url, &["server", "uri", "name"])?;
+                    let server_id = query_param(&url, "server")
+                        .context("Missing context server for MCP resource")?;
+                    let uri = query_param(&url, "uri").context("Missing MCP resource URI")?;
+                    let name = query_param(&url, "name").unwrap_or_else(|| uri.clone());
+                    Ok(Self::McpResource {
+                        server_id,
+                        uri,
+                        name,
+                    })
                 } else {
                     bail!("invalid zed url: {:?}", input);
                 }
@@ -328,7 +344,8 @@ impl MentionUri {
             | MentionUri::Fetch { .. }
             | MentionUri::TerminalSelection { .. }
             | MentionUri::GitDiff { .. }
-            | MentionUri::MergeConflict { .. } => None,
+            | MentionUri::MergeConflict { .. }
+            | MentionUri::McpResource { .. } => None,
         }
     }
 
@@ -366,6 +383,7 @@ impl MentionUri {
             } => selection_name(path.as_deref(), line_range),
             MentionUri::Fetch { url } => url.to_string(),
             MentionUri::Skill { name, .. } => name.clone(),
+            MentionUri::McpResource { name, .. } => name.clone(),
         }
     }
 
@@ -429,6 +447,9 @@ impl MentionUri {
             MentionUri::Skill {
                 skill_file_path, ..
             } => Some(skill_file_path.to_string_lossy().into_owned().into()),
+            MentionUri::McpResource { server_id, uri, .. } => {
+                Some(format!("{uri} ({server_id})").into())
+            }
             _ => None,
         }
     }
@@ -451,6 +472,7 @@ impl MentionUri {
             MentionUri::GitDiff { .. } => IconName::GitBranch.path().into(),
             MentionUri::MergeConflict { .. } => IconName::GitMergeConflict.path().into(),
             MentionUri::Skill { .. } => IconName::Sparkle.path().into(),
+            MentionUri::McpResource { .. } => IconName::ToolHammer.path().into(),
         }
     }
 
@@ -582,6 +604,18 @@ impl MentionUri {
                     .append_pair("path", &skill_file_path.to_string_lossy());
                 url
             }
+            MentionUri::McpResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                let mut url = Url::parse("zed:///agent/mcp-resource").unwrap();
+                url.query_pairs_mut()
+                    .append_pair("server", server_id)
+                    .append_pair("uri", uri)
+                    .append_pair("name", name);
+                url
+            }
         }
     }
 }
@@ -1386,6 +1420,20 @@ mod tests {
         assert_eq!(parsed, skill_uri);
     }
 
+    #[test]
+    fn test_parse_mcp_resource_uri_round_trip() {
+        let resource_uri = MentionUri::McpResource {
+            server_id: "github".to_string(),
+            uri: "repo://zed-industries/zed/issues?state=open&label=agent".to_string(),
+            name: "Open agent issues".to_string(),
+        };
+
+        let serialized = resource_uri.to_uri().to_string();
+        let parsed = MentionUri::parse(&serialized, PathStyle::local()).unwrap();
+
+        assert_eq!(parsed, resource_uri);
+    }
+
     #[test]
     fn test_parse_fetch_http_uri() {
         let http_uri = "http://example.com/path?query=value#fragment";
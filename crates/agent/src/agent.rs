@@ -1,4 +1,8 @@
+mod budget;
+mod context_server_elicitation;
+mod context_server_sampling;
 mod db;
+mod hooks;
 mod legacy_thread;
 mod native_agent_server;
 pub mod outline;
@@ -12,7 +16,10 @@ mod thread_store;
 mod tool_permissions;
 mod tools;
 
+pub use budget::*;
 use context_server::ContextServerId;
+pub use context_server_elicitation::*;
+pub use context_server_sampling::*;
 pub use db::*;
 use itertools::Itertools;
 pub use native_agent_server::NativeAgentServer;
@@ -1772,15 +1779,7 @@ impl NativeAgent {
         if session.thread.read(cx).is_empty() {
             return None;
         }
-        let state = self.projects.get(&session.project_id)?;
-        let folder_paths = PathList::new(
-            &state
-                .project
-                .read(cx)
-                .visible_worktrees(cx)
-                .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
-                .collect::<Vec<_>>(),
-        );
+        let folder_paths = self.session_folder_paths(session, cx)?;
         let draft_prompt = session.acp_thread.read(cx).draft_prompt().map(Vec::from);
         let id = session.thread.read(cx).id().clone();
         let db_thread = session.thread.update(cx, |thread, cx| {
@@ -1790,6 +1789,57 @@ impl NativeAgent {
         Some((id, folder_paths, db_thread))
     }
 
+    fn session_folder_paths(&self, session: &Session, cx: &App) -> Option<PathList> {
+        let state = self.projects.get(&session.project_id)?;
+        Some(PathList::new(
+            &state
+                .project
+                .read(cx)
+                .visible_worktrees(cx)
+                .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Saves a fork of a session's thread at the given user message under a
+    /// new session ID, which can then be loaded like any other saved thread.
+    /// The fork is saved under `work_dirs` (e.g. a worktree the fork's
+    /// checkpoint was restored into) or, by default, the session's folders.
+    fn fork_thread(
+        &mut self,
+        session_id: &acp::SessionId,
+        client_user_message_id: ClientUserMessageId,
+        work_dirs: Option<PathList>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<acp::SessionId>> {
+        let Some(session) = self.sessions.get(session_id) else {
+            return Task::ready(Err(anyhow!("Session not found")));
+        };
+        let Some(folder_paths) = work_dirs.or_else(|| self.session_folder_paths(session, cx))
+        else {
+            return Task::ready(Err(anyhow!("Project not found")));
+        };
+        let db_thread = match session
+            .thread
+            .read(cx)
+            .fork_to_db(&client_user_message_id, cx)
+        {
+            Ok(db_thread) => db_thread,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let fork_id = acp::SessionId::new(uuid::Uuid::new_v4().to_string());
+        let thread_store = self.thread_store.clone();
+        cx.spawn(async move |_, cx| {
+            let db_thread = db_thread.await;
+            thread_store
+                .update(cx, |store, cx| {
+                    store.save_thread(fork_id.clone(), db_thread, folder_paths, cx)
+                })
+                .await?;
+            Ok(fork_id)
+        })
+    }
+
     /// Commits every non-empty thread's content on shutdown so the async
     /// `save_thread` losing the race can't leave metadata without content.
     fn flush_threads_on_quit(
@@ -2235,6 +2285,41 @@ impl NativeAgentConnection {
                                     thread.authorize_tool_call(tool_call_id, outcome, cx);
                                 })?;
                             }
+                            ThreadEvent::ToolCallElicitation(ToolCallElicitation {
+                                tool_call_id,
+                                message,
+                                schema,
+                                response,
+                            }) => {
+                                let response_task = acp_thread.update(cx, |thread, cx| {
+                                    let scope =
+                                        acp::ElicitationSessionScope::new(thread.session_id().clone())
+                                            .tool_call_id(tool_call_id);
+                                    thread.request_elicitation(
+                                        acp::CreateElicitationRequest::new(
+                                            acp::ElicitationFormMode::new(scope, schema),
+                                            message,
+                                        ),
+                                        cx,
+                                    )
+                                })?;
+                                match response_task {
+                                    Ok(response_task) => {
+                                        cx.background_spawn(async move {
+                                            response.send(response_task.await).ok();
+                                        })
+                                        .detach();
+                                    }
+                                    Err(error) => {
+                                        log::error!("Failed to request elicitation: {error:?}");
+                                        response
+                                            .send(acp::CreateElicitationResponse::new(
+                                                acp::ElicitationAction::Cancel,
+                                            ))
+                                            .ok();
+                                    }
+                                }
+                            }
                             ThreadEvent::ToolCall(tool_call) => {
                                 acp_thread.update(cx, |thread, cx| {
                                     thread.upsert_tool_call(tool_call, cx)
@@ -2691,6 +2776,25 @@ impl acp_thread::AgentConnection for NativeAgentConnection {
         })
     }
 
+    fn fork(
+        &self,
+        session_id: &acp::SessionId,
+        cx: &App,
+    ) -> Option<Rc<dyn acp_thread::AgentSessionFork>> {
+        self.0.read_with(cx, |agent, cx| {
+            agent
+                .sessions
+                .get(session_id)
+                .filter(|s| !s.thread.read(cx).is_subagent())
+                .map(|_| {
+                    Rc::new(NativeAgentSessionFork {
+                        agent: self.0.clone(),
+                        session_id: session_id.clone(),
+                    }) as _
+                })
+        })
+    }
+
     fn set_title(
         &self,
         session_id: &acp::SessionId,
@@ -3005,6 +3109,24 @@ impl acp_thread::AgentSessionTruncate for NativeAgentSessionTruncate {
     }
 }
 
+struct NativeAgentSessionFork {
+    agent: Entity<NativeAgent>,
+    session_id: acp::SessionId,
+}
+
+impl acp_thread::AgentSessionFork for NativeAgentSessionFork {
+    fn run(
+        &self,
+        client_user_message_id: ClientUserMessageId,
+        work_dirs: Option<PathList>,
+        cx: &mut App,
+    ) -> Task<Result<acp::SessionId>> {
+        self.agent.update(cx, |agent, cx| {
+            agent.fork_thread(&self.session_id, client_user_message_id, work_dirs, cx)
+        })
+    }
+}
+
 struct NativeAgentSessionRetry {
     connection: NativeAgentConnection,
     session_id: acp::SessionId,
@@ -6542,6 +6664,145 @@ mod internal_tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_fork_thread(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/", json!({ "a": { "file.txt": "hello" } }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/a").as_ref()], cx).await;
+        let thread_store = cx.new(|cx| ThreadStore::new(cx));
+        let agent = cx
+            .update(|cx| NativeAgent::new(thread_store.clone(), Templates::new(), fs.clone(), cx));
+        let connection = Rc::new(NativeAgentConnection(agent.clone()));
+
+        let acp_thread = cx
+            .update(|cx| {
+                connection
+                    .clone()
+                    .new_session(project.clone(), PathList::new(&[Path::new("")]), cx)
+            })
+            .await
+            .unwrap();
+        let session_id = acp_thread.read_with(cx, |thread, _| thread.session_id().clone());
+        let thread = agent.read_with(cx, |agent, _| {
+            agent.sessions.get(&session_id).unwrap().thread.clone()
+        });
+        let model = Arc::new(FakeLanguageModel::default());
+        thread.update(cx, |thread, cx| {
+            thread.set_model(model.clone(), cx);
+            thread.set_title("Greeting".into(), cx);
+        });
+
+        for (prompt, response) in [("first", "one"), ("second", "two")] {
+            let send = acp_thread.update(cx, |thread, cx| thread.send(vec![prompt.into()], cx));
+            let send = cx.foreground_executor().spawn(send);
+            cx.run_until_parked();
+            model.send_last_completion_stream_text_chunk(response);
+            model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+                language_model::TokenUsage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    ..Default::default()
+                },
+            ));
+            model.end_last_completion_stream();
+            send.await.unwrap();
+            cx.run_until_parked();
+        }
+
+        let user_message_ids = acp_thread.read_with(cx, |thread, _| {
+            thread
+                .entries()
+                .iter()
+                .filter_map(|entry| entry.user_message()?.client_id.clone())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(user_message_ids.len(), 2);
+
+        // The fork keeps the history before the message, which becomes the
+        // draft prompt, and only the usage of the requests it kept.
+        let db_thread = thread
+            .read_with(cx, |thread, cx| thread.fork_to_db(&user_message_ids[1], cx))
+            .unwrap()
+            .await;
+        assert_eq!(db_thread.title, "Greeting (fork)");
+        assert_eq!(db_thread.messages.len(), 2);
+        assert_eq!(
+            db_thread.draft_prompt,
+            Some(vec![acp::ContentBlock::Text(acp::TextContent::new(
+                "second"
+            ))])
+        );
+        assert_eq!(
+            db_thread.request_token_usage.keys().collect::<Vec<_>>(),
+            vec![&user_message_ids[0]]
+        );
+        assert!(
+            thread
+                .read_with(cx, |thread, cx| {
+                    thread.fork_to_db(&acp_thread::ClientUserMessageId::new(), cx)
+                })
+                .is_err()
+        );
+
+        // Forking saves a new thread and leaves the original untouched.
+        let fork_id = acp_thread
+            .update(cx, |thread, cx| {
+                thread.fork(user_message_ids[1].clone(), None, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_ne!(fork_id, session_id);
+        thread_store.read_with(cx, |store, _| {
+            let fork = store.thread_from_session_id(&fork_id).unwrap();
+            assert_eq!(fork.title, "Greeting (fork)");
+            assert_eq!(fork.folder_paths, PathList::new(&[path!("/a")]));
+        });
+        acp_thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                "## User\n\nfirst\n\n## Assistant\n\none\n\n\
+                 ## User\n\nsecond\n\n## Assistant\n\ntwo\n\n"
+            );
+        });
+
+        let fork = agent
+            .update(cx, |agent, cx| {
+                agent.open_thread(fork_id.clone(), project.clone(), cx)
+            })
+            .await
+            .unwrap();
+        fork.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                "## User\n\nfirst\n\n## Assistant\n\none\n\n"
+            );
+            assert_eq!(
+                thread.draft_prompt(),
+                Some([acp::ContentBlock::Text(acp::TextContent::new("second"))].as_slice())
+            );
+        });
+
+        // A fork restored into another worktree is saved under its folders.
+        let worktree_fork_id = acp_thread
+            .update(cx, |thread, cx| {
+                thread.fork(
+                    user_message_ids[0].clone(),
+                    Some(PathList::new(&[path!("/worktrees/a")])),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        thread_store.read_with(cx, |store, _| {
+            let fork = store.thread_from_session_id(&worktree_fork_id).unwrap();
+            assert_eq!(fork.folder_paths, PathList::new(&[path!("/worktrees/a")]));
+        });
+    }
+
     #[gpui::test]
     async fn test_thread_summary_releases_loaded_session(cx: &mut TestAppContext) {
         init_test(cx);
@@ -0,0 +1,323 @@
+//! Token and cost budgets for the agent.
+//!
+//! Limits are configured under `agent.budgets` in settings, per thread, per
+//! calendar day, and per profile and calendar day. The usage of every
+//! completion request is recorded in the threads database along with its
+//! profile and cost, when the model reports per-token prices. Before each
+//! request the thread checks what it, its profile and the day have spent so
+//! far: past a limit the turn ends with a [`BudgetExceededError`], and past the
+//! warning threshold the thread reports a [`BudgetStatus::Warning`] for the UI
+//! to show.
+//!
+//! Budgets are only checked before a request starts, since its cost isn't
+//! known until it finishes. A request that started under a limit may take
+//! spending past it, as may requests that threads are running concurrently,
+//! so a limit can be overshot by up to one request per running thread.
+
+use agent_settings::{AgentBudgetLimits, AgentBudgets, AgentProfileId};
+use gpui::SharedString;
+use language_model::{LanguageModelCostInfo, TokenUsage};
+use std::ops::Add;
+
+/// Tokens and money spent, in US dollars.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Spend {
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+impl Add for Spend {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            tokens: self.tokens + other.tokens,
+            cost: self.cost + other.cost,
+        }
+    }
+}
+
+/// What has been spent so far, for each kind of budget.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BudgetSpend {
+    /// By the thread, in total.
+    pub thread: Spend,
+    /// By every thread using the thread's profile, today.
+    pub profile: Spend,
+    /// By every thread, today.
+    pub daily: Spend,
+}
+
+/// What a request with the given usage cost, or `None` if the model doesn't
+/// report per-token prices. Cached input is charged at the input price.
+pub fn request_cost(cost_info: Option<LanguageModelCostInfo>, usage: &TokenUsage) -> Option<f64> {
+    match cost_info? {
+        LanguageModelCostInfo::TokenCost {
+            input_token_cost_per_1m,
+            output_token_cost_per_1m,
+        } => {
+            let input_tokens = usage.input_tokens
+                + usage.cache_creation_input_tokens
+                + usage.cache_read_input_tokens;
+            Some(
+                (input_tokens as f64 * input_token_cost_per_1m
+                    + usage.output_tokens as f64 * output_token_cost_per_1m)
+                    / 1_000_000.0,
+            )
+        }
+        // A multiplier on the subscription's request allowance, not money.
+        LanguageModelCostInfo::RequestCost { .. } => None,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BudgetStatus {
+    /// Spending is past the warning threshold of a limit.
+    Warning(SharedString),
+    /// A limit has been reached, so the agent won't start another request.
+    Exceeded(SharedString),
+}
+
+/// Returned when a turn stops because a budget was reached.
+#[derive(Debug)]
+pub struct BudgetExceededError(pub SharedString);
+
+impl std::fmt::Display for BudgetExceededError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for BudgetExceededError {}
+
+/// Compares what a thread, its profile and the current day have spent
+/// against their limits, reporting the most severe status.
+pub fn check_budgets(
+    budgets: &AgentBudgets,
+    profile_id: &AgentProfileId,
+    spend: BudgetSpend,
+) -> Option<BudgetStatus> {
+    let profile_subject = format!("Today's usage of the \"{}\" profile", profile_id.0);
+    let statuses = [
+        check_limits(
+            budgets.thread,
+            spend.thread,
+            budgets.warning_threshold,
+            "This thread",
+        ),
+        check_limits(
+            budgets.profile_limits(profile_id),
+            spend.profile,
+            budgets.warning_threshold,
+            &profile_subject,
+        ),
+        check_limits(
+            budgets.daily,
+            spend.daily,
+            budgets.warning_threshold,
+            "Today's agent usage",
+        ),
+    ];
+    let mut warning = None;
+    for status in statuses.into_iter().flatten() {
+        match status {
+            BudgetStatus::Exceeded(_) => return Some(status),
+            BudgetStatus::Warning(_) => {
+                warning.get_or_insert(status);
+            }
+        }
+    }
+    warning
+}
+
+fn check_limits(
+    limits: AgentBudgetLimits,
+    spend: Spend,
+    warning_threshold: f32,
+    subject: &str,
+) -> Option<BudgetStatus> {
+    let mut status = None;
+    if let Some(max_tokens) = limits.max_tokens {
+        let used = format_tokens(spend.tokens);
+        let max = format_tokens(max_tokens);
+        if spend.tokens >= max_tokens {
+            return Some(BudgetStatus::Exceeded(
+                format!("{subject} reached its budget of {max} tokens ({used} used).").into(),
+            ));
+        } else if spend.tokens as f64 >= max_tokens as f64 * warning_threshold as f64 {
+            status = Some(BudgetStatus::Warning(
+                format!("{subject} has used {used} of its {max} token budget.").into(),
+            ));
+        }
+    }
+    if let Some(max_cost) = limits.max_cost {
+        if spend.cost >= max_cost {
+            return Some(BudgetStatus::Exceeded(
+                format!(
+                    "{subject} reached its budget of ${max_cost:.2} (${:.2} spent).",
+                    spend.cost
+                )
+                .into(),
+            ));
+        } else if spend.cost >= max_cost * warning_threshold as f64 {
+            status = Some(BudgetStatus::Warning(
+                format!(
+                    "{subject} has spent ${:.2} of its ${max_cost:.2} budget.",
+                    spend.cost
+                )
+                .into(),
+            ));
+        }
+    }
+    status
+}
+
+fn format_tokens(tokens: u64) -> String {
+    match tokens {
+        0..1_000 => tokens.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", tokens as f64 / 1_000.0),
+        _ => format!("{:.1}M", tokens as f64 / 1_000_000.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_cost() {
+        let usage = TokenUsage {
+            input_tokens: 600_000,
+            output_tokens: 100_000,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 400_000,
+        };
+        let cost_info = LanguageModelCostInfo::TokenCost {
+            input_token_cost_per_1m: 3.0,
+            output_token_cost_per_1m: 15.0,
+        };
+        assert_eq!(request_cost(Some(cost_info), &usage), Some(4.5));
+        assert_eq!(
+            request_cost(
+                Some(LanguageModelCostInfo::RequestCost {
+                    cost_per_request: 1.0
+                }),
+                &usage
+            ),
+            None
+        );
+        assert_eq!(request_cost(None, &usage), None);
+    }
+
+    #[test]
+    fn test_check_budgets() {
+        let budgets = AgentBudgets {
+            thread: AgentBudgetLimits {
+                max_tokens: Some(100_000),
+                max_cost: None,
+            },
+            daily: AgentBudgetLimits {
+                max_tokens: None,
+                max_cost: Some(10.0),
+            },
+            ..Default::default()
+        };
+        let profile_id = AgentProfileId("write".into());
+        let spend = |thread_tokens, daily_cost| BudgetSpend {
+            thread: Spend {
+                tokens: thread_tokens,
+                cost: 1.0,
+            },
+            profile: Spend::default(),
+            daily: Spend {
+                tokens: 0,
+                cost: daily_cost,
+            },
+        };
+
+        assert_eq!(
+            check_budgets(&budgets, &profile_id, spend(10_000, 1.0)),
+            None
+        );
+        assert_eq!(
+            check_budgets(&budgets, &profile_id, spend(85_000, 1.0)),
+            Some(BudgetStatus::Warning(
+                "This thread has used 85.0k of its 100.0k token budget.".into()
+            ))
+        );
+        // A limit that's been reached wins over a warning about another one.
+        assert_eq!(
+            check_budgets(&budgets, &profile_id, spend(85_000, 10.5)),
+            Some(BudgetStatus::Exceeded(
+                "Today's agent usage reached its budget of $10.00 ($10.50 spent).".into()
+            ))
+        );
+        assert!(!budgets.is_unlimited(&profile_id));
+        assert!(AgentBudgets::default().is_unlimited(&profile_id));
+        assert_eq!(
+            check_budgets(
+                &AgentBudgets::default(),
+                &profile_id,
+                BudgetSpend {
+                    thread: Spend {
+                        tokens: u64::MAX,
+                        cost: f64::MAX,
+                    },
+                    profile: Spend {
+                        tokens: u64::MAX,
+                        cost: f64::MAX,
+                    },
+                    daily: Spend {
+                        tokens: u64::MAX,
+                        cost: f64::MAX,
+                    },
+                }
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_profile_budgets_limit_spend_across_threads() {
+        let write_profile = AgentProfileId("write".into());
+        let ask_profile = AgentProfileId("ask".into());
+        let budgets = AgentBudgets {
+            profiles: [(
+                write_profile.clone(),
+                AgentBudgetLimits {
+                    max_tokens: None,
+                    max_cost: Some(5.0),
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        // Each thread is well within budget, but together the profile's
+        // threads have spent its daily limit.
+        let spend = BudgetSpend {
+            thread: Spend {
+                tokens: 1_000,
+                cost: 0.5,
+            },
+            profile: Spend {
+                tokens: 50_000,
+                cost: 5.5,
+            },
+            daily: Spend {
+                tokens: 50_000,
+                cost: 5.5,
+            },
+        };
+
+        assert_eq!(
+            check_budgets(&budgets, &write_profile, spend),
+            Some(BudgetStatus::Exceeded(
+                "Today's usage of the \"write\" profile reached its budget of $5.00 ($5.50 spent)."
+                    .into()
+            ))
+        );
+        assert!(budgets.is_unlimited(&ask_profile));
+        assert_eq!(check_budgets(&budgets, &ask_profile, spend), None);
+    }
+}
@@ -0,0 +1,184 @@
+//! Serves `elicitation/create` requests from context servers by showing a
+//! form in the thread that is running one of the server's tools.
+
+use std::{cell::RefCell, rc::Rc};
+
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::Result;
+use context_server::{
+    ContextServerId,
+    client::{Error, INVALID_PARAMS},
+    types::{CreateElicitationParams, CreateElicitationResult, ElicitationAction},
+};
+use gpui::{App, AsyncApp, Global, Subscription};
+
+use crate::ToolCallEventStream;
+
+/// The tool calls currently waiting on a context server, most recent last.
+///
+/// Elicitation requests aren't tied to a particular tool call, so they are
+/// shown in the most recent call to the server that sent them.
+#[derive(Default)]
+struct ContextServerElicitationTargets {
+    next_id: usize,
+    targets: Rc<RefCell<Vec<ElicitationTarget>>>,
+}
+
+struct ElicitationTarget {
+    id: usize,
+    server_id: ContextServerId,
+    event_stream: ToolCallEventStream,
+}
+
+impl Global for ContextServerElicitationTargets {}
+
+/// Routes the server's elicitation requests to the given tool call until the
+/// returned subscription is dropped.
+pub(crate) fn register_elicitation_target(
+    server_id: ContextServerId,
+    event_stream: ToolCallEventStream,
+    cx: &mut App,
+) -> Subscription {
+    let state = cx.default_global::<ContextServerElicitationTargets>();
+    let id = state.next_id;
+    state.next_id += 1;
+    state.targets.borrow_mut().push(ElicitationTarget {
+        id,
+        server_id,
+        event_stream,
+    });
+
+    let targets = Rc::downgrade(&state.targets);
+    Subscription::new(move || {
+        if let Some(targets) = targets.upgrade() {
+            targets.borrow_mut().retain(|target| target.id != id);
+        }
+    })
+}
+
+/// Answers an `elicitation/create` request from the given context server with
+/// the user's input.
+pub async fn handle_create_elicitation(
+    server_id: ContextServerId,
+    params: CreateElicitationParams,
+    cx: &mut AsyncApp,
+) -> Result<CreateElicitationResult> {
+    let schema: acp::ElicitationSchema =
+        serde_json::from_value(params.requested_schema).map_err(|error| Error {
+            message: format!("Unsupported elicitation schema: {error}"),
+            code: INVALID_PARAMS,
+        })?;
+
+    let event_stream = cx.update(|cx| {
+        let state = cx.try_global::<ContextServerElicitationTargets>()?;
+        let targets = state.targets.borrow();
+        targets
+            .iter()
+            .rev()
+            .find(|target| target.server_id == server_id)
+            .map(|target| target.event_stream.clone())
+    });
+    let Some(event_stream) = event_stream else {
+        log::warn!(
+            "context server {} requested input outside of a tool call",
+            server_id
+        );
+        return Ok(CreateElicitationResult {
+            action: ElicitationAction::Cancel,
+            content: None,
+        });
+    };
+
+    let response = event_stream.elicit(params.message, schema).await?;
+    Ok(match response.action {
+        acp::ElicitationAction::Accept(accept) => CreateElicitationResult {
+            action: ElicitationAction::Accept,
+            content: accept
+                .content
+                .map(|content| {
+                    content
+                        .into_iter()
+                        .map(|(name, value)| Ok((name, serde_json::to_value(value)?)))
+                        .collect::<Result<_>>()
+                })
+                .transpose()?,
+        },
+        acp::ElicitationAction::Decline => CreateElicitationResult {
+            action: ElicitationAction::Decline,
+            content: None,
+        },
+        _ => CreateElicitationResult {
+            action: ElicitationAction::Cancel,
+            content: None,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn name_request() -> CreateElicitationParams {
+        CreateElicitationParams {
+            message: "What's your name?".into(),
+            requested_schema: json!({
+                "type": "object",
+                "properties": { "name": { "type": "string" } },
+                "required": ["name"],
+            }),
+            meta: None,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_elicitation_is_shown_in_latest_tool_call(cx: &mut TestAppContext) {
+        let server_id = ContextServerId("server".into());
+        let (first_stream, _first_receiver) = ToolCallEventStream::test();
+        let (second_stream, mut second_receiver) = ToolCallEventStream::test();
+        let _first_target =
+            cx.update(|cx| register_elicitation_target(server_id.clone(), first_stream, cx));
+        let second_target =
+            cx.update(|cx| register_elicitation_target(server_id.clone(), second_stream, cx));
+
+        let response =
+            cx.spawn({
+                let server_id = server_id.clone();
+                |mut cx| async move {
+                    handle_create_elicitation(server_id, name_request(), &mut cx).await
+                }
+            });
+        let elicitation = second_receiver.expect_elicitation().await;
+        assert_eq!(elicitation.message, "What's your name?");
+        elicitation
+            .response
+            .send(acp::CreateElicitationResponse::new(
+                acp::ElicitationAction::Accept(acp::ElicitationAcceptAction::new().content(
+                    BTreeMap::from([(
+                        "name".to_string(),
+                        acp::ElicitationContentValue::from("Ada"),
+                    )]),
+                )),
+            ))
+            .unwrap();
+
+        let response = response.await.unwrap();
+        assert_eq!(response.action, ElicitationAction::Accept);
+        assert_eq!(
+            response.content.map(serde_json::Value::Object),
+            Some(json!({ "name": "Ada" }))
+        );
+
+        drop(second_target);
+        let other_server_response = cx
+            .spawn(|mut cx| async move {
+                handle_create_elicitation(ContextServerId("other".into()), name_request(), &mut cx)
+                    .await
+            })
+            .await
+            .unwrap();
+        assert_eq!(other_server_response.action, ElicitationAction::Cancel);
+    }
+}
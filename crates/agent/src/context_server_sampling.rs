@@ -0,0 +1,228 @@
+//! Serves `sampling/createMessage` requests from context servers with the
+//! user's default language model.
+
+use std::rc::Rc;
+
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use context_server::{
+    ContextServerId,
+    client::{Error, INVALID_PARAMS},
+    types::{self, CreateMessageRequest, CreateMessageResult, SamplingMessage},
+};
+use futures::StreamExt as _;
+use gpui::{App, AsyncApp, Global, SharedString, Task};
+use language_model::{
+    LanguageModelCompletionEvent, LanguageModelImage, LanguageModelRegistry, LanguageModelRequest,
+    LanguageModelRequestMessage, MessageContent, Role, StopReason, TokenUsage,
+};
+
+/// The error code MCP clients use when the user declines a sampling request.
+const USER_REJECTED: i32 = -1;
+
+/// The user's answer to a context server asking to sample the language model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SamplingApproval {
+    Allow,
+    /// Allow this request and every later one from the same server until Zed restarts.
+    AlwaysAllow,
+    Deny,
+}
+
+/// What the user is shown when a context server asks to sample the language model.
+#[derive(Clone, Debug)]
+pub struct SamplingApprovalRequest {
+    pub server_id: ContextServerId,
+    pub model_name: SharedString,
+    pub system_prompt: Option<String>,
+    /// The text of the last message the server wants answered.
+    pub last_message: Option<String>,
+    pub max_tokens: u32,
+    /// Tokens this server has already used through sampling in this session.
+    pub token_usage: TokenUsage,
+}
+
+/// Asks the user whether a context server may sample the language model.
+///
+/// Set by the UI; sampling requests are denied when it isn't set.
+pub struct ContextServerSamplingPrompt(
+    pub Rc<dyn Fn(SamplingApprovalRequest, &mut App) -> Task<SamplingApproval>>,
+);
+
+impl Global for ContextServerSamplingPrompt {}
+
+#[derive(Default)]
+struct ContextServerSamplingState {
+    always_allowed: HashSet<ContextServerId>,
+    token_usage: HashMap<ContextServerId, TokenUsage>,
+}
+
+impl Global for ContextServerSamplingState {}
+
+/// Returns the tokens the given context server has used through sampling in this session.
+pub fn context_server_sampling_usage(server_id: &ContextServerId, cx: &App) -> TokenUsage {
+    cx.try_global::<ContextServerSamplingState>()
+        .and_then(|state| state.token_usage.get(server_id).copied())
+        .unwrap_or_default()
+}
+
+/// Answers a `sampling/createMessage` request from the given context server,
+/// after the user approves it.
+pub async fn handle_create_message(
+    server_id: ContextServerId,
+    request: CreateMessageRequest,
+    cx: &mut AsyncApp,
+) -> Result<CreateMessageResult> {
+    let model = cx
+        .update(|cx| LanguageModelRegistry::read_global(cx).default_model())
+        .map(|configured| configured.model)
+        .ok_or_else(|| anyhow::anyhow!("No language model is configured"))?;
+
+    let completion_request = LanguageModelRequest {
+        messages: request_messages(&request, model.supports_images())?,
+        stop: request.stop_sequences.clone().unwrap_or_default(),
+        temperature: request.temperature.map(|temperature| temperature as f32),
+        ..Default::default()
+    };
+
+    let approval = cx
+        .update(|cx| {
+            let state = cx.default_global::<ContextServerSamplingState>();
+            if state.always_allowed.contains(&server_id) {
+                return Task::ready(SamplingApproval::Allow);
+            }
+            let Some(prompt) = cx.try_global::<ContextServerSamplingPrompt>() else {
+                return Task::ready(SamplingApproval::Deny);
+            };
+            let prompt = prompt.0.clone();
+            let approval_request = SamplingApprovalRequest {
+                server_id: server_id.clone(),
+                model_name: model.name().0,
+                system_prompt: request.system_prompt.clone(),
+                last_message: request.messages.last().and_then(message_text),
+                max_tokens: request.max_tokens,
+                token_usage: context_server_sampling_usage(&server_id, cx),
+            };
+            prompt(approval_request, cx)
+        })
+        .await;
+    match approval {
+        SamplingApproval::Allow => {}
+        SamplingApproval::AlwaysAllow => cx.update(|cx| {
+            cx.default_global::<ContextServerSamplingState>()
+                .always_allowed
+                .insert(server_id.clone());
+        }),
+        SamplingApproval::Deny => {
+            return Err(Error {
+                message: "User rejected sampling request".into(),
+                code: USER_REJECTED,
+            }
+            .into());
+        }
+    }
+
+    let mut events = model.stream_completion(completion_request, cx).await?;
+    let mut text = String::new();
+    let mut usage = TokenUsage::default();
+    let mut stop_reason = None;
+    while let Some(event) = events.next().await {
+        match event? {
+            LanguageModelCompletionEvent::Text(chunk) => text.push_str(&chunk),
+            LanguageModelCompletionEvent::UsageUpdate(update) => usage = update,
+            LanguageModelCompletionEvent::Stop(reason) => {
+                stop_reason = Some(reason);
+                break;
+            }
+            _ => {}
+        }
+        // Not every model accepts an output limit, so enforce the one the server asked for here.
+        if usage.output_tokens >= request.max_tokens as u64 {
+            stop_reason = Some(StopReason::MaxTokens);
+            break;
+        }
+    }
+
+    log::info!(
+        "context server {} used {} tokens through sampling",
+        server_id,
+        usage.total_tokens()
+    );
+    cx.update(|cx| {
+        let total = cx
+            .default_global::<ContextServerSamplingState>()
+            .token_usage
+            .entry(server_id)
+            .or_default();
+        *total = *total + usage;
+    });
+
+    Ok(CreateMessageResult {
+        role: types::Role::Assistant,
+        content: types::MessageContent::Text {
+            text,
+            annotations: None,
+        },
+        model: model.id().0.to_string(),
+        stop_reason: stop_reason.map(|reason| {
+            match reason {
+                StopReason::MaxTokens => "maxTokens",
+                StopReason::EndTurn | StopReason::ToolUse | StopReason::Refusal => "endTurn",
+            }
+            .to_string()
+        }),
+    })
+}
+
+fn request_messages(
+    request: &CreateMessageRequest,
+    supports_images: bool,
+) -> Result<Vec<LanguageModelRequestMessage>> {
+    let system_message = request
+        .system_prompt
+        .as_ref()
+        .map(|prompt| LanguageModelRequestMessage {
+            role: Role::System,
+            content: vec![MessageContent::Text(prompt.clone())],
+            cache: false,
+            reasoning_details: None,
+        });
+
+    let messages = request.messages.iter().map(|message| {
+        let content = match &message.content {
+            types::MessageContent::Text { text, .. } => MessageContent::Text(text.clone()),
+            types::MessageContent::Image {
+                data, mime_type, ..
+            } if supports_images && mime_type == "image/png" => {
+                MessageContent::Image(LanguageModelImage {
+                    source: data.clone().into(),
+                })
+            }
+            _ => {
+                return Err(Error {
+                    message: "Unsupported sampling message content".into(),
+                    code: INVALID_PARAMS,
+                }
+                .into());
+            }
+        };
+        Ok(LanguageModelRequestMessage {
+            role: match message.role {
+                types::Role::User => Role::User,
+                types::Role::Assistant => Role::Assistant,
+            },
+            content: vec![content],
+            cache: false,
+            reasoning_details: None,
+        })
+    });
+
+    system_message.map(Ok).into_iter().chain(messages).collect()
+}
+
+fn message_text(message: &SamplingMessage) -> Option<String> {
+    match &message.content {
+        types::MessageContent::Text { text, .. } => Some(text.clone()),
+        _ => None,
+    }
+}
@@ -1,14 +1,16 @@
-use crate::{AgentMessage, AgentMessageContent, UserMessage, UserMessageContent};
+use crate::{
+    AgentMessage, AgentMessageContent, BudgetSpend, Spend, UserMessage, UserMessageContent,
+};
 use acp_thread::ClientUserMessageId;
 use agent_client_protocol::schema::v1 as acp;
 use agent_settings::AgentProfileId;
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, Utc};
 use collections::{HashMap, IndexMap};
 use futures::{FutureExt, future::Shared};
 use gpui::{BackgroundExecutor, Global, Task};
 use indoc::indoc;
-use language_model::Speed;
+use language_model::{LanguageModelToolUseInput, Speed};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use sqlez::{
@@ -18,7 +20,7 @@ use sqlez::{
 };
 use std::{io::ErrorKind, path::PathBuf, sync::Arc};
 use ui::{App, SharedString};
-use util::path_list::PathList;
+use util::{ResultExt as _, path_list::PathList};
 use zed_env_vars::ZED_STATELESS;
 
 pub type DbMessage = crate::Message;
@@ -359,6 +361,172 @@ impl DbThread {
     }
 }
 
+/// A saved thread message that matched a full-text search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbThreadSearchMatch {
+    pub id: acp::SessionId,
+    /// The position, among the thread's user messages, of the message the
+    /// match belongs to: the user message itself, or the prompt that an agent
+    /// response answered.
+    pub user_message_ix: Option<usize>,
+    /// A short excerpt of the matching text.
+    pub snippet: SharedString,
+}
+
+/// Spend on one model within one project, summed from the recorded usage of
+/// every completion request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbUsageReportRow {
+    /// The model's provider and id, joined with a `/`.
+    pub model: SharedString,
+    /// The folder paths of the project the requests were made from, empty if
+    /// they weren't made from a project.
+    pub folder_paths: PathList,
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_tokens: u64,
+    /// `None` if the model doesn't report per-token prices.
+    pub cost: Option<f64>,
+}
+
+impl DbUsageReportRow {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_tokens
+    }
+}
+
+/// The text indexed for one message of a thread.
+#[derive(Debug, Default, PartialEq)]
+struct SearchIndexRow {
+    user_message_ix: Option<usize>,
+    text: String,
+    tool_calls: String,
+    paths: String,
+}
+
+impl SearchIndexRow {
+    fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.tool_calls.is_empty() && self.paths.is_empty()
+    }
+}
+
+/// The local calendar day that daily budgets apply to.
+/// The columns a path list is stored in, which are null for an empty list.
+fn serialize_folder_paths(folder_paths: &PathList) -> (Option<String>, Option<String>) {
+    if folder_paths.is_empty() {
+        return (None, None);
+    }
+    let serialized = folder_paths.serialize();
+    (Some(serialized.paths), Some(serialized.order))
+}
+
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn push_line(target: &mut String, line: &str) {
+    if !target.is_empty() {
+        target.push('\n');
+    }
+    target.push_str(line);
+}
+
+/// Splits a thread into the rows of the full-text index: one per message, with
+/// its prose, tool calls, and the file paths it mentions or touches indexed
+/// separately. Thinking is left out.
+fn search_index_rows(messages: &[Arc<DbMessage>]) -> Vec<SearchIndexRow> {
+    let mut rows = Vec::new();
+    let mut user_message_ix = None;
+    for message in messages {
+        let mut row = SearchIndexRow::default();
+        match message.as_ref() {
+            DbMessage::User(message) => {
+                user_message_ix = Some(user_message_ix.map_or(0, |ix| ix + 1));
+                for content in message.content.iter() {
+                    match content {
+                        UserMessageContent::Text(text) => push_line(&mut row.text, text),
+                        UserMessageContent::Mention { uri, .. } => match uri.abs_path() {
+                            Some(path) => push_line(&mut row.paths, &path.to_string_lossy()),
+                            None => push_line(&mut row.text, &uri.name()),
+                        },
+                        UserMessageContent::Image(_) => {}
+                    }
+                }
+            }
+            DbMessage::Agent(message) => {
+                for content in &message.content {
+                    match content {
+                        AgentMessageContent::Text(text) => push_line(&mut row.text, text),
+                        AgentMessageContent::ToolUse(tool_use) => {
+                            push_line(&mut row.tool_calls, &tool_use.name);
+                            match &tool_use.input {
+                                LanguageModelToolUseInput::Json(input) => {
+                                    push_line(&mut row.tool_calls, &input.to_string());
+                                    collect_tool_input_paths(input, &mut row.paths);
+                                }
+                                LanguageModelToolUseInput::Text(input) => {
+                                    push_line(&mut row.tool_calls, input);
+                                }
+                            }
+                        }
+                        AgentMessageContent::Thinking { .. }
+                        | AgentMessageContent::RedactedThinking(_) => {}
+                    }
+                }
+            }
+            DbMessage::Resume | DbMessage::Compaction(_) => continue,
+        }
+        row.user_message_ix = user_message_ix;
+        if !row.is_empty() {
+            rows.push(row);
+        }
+    }
+    rows
+}
+
+/// Collects the values of `path`-like fields from a tool's input, which is how
+/// the built-in tools name the files they touch.
+fn collect_tool_input_paths(input: &serde_json::Value, paths: &mut String) {
+    match input {
+        serde_json::Value::Object(fields) => {
+            for (key, value) in fields {
+                if key == "path" || key == "paths" || key.ends_with("_path") {
+                    match value {
+                        serde_json::Value::String(path) => push_line(paths, path),
+                        serde_json::Value::Array(values) => {
+                            for path in values.iter().filter_map(|value| value.as_str()) {
+                                push_line(paths, path);
+                            }
+                        }
+                        _ => {}
+                    }
+                } else {
+                    collect_tool_input_paths(value, paths);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_tool_input_paths(value, paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Turns free-form input into an FTS5 query matching rows that contain every
+/// word, each as a prefix. Returns `None` if there's nothing to search for.
+fn search_index_query(query: &str) -> Option<String> {
+    let terms = query
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{word}\"*"))
+        .collect::<Vec<_>>();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DataType {
     #[serde(rename = "json")]
@@ -392,6 +560,9 @@ impl Column for DataType {
 pub(crate) struct ThreadsDatabase {
     executor: BackgroundExecutor,
     connection: Arc<Mutex<Connection>>,
+    /// Whether the full-text search index could be created. SQLite may be
+    /// built without FTS5, in which case threads can't be searched.
+    search_available: bool,
 }
 
 struct GlobalThreadsDatabase(Shared<Task<Result<Arc<ThreadsDatabase>, Arc<anyhow::Error>>>>);
@@ -478,16 +649,201 @@ impl ThreadsDatabase {
             }
         }
 
+        let search_available = Self::create_search_index(&connection);
+
+        let token_usage_exists = connection.select_row::<bool>(indoc! {"
+                SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'token_usage'
+            "})?()?
+        .is_some();
+        // Usage is kept when threads are deleted, along with the project it was
+        // spent on, so that daily budgets and reports still account for it.
+        connection.exec(indoc! {"
+            CREATE TABLE IF NOT EXISTS token_usage (
+                thread_id TEXT NOT NULL,
+                day TEXT NOT NULL,
+                profile TEXT,
+                model TEXT NOT NULL,
+                folder_paths TEXT,
+                folder_paths_order TEXT,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cache_creation_input_tokens INTEGER NOT NULL,
+                cache_read_input_tokens INTEGER NOT NULL,
+                cost REAL
+            );
+            CREATE INDEX IF NOT EXISTS token_usage_thread_id ON token_usage(thread_id);
+            CREATE INDEX IF NOT EXISTS token_usage_day ON token_usage(day);
+        "})?()
+        .map_err(|e| e.context("Failed to create token usage table"))?;
+        if !token_usage_exists {
+            Self::backfill_token_usage(&connection).log_err();
+        }
+
         let db = Self {
             executor,
             connection: Arc::new(Mutex::new(connection)),
+            search_available,
         };
 
         Ok(db)
     }
 
+    /// Records the usage of the threads saved before usage was recorded per
+    /// request. Only their totals were kept, so each thread's usage is counted
+    /// as one request on the day it was last updated, with no known cost.
+    fn backfill_token_usage(connection: &Connection) -> Result<()> {
+        let mut select = connection
+            .select::<(Arc<str>, Option<String>, Option<String>, DataType, Vec<u8>)>(indoc! {"
+            SELECT id, folder_paths, folder_paths_order, data_type, data FROM threads
+        "})?;
+        let mut insert = connection.exec_bound::<(
+            Arc<str>,
+            String,
+            Option<Arc<str>>,
+            String,
+            Option<String>,
+            Option<String>,
+            u64,
+            u64,
+            u64,
+            u64,
+        )>(indoc! {"
+            INSERT INTO token_usage (
+                thread_id,
+                day,
+                profile,
+                model,
+                folder_paths,
+                folder_paths_order,
+                input_tokens,
+                output_tokens,
+                cache_creation_input_tokens,
+                cache_read_input_tokens
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+        "})?;
+        for (id, folder_paths, folder_paths_order, data_type, data) in select()? {
+            let thread = match Self::deserialize_thread(data_type, data) {
+                Ok(thread) => thread,
+                Err(error) => {
+                    log::warn!(
+                        "failed to deserialize thread {id} to backfill its usage: {error:#}"
+                    );
+                    continue;
+                }
+            };
+            let usage = thread.cumulative_token_usage;
+            if usage.total_tokens() == 0 {
+                continue;
+            }
+            insert((
+                id,
+                thread
+                    .updated_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d")
+                    .to_string(),
+                thread.profile.map(|profile| profile.0),
+                thread.model.map_or_else(
+                    || "unknown".to_string(),
+                    |model| format!("{}/{}", model.provider, model.model),
+                ),
+                folder_paths,
+                folder_paths_order,
+                usage.input_tokens,
+                usage.output_tokens,
+                usage.cache_creation_input_tokens,
+                usage.cache_read_input_tokens,
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Creates the full-text search index if it doesn't exist yet, returning
+    /// whether it is available.
+    fn create_search_index(connection: &Connection) -> bool {
+        let search_index_exists = connection
+            .select_row::<bool>(indoc! {"
+                SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'thread_search'
+            "})
+            .and_then(|mut select| select())
+            .map(|row| row.is_some());
+        match search_index_exists {
+            Ok(true) => return true,
+            Ok(false) => {}
+            Err(error) => {
+                log::error!("failed to look up the thread search index: {error:#}");
+                return false;
+            }
+        }
+
+        let created = connection
+            .exec(indoc! {"
+                CREATE VIRTUAL TABLE thread_search USING fts5(
+                    thread_id UNINDEXED,
+                    user_message_ix UNINDEXED,
+                    text,
+                    tool_calls,
+                    paths,
+                    tokenize = 'porter unicode61'
+                )
+            "})
+            .and_then(|mut create| create());
+        if let Err(error) = created {
+            log::warn!("thread search is unavailable: {error:#}");
+            return false;
+        }
+        Self::backfill_search_index(connection).log_err();
+        true
+    }
+
+    /// Indexes threads saved before the search index existed.
+    fn backfill_search_index(connection: &Connection) -> Result<()> {
+        let threads = connection.select::<(Arc<str>, DataType, Vec<u8>)>(indoc! {"
+                SELECT id, data_type, data FROM threads
+            "})?()?;
+        connection.with_savepoint("backfill_thread_search", || {
+            for (id, data_type, data) in threads {
+                match Self::deserialize_thread(data_type, data) {
+                    Ok(thread) if thread.subagent_context.is_some() => {}
+                    Ok(thread) => Self::index_thread(connection, id, &thread.messages)?,
+                    Err(error) => log::warn!("failed to index thread {id} for search: {error:#}"),
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Replaces a thread's rows in the full-text search index.
+    fn index_thread(
+        connection: &Connection,
+        id: Arc<str>,
+        messages: &[Arc<DbMessage>],
+    ) -> Result<()> {
+        connection.exec_bound::<Arc<str>>(indoc! {"
+            DELETE FROM thread_search WHERE thread_id = ?
+        "})?(id.clone())?;
+
+        let mut insert = connection
+            .exec_bound::<(Arc<str>, Option<usize>, String, String, String)>(indoc! {"
+                INSERT INTO thread_search (thread_id, user_message_ix, text, tool_calls, paths)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+            "})?;
+        for row in search_index_rows(messages) {
+            insert((
+                id.clone(),
+                row.user_message_ix,
+                row.text,
+                row.tool_calls,
+                row.paths,
+            ))?;
+        }
+        Ok(())
+    }
+
     fn save_thread_sync(
         connection: &Arc<Mutex<Connection>>,
+        search_available: bool,
         id: acp::SessionId,
         thread: DbThread,
         folder_paths: &PathList,
@@ -507,16 +863,14 @@ impl ThreadsDatabase {
             .subagent_context
             .as_ref()
             .map(|ctx| ctx.parent_thread_id.0.clone());
-        let serialized_folder_paths = folder_paths.serialize();
-        let (folder_paths_str, folder_paths_order_str): (Option<String>, Option<String>) =
-            if folder_paths.is_empty() {
-                (None, None)
-            } else {
-                (
-                    Some(serialized_folder_paths.paths),
-                    Some(serialized_folder_paths.order),
-                )
-            };
+        let (folder_paths_str, folder_paths_order_str) = serialize_folder_paths(folder_paths);
+        // Subagent threads are only reachable through their parent, so they
+        // aren't worth finding on their own.
+        let searchable_messages = if thread.subagent_context.is_none() {
+            thread.messages.clone()
+        } else {
+            Vec::new()
+        };
         let json_data = serde_json::to_string(&SerializedThread {
             thread,
             version: DbThread::VERSION,
@@ -533,32 +887,40 @@ impl ThreadsDatabase {
         // created, not when it was saved to the database.
         let created_at = updated_at.clone();
 
-        let mut insert = connection.exec_bound::<(Arc<str>, Option<Arc<str>>, Option<String>, Option<String>, String, String, DataType, Vec<u8>, String)>(indoc! {"
-            INSERT INTO threads (id, parent_id, folder_paths, folder_paths_order, summary, updated_at, data_type, data, created_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-            ON CONFLICT(id) DO UPDATE SET
-                parent_id = excluded.parent_id,
-                folder_paths = excluded.folder_paths,
-                folder_paths_order = excluded.folder_paths_order,
-                summary = excluded.summary,
-                updated_at = excluded.updated_at,
-                data_type = excluded.data_type,
-                data = excluded.data
-        "})?;
+        // The thread and its search index rows are written together, so that
+        // the index never goes out of sync with the saved thread.
+        connection.with_savepoint("save_thread", || {
+            let mut insert = connection.exec_bound::<(Arc<str>, Option<Arc<str>>, Option<String>, Option<String>, String, String, DataType, Vec<u8>, String)>(indoc! {"
+                INSERT INTO threads (id, parent_id, folder_paths, folder_paths_order, summary, updated_at, data_type, data, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                ON CONFLICT(id) DO UPDATE SET
+                    parent_id = excluded.parent_id,
+                    folder_paths = excluded.folder_paths,
+                    folder_paths_order = excluded.folder_paths_order,
+                    summary = excluded.summary,
+                    updated_at = excluded.updated_at,
+                    data_type = excluded.data_type,
+                    data = excluded.data
+            "})?;
 
-        insert((
-            id.0,
-            parent_id,
-            folder_paths_str,
-            folder_paths_order_str,
-            title,
-            updated_at,
-            data_type,
-            data,
-            created_at,
-        ))?;
+            insert((
+                id.0.clone(),
+                parent_id,
+                folder_paths_str,
+                folder_paths_order_str,
+                title,
+                updated_at,
+                data_type,
+                data,
+                created_at,
+            ))?;
+
+            if search_available {
+                Self::index_thread(&connection, id.0, &searchable_messages)?;
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     pub fn list_threads(&self) -> Task<Result<Vec<DbThreadMetadata>>> {
@@ -604,6 +966,213 @@ impl ThreadsDatabase {
         })
     }
 
+    /// Searches the text, tool calls and file paths of every saved thread,
+    /// returning the best matching messages first. Nothing is found when the
+    /// search index is unavailable.
+    pub fn search_threads(
+        &self,
+        query: String,
+        limit: usize,
+    ) -> Task<Result<Vec<DbThreadSearchMatch>>> {
+        let connection = self.connection.clone();
+        let search_available = self.search_available;
+
+        self.executor.spawn(async move {
+            let Some(query) = search_index_query(&query).filter(|_| search_available) else {
+                return Ok(Vec::new());
+            };
+            let connection = connection.lock();
+            let mut select = connection
+                .select_bound::<(String, usize), (Arc<str>, Option<usize>, String)>(indoc! {"
+                    SELECT thread_id, user_message_ix, snippet(thread_search, -1, '', '', '…', 16)
+                    FROM thread_search
+                    WHERE thread_search MATCH ?
+                    ORDER BY rank
+                    LIMIT ?
+                "})?;
+
+            Ok(select((query, limit))?
+                .into_iter()
+                .map(|(id, user_message_ix, snippet)| DbThreadSearchMatch {
+                    id: acp::SessionId::new(id),
+                    user_message_ix,
+                    snippet: snippet.into(),
+                })
+                .collect())
+        })
+    }
+
+    /// Records the usage of one completion request, made today from a thread
+    /// in the project with the given folder paths.
+    pub fn record_token_usage(
+        &self,
+        thread_id: acp::SessionId,
+        profile_id: AgentProfileId,
+        model: String,
+        folder_paths: PathList,
+        usage: language_model::TokenUsage,
+        cost: Option<f64>,
+    ) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+        let day = today();
+        let folder_paths = serialize_folder_paths(&folder_paths);
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut insert = connection.exec_bound::<(
+                Arc<str>,
+                String,
+                Arc<str>,
+                String,
+                (Option<String>, Option<String>),
+                u64,
+                u64,
+                u64,
+                u64,
+                Option<f64>,
+            )>(indoc! {"
+                    INSERT INTO token_usage (
+                        thread_id,
+                        day,
+                        profile,
+                        model,
+                        folder_paths,
+                        folder_paths_order,
+                        input_tokens,
+                        output_tokens,
+                        cache_creation_input_tokens,
+                        cache_read_input_tokens,
+                        cost
+                    )
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                "})?;
+            insert((
+                thread_id.0,
+                day,
+                profile_id.0,
+                model,
+                folder_paths,
+                usage.input_tokens,
+                usage.output_tokens,
+                usage.cache_creation_input_tokens,
+                usage.cache_read_input_tokens,
+                cost,
+            ))
+        })
+    }
+
+    /// What a thread has spent in total, and what the threads using its
+    /// profile, and every thread, have spent today.
+    pub fn spend(
+        &self,
+        thread_id: acp::SessionId,
+        profile_id: AgentProfileId,
+    ) -> Task<Result<BudgetSpend>> {
+        let connection = self.connection.clone();
+        let day = today();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut thread_spend = connection.select_row_bound::<Arc<str>, (u64, f64)>(indoc! {"
+                SELECT
+                    COALESCE(SUM(input_tokens + output_tokens + cache_creation_input_tokens + cache_read_input_tokens), 0),
+                    COALESCE(SUM(cost), 0.0)
+                FROM token_usage
+                WHERE thread_id = ?
+            "})?;
+            let mut daily_spend = connection.select_row_bound::<String, (u64, f64)>(indoc! {"
+                SELECT
+                    COALESCE(SUM(input_tokens + output_tokens + cache_creation_input_tokens + cache_read_input_tokens), 0),
+                    COALESCE(SUM(cost), 0.0)
+                FROM token_usage
+                WHERE day = ?
+            "})?;
+            let mut profile_spend = connection
+                .select_row_bound::<(String, Arc<str>), (u64, f64)>(indoc! {"
+                    SELECT
+                        COALESCE(SUM(input_tokens + output_tokens + cache_creation_input_tokens + cache_read_input_tokens), 0),
+                        COALESCE(SUM(cost), 0.0)
+                    FROM token_usage
+                    WHERE day = ? AND profile = ?
+                "})?;
+            let to_spend = |row: Option<(u64, f64)>| {
+                let (tokens, cost) = row.unwrap_or_default();
+                Spend { tokens, cost }
+            };
+            Ok(BudgetSpend {
+                thread: to_spend(thread_spend(thread_id.0)?),
+                profile: to_spend(profile_spend((day.clone(), profile_id.0))?),
+                daily: to_spend(daily_spend(day)?),
+            })
+        })
+    }
+
+    /// Spend grouped by model and project, most expensive first.
+    pub fn usage_report(&self) -> Task<Result<Vec<DbUsageReportRow>>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut select = connection.select::<(
+                String,
+                Option<String>,
+                Option<String>,
+                u64,
+                u64,
+                u64,
+                u64,
+                Option<f64>,
+            )>(indoc! {"
+                SELECT
+                    model,
+                    folder_paths,
+                    folder_paths_order,
+                    COUNT(*),
+                    SUM(input_tokens),
+                    SUM(output_tokens),
+                    SUM(cache_creation_input_tokens + cache_read_input_tokens),
+                    SUM(cost)
+                FROM token_usage
+                GROUP BY model, folder_paths, folder_paths_order
+                ORDER BY SUM(cost) DESC, SUM(input_tokens + output_tokens) DESC
+            "})?;
+
+            Ok(select()?
+                .into_iter()
+                .map(
+                    |(
+                        model,
+                        folder_paths,
+                        folder_paths_order,
+                        requests,
+                        input_tokens,
+                        output_tokens,
+                        cache_tokens,
+                        cost,
+                    )| {
+                        let folder_paths = folder_paths
+                            .map(|paths| {
+                                PathList::deserialize(&util::path_list::SerializedPathList {
+                                    paths,
+                                    order: folder_paths_order.unwrap_or_default(),
+                                })
+                            })
+                            .unwrap_or_default();
+                        DbUsageReportRow {
+                            model: model.into(),
+                            folder_paths,
+                            requests,
+                            input_tokens,
+                            output_tokens,
+                            cache_tokens,
+                            cost,
+                        }
+                    },
+                )
+                .collect())
+        })
+    }
+
     pub fn load_thread(&self, id: acp::SessionId) -> Task<Result<Option<DbThread>>> {
         let connection = self.connection.clone();
 
@@ -629,9 +1198,11 @@ impl ThreadsDatabase {
         folder_paths: PathList,
     ) -> Task<Result<()>> {
         let connection = self.connection.clone();
+        let search_available = self.search_available;
 
-        self.executor
-            .spawn(async move { Self::save_thread_sync(&connection, id, thread, &folder_paths) })
+        self.executor.spawn(async move {
+            Self::save_thread_sync(&connection, search_available, id, thread, &folder_paths)
+        })
     }
 
     fn deserialize_thread(data_type: DataType, data: Vec<u8>) -> Result<DbThread> {
@@ -670,6 +1241,7 @@ impl ThreadsDatabase {
 
     pub fn delete_thread(&self, id: acp::SessionId) -> Task<Result<()>> {
         let connection = self.connection.clone();
+        let search_available = self.search_available;
 
         self.executor.spawn(async move {
             let sandboxed_terminal_temp_dirs = {
@@ -700,6 +1272,14 @@ impl ThreadsDatabase {
                     DELETE FROM threads WHERE id = ?
                 "})?;
 
+                let mut delete_search_rows = search_available
+                    .then(|| {
+                        connection.exec_bound::<Arc<str>>(indoc! {"
+                            DELETE FROM thread_search WHERE thread_id = ?
+                        "})
+                    })
+                    .transpose()?;
+
                 let mut sandboxed_terminal_temp_dirs = Vec::new();
                 for thread_id in ids_to_delete {
                     if let Some(temp_dir) = select(thread_id.clone())?.into_iter().next().and_then(
@@ -707,7 +1287,10 @@ impl ThreadsDatabase {
                     ) {
                         sandboxed_terminal_temp_dirs.push(temp_dir);
                     }
-                    delete(thread_id)?;
+                    delete(thread_id.clone())?;
+                    if let Some(delete_search_rows) = &mut delete_search_rows {
+                        delete_search_rows(thread_id)?;
+                    }
                 }
 
                 sandboxed_terminal_temp_dirs
@@ -723,6 +1306,7 @@ impl ThreadsDatabase {
 
     pub fn delete_threads(&self) -> Task<Result<()>> {
         let connection = self.connection.clone();
+        let search_available = self.search_available;
 
         self.executor.spawn(async move {
             let sandboxed_terminal_temp_dirs = {
@@ -739,11 +1323,14 @@ impl ThreadsDatabase {
                     })
                     .collect::<Vec<_>>();
 
-                let mut delete = connection.exec_bound::<()>(indoc! {"
+                connection.exec(indoc! {"
                     DELETE FROM threads
-                "})?;
-
-                delete(())?;
+                "})?()?;
+                if search_available {
+                    connection.exec(indoc! {"
+                        DELETE FROM thread_search
+                    "})?()?;
+                }
 
                 sandboxed_terminal_temp_dirs
             };
@@ -1038,6 +1625,338 @@ mod tests {
         assert!(!temp_dir.exists());
     }
 
+    #[gpui::test]
+    async fn test_search_threads(cx: &mut TestAppContext) {
+        let database = ThreadsDatabase::new(cx.executor()).unwrap();
+        let thread_id = session_id("search-thread");
+        let mut thread = make_thread(
+            "Search Thread",
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        );
+        thread.messages = vec![
+            Arc::new(DbMessage::User(UserMessage {
+                id: ClientUserMessageId::new(),
+                content: vec![UserMessageContent::Text("Hello".into())].into(),
+            })),
+            Arc::new(DbMessage::User(UserMessage {
+                id: ClientUserMessageId::new(),
+                content: vec![UserMessageContent::Text("Please fix the migration".into())].into(),
+            })),
+            Arc::new(DbMessage::Agent(AgentMessage {
+                content: vec![AgentMessageContent::ToolUse(
+                    language_model::LanguageModelToolUse {
+                        id: "tool_1".into(),
+                        name: "edit_file".into(),
+                        raw_input: String::new(),
+                        input: LanguageModelToolUseInput::Json(
+                            serde_json::json!({ "path": "db/schema_migrations.sql" }),
+                        ),
+                        is_input_complete: true,
+                        thought_signature: None,
+                    },
+                )],
+                tool_results: Default::default(),
+                reasoning_details: None,
+            })),
+        ];
+        database
+            .save_thread(thread_id.clone(), thread, PathList::default())
+            .await
+            .unwrap();
+
+        let matches = database.search_threads("migrat".into(), 10).await.unwrap();
+        assert_eq!(matches.len(), 2);
+        assert!(matches.iter().all(|m| m.id == thread_id));
+        assert!(matches.iter().all(|m| m.user_message_ix == Some(1)));
+
+        let matches = database
+            .search_threads("schema_migrations".into(), 10)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].snippet.contains("schema_migrations.sql"));
+
+        assert!(
+            database
+                .search_threads("\"".into(), 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        database.delete_thread(thread_id).await.unwrap();
+        assert!(
+            database
+                .search_threads("migration".into(), 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    fn searchable_thread(title: &str, text: &str) -> DbThread {
+        let mut thread = make_thread(title, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        thread.messages = vec![Arc::new(DbMessage::User(UserMessage {
+            id: ClientUserMessageId::new(),
+            content: vec![UserMessageContent::Text(text.into())].into(),
+        }))];
+        thread
+    }
+
+    #[gpui::test]
+    async fn test_save_thread_rolls_back_when_indexing_fails(cx: &mut TestAppContext) {
+        let database = ThreadsDatabase::new(cx.executor()).unwrap();
+        // A search index without the expected columns makes indexing fail.
+        database
+            .connection
+            .lock()
+            .exec(indoc! {"
+                DROP TABLE thread_search;
+                CREATE TABLE thread_search (thread_id TEXT);
+            "})
+            .unwrap()()
+        .unwrap();
+
+        let thread_id = session_id("unindexed-thread");
+        let result = database
+            .save_thread(
+                thread_id.clone(),
+                searchable_thread("Unindexed", "Please fix the migration"),
+                PathList::default(),
+            )
+            .await;
+        assert!(result.is_err());
+        assert!(database.load_thread(thread_id).await.unwrap().is_none());
+    }
+
+    #[gpui::test]
+    async fn test_threads_saved_without_search_index(cx: &mut TestAppContext) {
+        let mut database = ThreadsDatabase::new(cx.executor()).unwrap();
+        database
+            .connection
+            .lock()
+            .exec("DROP TABLE thread_search")
+            .unwrap()()
+        .unwrap();
+        database.search_available = false;
+
+        let thread_id = session_id("unsearchable-thread");
+        database
+            .save_thread(
+                thread_id.clone(),
+                searchable_thread("Unsearchable", "Please fix the migration"),
+                PathList::default(),
+            )
+            .await
+            .unwrap();
+        assert!(
+            database
+                .load_thread(thread_id.clone())
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            database
+                .search_threads("migration".into(), 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        database.delete_thread(thread_id.clone()).await.unwrap();
+        assert!(database.load_thread(thread_id).await.unwrap().is_none());
+        database.delete_threads().await.unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_token_usage_spend_and_report(cx: &mut TestAppContext) {
+        let database = ThreadsDatabase::new(cx.executor()).unwrap();
+        let write_profile = AgentProfileId("write".into());
+        let ask_profile = AgentProfileId("ask".into());
+        let first_thread = session_id("first-thread");
+        let second_thread = session_id("second-thread");
+        database
+            .save_thread(
+                first_thread.clone(),
+                make_thread("First", Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+                PathList::new(&[std::path::PathBuf::from("/project")]),
+            )
+            .await
+            .unwrap();
+
+        let usage = |input_tokens, output_tokens| language_model::TokenUsage {
+            input_tokens,
+            output_tokens,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 10,
+        };
+        let project = PathList::new(&[std::path::PathBuf::from("/project")]);
+        for (thread_id, profile_id, model, folder_paths, usage, cost) in [
+            (
+                &first_thread,
+                &write_profile,
+                "anthropic/sonnet",
+                &project,
+                usage(100, 20),
+                Some(1.0),
+            ),
+            (
+                &first_thread,
+                &write_profile,
+                "anthropic/sonnet",
+                &project,
+                usage(200, 40),
+                Some(2.0),
+            ),
+            (
+                &second_thread,
+                &write_profile,
+                "ollama/llama",
+                &PathList::default(),
+                usage(50, 10),
+                None,
+            ),
+            (
+                &second_thread,
+                &ask_profile,
+                "anthropic/sonnet",
+                &PathList::default(),
+                usage(10, 5),
+                Some(0.5),
+            ),
+        ] {
+            database
+                .record_token_usage(
+                    thread_id.clone(),
+                    profile_id.clone(),
+                    model.to_string(),
+                    folder_paths.clone(),
+                    usage,
+                    cost,
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            database
+                .spend(first_thread.clone(), write_profile.clone())
+                .await
+                .unwrap(),
+            BudgetSpend {
+                thread: Spend {
+                    tokens: 380,
+                    cost: 3.0,
+                },
+                profile: Spend {
+                    tokens: 450,
+                    cost: 3.0,
+                },
+                daily: Spend {
+                    tokens: 475,
+                    cost: 3.5,
+                },
+            }
+        );
+        assert_eq!(
+            database
+                .spend(
+                    session_id("unknown-thread"),
+                    AgentProfileId("minimal".into())
+                )
+                .await
+                .unwrap()
+                .profile,
+            Spend::default()
+        );
+
+        // Usage outlives the threads it was recorded for, and keeps their project.
+        database.delete_thread(first_thread).await.unwrap();
+        let report = database.usage_report().await.unwrap();
+        assert_eq!(
+            report
+                .iter()
+                .map(|row| (
+                    row.model.as_ref(),
+                    &row.folder_paths,
+                    row.requests,
+                    row.cost
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("anthropic/sonnet", &project, 2, Some(3.0)),
+                ("anthropic/sonnet", &PathList::default(), 1, Some(0.5)),
+                ("ollama/llama", &PathList::default(), 1, None),
+            ]
+        );
+        assert_eq!(report[0].input_tokens, 300);
+        assert_eq!(report[0].output_tokens, 60);
+        assert_eq!(report[0].cache_tokens, 20);
+    }
+
+    #[gpui::test]
+    async fn test_token_usage_is_backfilled_from_saved_threads(cx: &mut TestAppContext) {
+        let database = ThreadsDatabase::new(cx.executor()).unwrap();
+        let project = PathList::new(&[std::path::PathBuf::from("/project")]);
+        let mut thread = make_thread("Old", Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap());
+        thread.cumulative_token_usage = language_model::TokenUsage {
+            input_tokens: 100,
+            output_tokens: 20,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        };
+        thread.model = Some(DbLanguageModel {
+            provider: "anthropic".into(),
+            model: "sonnet".into(),
+        });
+        database
+            .save_thread(session_id("old-thread"), thread, project.clone())
+            .await
+            .unwrap();
+        database
+            .save_thread(
+                session_id("unused-thread"),
+                make_thread("Unused", Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+                PathList::default(),
+            )
+            .await
+            .unwrap();
+
+        // Reopen the database as it was before usage was recorded per request.
+        database
+            .connection
+            .lock()
+            .exec("DROP TABLE token_usage")
+            .unwrap()()
+        .unwrap();
+        // The database is in memory, so it's kept open while it's reopened.
+        let database = ThreadsDatabase::new(cx.executor()).unwrap();
+
+        let report = database.usage_report().await.unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].model.as_ref(), "anthropic/sonnet");
+        assert_eq!(report[0].folder_paths, project);
+        assert_eq!(
+            (
+                report[0].requests,
+                report[0].input_tokens,
+                report[0].output_tokens
+            ),
+            (1, 100, 20)
+        );
+        assert_eq!(report[0].cost, None);
+        assert_eq!(
+            database
+                .spend(session_id("old-thread"), AgentProfileId::default())
+                .await
+                .unwrap()
+                .thread
+                .tokens,
+            120
+        );
+    }
+
     #[gpui::test]
     async fn test_delete_thread_deletes_subagent_threads(cx: &mut TestAppContext) {
         let database = ThreadsDatabase::new(cx.executor()).unwrap();
@@ -0,0 +1,388 @@
+//! User-configured commands that run around the agent's actions.
+//!
+//! Hooks are configured per event under `agent.hooks` in settings. Each one is
+//! spawned with a JSON [`HookPayload`] on stdin, in the project's first
+//! worktree, and wrapped in the same OS sandbox (worktrees writable, `.git`
+//! protected, plus the user's persistent grants) as agent terminal commands.
+//!
+//! A hook's exit status decides what happens next:
+//! - `0`: its stdout, if any, is passed to the model as feedback (except for
+//!   `turn_end`, which has nothing left to attach it to).
+//! - [`BLOCKING_EXIT_CODE`]: its stderr is passed to the model, and for
+//!   `before_tool_call` the tool call is refused. Later hooks don't run.
+//! - Anything else (including failing to spawn or timing out) is logged and
+//!   otherwise ignored, so a broken hook can't wedge the agent.
+
+use crate::sandboxing::{
+    ThreadSandbox, sandbox_git_dirs, sandbox_worktree_writable_paths,
+    sandboxing_enabled_for_project, settings_thread_sandbox,
+};
+use agent_client_protocol::schema::v1 as acp;
+use agent_settings::{AgentHook, AgentHooks, AgentSettings};
+use anyhow::{Context as _, Result};
+use futures::{AsyncWriteExt as _, FutureExt as _};
+use gpui::{App, BackgroundExecutor, Task};
+use project::Project;
+use sandbox::{HostFilesystemLocation, Sandbox, SandboxFsPolicy, SandboxNetPolicy, SandboxPolicy};
+use serde::Serialize;
+use settings::Settings as _;
+use std::{collections::HashMap, path::PathBuf};
+use util::command::{Stdio, new_command};
+
+/// Exit status a hook uses to block the action it was run for.
+pub const BLOCKING_EXIT_CODE: i32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    BeforeToolCall,
+    AfterFileEdit,
+    TurnEnd,
+    SessionStart,
+}
+
+impl HookEvent {
+    fn hooks(self, hooks: &AgentHooks) -> &[AgentHook] {
+        match self {
+            HookEvent::BeforeToolCall => &hooks.before_tool_call,
+            HookEvent::AfterFileEdit => &hooks.after_file_edit,
+            HookEvent::TurnEnd => &hooks.turn_end,
+            HookEvent::SessionStart => &hooks.session_start,
+        }
+    }
+}
+
+/// The JSON document written to a hook's stdin.
+#[derive(Debug, Serialize)]
+pub struct HookPayload {
+    pub event: HookEvent,
+    pub session_id: String,
+    pub cwd: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_input: Option<serde_json::Value>,
+    /// For `turn_end`, how many times hooks have already kept this turn going,
+    /// so a hook can avoid looping forever.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuations: Option<usize>,
+}
+
+impl HookPayload {
+    pub fn new(event: HookEvent, session_id: &acp::SessionId) -> Self {
+        Self {
+            event,
+            session_id: session_id.to_string(),
+            cwd: None,
+            tool_name: None,
+            tool_input: None,
+            continuations: None,
+        }
+    }
+
+    pub fn with_tool(mut self, tool_name: &str, tool_input: serde_json::Value) -> Self {
+        self.tool_name = Some(tool_name.to_string());
+        self.tool_input = Some(tool_input);
+        self
+    }
+}
+
+/// The combined result of running every hook configured for an event.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct HookOutcome {
+    /// Set when a hook exited with [`BLOCKING_EXIT_CODE`], holding its stderr.
+    pub block_reason: Option<String>,
+    /// The non-empty stdout of each hook that succeeded, in the order they ran.
+    pub feedback: Vec<String>,
+}
+
+impl HookOutcome {
+    /// Everything the hooks had to say, for events where blocking isn't
+    /// meaningful and a blocking hook's reason is just more feedback.
+    pub fn into_feedback(self) -> Vec<String> {
+        let mut feedback = self.feedback;
+        feedback.extend(self.block_reason);
+        feedback
+    }
+}
+
+/// Formats hook output for inclusion in a tool result or message.
+pub fn format_hook_feedback(feedback: &[String]) -> Option<String> {
+    if feedback.is_empty() {
+        return None;
+    }
+    let mut text = String::from("<hook_feedback>\n");
+    for entry in feedback {
+        text.push_str(entry);
+        text.push('\n');
+    }
+    text.push_str("</hook_feedback>");
+    Some(text)
+}
+
+/// Whether any hook is configured for `event` (and `tool_name`, for the tool
+/// events). Lets callers skip work such as buffering streamed tool input.
+pub fn has_hooks(event: HookEvent, tool_name: Option<&str>, cx: &App) -> bool {
+    event
+        .hooks(&AgentSettings::get_global(cx).hooks)
+        .iter()
+        .any(|hook| hook.applies_to_tool(tool_name))
+}
+
+/// Runs the hooks configured for `payload.event`, returning `None` when there
+/// are none. Hooks only run for local projects, since they're spawned on this
+/// machine.
+pub fn run_hooks(
+    mut payload: HookPayload,
+    project: &Project,
+    cx: &App,
+) -> Option<Task<HookOutcome>> {
+    let hooks = payload
+        .event
+        .hooks(&AgentSettings::get_global(cx).hooks)
+        .iter()
+        .filter(|hook| hook.applies_to_tool(payload.tool_name.as_deref()))
+        .cloned()
+        .collect::<Vec<_>>();
+    if hooks.is_empty() || !project.is_local() {
+        return None;
+    }
+
+    let worktree_paths = sandbox_worktree_writable_paths(project, cx);
+    let cwd = worktree_paths.first().cloned();
+    payload.cwd = cwd.clone();
+    let policy = if sandboxing_enabled_for_project(project, cx) {
+        hook_sandbox_policy(worktree_paths, project, cx)
+    } else {
+        None
+    };
+    let payload = match serde_json::to_string(&payload) {
+        Ok(payload) => payload,
+        Err(error) => {
+            log::error!("failed to serialize agent hook payload: {error}");
+            return None;
+        }
+    };
+
+    let executor = cx.background_executor().clone();
+    Some(cx.background_spawn(async move {
+        let mut outcome = HookOutcome::default();
+        for hook in hooks {
+            let result = run_hook(&hook, &payload, cwd.clone(), policy.clone(), &executor).await;
+            match result {
+                Ok(HookResult::Success { stdout }) => {
+                    let stdout = stdout.trim();
+                    if !stdout.is_empty() {
+                        outcome.feedback.push(stdout.to_string());
+                    }
+                }
+                Ok(HookResult::Blocked { stderr }) => {
+                    let stderr = stderr.trim();
+                    outcome.block_reason = Some(if stderr.is_empty() {
+                        format!("Blocked by hook `{}`", hook.command)
+                    } else {
+                        stderr.to_string()
+                    });
+                    break;
+                }
+                Err(error) => {
+                    log::warn!("agent hook `{}` failed: {error:#}", hook.command);
+                }
+            }
+        }
+        outcome
+    }))
+}
+
+/// The sandbox hooks run in: write access to the project's worktrees, combined
+/// with the user's persistent grants, with Git metadata kept read-only. `None`
+/// when those grants turn the sandbox off.
+fn hook_sandbox_policy(
+    worktree_paths: Vec<PathBuf>,
+    project: &Project,
+    cx: &App,
+) -> Option<SandboxPolicy> {
+    let baseline = ThreadSandbox::Sandboxed(SandboxPolicy {
+        fs: SandboxFsPolicy::Restricted {
+            writable_paths: sandbox::normalize_host_filesystem_locations(
+                worktree_paths
+                    .into_iter()
+                    .filter_map(|path| HostFilesystemLocation::capture(path).ok()),
+            ),
+            protected_paths: Vec::new(),
+        },
+        network: SandboxNetPolicy::Blocked,
+    });
+    let persistent = &AgentSettings::get_global(cx).sandbox_permissions;
+    match baseline
+        .merge(settings_thread_sandbox(persistent))
+        .with_protected_paths(sandbox_git_dirs(project, cx))
+    {
+        ThreadSandbox::Sandboxed(policy) => Some(policy),
+        ThreadSandbox::Unsandboxed => None,
+    }
+}
+
+enum HookResult {
+    Success { stdout: String },
+    Blocked { stderr: String },
+}
+
+async fn run_hook(
+    hook: &AgentHook,
+    payload: &str,
+    cwd: Option<PathBuf>,
+    policy: Option<SandboxPolicy>,
+    executor: &BackgroundExecutor,
+) -> Result<HookResult> {
+    let command = sandbox::CommandAndArgs {
+        program: hook.command.clone(),
+        args: hook.args.clone(),
+        env: HashMap::default(),
+        cwd,
+    };
+    let (command, sandbox) = match policy {
+        Some(policy) => {
+            let mut sandbox = Sandbox::new(policy)?;
+            let wrapped = sandbox.wrap(&command).await?;
+            (
+                sandbox::CommandAndArgs {
+                    program: wrapped.program,
+                    args: wrapped.args,
+                    env: wrapped.env,
+                    cwd: wrapped.cwd,
+                },
+                Some(sandbox),
+            )
+        }
+        None => (command, None),
+    };
+
+    let mut process = new_command(&command.program);
+    process
+        .args(&command.args)
+        .envs(&command.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(cwd) = &command.cwd {
+        process.current_dir(cwd);
+    }
+    let mut child = process
+        .spawn()
+        .with_context(|| format!("spawning `{}`", hook.command))?;
+    // The payload is written while the output is collected, and both are
+    // covered by the timeout, so a hook that never reads a payload larger than
+    // the pipe buffer can't block the agent.
+    let stdin = child.stdin.take();
+    let write_payload = async move {
+        if let Some(mut stdin) = stdin {
+            // A hook may exit without reading its input; that's not an error.
+            stdin.write_all(payload.as_bytes()).await.ok();
+        }
+    };
+    let run = futures::future::join(write_payload, child.output());
+    let output = futures::select_biased! {
+        (_, output) = run.fuse() => Some(output?),
+        _ = executor.timer(hook.timeout).fuse() => None,
+    };
+    if let Some(sandbox) = sandbox {
+        sandbox.drop_on_current_thread();
+    }
+    let output = output.with_context(|| format!("timed out after {:?}", hook.timeout))?;
+
+    match output.status.code() {
+        Some(0) => Ok(HookResult::Success {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        }),
+        Some(BLOCKING_EXIT_CODE) => Ok(HookResult::Blocked {
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }),
+        _ => anyhow::bail!(
+            "exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use std::time::Duration;
+
+    fn hook(tools: &[&str]) -> AgentHook {
+        AgentHook {
+            command: "true".into(),
+            args: Vec::new(),
+            tools: tools.iter().map(|tool| tool.to_string()).collect(),
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn test_hook_applies_to_tool() {
+        assert!(hook(&[]).applies_to_tool(Some("edit_file")));
+        assert!(hook(&[]).applies_to_tool(None));
+        assert!(hook(&["edit_file"]).applies_to_tool(Some("edit_file")));
+        assert!(!hook(&["edit_file"]).applies_to_tool(Some("terminal")));
+        assert!(hook(&["edit_file"]).applies_to_tool(None));
+    }
+
+    #[test]
+    fn test_payload_serialization() {
+        let payload = HookPayload::new(HookEvent::BeforeToolCall, &acp::SessionId::new("session"))
+            .with_tool("terminal", serde_json::json!({ "command": "ls" }));
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            serde_json::json!({
+                "event": "before_tool_call",
+                "session_id": "session",
+                "cwd": null,
+                "tool_name": "terminal",
+                "tool_input": { "command": "ls" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_outcome_into_feedback() {
+        let outcome = HookOutcome {
+            block_reason: Some("lint failed".into()),
+            feedback: vec!["formatted 1 file".into()],
+        };
+        assert_eq!(
+            outcome.into_feedback(),
+            vec!["formatted 1 file".to_string(), "lint failed".to_string()]
+        );
+        assert_eq!(format_hook_feedback(&[]), None);
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_hook_ignoring_large_payload_times_out(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let hook = AgentHook {
+            command: "sleep".into(),
+            args: vec!["30".into()],
+            tools: Vec::new(),
+            timeout: Duration::from_secs(1),
+        };
+        // Larger than a pipe's buffer, so writing it can't finish until the
+        // hook reads it, which this one never does.
+        let payload = "x".repeat(1024 * 1024);
+        let executor = cx.executor();
+        let task = cx
+            .executor()
+            .spawn(async move { run_hook(&hook, &payload, None, None, &executor).await });
+        cx.run_until_parked();
+        cx.executor().advance_clock(Duration::from_secs(2));
+
+        let Err(error) = task.await else {
+            panic!("expected the hook to time out");
+        };
+        assert!(error.to_string().contains("timed out"), "{error:#}");
+    }
+}
@@ -25,11 +25,11 @@ use gpui::{
 };
 use indoc::indoc;
 use language_model::{
-    CompletionIntent, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
-    LanguageModelId, LanguageModelImageExt, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage,
-    LanguageModelToolResult, LanguageModelToolSchemaFormat, LanguageModelToolUse, MessageContent,
-    Role, StopReason, TokenUsage,
+    CompletionIntent, ConfiguredModel, LanguageModel, LanguageModelCompletionError,
+    LanguageModelCompletionEvent, LanguageModelId, LanguageModelImageExt, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelRegistry, LanguageModelRequest,
+    LanguageModelRequestMessage, LanguageModelToolResult, LanguageModelToolSchemaFormat,
+    LanguageModelToolUse, MessageContent, Role, StopReason, TokenUsage,
     fake_provider::{FakeLanguageModel, FakeLanguageModelProvider},
 };
 use pretty_assertions::assert_eq;
@@ -186,7 +186,9 @@ pub(crate) struct FakeThreadEnvironment {
     terminal_handle: Option<Rc<FakeTerminalHandle>>,
     subagent_handle: Option<Rc<FakeSubagentHandle>>,
     terminal_creations: Arc<AtomicUsize>,
+    terminal_commands: std::cell::RefCell<Vec<String>>,
     terminal_output_limits: std::cell::RefCell<Vec<Option<u64>>>,
+    terminal_sandbox_wraps: std::cell::RefCell<Vec<Option<acp_thread::SandboxWrap>>>,
 }
 
 impl FakeThreadEnvironment {
@@ -201,25 +203,35 @@ impl FakeThreadEnvironment {
         self.terminal_creations.load(Ordering::SeqCst)
     }
 
+    pub(crate) fn terminal_commands(&self) -> Vec<String> {
+        self.terminal_commands.borrow().clone()
+    }
+
     pub(crate) fn terminal_output_limits(&self) -> Vec<Option<u64>> {
         self.terminal_output_limits.borrow().clone()
     }
+
+    pub(crate) fn terminal_sandbox_wraps(&self) -> Vec<Option<acp_thread::SandboxWrap>> {
+        self.terminal_sandbox_wraps.borrow().clone()
+    }
 }
 
 impl crate::ThreadEnvironment for FakeThreadEnvironment {
     fn create_terminal(
         &self,
-        _command: String,
+        command: String,
         _extra_env: Vec<acp::EnvVariable>,
         _cwd: Option<std::path::PathBuf>,
         output_byte_limit: Option<u64>,
-        _sandbox_wrap: Option<acp_thread::SandboxWrap>,
+        sandbox_wrap: Option<acp_thread::SandboxWrap>,
         _cx: &mut AsyncApp,
     ) -> Task<Result<Rc<dyn crate::TerminalHandle>>> {
         self.terminal_creations.fetch_add(1, Ordering::SeqCst);
+        self.terminal_commands.borrow_mut().push(command);
         self.terminal_output_limits
             .borrow_mut()
             .push(output_byte_limit);
+        self.terminal_sandbox_wraps.borrow_mut().push(sandbox_wrap);
         let handle = self
             .terminal_handle
             .clone()
@@ -398,6 +410,235 @@ async fn test_terminal_tool_timeout_kills_handle(cx: &mut TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_run_task_tool_lists_and_runs_tasks(cx: &mut TestAppContext) {
+    init_test(cx);
+    always_allow_tools(cx);
+    disable_sandboxing(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".zed": {
+                "tasks.json": r#"[{ "label": "check", "command": "cargo", "args": ["check", "--workspace"] }]"#,
+            },
+            "src": { "main.rs": "" },
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    cx.run_until_parked();
+
+    let environment = Rc::new(cx.update(|cx| {
+        FakeThreadEnvironment::default().with_terminal(
+            FakeTerminalHandle::new_with_immediate_exit(cx, 0).with_output(
+                acp::TerminalOutputResponse::new("Finished dev profile".to_string(), false),
+            ),
+        )
+    }));
+    #[allow(clippy::arc_with_non_send_sync)]
+    let tool = Arc::new(crate::RunTaskTool::new(project, environment.clone()));
+
+    let (event_stream, _rx) = crate::ToolCallEventStream::test();
+    let tasks = cx
+        .update(|cx| {
+            tool.clone().run(
+                ToolInput::resolved(crate::RunTaskToolInput {
+                    label: None,
+                    path: None,
+                    timeout_ms: None,
+                }),
+                event_stream,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(tasks).unwrap(),
+        json!({
+            "tasks": [{
+                "label": "check",
+                "source": "project tasks",
+                "command": "cargo check --workspace",
+            }]
+        })
+    );
+    assert_eq!(environment.terminal_creation_count(), 0);
+
+    let (event_stream, _rx) = crate::ToolCallEventStream::test();
+    let ran = cx
+        .update(|cx| {
+            tool.clone().run(
+                ToolInput::resolved(crate::RunTaskToolInput {
+                    label: Some("check".to_string()),
+                    path: None,
+                    timeout_ms: None,
+                }),
+                event_stream,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(ran).unwrap(),
+        json!({
+            "label": "check",
+            "command": "cargo check --workspace",
+            "exit_code": 0,
+            "timed_out": false,
+            "stopped_by_user": false,
+            "output": "Finished dev profile",
+            "output_truncated": false,
+            "failures": [],
+        })
+    );
+    assert_eq!(
+        environment.terminal_commands(),
+        vec!["cargo check --workspace".to_string()]
+    );
+}
+
+#[gpui::test]
+async fn test_run_task_tool_permissions_match_command(cx: &mut TestAppContext) {
+    init_test(cx);
+    disable_sandboxing(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".zed": {
+                "tasks.json": r#"[
+                    { "label": "check", "command": "cargo", "args": ["check"] },
+                    { "label": "clean up", "command": "rm", "args": ["-rf", "target"] }
+                ]"#,
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    cx.run_until_parked();
+
+    cx.update(|cx| {
+        let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+        settings.tool_permissions.tools.insert(
+            crate::RunTaskTool::NAME.into(),
+            agent_settings::ToolRules {
+                default: Some(settings::ToolPermissionMode::Confirm),
+                always_allow: vec![agent_settings::CompiledRegex::new(r"^cargo\b", false).unwrap()],
+                always_deny: vec![agent_settings::CompiledRegex::new(r"\brm\b", false).unwrap()],
+                always_confirm: vec![],
+                invalid_patterns: vec![],
+            },
+        );
+        agent_settings::AgentSettings::override_global(settings, cx);
+    });
+
+    let environment = Rc::new(cx.update(|cx| {
+        FakeThreadEnvironment::default()
+            .with_terminal(FakeTerminalHandle::new_with_immediate_exit(cx, 0))
+    }));
+    #[allow(clippy::arc_with_non_send_sync)]
+    let tool = Arc::new(crate::RunTaskTool::new(project, environment.clone()));
+
+    // The allow rule matches the task's command, not its label.
+    let (event_stream, mut rx) = crate::ToolCallEventStream::test();
+    let task = cx.update(|cx| {
+        tool.clone().run(
+            ToolInput::resolved(crate::RunTaskToolInput {
+                label: Some("check".to_string()),
+                path: None,
+                timeout_ms: None,
+            }),
+            event_stream,
+            cx,
+        )
+    });
+    cx.run_until_parked();
+    let event = rx.try_recv();
+    assert!(
+        !matches!(event, Ok(Ok(ThreadEvent::ToolCallAuthorization(_)))),
+        "expected no authorization request for an allowed command"
+    );
+    task.await.unwrap();
+
+    // So does the deny rule, even though the label doesn't mention `rm`.
+    let (event_stream, _rx) = crate::ToolCallEventStream::test();
+    let result = cx
+        .update(|cx| {
+            tool.clone().run(
+                ToolInput::resolved(crate::RunTaskToolInput {
+                    label: Some("clean up".to_string()),
+                    path: None,
+                    timeout_ms: None,
+                }),
+                event_stream,
+                cx,
+            )
+        })
+        .await;
+    let Err(crate::RunTaskToolOutput::Error { error }) = result else {
+        panic!("expected the task to be denied, got {result:?}");
+    };
+    assert!(error.contains("blocked"), "unexpected error: {error}");
+    assert_eq!(
+        environment.terminal_commands(),
+        vec!["cargo check".to_string()]
+    );
+}
+
+#[cfg(target_os = "macos")]
+#[gpui::test]
+async fn test_run_task_tool_runs_tasks_sandboxed(cx: &mut TestAppContext) {
+    use feature_flags::FeatureFlagAppExt as _;
+
+    init_test(cx);
+    always_allow_tools(cx);
+    cx.update(|cx| cx.update_flags(true, vec!["sandboxing".to_string()]));
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".zed": {
+                "tasks.json": r#"[{ "label": "check", "command": "cargo", "args": ["check"] }]"#,
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    cx.run_until_parked();
+
+    let environment = Rc::new(cx.update(|cx| {
+        FakeThreadEnvironment::default()
+            .with_terminal(FakeTerminalHandle::new_with_immediate_exit(cx, 0))
+    }));
+    #[allow(clippy::arc_with_non_send_sync)]
+    let tool = Arc::new(crate::RunTaskTool::new(project, environment.clone()));
+
+    let (event_stream, _rx) = crate::ToolCallEventStream::test();
+    cx.update(|cx| {
+        tool.clone().run(
+            ToolInput::resolved(crate::RunTaskToolInput {
+                label: Some("check".to_string()),
+                path: None,
+                timeout_ms: None,
+            }),
+            event_stream,
+            cx,
+        )
+    })
+    .await
+    .unwrap();
+
+    let wraps = environment.terminal_sandbox_wraps();
+    assert_eq!(wraps.len(), 1);
+    assert!(wraps[0].is_some(), "the task should run sandboxed");
+}
+
 #[gpui::test]
 #[ignore]
 async fn test_terminal_tool_without_timeout_does_not_kill_handle(cx: &mut TestAppContext) {
@@ -1076,6 +1317,97 @@ async fn test_tool_hallucination(cx: &mut TestAppContext) {
     assert_eq!(update.fields.status, Some(acp::ToolCallStatus::Failed));
 }
 
+#[cfg(unix)]
+#[gpui::test]
+async fn test_before_tool_call_hook_blocks_tool(cx: &mut TestAppContext) {
+    let ThreadTest {
+        model, thread, fs, ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    // Hooks run in the project's first worktree, so it has to exist on disk.
+    let hook_dir = tempfile::tempdir().unwrap();
+    fs.insert_tree(hook_dir.path(), json!({})).await;
+    let project = thread.read_with(cx, |thread, _| thread.project().clone());
+    project.update(cx, |project, cx| {
+        let worktree_id = project.worktrees(cx).next().unwrap().read(cx).id();
+        project.remove_worktree(worktree_id, cx);
+    });
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(hook_dir.path(), true, cx)
+        })
+        .await
+        .unwrap();
+
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.agent.get_or_insert_default().hooks = Some(settings::AgentHooksContent {
+                    before_tool_call: Some(vec![settings::AgentHookContent {
+                        command: "sh".into(),
+                        args: Some(vec![
+                            "-c".into(),
+                            "cat > /dev/null; echo 'echo is not allowed' >&2; exit 2".into(),
+                        ]),
+                        tools: Some(vec![EchoTool::NAME.into()]),
+                        timeout: None,
+                    }]),
+                    ..Default::default()
+                });
+            });
+        });
+    });
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(ClientUserMessageId::new(), ["abc"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_1".into(),
+            name: EchoTool::NAME.into(),
+            raw_input: json!({"text": "test"}).to_string(),
+            input: language_model::LanguageModelToolUseInput::Json(json!({"text": "test"})),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+
+    loop {
+        let event = events
+            .next()
+            .await
+            .expect("no tool call update received")
+            .unwrap();
+        if let ThreadEvent::ToolCallUpdate(acp_thread::ToolCallUpdate::UpdateFields(update)) = event
+            && update.fields.status == Some(acp::ToolCallStatus::Failed)
+        {
+            break;
+        }
+    }
+    cx.run_until_parked();
+
+    let completion = fake_model.pending_completions().pop().unwrap();
+    let message = completion.messages.last().unwrap();
+    assert_eq!(
+        message.content,
+        vec![language_model::MessageContent::ToolResult(
+            LanguageModelToolResult {
+                tool_use_id: "tool_id_1".into(),
+                tool_name: EchoTool::NAME.into(),
+                is_error: true,
+                content: vec!["Tool call blocked by hook: echo is not allowed".into()],
+                output: None,
+            }
+        )]
+    );
+}
+
 /// Regression test: some providers (confirmed on Bedrock Mantle/GPT-5.x)
 /// reset their raw `tool_use` id counter every request/response cycle, so
 /// the same id (e.g. `call_1`) can recur within one turn. Used verbatim as
@@ -2463,6 +2795,163 @@ async fn test_mcp_tool_truncation(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_mcp_sampling_uses_default_model(cx: &mut TestAppContext) {
+    let ThreadTest {
+        context_server_store,
+        ..
+    } = setup(cx, TestModel::Fake).await;
+    let (sampling_model, mut approvals) = setup_sampling_model(cx);
+    let transport = setup_sampling_context_server("sampler", &context_server_store, cx);
+
+    let response = cx.background_spawn(
+        transport.request::<context_server::types::requests::CreateMessage>(sampling_request(
+            "Summarize the release notes",
+        )),
+    );
+    cx.run_until_parked();
+    let (approval_request, approval_tx) = approvals.next().await.unwrap();
+    assert_eq!(
+        approval_request.server_id,
+        ContextServerId("sampler".into())
+    );
+    assert_eq!(approval_request.model_name.to_string(), "Sampling Model");
+    assert_eq!(
+        approval_request.system_prompt.as_deref(),
+        Some("You summarize things.")
+    );
+    assert_eq!(
+        approval_request.last_message.as_deref(),
+        Some("Summarize the release notes")
+    );
+    assert_eq!(approval_request.max_tokens, 100);
+    approval_tx.send(SamplingApproval::Allow).unwrap();
+    cx.run_until_parked();
+
+    let completion = sampling_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages,
+        vec![
+            LanguageModelRequestMessage {
+                role: Role::System,
+                content: vec!["You summarize things.".into()],
+                cache: false,
+                reasoning_details: None,
+            },
+            LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec!["Summarize the release notes".into()],
+                cache: false,
+                reasoning_details: None,
+            },
+        ]
+    );
+    sampling_model.send_last_completion_stream_text_chunk("Nothing notable.");
+    sampling_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+        TokenUsage {
+            input_tokens: 12,
+            output_tokens: 3,
+            ..Default::default()
+        },
+    ));
+    sampling_model
+        .send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(StopReason::EndTurn));
+    sampling_model.end_last_completion_stream();
+
+    assert_eq!(
+        response.await,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "result": {
+                "role": "assistant",
+                "content": { "type": "text", "text": "Nothing notable." },
+                "model": "sampling-model",
+                "stopReason": "endTurn"
+            }
+        })
+    );
+    cx.update(|cx| {
+        assert_eq!(
+            context_server_sampling_usage(&ContextServerId("sampler".into()), cx),
+            TokenUsage {
+                input_tokens: 12,
+                output_tokens: 3,
+                ..Default::default()
+            }
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_mcp_sampling_denied(cx: &mut TestAppContext) {
+    let ThreadTest {
+        context_server_store,
+        ..
+    } = setup(cx, TestModel::Fake).await;
+    let (sampling_model, mut approvals) = setup_sampling_model(cx);
+    let transport = setup_sampling_context_server("sampler", &context_server_store, cx);
+
+    let response = cx.background_spawn(
+        transport.request::<context_server::types::requests::CreateMessage>(sampling_request(
+            "Summarize the release notes",
+        )),
+    );
+    cx.run_until_parked();
+    let (_, approval_tx) = approvals.next().await.unwrap();
+    approval_tx.send(SamplingApproval::Deny).unwrap();
+
+    let response = response.await;
+    assert_eq!(response["error"]["code"], json!(-1));
+    assert!(response.get("result").is_none());
+    assert!(sampling_model.pending_completions().is_empty());
+    cx.update(|cx| {
+        assert_eq!(
+            context_server_sampling_usage(&ContextServerId("sampler".into()), cx),
+            TokenUsage::default()
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_mcp_sampling_always_allow(cx: &mut TestAppContext) {
+    let ThreadTest {
+        context_server_store,
+        ..
+    } = setup(cx, TestModel::Fake).await;
+    let (sampling_model, mut approvals) = setup_sampling_model(cx);
+    let transport = setup_sampling_context_server("sampler", &context_server_store, cx);
+
+    let first_response = cx.background_spawn(
+        transport
+            .request::<context_server::types::requests::CreateMessage>(sampling_request("First")),
+    );
+    cx.run_until_parked();
+    let (_, approval_tx) = approvals.next().await.unwrap();
+    approval_tx.send(SamplingApproval::AlwaysAllow).unwrap();
+    cx.run_until_parked();
+    sampling_model.send_last_completion_stream_text_chunk("One");
+    sampling_model.end_last_completion_stream();
+    assert_eq!(
+        first_response.await["result"]["content"]["text"],
+        json!("One")
+    );
+
+    // Later requests from the same server are no longer prompted for.
+    let second_response = cx.background_spawn(
+        transport
+            .request::<context_server::types::requests::CreateMessage>(sampling_request("Second")),
+    );
+    cx.run_until_parked();
+    assert!(approvals.try_next().is_err());
+    sampling_model.send_last_completion_stream_text_chunk("Two");
+    sampling_model.end_last_completion_stream();
+    assert_eq!(
+        second_response.await["result"]["content"]["text"],
+        json!("Two")
+    );
+}
+
 #[gpui::test]
 #[cfg_attr(not(feature = "e2e"), ignore)]
 async fn test_cancellation(cx: &mut TestAppContext) {
@@ -5024,23 +5513,7 @@ fn setup_context_server(
     context_server::types::CallToolParams,
     oneshot::Sender<context_server::types::CallToolResponse>,
 )> {
-    cx.update(|cx| {
-        let mut settings = ProjectSettings::get_global(cx).clone();
-        settings.context_servers.insert(
-            name.into(),
-            project::project_settings::ContextServerSettings::Stdio {
-                enabled: true,
-                remote: false,
-                command: ContextServerCommand {
-                    path: "somebinary".into(),
-                    args: Vec::new(),
-                    env: None,
-                    timeout: None,
-                },
-            },
-        );
-        ProjectSettings::override_global(settings, cx);
-    });
+    add_context_server_settings(name, cx);
 
     let (mcp_tool_calls_tx, mcp_tool_calls_rx) = mpsc::unbounded();
     let fake_transport = context_server::test::create_fake_transport(name, cx.executor())
@@ -5097,6 +5570,108 @@ fn setup_context_server(
     mcp_tool_calls_rx
 }
 
+fn add_context_server_settings(name: &str, cx: &mut TestAppContext) {
+    cx.update(|cx| {
+        let mut settings = ProjectSettings::get_global(cx).clone();
+        settings.context_servers.insert(
+            name.into(),
+            project::project_settings::ContextServerSettings::Stdio {
+                enabled: true,
+                remote: false,
+                command: ContextServerCommand {
+                    path: "somebinary".into(),
+                    args: Vec::new(),
+                    env: None,
+                    timeout: None,
+                },
+            },
+        );
+        ProjectSettings::override_global(settings, cx);
+    });
+}
+
+/// Starts a context server that can send sampling requests through the returned transport.
+fn setup_sampling_context_server(
+    name: &'static str,
+    context_server_store: &Entity<ContextServerStore>,
+    cx: &mut TestAppContext,
+) -> Arc<context_server::test::FakeTransport> {
+    add_context_server_settings(name, cx);
+    let fake_transport = Arc::new(context_server::test::create_fake_transport(
+        name,
+        cx.executor(),
+    ));
+    context_server_store.update(cx, |store, cx| {
+        store.start_server(
+            Arc::new(ContextServer::new(
+                ContextServerId(name.into()),
+                fake_transport.clone(),
+            )),
+            cx,
+        );
+    });
+    cx.run_until_parked();
+    fake_transport
+}
+
+/// Makes a model with its own id the default one, and answers sampling
+/// approval prompts through the returned channel.
+fn setup_sampling_model(
+    cx: &mut TestAppContext,
+) -> (
+    Arc<FakeLanguageModel>,
+    UnboundedReceiver<(SamplingApprovalRequest, oneshot::Sender<SamplingApproval>)>,
+) {
+    let model = Arc::new(FakeLanguageModel::with_id_and_thinking(
+        "fake",
+        "sampling-model",
+        "Sampling Model",
+        false,
+    ));
+    let (approvals_tx, approvals_rx) = mpsc::unbounded();
+    cx.update(|cx| {
+        LanguageModelRegistry::test(cx);
+        let provider = Arc::new(
+            FakeLanguageModelProvider::default()
+                .with_models(vec![model.clone() as Arc<dyn LanguageModel>]),
+        );
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry.set_default_model(
+                Some(ConfiguredModel {
+                    provider,
+                    model: model.clone(),
+                }),
+                cx,
+            );
+        });
+        cx.set_global(ContextServerSamplingPrompt(Rc::new(move |request, cx| {
+            let (approval_tx, approval_rx) = oneshot::channel();
+            approvals_tx.unbounded_send((request, approval_tx)).unwrap();
+            cx.background_spawn(async move { approval_rx.await.unwrap_or(SamplingApproval::Deny) })
+        })));
+    });
+    (model, approvals_rx)
+}
+
+fn sampling_request(text: &str) -> context_server::types::CreateMessageRequest {
+    context_server::types::CreateMessageRequest {
+        messages: vec![context_server::types::SamplingMessage {
+            role: context_server::types::Role::User,
+            content: context_server::types::MessageContent::Text {
+                text: text.into(),
+                annotations: None,
+            },
+        }],
+        model_preferences: None,
+        system_prompt: Some("You summarize things.".into()),
+        include_context: None,
+        temperature: None,
+        max_tokens: 100,
+        stop_sequences: None,
+        metadata: None,
+    }
+}
+
 #[gpui::test]
 async fn test_tokens_before_message(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
@@ -6392,6 +6967,79 @@ async fn test_lsp_tools_gated_by_feature_flag(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_semantic_search_tool_gated_by_semantic_index_setting(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/test"), json!({})).await;
+    let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+    let project_context = cx.new(|_cx| ProjectContext::default());
+    let context_server_store = project.read_with(cx, |project, _| project.context_server_store());
+    let context_server_registry =
+        cx.new(|cx| ContextServerRegistry::new(context_server_store.clone(), cx));
+    let model = Arc::new(FakeLanguageModel::default());
+    let environment = Rc::new(cx.update(|cx| {
+        FakeThreadEnvironment::default().with_terminal(FakeTerminalHandle::new_never_exits(cx))
+    }));
+
+    let thread = cx.new(|cx| {
+        let mut thread = Thread::new(
+            project,
+            project_context,
+            context_server_registry,
+            Templates::new(),
+            Some(model.clone() as Arc<dyn LanguageModel>),
+            cx,
+        );
+        thread.add_default_tools(environment, cx);
+        thread
+    });
+    thread.read_with(cx, |thread, _| {
+        assert!(thread.has_registered_tool(SemanticSearchTool::NAME));
+    });
+
+    // The semantic index is disabled by default, so the tool isn't offered
+    // even though the default profiles enable it.
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(ClientUserMessageId::new(), ["hello"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let completion = model.pending_completions().pop().unwrap();
+    let tool_names = tool_names_for_completion(&completion);
+    assert!(
+        !tool_names.iter().any(|t| t == SemanticSearchTool::NAME),
+        "expected semantic_search to be hidden while the index is disabled, \
+         but completion tools were: {tool_names:?}"
+    );
+    model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.semantic_index.get_or_insert_default().enabled = Some(true);
+            });
+        });
+    });
+
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(ClientUserMessageId::new(), ["hello again"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let completion = model.pending_completions().pop().unwrap();
+    let tool_names = tool_names_for_completion(&completion);
+    assert!(
+        tool_names.iter().any(|t| t == SemanticSearchTool::NAME),
+        "expected semantic_search to be exposed once the index is enabled, \
+         but completion tools were: {tool_names:?}"
+    );
+}
+
 #[gpui::test]
 async fn test_sibling_thread_tools_gated_by_feature_flag(cx: &mut TestAppContext) {
     init_test(cx);
@@ -1,16 +1,18 @@
 use crate::{
-    ApplyCodeActionTool, CodeActionStore, ContextServerRegistry, CopyPathTool, CreateDirectoryTool,
-    CreateThreadTool, DbLanguageModel, DbThread, DeletePathTool, DiagnosticsTool, EditFileTool,
-    FetchTool, FindPathTool, FindReferencesTool, GetCodeActionsTool, GoToDefinitionTool, GrepTool,
-    ListAgentsAndModelsTool, ListDirectoryTool, MovePathTool, ProjectSnapshot, ReadFileTool,
-    RenameTool, SandboxedTerminalTool, SpawnAgentTool, SystemPromptTemplate, Template, Templates,
-    TerminalTool, ToolPermissionDecision, WebSearchTool, WriteFileTool,
-    decide_permission_from_settings,
+    ApplyCodeActionTool, BudgetExceededError, BudgetStatus, CodeActionStore, ContextServerRegistry,
+    CopyPathTool, CreateDirectoryTool, CreateThreadTool, DbLanguageModel, DbThread, DeletePathTool,
+    DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, FindReferencesTool, GetCodeActionsTool,
+    GoToDefinitionTool, GrepTool, ListAgentsAndModelsTool, ListDirectoryTool, MovePathTool,
+    ProjectSnapshot, ReadFileTool, RenameTool, RunTaskTool, SandboxedTerminalTool,
+    SemanticSearchTool, SpawnAgentTool, SystemPromptTemplate, Template, Templates, TerminalTool,
+    ThreadsDatabase, ToolPermissionDecision, WebSearchTool, WriteFileTool, check_budgets,
+    decide_permission_from_settings, request_cost, runs_shell_commands,
 };
 use acp_thread::{ClientUserMessageId, MentionUri};
 use action_log::ActionLog;
 use agent_settings::UserAgentsMd;
 
+use crate::hooks::{self, HookEvent, HookPayload};
 use crate::sandboxing::{
     SandboxRequest, ThreadSandbox, ThreadSandboxGrants, sandbox_git_dirs,
     sandbox_worktree_writable_paths, sandboxing_available_for_project,
@@ -66,7 +68,9 @@ use std::{
     sync::Arc,
     time::{Duration, Instant},
 };
-use util::{ResultExt, debug_panic, markdown::MarkdownCodeBlock, paths::PathStyle};
+use util::{
+    ResultExt, debug_panic, markdown::MarkdownCodeBlock, path_list::PathList, paths::PathStyle,
+};
 use uuid::Uuid;
 
 const TOOL_CANCELED_MESSAGE: &str = "Tool canceled by user";
@@ -75,6 +79,8 @@ const TOOL_CALL_INTERRUPTED_BY_FOLLOW_UP_MESSAGE: &str =
 pub(crate) const FOLLOW_UP_PERMISSION_DENIED_OPTION_ID: &str = "follow_up_permission_denied";
 pub const MAX_TOOL_NAME_LENGTH: usize = 64;
 pub const MAX_SUBAGENT_DEPTH: u8 = 1;
+/// How many times `turn_end` hooks may keep a single turn going.
+const MAX_HOOK_CONTINUATIONS: usize = 8;
 
 pub(crate) fn provider_compatible_tool_name(tool_name: &str) -> String {
     let mut sanitized = String::new();
@@ -326,6 +332,7 @@ impl UserMessage {
         const OPEN_DIAGNOSTICS_TAG: &str = "<diagnostics>";
         const OPEN_DIFFS_TAG: &str = "<diffs>";
         const MERGE_CONFLICT_TAG: &str = "<merge_conflicts>";
+        const OPEN_MCP_RESOURCES_TAG: &str = "<mcp_resources>";
         const OPEN_SKILLS_TAG: &str =
             "<skills>\nThe user has attached the following agent skills:\n";
 
@@ -340,6 +347,7 @@ impl UserMessage {
         let mut diffs_context = OPEN_DIFFS_TAG.to_string();
         let mut merge_conflict_context = MERGE_CONFLICT_TAG.to_string();
         let mut skills_context = OPEN_SKILLS_TAG.to_string();
+        let mut mcp_resources_context = OPEN_MCP_RESOURCES_TAG.to_string();
 
         for chunk in &*self.content {
             let chunk = match chunk {
@@ -461,6 +469,14 @@ impl UserMessage {
                             let label = format!("{} ({})", name, source);
                             write!(&mut skills_context, "\nSkill: {}\n{}\n", label, content).ok();
                         }
+                        MentionUri::McpResource { server_id, uri, .. } => {
+                            write!(
+                                &mut mcp_resources_context,
+                                "\nResource {} from {}:\n{}\n",
+                                uri, server_id, content
+                            )
+                            .ok();
+                        }
                     }
 
                     language_model::MessageContent::Text(uri.as_link().to_string())
@@ -549,6 +565,13 @@ impl UserMessage {
                 .push(language_model::MessageContent::Text(merge_conflict_context));
         }
 
+        if mcp_resources_context.len() > OPEN_MCP_RESOURCES_TAG.len() {
+            mcp_resources_context.push_str("</mcp_resources>\n");
+            message
+                .content
+                .push(language_model::MessageContent::Text(mcp_resources_context));
+        }
+
         if message.content.len() > len_before_context {
             message.content.insert(
                 len_before_context,
@@ -880,6 +903,7 @@ pub enum ThreadEvent {
         tool_call_id: acp::ToolCallId,
         outcome: acp_thread::SelectedPermissionOutcome,
     },
+    ToolCallElicitation(ToolCallElicitation),
     SubagentSpawned(acp::SessionId),
     Retry(acp_thread::RetryStatus),
     ContextCompaction(acp_thread::ContextCompaction),
@@ -938,7 +962,7 @@ impl ToolPermissionContext {
     ///
     /// # Shell Compatibility for Terminal Tool
     ///
-    /// For the terminal tool, "Always allow" options are only shown when the user's
+    /// For the terminal and `run_task` tools, "Always allow" options are only shown when the user's
     /// shell supports POSIX-like command chaining syntax (`&&`, `||`, `;`, `|`).
     ///
     /// **Why this matters:** When a user sets up an "always allow" pattern like `^cargo`,
@@ -994,7 +1018,7 @@ impl ToolPermissionContext {
 
         // Check if the user's shell supports POSIX-like command chaining.
         // See the doc comment above for the full explanation of why this is needed.
-        let shell_supports_always_allow = if tool_name == TerminalTool::NAME {
+        let shell_supports_always_allow = if runs_shell_commands(&tool_name) {
             ShellKind::system().supports_posix_chaining()
         } else {
             true
@@ -1043,7 +1067,7 @@ impl ToolPermissionContext {
         }
 
         let extract_for_value = |value: &str| -> (Option<String>, Option<String>) {
-            if tool_name == TerminalTool::NAME {
+            if runs_shell_commands(&tool_name) {
                 (
                     extract_terminal_pattern(value),
                     extract_terminal_pattern_display(value),
@@ -1156,6 +1180,15 @@ pub struct ToolCallAuthorization {
     pub kind: acp_thread::AuthorizationKind,
 }
 
+/// A form a tool needs the user to fill in before it can continue.
+#[derive(Debug)]
+pub struct ToolCallElicitation {
+    pub tool_call_id: acp::ToolCallId,
+    pub message: String,
+    pub schema: acp::ElicitationSchema,
+    pub response: oneshot::Sender<acp::CreateElicitationResponse>,
+}
+
 fn ensure_tool_call_authorization_not_interrupted(
     outcome: &acp_thread::SelectedPermissionOutcome,
 ) -> Result<()> {
@@ -1253,6 +1286,9 @@ pub struct Thread {
     /// `cumulative_token_usage` for the in-flight completion request. Reset at
     /// the start of each request.
     current_request_token_usage: TokenUsage,
+    /// How spending compares to the configured budgets, as of the last
+    /// request.
+    budget_status: Option<BudgetStatus>,
     pending_compaction_telemetry: Option<CompactionTelemetry>,
     #[allow(unused)]
     initial_project_snapshot: Shared<Task<Option<Arc<ProjectSnapshot>>>>,
@@ -1286,6 +1322,9 @@ pub struct Thread {
     /// already-granted permissions skip the approval prompt.
     /// Never persisted — lives and dies with this thread.
     sandbox_grants: Rc<RefCell<ThreadSandboxGrants>>,
+    /// Whether the `session_start` hooks have run for this thread since it was
+    /// created or loaded.
+    session_start_hooks_ran: bool,
 }
 
 impl Thread {
@@ -1394,6 +1433,7 @@ impl Thread {
             request_token_usage: HashMap::default(),
             cumulative_token_usage: TokenUsage::default(),
             current_request_token_usage: TokenUsage::default(),
+            budget_status: None,
             pending_compaction_telemetry: None,
             initial_project_snapshot: {
                 let project_snapshot = Self::project_snapshot(project.clone(), cx);
@@ -1422,6 +1462,7 @@ impl Thread {
             inherits_parent_model_settings: true,
             sandboxed_terminal_temp_dir: None,
             sandbox_grants: Rc::new(RefCell::new(ThreadSandboxGrants::default())),
+            session_start_hooks_ran: false,
         }
     }
 
@@ -1777,6 +1818,7 @@ impl Thread {
             request_token_usage: db_thread.request_token_usage.clone(),
             cumulative_token_usage: db_thread.cumulative_token_usage,
             current_request_token_usage: TokenUsage::default(),
+            budget_status: None,
             pending_compaction_telemetry: None,
             initial_project_snapshot: Task::ready(db_thread.initial_project_snapshot).shared(),
             context_server_registry,
@@ -1806,6 +1848,7 @@ impl Thread {
             sandbox_grants: Rc::new(RefCell::new(ThreadSandboxGrants::from_db(
                 &db_thread.sandbox_grants,
             ))),
+            session_start_hooks_ran: false,
         }
     }
 
@@ -1912,6 +1955,57 @@ impl Thread {
         })
     }
 
+    /// Serializes a fork of this thread: the history before the given user
+    /// message, with that message as the draft prompt so it can be edited
+    /// and resent. Per-thread state such as sandbox grants isn't carried over.
+    pub fn fork_to_db(
+        &self,
+        client_user_message_id: &ClientUserMessageId,
+        cx: &App,
+    ) -> Result<Task<DbThread>> {
+        let Some(position) = self.messages.iter().position(|msg| {
+            matches!(&**msg, Message::User(UserMessage { id, .. }) if id == client_user_message_id)
+        }) else {
+            return Err(anyhow!("Message not found"));
+        };
+        let Message::User(message) = &*self.messages[position] else {
+            unreachable!("position only matches user messages");
+        };
+        let draft_prompt = message
+            .content
+            .iter()
+            .cloned()
+            .map(acp::ContentBlock::from)
+            .collect::<Vec<_>>();
+        let messages = self.messages[..position].to_vec();
+        let kept_message_ids = messages
+            .iter()
+            .filter_map(|message| match &**message {
+                Message::User(message) => Some(message.id.clone()),
+                Message::Agent(_) | Message::Resume | Message::Compaction(_) => None,
+            })
+            .collect::<HashSet<_>>();
+
+        let db_thread = self.to_db(cx);
+        Ok(cx.background_spawn(async move {
+            let mut thread = db_thread.await;
+            if !thread.title.is_empty() {
+                thread.title = format!("{} (fork)", thread.title).into();
+            }
+            thread.messages = messages;
+            thread.updated_at = Utc::now();
+            thread.detailed_summary = None;
+            thread
+                .request_token_usage
+                .retain(|id, _| kept_message_ids.contains(id));
+            thread.draft_prompt = Some(draft_prompt);
+            thread.ui_scroll_position = None;
+            thread.sandboxed_terminal_temp_dir = None;
+            thread.sandbox_grants = Default::default();
+            thread
+        }))
+    }
+
     /// Create a snapshot of the current project state including git information and unsaved buffers.
     fn project_snapshot(
         project: Entity<Project>,
@@ -2135,6 +2229,12 @@ impl Thread {
         self.add_tool(FetchTool::new(self.project.read(cx).client().http_client()));
         self.add_tool(FindPathTool::new(self.project.clone()));
         self.add_tool(GrepTool::new(self.project.clone()));
+        // The semantic index only covers local projects. Whether the tool is
+        // offered also depends on the index being enabled in the settings,
+        // which `enabled_tools` checks on every request.
+        if self.project.read(cx).is_local() {
+            self.add_tool(SemanticSearchTool::new(self.project.clone()));
+        }
         self.add_tool(ListDirectoryTool::new(self.project.clone()));
         self.add_tool(MovePathTool::new(self.project.clone()));
         self.add_tool(ReadFileTool::new(
@@ -2149,6 +2249,7 @@ impl Thread {
             self.project.clone(),
             environment.clone(),
         ));
+        self.add_tool(RunTaskTool::new(self.project.clone(), environment.clone()));
         self.add_tool(WebSearchTool);
 
         self.add_tool(DiagnosticsTool::new(self.project.clone()));
@@ -2390,6 +2491,10 @@ impl Thread {
         Some(*tokens)
     }
 
+    pub fn budget_status(&self) -> Option<&BudgetStatus> {
+        self.budget_status.as_ref()
+    }
+
     pub fn cumulative_token_usage(&self) -> language_model::TokenUsage {
         self.cumulative_token_usage
     }
@@ -2726,6 +2831,8 @@ impl Thread {
         let mut intent = CompletionIntent::UserPrompt;
         // Set when a refusal fallback occurs so subsequent iterations use the fallback model.
         let mut refusal_fallback_model: Option<Arc<dyn LanguageModel>> = None;
+        let mut hook_continuations = 0;
+        Self::run_session_start_hooks(this, event_stream, cx).await?;
         loop {
             match Self::perform_compaction_if_needed(
                 this,
@@ -2802,6 +2909,8 @@ impl Thread {
                 }
             }
 
+            Self::enforce_budgets(this, cx).await?;
+
             // Re-read the model and refresh tools on each iteration so that
             // mid-turn changes (e.g. the user switches model, toggles tools,
             // or changes profile) take effect between tool-call rounds.
@@ -2954,6 +3063,7 @@ impl Thread {
                     running_turn.streaming_tool_inputs.drain();
                 }
             })?;
+            Self::record_token_usage(this, &model, cx).await;
 
             if had_refusal {
                 let maybe_fallback = this.update(cx, |this, cx| -> Option<Arc<dyn LanguageModel>> {
@@ -3053,6 +3163,14 @@ impl Thread {
                     }
                 })?;
             } else if end_turn {
+                if hook_continuations < MAX_HOOK_CONTINUATIONS
+                    && Self::run_turn_end_hooks(this, event_stream, hook_continuations, cx).await?
+                {
+                    hook_continuations += 1;
+                    intent = CompletionIntent::UserPrompt;
+                    attempt = 0;
+                    continue;
+                }
                 return Ok(());
             } else {
                 let end_at_boundary =
@@ -3067,6 +3185,76 @@ impl Thread {
         }
     }
 
+    /// Runs the `session_start` hooks the first time this thread runs a turn,
+    /// adding anything they print to the thread as context for the model.
+    async fn run_session_start_hooks(
+        this: &WeakEntity<Self>,
+        event_stream: &ThreadEventStream,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let hooks = this.update(cx, |this, cx| {
+            if std::mem::replace(&mut this.session_start_hooks_ran, true) {
+                return None;
+            }
+            hooks::run_hooks(
+                HookPayload::new(HookEvent::SessionStart, &this.id),
+                this.project.read(cx),
+                cx,
+            )
+        })?;
+        let Some(hooks) = hooks else {
+            return Ok(());
+        };
+        let feedback = hooks.await.into_feedback();
+        if let Some(text) = hooks::format_hook_feedback(&feedback) {
+            this.update(cx, |this, cx| {
+                this.push_hook_message(text, event_stream, cx)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Runs the `turn_end` hooks, returning whether one of them asked for the
+    /// turn to keep going. Its reason is sent to the model as a user message.
+    async fn run_turn_end_hooks(
+        this: &WeakEntity<Self>,
+        event_stream: &ThreadEventStream,
+        continuations: usize,
+        cx: &mut AsyncApp,
+    ) -> Result<bool> {
+        let hooks = this.update(cx, |this, cx| {
+            let mut payload = HookPayload::new(HookEvent::TurnEnd, &this.id);
+            payload.continuations = Some(continuations);
+            hooks::run_hooks(payload, this.project.read(cx), cx)
+        })?;
+        let Some(hooks) = hooks else {
+            return Ok(false);
+        };
+        let Some(reason) = hooks.await.block_reason else {
+            return Ok(false);
+        };
+        let text = hooks::format_hook_feedback(&[reason]).unwrap_or_default();
+        this.update(cx, |this, cx| {
+            this.push_hook_message(text, event_stream, cx)
+        })?;
+        Ok(true)
+    }
+
+    fn push_hook_message(
+        &mut self,
+        text: String,
+        event_stream: &ThreadEventStream,
+        cx: &mut Context<Self>,
+    ) {
+        let message = UserMessage {
+            id: ClientUserMessageId::new(),
+            content: Arc::from([UserMessageContent::Text(text)]),
+        };
+        event_stream.send_user_message(&message);
+        self.messages.push(Arc::new(Message::User(message)));
+        cx.notify();
+    }
+
     /// Computes the retry status for a failed completion, notifies listeners,
     /// and waits out the backoff delay (or returns early if the turn is
     /// cancelled while waiting). Returns an error if the completion is not
@@ -3097,6 +3285,88 @@ impl Thread {
         Ok(ControlFlow::Continue(()))
     }
 
+    /// Fails with a [`BudgetExceededError`] if a configured budget has been
+    /// reached, so that no further request is made.
+    async fn enforce_budgets(this: &WeakEntity<Self>, cx: &mut AsyncApp) -> Result<()> {
+        match Self::refresh_budget_status(this, cx).await? {
+            Some(BudgetStatus::Exceeded(message)) => Err(anyhow!(BudgetExceededError(message))),
+            _ => Ok(()),
+        }
+    }
+
+    async fn refresh_budget_status(
+        this: &WeakEntity<Self>,
+        cx: &mut AsyncApp,
+    ) -> Result<Option<BudgetStatus>> {
+        let (budgets, profile_id, id) = this.read_with(cx, |this, cx| {
+            let budgets = AgentSettings::get_global(cx).budgets.clone();
+            (budgets, this.profile_id.clone(), this.id.clone())
+        })?;
+        let status = if budgets.is_unlimited(&profile_id) {
+            None
+        } else {
+            let database = cx
+                .update(ThreadsDatabase::connect)
+                .await
+                .map_err(|err| anyhow!(err))?;
+            let spend = database.spend(id, profile_id.clone()).await?;
+            check_budgets(&budgets, &profile_id, spend)
+        };
+        this.update(cx, |this, cx| {
+            if this.budget_status != status {
+                this.budget_status = status.clone();
+                cx.notify();
+            }
+        })?;
+        Ok(status)
+    }
+
+    /// Stores the usage of the request that just finished, for budgets and the
+    /// usage report.
+    async fn record_token_usage(
+        this: &WeakEntity<Self>,
+        model: &Arc<dyn LanguageModel>,
+        cx: &mut AsyncApp,
+    ) {
+        let Ok((id, profile_id, folder_paths, usage)) = this.read_with(cx, |this, cx| {
+            let folder_paths = PathList::new(
+                &this
+                    .project
+                    .read(cx)
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+                    .collect::<Vec<_>>(),
+            );
+            (
+                this.id.clone(),
+                this.profile_id.clone(),
+                folder_paths,
+                this.current_request_token_usage,
+            )
+        }) else {
+            return;
+        };
+        if usage.total_tokens() == 0 {
+            return;
+        }
+        let cost = request_cost(model.model_cost_info(), &usage);
+        let model_name = format!("{}/{}", model.provider_id(), model.id().0);
+        let result = async {
+            let database = cx
+                .update(ThreadsDatabase::connect)
+                .await
+                .map_err(|err| anyhow!(err))?;
+            database
+                .record_token_usage(id, profile_id, model_name, folder_paths, usage, cost)
+                .await?;
+            Self::refresh_budget_status(this, cx).await
+        }
+        .await;
+        if let Err(error) = result {
+            log::error!("Failed to record agent token usage: {error:#}");
+        }
+    }
+
     async fn perform_compaction_if_needed(
         this: &WeakEntity<Self>,
         event_stream: &ThreadEventStream,
@@ -3507,8 +3777,13 @@ impl Thread {
             }
         };
 
+        // `before_tool_call` hooks need the complete input before the tool
+        // starts, so tools they apply to don't stream their input.
+        let run_before_hooks =
+            hooks::has_hooks(HookEvent::BeforeToolCall, Some(tool_use.name.as_ref()), cx);
+
         if !tool_use.is_input_complete {
-            if tool.supports_input_streaming() {
+            if tool.supports_input_streaming() && !run_before_hooks {
                 let running_turn = self.running_turn.as_mut()?;
                 if let Some(sender) = running_turn.streaming_tool_inputs.get_mut(&tool_use.id) {
                     sender.send_partial(input);
@@ -3549,6 +3824,18 @@ impl Thread {
         }
 
         log::debug!("Running tool {}", tool_use.name);
+        if run_before_hooks {
+            return Some(self.run_tool_with_before_hooks(
+                tool,
+                input,
+                tool_use.id,
+                tool_use.name,
+                owning_message_ix,
+                event_stream,
+                cancellation_rx,
+                cx,
+            ));
+        }
         let tool_input = ToolInput::ready(input);
         Some(self.run_tool(
             tool,
@@ -3562,6 +3849,83 @@ impl Thread {
         ))
     }
 
+    /// Runs the `before_tool_call` hooks for a tool call, then the tool itself
+    /// unless a hook blocked it. Anything the hooks printed is appended to the
+    /// tool's result.
+    fn run_tool_with_before_hooks(
+        &self,
+        tool: Arc<dyn AnyAgentTool>,
+        input: serde_json::Value,
+        tool_use_id: LanguageModelToolUseId,
+        tool_name: Arc<str>,
+        owning_message_ix: usize,
+        event_stream: &ThreadEventStream,
+        cancellation_rx: watch::Receiver<bool>,
+        cx: &mut Context<Self>,
+    ) -> Task<(usize, LanguageModelToolResult)> {
+        let hooks = hooks::run_hooks(
+            HookPayload::new(HookEvent::BeforeToolCall, &self.id)
+                .with_tool(&tool_name, input.clone()),
+            self.project.read(cx),
+            cx,
+        );
+        let event_stream = event_stream.clone();
+        cx.spawn(async move |this, cx| {
+            let mut feedback = Vec::new();
+            if let Some(hooks) = hooks {
+                let outcome = hooks.await;
+                if let Some(reason) = outcome.block_reason {
+                    let content = format!("Tool call blocked by hook: {reason}");
+                    return (
+                        owning_message_ix,
+                        LanguageModelToolResult {
+                            tool_use_id,
+                            tool_name,
+                            is_error: true,
+                            content: vec![LanguageModelToolResultContent::Text(Arc::from(content))],
+                            output: None,
+                        },
+                    );
+                }
+                feedback = outcome.feedback;
+            }
+
+            let task = this.update(cx, |this, cx| {
+                this.run_tool(
+                    tool,
+                    ToolInput::ready(input),
+                    tool_use_id.clone(),
+                    tool_name.clone(),
+                    owning_message_ix,
+                    &event_stream,
+                    cancellation_rx,
+                    cx,
+                )
+            });
+            let Ok(task) = task else {
+                return (
+                    owning_message_ix,
+                    LanguageModelToolResult {
+                        tool_use_id,
+                        tool_name,
+                        is_error: true,
+                        content: vec![LanguageModelToolResultContent::Text(Arc::from(
+                            "thread was dropped",
+                        ))],
+                        output: None,
+                    },
+                );
+            };
+            let (owning_message_ix, mut result) = task.await;
+            if let Some(text) = hooks::format_hook_feedback(&feedback) {
+                result
+                    .content
+                    .push(LanguageModelToolResultContent::Text(Arc::from(text)));
+            }
+            (owning_message_ix, result)
+        })
+    }
+
     fn run_tool(
         &self,
         tool: Arc<dyn AnyAgentTool>,
@@ -3611,8 +3975,9 @@ impl Thread {
             acp::ToolCallUpdateFields::new().status(acp::ToolCallStatus::InProgress),
         );
         let supports_images = self.model().is_some_and(|model| model.supports_images());
+        let is_edit = tool.kind() == acp::ToolKind::Edit;
         let tool_result = tool.run(tool_input, tool_event_stream, cx);
-        cx.foreground_executor().spawn(async move {
+        cx.spawn(async move |this, cx| {
             let (is_error, output) = match tool_result.await {
                 Ok(mut output) => {
                     let contains_image = output
@@ -3656,19 +4021,57 @@ impl Thread {
                 Err(output) => (true, output),
             };
 
+            let mut content = output.llm_output;
+            if is_edit && !is_error {
+                let hooks = this.update(cx, |this, cx| {
+                    let input = this.pending_tool_use_input(&tool_use_id)?;
+                    hooks::run_hooks(
+                        HookPayload::new(HookEvent::AfterFileEdit, &this.id)
+                            .with_tool(&tool_name, input),
+                        this.project.read(cx),
+                        cx,
+                    )
+                });
+                if let Ok(Some(hooks)) = hooks
+                    && let Some(text) = hooks::format_hook_feedback(&hooks.await.into_feedback())
+                {
+                    content.push(LanguageModelToolResultContent::Text(Arc::from(text)));
+                }
+            }
+
             (
                 owning_message_ix,
                 LanguageModelToolResult {
                     tool_use_id,
                     tool_name,
                     is_error,
-                    content: output.llm_output,
+                    content,
                     output: Some(output.raw_output),
                 },
             )
         })
     }
 
+    /// The complete input of a tool call in the message currently being
+    /// streamed, if it has been received.
+    fn pending_tool_use_input(
+        &self,
+        tool_use_id: &LanguageModelToolUseId,
+    ) -> Option<serde_json::Value> {
+        self.pending_message
+            .as_ref()?
+            .content
+            .iter()
+            .find_map(|content| match content {
+                AgentMessageContent::ToolUse(tool_use)
+                    if &tool_use.id == tool_use_id && tool_use.is_input_complete =>
+                {
+                    tool_use.input.clone().into_json().ok()
+                }
+                _ => None,
+            })
+    }
+
     fn handle_tool_use_json_parse_error_event(
         &mut self,
         tool_use_id: LanguageModelToolUseId,
@@ -5620,6 +6023,31 @@ impl ToolCallEventStream {
             .update_tool_call_fields(&self.tool_call_id, fields, meta);
     }
 
+    /// Shows a form for this tool call in the thread and waits for the user to
+    /// accept, decline, or dismiss it.
+    pub fn elicit(
+        &self,
+        message: impl Into<String>,
+        schema: acp::ElicitationSchema,
+    ) -> impl Future<Output = Result<acp::CreateElicitationResponse>> + use<> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let sent = self
+            .stream
+            .0
+            .unbounded_send(Ok(ThreadEvent::ToolCallElicitation(ToolCallElicitation {
+                tool_call_id: self.tool_call_id.clone(),
+                message: message.into(),
+                schema,
+                response: response_tx,
+            })));
+        async move {
+            sent.map_err(|error| anyhow!("Failed to send tool call elicitation: {error}"))?;
+            response_rx
+                .await
+                .map_err(|_| anyhow!("elicitation channel closed"))
+        }
+    }
+
     pub fn resolve_authorization(&self, outcome: acp_thread::SelectedPermissionOutcome) {
         self.stream
             .resolve_tool_call_authorization(&self.tool_call_id, outcome);
@@ -6613,6 +7041,15 @@ impl ToolCallEventStreamReceiver {
         }
     }
 
+    pub async fn expect_elicitation(&mut self) -> ToolCallElicitation {
+        let event = self.0.next().await;
+        if let Some(Ok(ThreadEvent::ToolCallElicitation(elicitation))) = event {
+            elicitation
+        } else {
+            panic!("Expected ToolCallElicitation but got: {:?}", event);
+        }
+    }
+
     pub async fn expect_update_fields(&mut self) -> acp::ToolCallUpdateFields {
         let event = self.0.next().await;
         if let Some(Ok(ThreadEvent::ToolCallUpdate(acp_thread::ToolCallUpdate::UpdateFields(
@@ -1,4 +1,4 @@
-use crate::{DbThread, DbThreadMetadata, ThreadsDatabase};
+use crate::{DbThread, DbThreadMetadata, DbThreadSearchMatch, DbUsageReportRow, ThreadsDatabase};
 use agent_client_protocol::schema::v1 as acp;
 use anyhow::{Result, anyhow};
 use futures::{FutureExt, future::Shared};
@@ -59,6 +59,29 @@ impl ThreadStore {
         })
     }
 
+    /// Searches the text, tool calls and file paths of every saved thread.
+    pub fn search_threads(
+        &mut self,
+        query: String,
+        limit: usize,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DbThreadSearchMatch>>> {
+        let database_future = ThreadsDatabase::connect(cx);
+        cx.background_spawn(async move {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database.search_threads(query, limit).await
+        })
+    }
+
+    /// Spend from every recorded request, grouped by model and project.
+    pub fn usage_report(&mut self, cx: &mut Context<Self>) -> Task<Result<Vec<DbUsageReportRow>>> {
+        let database_future = ThreadsDatabase::connect(cx);
+        cx.background_spawn(async move {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database.usage_report().await
+        })
+    }
+
     pub fn save_thread(
         &mut self,
         id: acp::SessionId,
@@ -1,5 +1,5 @@
 use crate::AgentTool;
-use crate::tools::TerminalTool;
+use crate::tools::{RunTaskTool, TerminalTool};
 use agent_settings::{AgentSettings, CompiledRegex, ToolPermissions, ToolRules};
 use settings::ToolPermissionMode;
 use shell_command_parser::{
@@ -65,6 +65,12 @@ pub static HARDCODED_SECURITY_RULES: LazyLock<HardcodedSecurityRules> = LazyLock
     }
 });
 
+/// Whether the tool's inputs are command lines run by the user's shell, such
+/// as the terminal tool's commands and the commands of the tasks `run_task` runs.
+pub(crate) fn runs_shell_commands(tool_name: &str) -> bool {
+    tool_name == TerminalTool::NAME || tool_name == RunTaskTool::NAME
+}
+
 /// Checks if input matches any hardcoded security rules that cannot be bypassed.
 /// Returns a Deny decision if blocked, None otherwise.
 fn check_hardcoded_security_rules(
@@ -72,8 +78,8 @@ fn check_hardcoded_security_rules(
     inputs: &[String],
     shell_kind: ShellKind,
 ) -> Option<ToolPermissionDecision> {
-    // Currently only terminal tool has hardcoded rules
-    if tool_name != TerminalTool::NAME {
+    // Currently only tools that run shell commands have hardcoded rules
+    if !runs_shell_commands(tool_name) {
         return None;
     }
 
@@ -229,9 +235,10 @@ impl ToolPermissionDecision {
     /// 6. **Global `default`** - Falls back to `tool_permissions.default` when no
     ///    tool-specific default is set, or when the tool has no entry at all.
     ///
-    /// # Shell Compatibility (Terminal Tool Only)
+    /// # Shell Compatibility (Terminal and Run Task Tools Only)
     ///
-    /// For the terminal tool, commands are parsed to extract sub-commands for security.
+    /// For the terminal and `run_task` tools, commands are parsed to extract sub-commands
+    /// for security.
     /// All currently supported `ShellKind` variants are treated as compatible because
     /// brush-parser can handle their command chaining syntax. If a new `ShellKind`
     /// variant is added that brush-parser cannot safely parse, it should be excluded
@@ -299,13 +306,13 @@ impl ToolPermissionDecision {
             }
         };
 
-        // For the terminal tool, parse each input command to extract all sub-commands.
+        // For tools that run shell commands, parse each input command to extract all sub-commands.
         // This prevents shell injection attacks where a user configures an allow
         // pattern like "^ls" and an attacker crafts "ls && rm -rf /".
         //
         // If parsing fails or the shell syntax is unsupported, always_allow is
         // disabled for this command (we set allow_enabled to false to signal this).
-        if tool_name == TerminalTool::NAME {
+        if runs_shell_commands(tool_name) {
             // Our shell parser (brush-parser) only supports POSIX-like shell syntax.
             // See the doc comment above for the list of compatible/incompatible shells.
             if !shell_kind.supports_posix_chaining() {
@@ -605,6 +612,8 @@ mod tests {
             message_editor_min_lines: 1,
             tool_permissions,
             sandbox_permissions: Default::default(),
+            hooks: Default::default(),
+            budgets: Default::default(),
             show_turn_stats: false,
             show_merge_conflict_indicator: true,
             sidebar_side: Default::default(),
@@ -20,6 +20,8 @@ mod list_directory_tool;
 mod move_path_tool;
 mod read_file_tool;
 mod rename_tool;
+mod run_task_tool;
+mod semantic_search_tool;
 mod skill_tool;
 mod spawn_agent_tool;
 mod symbol_locator;
@@ -34,10 +36,12 @@ use feature_flags::{
 };
 use gpui::App;
 use language_model::{LanguageModelRequestTool, LanguageModelToolSchemaFormat};
+use semantic_index::SemanticIndexSettings;
 use serde::{
     Deserialize, Deserializer,
     de::{DeserializeOwned, Error as _},
 };
+use settings::Settings as _;
 
 /// Deserialize a value that may have been provided as a JSON-encoded string
 /// instead of the structured value. Some models occasionally stringify nested
@@ -88,6 +92,8 @@ pub use list_directory_tool::*;
 pub use move_path_tool::*;
 pub use read_file_tool::*;
 pub use rename_tool::*;
+pub use run_task_tool::*;
+pub use semantic_search_tool::*;
 pub use skill_tool::*;
 pub use spawn_agent_tool::*;
 pub use symbol_locator::*;
@@ -211,6 +217,8 @@ tools! {
     MovePathTool,
     ReadFileTool,
     RenameTool,
+    RunTaskTool,
+    SemanticSearchTool,
     SkillTool,
     SpawnAgentTool,
     TerminalTool,
@@ -218,8 +226,8 @@ tools! {
     WriteFileTool,
 }
 
-/// Some built-in tools are gated behind a feature flag and only become usable
-/// once that flag is active. Tools without a flag are always available.
+/// Some built-in tools are gated behind a feature flag or a setting and only
+/// become usable once it is enabled. Other tools are always available.
 ///
 /// This is the single source of truth for that gating: `Thread::enabled_tools`
 /// uses it to decide what the model receives, and the agent-profile
@@ -235,6 +243,7 @@ pub fn tool_feature_flag_enabled(tool_name: &str, cx: &App) -> bool {
         CreateThreadTool::NAME | ListAgentsAndModelsTool::NAME => {
             cx.has_flag::<CreateThreadToolFeatureFlag>()
         }
+        SemanticSearchTool::NAME => SemanticIndexSettings::get_global(cx).enabled,
         _ => true,
     }
 }
@@ -244,13 +253,16 @@ mod tests {
     use super::*;
 
     #[test]
-    fn fetch_and_terminal_are_forbidden_in_restricted_mode() {
+    fn fetch_and_command_tools_are_forbidden_in_restricted_mode() {
         assert!(!tool_allowed_in_restricted_mode(FetchTool::NAME));
         assert!(!tool_allowed_in_restricted_mode(TerminalTool::NAME));
+        assert!(!tool_allowed_in_restricted_mode(RunTaskTool::NAME));
 
         // Every other built-in tool, and unknown (e.g. MCP) tools, are allowed.
         for name in ALL_TOOL_NAMES {
-            let expected = *name != FetchTool::NAME && *name != TerminalTool::NAME;
+            let expected = *name != FetchTool::NAME
+                && *name != TerminalTool::NAME
+                && *name != RunTaskTool::NAME;
             assert_eq!(
                 tool_allowed_in_restricted_mode(name),
                 expected,
@@ -55,6 +55,7 @@ impl ContextServerRegistry {
             _subscription: cx.subscribe(&server_store, Self::handle_context_server_store_event),
         };
         for server in server_store.read(cx).running_servers() {
+            this.register_request_handlers(&server.id(), cx);
             this.reload_tools_for_server(server.id(), cx);
             this.reload_prompts_for_server(server.id(), cx);
         }
@@ -167,6 +168,34 @@ impl ContextServerRegistry {
         }
     }
 
+    fn register_request_handlers(&self, server_id: &ContextServerId, cx: &App) {
+        let Some(client) = self
+            .server_store
+            .read(cx)
+            .get_running_server(server_id)
+            .and_then(|server| server.client())
+        else {
+            return;
+        };
+
+        client.on_request::<context_server::types::requests::CreateMessage>({
+            let server_id = server_id.clone();
+            move |request, cx: AsyncApp| {
+                let server_id = server_id.clone();
+                cx.spawn(async move |cx| crate::handle_create_message(server_id, request, cx).await)
+            }
+        });
+        client.on_request::<context_server::types::requests::CreateElicitation>({
+            let server_id = server_id.clone();
+            move |params, cx: AsyncApp| {
+                let server_id = server_id.clone();
+                cx.spawn(async move |cx| {
+                    crate::handle_create_elicitation(server_id, params, cx).await
+                })
+            }
+        });
+    }
+
     fn reload_tools_for_server(&mut self, server_id: ContextServerId, cx: &mut Context<Self>) {
         let Some(server) = self.server_store.read(cx).get_running_server(&server_id) else {
             return;
@@ -260,6 +289,7 @@ impl ContextServerRegistry {
         match status {
             ContextServerStatus::Starting | ContextServerStatus::Authenticating => {}
             ContextServerStatus::Running => {
+                self.register_request_handlers(server_id, cx);
                 self.reload_tools_for_server(server_id.clone(), cx);
                 self.reload_prompts_for_server(server_id.clone(), cx);
             }
@@ -344,6 +374,7 @@ impl AnyAgentTool for ContextServerTool {
         let Some(server) = self.store.read(cx).get_running_server(&self.server_id) else {
             return Task::ready(Err(anyhow::anyhow!("Context server not found").into()));
         };
+        let server_id = self.server_id.clone();
         let tool_name = self.tool.name.clone();
         let tool_id = mcp_tool_id(&self.server_id.0, &self.tool.name);
         let display_name = self.tool.name.clone();
@@ -365,6 +396,10 @@ impl AnyAgentTool for ContextServerTool {
                 return Err(anyhow::anyhow!("Context server not initialized").into());
             };
 
+            let _elicitation_target = cx.update(|cx| {
+                crate::register_elicitation_target(server_id, event_stream.clone(), cx)
+            });
+
             let arguments = if let serde_json::Value::Object(map) = input {
                 Some(map.into_iter().collect())
             } else {
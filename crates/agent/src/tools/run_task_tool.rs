@@ -0,0 +1,795 @@
+use agent_client_protocol::schema::v1 as acp;
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashSet;
+use futures::FutureExt as _;
+use gpui::{App, AsyncApp, Entity, SharedString, Task};
+use language::{Anchor, Buffer};
+use language_model::LanguageModelToolResultContent;
+use project::{Location, Project, TaskSourceKind};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Write as _,
+    rc::Rc,
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+use task::{
+    ResolvedTask, RunnableTag, ShellKind, TaskContext, TaskTemplate, TaskVariables, VariableName,
+};
+
+use crate::tools::terminal_tool::standing_sandbox_wrap;
+use crate::{AgentTool, ThreadEnvironment, ToolCallEventStream, ToolInput};
+
+/// How much of a task's output is returned, counted from its end, where test
+/// runners and compilers print their summaries.
+const TASK_OUTPUT_LIMIT: usize = 16 * 1024;
+const MAX_FAILURES: usize = 25;
+
+/// Lists or runs the tasks the user has defined for this project, such as builds, test suites and linters.
+///
+/// Tasks come from the project's `.zed/tasks.json`, the user's global tasks, and the languages in use. When a `path` is given, tasks for that file's language are included too, along with its runnables: individual tests, benchmarks and main functions, labelled with the symbol they run.
+///
+/// Call this tool without a `label` to list the available tasks, then call it again with the exact label of the one to run. Prefer running a task over composing an equivalent command for the `terminal` tool, since tasks are the commands the user has vetted for this project.
+///
+/// Running a task returns its exit code, the end of its output, and any failures (failing tests, compiler errors) recognized in that output.
+///
+/// <example>
+/// To list the tasks and runnables for a file:
+/// {
+///     "path": "project/src/main.rs"
+/// }
+///
+/// To run one of them:
+/// {
+///     "path": "project/src/main.rs",
+///     "label": "cargo test -p project tests::test_parse"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunTaskToolInput {
+    /// The exact label of the task to run, as listed by this tool. Omit it to list the available tasks.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// A file in the project whose language tasks and runnables should be included, and which task variables such as `$ZED_FILE` refer to.
+    ///
+    /// This path should never be absolute, and the first component of the path should always be a root directory in a project.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Optional maximum runtime (in milliseconds). If exceeded, the task is killed.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RunTaskToolOutput {
+    Tasks {
+        tasks: Vec<TaskSummary>,
+    },
+    Ran {
+        label: String,
+        command: String,
+        exit_code: Option<u32>,
+        timed_out: bool,
+        stopped_by_user: bool,
+        output: String,
+        output_truncated: bool,
+        failures: Vec<TaskFailure>,
+    },
+    Error {
+        error: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskSummary {
+    pub label: String,
+    pub source: String,
+    pub command: String,
+}
+
+/// A failure recognized in a task's output.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TaskFailure {
+    /// Where the failure was reported, as `path:line[:column]`, if known.
+    pub location: Option<String>,
+    pub message: String,
+}
+
+impl From<RunTaskToolOutput> for LanguageModelToolResultContent {
+    fn from(output: RunTaskToolOutput) -> Self {
+        match output {
+            RunTaskToolOutput::Tasks { tasks } => {
+                if tasks.is_empty() {
+                    return "No tasks are defined for this project.".into();
+                }
+                let mut text = String::from("Available tasks:\n");
+                for task in tasks {
+                    writeln!(
+                        text,
+                        "- `{}` ({}): `{}`",
+                        task.label, task.source, task.command
+                    )
+                    .ok();
+                }
+                text.into()
+            }
+            RunTaskToolOutput::Ran {
+                label,
+                command,
+                exit_code,
+                timed_out,
+                stopped_by_user,
+                output,
+                output_truncated,
+                failures,
+            } => {
+                let mut text = if stopped_by_user {
+                    format!(
+                        "The user stopped task `{label}`. Ask them what they would like to do next \
+                        rather than running it again."
+                    )
+                } else if timed_out {
+                    format!("Task `{label}` timed out.")
+                } else {
+                    match exit_code {
+                        Some(0) => format!("Task `{label}` succeeded."),
+                        Some(exit_code) => {
+                            format!("Task `{label}` failed with exit code {exit_code}.")
+                        }
+                        None => format!("Task `{label}` terminated unexpectedly."),
+                    }
+                };
+                write!(text, "\nCommand: `{command}`").ok();
+                if !failures.is_empty() {
+                    text.push_str("\n\nFailures:");
+                    for failure in &failures {
+                        match &failure.location {
+                            Some(location) => {
+                                write!(text, "\n- {location}: {}", failure.message).ok()
+                            }
+                            None => write!(text, "\n- {}", failure.message).ok(),
+                        };
+                    }
+                }
+                if output.is_empty() {
+                    text.push_str("\n\nNo output.");
+                } else {
+                    if output_truncated {
+                        write!(text, "\n\nThe last {} bytes of output:", output.len()).ok();
+                    } else {
+                        text.push_str("\n\nOutput:");
+                    }
+                    write!(text, "\n```\n{output}\n```").ok();
+                }
+                text.into()
+            }
+            RunTaskToolOutput::Error { error } => error.into(),
+        }
+    }
+}
+
+pub struct RunTaskTool {
+    project: Entity<Project>,
+    environment: Rc<dyn ThreadEnvironment>,
+}
+
+impl RunTaskTool {
+    pub fn new(project: Entity<Project>, environment: Rc<dyn ThreadEnvironment>) -> Self {
+        Self {
+            project,
+            environment,
+        }
+    }
+}
+
+impl AgentTool for RunTaskTool {
+    type Input = RunTaskToolInput;
+    type Output = RunTaskToolOutput;
+
+    const NAME: &'static str = "run_task";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Execute
+    }
+
+    fn allow_in_restricted_mode() -> bool {
+        false
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input.ok().and_then(|input| input.label) {
+            Some(label) => format!("Run task `{label}`").into(),
+            None => "List tasks".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|error| RunTaskToolOutput::Error {
+                    error: error.to_string(),
+                })?;
+            let tasks = available_tasks(&self.project, input.path.as_deref(), cx)
+                .await
+                .map_err(|error| RunTaskToolOutput::Error {
+                    error: format!("{error:#}"),
+                })?;
+
+            let Some(label) = input.label else {
+                return Ok(RunTaskToolOutput::Tasks {
+                    tasks: tasks
+                        .iter()
+                        .map(|(kind, task)| TaskSummary {
+                            label: task.resolved_label.clone(),
+                            source: source_description(kind),
+                            command: command_line(task),
+                        })
+                        .collect(),
+                });
+            };
+            let Some((_, task)) = tasks
+                .into_iter()
+                .find(|(_, task)| task.resolved_label == label)
+            else {
+                return Err(RunTaskToolOutput::Error {
+                    error: format!(
+                        "No task is labelled `{label}`. Call this tool without a label to list \
+                        the available tasks{}.",
+                        if input.path.is_some() {
+                            ""
+                        } else {
+                            ", or pass a `path` to include a file's runnables"
+                        }
+                    ),
+                });
+            };
+
+            run_task(
+                &self.project,
+                &self.environment,
+                &label,
+                task,
+                input.timeout_ms.map(Duration::from_millis),
+                &event_stream,
+                cx,
+            )
+            .await
+            .map_err(|error| RunTaskToolOutput::Error {
+                error: format!("{error:#}"),
+            })
+        })
+    }
+}
+
+async fn run_task(
+    project: &Entity<Project>,
+    environment: &Rc<dyn ThreadEnvironment>,
+    label: &str,
+    task: ResolvedTask,
+    timeout: Option<Duration>,
+    event_stream: &ToolCallEventStream,
+    cx: &mut AsyncApp,
+) -> Result<RunTaskToolOutput> {
+    let command = command_line(&task);
+    let authorize = cx.update(|cx| {
+        let context = crate::ToolPermissionContext::new(RunTaskTool::NAME, vec![command.clone()]);
+        event_stream.authorize(format!("Run task `{label}`: {command}"), context, cx)
+    });
+    authorize.await?;
+    // Tasks run under the same sandbox as the terminal tool's commands, with
+    // only the permissions the user has already granted.
+    let sandbox_wrap = standing_sandbox_wrap(project, event_stream, cx)
+        .await
+        .map_err(|error| anyhow!(error))?;
+
+    let env = task
+        .resolved
+        .env
+        .iter()
+        .map(|(name, value)| acp::EnvVariable::new(name, value))
+        .collect();
+    let terminal = environment
+        .create_terminal(
+            command.clone(),
+            env,
+            task.resolved.cwd.clone(),
+            None,
+            sandbox_wrap,
+            cx,
+        )
+        .await?;
+    event_stream.update_fields(acp::ToolCallUpdateFields::new().content(vec![
+        acp::ToolCallContent::Terminal(acp::Terminal::new(terminal.id(cx)?)),
+    ]));
+
+    let mut timed_out = false;
+    let mut stopped_by_user = false;
+    let wait_for_exit = terminal.wait_for_exit(cx)?;
+    let timer = match timeout {
+        Some(timeout) => cx.background_executor().timer(timeout).boxed_local(),
+        None => futures::future::pending().boxed_local(),
+    };
+    futures::select! {
+        _ = wait_for_exit.clone().fuse() => {}
+        _ = timer.fuse() => {
+            timed_out = true;
+            terminal.kill(cx)?;
+            wait_for_exit.await;
+        }
+        _ = event_stream.cancelled_by_user().fuse() => {
+            stopped_by_user = true;
+            terminal.kill(cx)?;
+            wait_for_exit.await;
+        }
+    }
+    let stopped_by_user = stopped_by_user
+        || event_stream.was_cancelled_by_user()
+        || terminal.was_stopped_by_user(cx).unwrap_or(false);
+
+    let output = terminal.current_output(cx)?;
+    let content = output.output.trim();
+    let failures = parse_failures(content);
+    let (content, output_truncated) = output_tail(content, TASK_OUTPUT_LIMIT);
+    Ok(RunTaskToolOutput::Ran {
+        label: label.to_string(),
+        command,
+        exit_code: output.exit_status.and_then(|status| status.exit_code),
+        timed_out,
+        stopped_by_user,
+        output: content.to_string(),
+        output_truncated: output_truncated || output.truncated,
+        failures,
+    })
+}
+
+/// Resolves every task that can run in the project, along with the tasks and
+/// runnables of the file at `path`. Tasks that need variables the context
+/// can't provide are left out, and later tasks with a label already taken
+/// are dropped.
+async fn available_tasks(
+    project: &Entity<Project>,
+    path: Option<&str>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<(TaskSourceKind, ResolvedTask)>> {
+    let buffer = match path {
+        Some(path) => {
+            let open_buffer = project.update(cx, |project, cx| {
+                let project_path = project
+                    .find_project_path(path, cx)
+                    .with_context(|| format!("Could not find path {path} in the project"))?;
+                anyhow::Ok(project.open_buffer(project_path, cx))
+            })?;
+            let buffer = open_buffer.await?;
+            let parsing_idle = buffer.read_with(cx, |buffer, _| buffer.parsing_idle());
+            parsing_idle.await;
+            Some(buffer)
+        }
+        None => None,
+    };
+
+    let (inventory, worktree) = project.read_with(cx, |project, cx| {
+        let worktree = match &buffer {
+            Some(buffer) => buffer
+                .read(cx)
+                .file()
+                .and_then(|file| project.worktree_for_id(file.worktree_id(cx), cx)),
+            None => project.visible_worktrees(cx).next(),
+        }
+        .map(|worktree| {
+            let worktree = worktree.read(cx);
+            (worktree.id(), worktree.abs_path())
+        });
+        (
+            project.task_store().read(cx).task_inventory().cloned(),
+            worktree,
+        )
+    });
+    let inventory = inventory.ok_or_else(|| anyhow!("Tasks are not available in this project"))?;
+    let worktree_id = worktree.as_ref().map(|(id, _)| *id);
+
+    let mut tasks = Vec::new();
+    let mut labels = HashSet::default();
+    let mut push_resolved = |templates: Vec<(TaskSourceKind, TaskTemplate)>,
+                             context: &TaskContext| {
+        for (kind, template) in templates {
+            if let Some(task) = template.resolve_task(&kind.to_id_base(), context)
+                && labels.insert(task.resolved_label.clone())
+            {
+                tasks.push((kind, task));
+            }
+        }
+    };
+
+    let templates = inventory
+        .read_with(cx, |inventory, cx| {
+            let language = buffer
+                .as_ref()
+                .and_then(|buffer| buffer.read(cx).language().cloned());
+            inventory.list_tasks(buffer.clone(), language, worktree_id, cx)
+        })
+        .await;
+
+    // Runnables come first, since they're the most specific to the file.
+    if let Some(buffer) = &buffer {
+        for (location, captured_variables, tags) in runnables(buffer, cx) {
+            let templates = templates_with_tags(&templates, &tags);
+            if templates.is_empty() {
+                continue;
+            }
+            let context = task_context(project, captured_variables, location, cx).await?;
+            push_resolved(templates, &context);
+        }
+    }
+
+    let context = match &buffer {
+        Some(buffer) => {
+            let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+            let location = Location {
+                buffer: buffer.clone(),
+                range: Anchor::min_min_range_for_buffer(buffer_id),
+            };
+            task_context(project, TaskVariables::default(), location, cx).await?
+        }
+        None => worktree_context(worktree.as_ref().map(|(_, path)| path.as_ref())),
+    };
+    push_resolved(templates, &context);
+
+    Ok(tasks)
+}
+
+/// The runnables in a buffer, with the location and captured variables their
+/// tasks are resolved with.
+fn runnables(
+    buffer: &Entity<Buffer>,
+    cx: &mut AsyncApp,
+) -> Vec<(Location, TaskVariables, Vec<RunnableTag>)> {
+    buffer.read_with(cx, |buffer_ref, _| {
+        let snapshot = buffer_ref.snapshot();
+        snapshot
+            .runnable_ranges(0..snapshot.len())
+            .map(|runnable| {
+                let range = runnable.run_range;
+                let location = Location {
+                    buffer: buffer.clone(),
+                    range: snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end),
+                };
+                let mut variables = TaskVariables::default();
+                for (name, value) in runnable.extra_captures {
+                    variables.insert(VariableName::Custom(name.into()), value);
+                }
+                (
+                    location,
+                    variables,
+                    runnable.runnable.tags.into_iter().collect(),
+                )
+            })
+            .collect()
+    })
+}
+
+/// The templates bound to any of a runnable's tags. As in the editor's gutter,
+/// only the strongest source's bindings are kept.
+fn templates_with_tags(
+    templates: &[(TaskSourceKind, TaskTemplate)],
+    tags: &[RunnableTag],
+) -> Vec<(TaskSourceKind, TaskTemplate)> {
+    let mut templates = templates
+        .iter()
+        .filter(|(_, template)| {
+            tags.iter()
+                .any(|RunnableTag(tag)| template.tags.iter().any(|source_tag| source_tag == tag))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    templates.sort_by_key(|(kind, _)| kind.clone());
+    if let Some((leading_source, _)) = templates.first() {
+        let leading_source = leading_source.clone();
+        templates.retain(|(kind, _)| kind == &leading_source);
+    }
+    templates
+}
+
+async fn task_context(
+    project: &Entity<Project>,
+    captured_variables: TaskVariables,
+    location: Location,
+    cx: &mut AsyncApp,
+) -> Result<TaskContext> {
+    let context = project.update(cx, |project, cx| {
+        project.task_store().update(cx, |task_store, cx| {
+            task_store.task_context_for_location(captured_variables, location, cx)
+        })
+    });
+    Ok(context.await?.unwrap_or_default())
+}
+
+fn worktree_context(worktree_abs_path: Option<&std::path::Path>) -> TaskContext {
+    let mut task_variables = TaskVariables::default();
+    if let Some(path) = worktree_abs_path {
+        task_variables.insert(
+            VariableName::WorktreeRoot,
+            path.to_string_lossy().into_owned(),
+        );
+    }
+    TaskContext {
+        cwd: worktree_abs_path.map(|path| path.to_path_buf()),
+        task_variables,
+        project_env: Default::default(),
+    }
+}
+
+fn source_description(kind: &TaskSourceKind) -> String {
+    match kind {
+        TaskSourceKind::UserInput => "user input".to_string(),
+        TaskSourceKind::Worktree {
+            directory_in_worktree,
+            ..
+        } => {
+            if directory_in_worktree.is_empty() {
+                "project tasks".to_string()
+            } else {
+                format!("project tasks in {}", directory_in_worktree.as_unix_str())
+            }
+        }
+        TaskSourceKind::AbsPath { .. } => "global tasks".to_string(),
+        TaskSourceKind::Language { name } => format!("{name} tasks"),
+        TaskSourceKind::Lsp { language_name, .. } => {
+            format!("{language_name} language server")
+        }
+    }
+}
+
+/// The shell one-liner a task runs, quoted the way the terminal panel quotes
+/// task arguments.
+fn command_line(task: &ResolvedTask) -> String {
+    let shell = ShellKind::system();
+    let command = task.resolved.command.clone().unwrap_or_default();
+    let command = if task.resolved.args.is_empty() {
+        command
+    } else {
+        shell
+            .try_quote_prefix_aware(&command)
+            .map_or(command.clone(), |command| command.into_owned())
+    };
+    task.resolved.args.iter().fold(command, |mut line, arg| {
+        let arg = shell.to_shell_variable(arg);
+        line.push(' ');
+        line.push_str(&shell.try_quote(&arg).unwrap_or(arg.as_str().into()));
+        line
+    })
+}
+
+/// Keeps the end of `output` within `limit` bytes, starting at a line
+/// boundary, and reports whether anything was cut.
+fn output_tail(output: &str, limit: usize) -> (&str, bool) {
+    if output.len() <= limit {
+        return (output, false);
+    }
+    let mut start = output.len() - limit;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    let start = output[start..]
+        .find('\n')
+        .map_or(start, |newline| start + newline + 1);
+    (&output[start..], true)
+}
+
+static RUST_ERROR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^error(?:\[E\d+\])?: (?P<message>.+)$").unwrap());
+static RUST_LOCATION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*--> (?P<location>\S+:\d+(?::\d+)?)$").unwrap());
+static RUST_PANIC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^thread '(?P<test>[^']+)' panicked at (?P<location>\S+:\d+:\d+):$").unwrap()
+});
+static RUST_TEST_FAILED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^test (?P<test>\S+) \.\.\. FAILED$").unwrap());
+static GO_TEST_FAILED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*--- FAIL: (?P<test>\S+)").unwrap());
+static PYTEST_FAILED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^FAILED (?P<location>[^\s:]+)::(?P<test>\S+)(?: - (?P<message>.+))?$").unwrap()
+});
+static TSC_ERROR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<path>[^\s(]+)\((?P<line>\d+),(?P<column>\d+)\): error (?P<message>.+)$")
+        .unwrap()
+});
+static COMPILER_ERROR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<location>[^\s:]+:\d+(?::\d+)?): (?:fatal )?error:? (?P<message>.+)$").unwrap()
+});
+
+/// Recognizes failing tests and compiler errors in the output of common tools:
+/// Cargo, Go, pytest, TypeScript, and anything reporting errors in the
+/// `path:line:column: error: message` form.
+fn parse_failures(output: &str) -> Vec<TaskFailure> {
+    let mut failures = Vec::<TaskFailure>::new();
+    let mut pending_rust_error: Option<String> = None;
+    let mut lines = output.lines().map(|line| line.trim_end()).peekable();
+    while let Some(line) = lines.next() {
+        if failures.len() >= MAX_FAILURES {
+            break;
+        }
+
+        if let Some(message) = pending_rust_error.take() {
+            let location = RUST_LOCATION
+                .captures(line)
+                .map(|captures| captures["location"].to_string());
+            failures.push(TaskFailure { location, message });
+            if failures.last().is_some_and(|f| f.location.is_some()) {
+                continue;
+            }
+        }
+
+        if let Some(captures) = RUST_ERROR.captures(line) {
+            let message = captures["message"].to_string();
+            // Cargo's closing summary isn't a failure of its own.
+            if !message.starts_with("could not compile") && !message.starts_with("test failed") {
+                pending_rust_error = Some(message);
+            }
+        } else if let Some(captures) = RUST_PANIC.captures(line) {
+            let message = lines
+                .peek()
+                .map(|message| message.trim())
+                .filter(|message| !message.is_empty())
+                .unwrap_or("panicked");
+            failures.push(TaskFailure {
+                location: Some(captures["location"].to_string()),
+                message: format!("{}: {message}", &captures["test"]),
+            });
+        } else if let Some(captures) = RUST_TEST_FAILED.captures(line) {
+            let test = &captures["test"];
+            // A panic message already reported this test.
+            if !failures
+                .iter()
+                .any(|failure| failure.message.starts_with(&format!("{test}:")))
+            {
+                failures.push(TaskFailure {
+                    location: None,
+                    message: format!("test {test} failed"),
+                });
+            }
+        } else if let Some(captures) = GO_TEST_FAILED.captures(line) {
+            failures.push(TaskFailure {
+                location: None,
+                message: format!("test {} failed", &captures["test"]),
+            });
+        } else if let Some(captures) = PYTEST_FAILED.captures(line) {
+            let test = &captures["test"];
+            failures.push(TaskFailure {
+                location: Some(captures["location"].to_string()),
+                message: match captures.name("message") {
+                    Some(message) => format!("{test}: {}", message.as_str()),
+                    None => format!("test {test} failed"),
+                },
+            });
+        } else if let Some(captures) = TSC_ERROR.captures(line) {
+            failures.push(TaskFailure {
+                location: Some(format!(
+                    "{}:{}:{}",
+                    &captures["path"], &captures["line"], &captures["column"]
+                )),
+                message: captures["message"].to_string(),
+            });
+        } else if let Some(captures) = COMPILER_ERROR.captures(line) {
+            failures.push(TaskFailure {
+                location: Some(captures["location"].to_string()),
+                message: captures["message"].to_string(),
+            });
+        }
+    }
+    if let Some(message) = pending_rust_error
+        && failures.len() < MAX_FAILURES
+    {
+        failures.push(TaskFailure {
+            location: None,
+            message,
+        });
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn failure(location: Option<&str>, message: &str) -> TaskFailure {
+        TaskFailure {
+            location: location.map(ToString::to_string),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_cargo_failures() {
+        let output = indoc! {"
+            error[E0425]: cannot find value `x` in this scope
+              --> src/main.rs:3:5
+               |
+            3  |     x
+               |     ^ not found in this scope
+
+            error: could not compile `demo` (bin \"demo\") due to 1 previous error
+        "};
+        assert_eq!(
+            parse_failures(output),
+            vec![failure(
+                Some("src/main.rs:3:5"),
+                "cannot find value `x` in this scope"
+            )]
+        );
+
+        let output = indoc! {"
+            running 2 tests
+            test tests::passes ... ok
+            test tests::fails ... FAILED
+
+            failures:
+
+            ---- tests::fails stdout ----
+
+            thread 'tests::fails' panicked at src/lib.rs:10:9:
+            assertion `left == right` failed
+            test tests::other ... FAILED
+        "};
+        assert_eq!(
+            parse_failures(output),
+            vec![
+                failure(None, "test tests::fails failed"),
+                failure(
+                    Some("src/lib.rs:10:9"),
+                    "tests::fails: assertion `left == right` failed"
+                ),
+                failure(None, "test tests::other failed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_other_failures() {
+        let output = indoc! {"
+            --- FAIL: TestParse (0.00s)
+            FAILED tests/test_app.py::test_login - AssertionError: expected 200
+            src/app.ts(12,7): error TS2322: Type 'string' is not assignable to type 'number'.
+            main.c:4:1: error: expected ';' before '}' token
+            src/lib.rs:1:1: warning: unused import
+        "};
+        assert_eq!(
+            parse_failures(output),
+            vec![
+                failure(None, "test TestParse failed"),
+                failure(
+                    Some("tests/test_app.py"),
+                    "test_login: AssertionError: expected 200"
+                ),
+                failure(
+                    Some("src/app.ts:12:7"),
+                    "TS2322: Type 'string' is not assignable to type 'number'."
+                ),
+                failure(Some("main.c:4:1"), "expected ';' before '}' token"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_tail() {
+        assert_eq!(output_tail("short", 10), ("short", false));
+        assert_eq!(
+            output_tail("first line\nsecond line\nthird", 16),
+            ("third", true)
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol::schema::v1 as acp;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use language::Point;
+use project::Project;
+use schemars::JsonSchema;
+use semantic_index::{IndexStatus, SemanticIndex};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::{MarkdownCodeBlock, MarkdownInlineCode};
+
+/// Searches the project's code by meaning rather than by text.
+///
+/// - Use this tool to find code related to a concept or behavior when you don't know the names it uses, e.g. "where are retries scheduled after a failed request" or "parsing of user config files".
+/// - Results are chunks of code (whole functions, types, or runs of lines), best match first, with their paths and line ranges.
+/// - Prefer the grep tool when you know an exact identifier or string to look for.
+/// - Results come from an index that's updated in the background, so very recent edits may not be reflected yet.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SemanticSearchToolInput {
+    /// A natural-language description of the code to find.
+    pub query: String,
+    /// The maximum number of chunks to return. Defaults to 8.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+const DEFAULT_LIMIT: usize = 8;
+const MAX_LIMIT: usize = 25;
+
+pub struct SemanticSearchTool {
+    project: Entity<Project>,
+}
+
+impl SemanticSearchTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for SemanticSearchTool {
+    type Input = SemanticSearchToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "semantic_search";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Search code for {}", MarkdownInlineCode(&input.query)).into(),
+            Err(_) => "Search code by meaning".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input.recv().await.map_err(|e| e.to_string())?;
+            let limit = input.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+            let (status, search) = cx.update(|cx| {
+                let project_index = SemanticIndex::global(cx)
+                    .and_then(|index| index.read(cx).project_index(&project))
+                    .ok_or_else(|| {
+                        "The semantic index isn't available for this project. \
+                        Use the grep tool instead."
+                            .to_string()
+                    })?;
+                let project_index = project_index.read(cx);
+                Ok::<_, String>((
+                    project_index.status(),
+                    project_index.search(input.query.clone(), limit, None, cx),
+                ))
+            })?;
+            if status == IndexStatus::Failed {
+                return Err(
+                    "The semantic index couldn't be opened. Use the grep tool instead.".into(),
+                );
+            }
+
+            let results = futures::select! {
+                results = search.fuse() => results.map_err(|error| {
+                    format!("Semantic search failed: {error:#}. Use the grep tool instead.")
+                })?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Search cancelled by user".to_string());
+                }
+            };
+
+            let mut output = String::new();
+            match status {
+                IndexStatus::Loading => {
+                    output.push_str("The index is still loading, so results may be missing.\n")
+                }
+                IndexStatus::Indexing { remaining_files } => {
+                    writeln!(
+                        output,
+                        "The index is still being built ({remaining_files} files remaining), so results may be incomplete."
+                    )
+                    .ok();
+                }
+                IndexStatus::Idle | IndexStatus::Failed => {}
+            }
+
+            let mut found = 0;
+            for result in results {
+                let open_buffer = project.update(cx, |project, cx| {
+                    project.open_buffer(result.project_path.clone(), cx)
+                });
+                // The file may have been deleted since it was indexed.
+                let Ok(buffer) = open_buffer.await else {
+                    continue;
+                };
+                let (path, snapshot) = buffer.read_with(cx, |buffer, cx| {
+                    (
+                        buffer.file().map(|file| file.full_path(cx)),
+                        buffer.snapshot(),
+                    )
+                });
+                let Some(path) = path else {
+                    continue;
+                };
+
+                let max_row = snapshot.max_point().row;
+                let start_row = result.rows.start.min(max_row);
+                let end_row = result.rows.end.saturating_sub(1).clamp(start_row, max_row);
+                let text = snapshot
+                    .text_for_range(
+                        Point::new(start_row, 0)..Point::new(end_row, snapshot.line_len(end_row)),
+                    )
+                    .collect::<String>();
+
+                write!(
+                    output,
+                    "\n## {} L{}-{}",
+                    path.display(),
+                    start_row + 1,
+                    end_row + 1
+                )
+                .ok();
+                if let Some(title) = &result.title {
+                    write!(output, " ({title})").ok();
+                }
+                writeln!(
+                    output,
+                    "\n{}",
+                    MarkdownCodeBlock {
+                        tag: &path.to_string_lossy(),
+                        text: &text,
+                    }
+                )
+                .ok();
+                found += 1;
+            }
+
+            if found == 0 {
+                output.push_str("No matching code found.");
+            }
+            Ok(output)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToolCallEventStream;
+    use gpui::{TestAppContext, UpdateGlobal};
+    use project::FakeFs;
+    use semantic_index::FakeEmbeddingProvider;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(enabled: bool, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.semantic_index.get_or_insert_default().enabled = Some(enabled);
+                });
+            });
+            semantic_index::init_with_provider(Arc::new(FakeEmbeddingProvider::default()), cx);
+        });
+    }
+
+    async fn project(cx: &mut TestAppContext) -> Entity<Project> {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "parse.rs": "fn parse_tokens() {}\n",
+                    "render.rs": "fn render_pixels() {\n    draw();\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        cx.run_until_parked();
+        project
+    }
+
+    async fn run_semantic_search_tool(
+        query: &str,
+        project: Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> Result<String, String> {
+        let tool = Arc::new(SemanticSearchTool::new(project));
+        let input = SemanticSearchToolInput {
+            query: query.to_string(),
+            limit: Some(1),
+        };
+        let task = cx.update(|cx| {
+            tool.run(
+                ToolInput::resolved(input),
+                ToolCallEventStream::test().0,
+                cx,
+            )
+        });
+        task.await
+    }
+
+    #[gpui::test]
+    async fn test_semantic_search_tool_returns_matching_code(cx: &mut TestAppContext) {
+        init_test(true, cx);
+        let project = project(cx).await;
+
+        let output = run_semantic_search_tool("render pixels", project, cx)
+            .await
+            .unwrap();
+        assert!(
+            output.contains("render.rs L1-3"),
+            "unexpected output: {output}"
+        );
+        assert!(
+            output.contains("    draw();"),
+            "unexpected output: {output}"
+        );
+        assert!(!output.contains("parse.rs"), "unexpected output: {output}");
+    }
+
+    #[gpui::test]
+    async fn test_semantic_search_tool_without_index(cx: &mut TestAppContext) {
+        init_test(false, cx);
+        let project = project(cx).await;
+
+        let error = run_semantic_search_tool("render pixels", project, cx)
+            .await
+            .unwrap_err();
+        assert!(
+            error.contains("isn't available"),
+            "unexpected error: {error}"
+        );
+    }
+}
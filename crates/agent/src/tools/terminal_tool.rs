@@ -722,21 +722,7 @@ async fn run_terminal_tool(
                         .to_string(),
                 );
             }
-            let (writable_paths, protected_paths) = cx.update(|cx| {
-                (
-                    sandbox_worktree_writable_paths(project.read(cx), cx),
-                    sandbox_git_dirs(project.read(cx), cx),
-                )
-            });
-            let wrap = acp_thread::SandboxWrap {
-                writable_paths,
-                extra_write_paths: effective.write_paths,
-                protected_paths,
-                network: network_request_to_sandbox_network_access(&effective.network),
-                allow_fs_write: effective.allow_fs_write_all,
-                is_local: is_local_project,
-                wsl_zed_release: wsl_zed_release.clone(),
-            };
+            let wrap = cx.update(|cx| sandbox_wrap(project.read(cx), effective, cx));
 
             // The viability check runs a brief probe subprocess, so do it off
             // the main thread. On Linux the sandbox can genuinely be unavailable
@@ -1126,6 +1112,77 @@ fn wsl_absolute_path(raw: &str) -> Option<PathBuf> {
     }
 }
 
+/// The sandbox a command runs in under the `effective` policy: the project's
+/// worktrees are writable, their `.git` directories are protected, and the
+/// request adds any further write paths and network access.
+fn sandbox_wrap(
+    project: &Project,
+    effective: crate::sandboxing::SandboxRequest,
+    cx: &App,
+) -> acp_thread::SandboxWrap {
+    acp_thread::SandboxWrap {
+        writable_paths: sandbox_worktree_writable_paths(project, cx),
+        extra_write_paths: effective.write_paths,
+        protected_paths: sandbox_git_dirs(project, cx),
+        network: network_request_to_sandbox_network_access(&effective.network),
+        allow_fs_write: effective.allow_fs_write_all,
+        is_local: project.is_local(),
+        wsl_zed_release: wsl_zed_release(cx),
+    }
+}
+
+/// The sandbox for a command that doesn't request permissions of its own, such
+/// as a task run by `run_task`: it gets only what the user has already granted
+/// in settings or for this thread. Returns `None` when sandboxing is off for
+/// the project or the user let this thread run unsandboxed, and fails closed
+/// when the sandbox can't be created.
+pub(crate) async fn standing_sandbox_wrap(
+    project: &Entity<Project>,
+    event_stream: &ToolCallEventStream,
+    cx: &mut AsyncApp,
+) -> Result<Option<acp_thread::SandboxWrap>, String> {
+    let (sandboxing, persistent) = cx.update(|cx| {
+        (
+            sandboxing_enabled_for_project(project.read(cx), cx),
+            agent_settings::AgentSettings::get_global(cx)
+                .sandbox_permissions
+                .clone(),
+        )
+    });
+    if !sandboxing
+        || event_stream.unsandboxed_granted_for_thread()
+        || event_stream.sandbox_fallback_granted_for_thread()
+    {
+        return Ok(None);
+    }
+
+    let mut effective = event_stream
+        .effective_sandbox_request(&crate::sandboxing::SandboxRequest::default(), &persistent);
+    let can_restrict_to_hosts = cfg!(any(target_os = "macos", target_os = "linux"));
+    if !can_restrict_to_hosts && matches!(effective.network, NetworkRequest::Hosts(_)) {
+        // A host allowlist can't be enforced here, so the command gets no
+        // network rather than silently getting all of it.
+        effective.network = NetworkRequest::None;
+    }
+    let wrap = cx.update(|cx| sandbox_wrap(project.read(cx), effective, cx));
+
+    let probe_wrap = wrap.clone();
+    match cx
+        .background_executor()
+        .spawn(async move { probe_wrap.can_create_sandbox() })
+        .await
+    {
+        Ok(()) => Ok(Some(wrap)),
+        Err(error) => {
+            log::warn!("Failed to create a sandbox for an agent command: {error:?}");
+            Err(format!(
+                "Cannot create a sandbox for this command: {}",
+                error.user_facing_message()
+            ))
+        }
+    }
+}
+
 /// Convert a (validated) network request into the access mode enforced by the
 /// terminal sandbox.
 fn network_request_to_sandbox_network_access(
@@ -5,6 +5,7 @@ use std::cmp::Ordering::{Equal, Greater, Less};
 use std::fmt;
 use std::path::{Component, Path};
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use anyhow::Context as _;
 use collections::{HashSet, IndexMap};
@@ -242,6 +243,8 @@ pub struct AgentSettings {
     pub show_merge_conflict_indicator: bool,
     pub tool_permissions: ToolPermissions,
     pub sandbox_permissions: SandboxPermissions,
+    pub hooks: AgentHooks,
+    pub budgets: AgentBudgets,
 }
 
 impl AgentSettings {
@@ -461,6 +464,90 @@ impl Default for SandboxPermissions {
     }
 }
 
+/// Spending limits for the agent. See `agent::budget` for how they're
+/// enforced.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AgentBudgets {
+    pub thread: AgentBudgetLimits,
+    pub daily: AgentBudgetLimits,
+    pub profiles: IndexMap<AgentProfileId, AgentBudgetLimits>,
+    pub warning_threshold: f32,
+}
+
+impl Default for AgentBudgets {
+    fn default() -> Self {
+        Self {
+            thread: AgentBudgetLimits::default(),
+            daily: AgentBudgetLimits::default(),
+            profiles: IndexMap::default(),
+            warning_threshold: Self::DEFAULT_WARNING_THRESHOLD,
+        }
+    }
+}
+
+impl AgentBudgets {
+    pub const DEFAULT_WARNING_THRESHOLD: f32 = 0.8;
+
+    /// The daily limits shared by every thread using the given profile.
+    pub fn profile_limits(&self, profile_id: &AgentProfileId) -> AgentBudgetLimits {
+        self.profiles.get(profile_id).copied().unwrap_or_default()
+    }
+
+    /// Whether no limit applies to threads using the given profile.
+    pub fn is_unlimited(&self, profile_id: &AgentProfileId) -> bool {
+        self.thread.is_unlimited()
+            && self.daily.is_unlimited()
+            && self.profile_limits(profile_id).is_unlimited()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AgentBudgetLimits {
+    pub max_tokens: Option<u64>,
+    /// In US dollars.
+    pub max_cost: Option<f64>,
+}
+
+impl AgentBudgetLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_tokens.is_none() && self.max_cost.is_none()
+    }
+}
+
+/// User commands run around the agent's actions. See `agent::hooks` for how
+/// they're invoked and how their exit status is interpreted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AgentHooks {
+    pub before_tool_call: Vec<AgentHook>,
+    pub after_file_edit: Vec<AgentHook>,
+    pub turn_end: Vec<AgentHook>,
+    pub session_start: Vec<AgentHook>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AgentHook {
+    pub command: String,
+    pub args: Vec<String>,
+    /// Tool names the hook is limited to. Empty means every tool.
+    pub tools: Vec<String>,
+    pub timeout: Duration,
+}
+
+impl AgentHook {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// Whether this hook should run for a call to `tool_name`. Events that
+    /// aren't about a tool pass `None` and always match.
+    pub fn applies_to_tool(&self, tool_name: Option<&str>) -> bool {
+        match tool_name {
+            Some(tool_name) => {
+                self.tools.is_empty() || self.tools.iter().any(|tool| tool == tool_name)
+            }
+            None => true,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct ToolPermissions {
     /// Global default permission when no tool-specific rules or patterns match.
@@ -818,8 +905,71 @@ impl Settings for AgentSettings {
             show_merge_conflict_indicator: agent.show_merge_conflict_indicator.unwrap(),
             tool_permissions: compile_tool_permissions(agent.tool_permissions),
             sandbox_permissions: compile_sandbox_permissions(agent.sandbox_permissions),
+            hooks: compile_agent_hooks(agent.hooks),
+            budgets: compile_agent_budgets(agent.budgets),
+        }
+    }
+}
+
+fn compile_agent_hooks(content: Option<settings::AgentHooksContent>) -> AgentHooks {
+    let Some(content) = content else {
+        return AgentHooks::default();
+    };
+
+    fn compile(hooks: Option<Vec<settings::AgentHookContent>>) -> Vec<AgentHook> {
+        hooks
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|hook| !hook.command.trim().is_empty())
+            .map(|hook| AgentHook {
+                command: hook.command,
+                args: hook.args.unwrap_or_default(),
+                tools: hook.tools.unwrap_or_default(),
+                timeout: hook
+                    .timeout
+                    .map(Duration::from_secs)
+                    .unwrap_or(AgentHook::DEFAULT_TIMEOUT),
+            })
+            .collect()
+    }
+
+    AgentHooks {
+        before_tool_call: compile(content.before_tool_call),
+        after_file_edit: compile(content.after_file_edit),
+        turn_end: compile(content.turn_end),
+        session_start: compile(content.session_start),
+    }
+}
+
+fn compile_agent_budgets(content: Option<settings::AgentBudgetsContent>) -> AgentBudgets {
+    let Some(content) = content else {
+        return AgentBudgets::default();
+    };
+
+    fn compile(limits: Option<settings::AgentBudgetLimitsContent>) -> AgentBudgetLimits {
+        let limits = limits.unwrap_or_default();
+        AgentBudgetLimits {
+            max_tokens: limits.max_tokens,
+            max_cost: limits
+                .max_cost
+                .filter(|cost| cost.is_finite() && *cost >= 0.0),
         }
     }
+
+    AgentBudgets {
+        thread: compile(content.thread),
+        daily: compile(content.daily),
+        profiles: content
+            .profiles
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(id, limits)| (AgentProfileId(id), compile(Some(limits))))
+            .collect(),
+        warning_threshold: content
+            .warning_threshold
+            .filter(|threshold| (0.0..=1.0).contains(threshold))
+            .unwrap_or(AgentBudgets::DEFAULT_WARNING_THRESHOLD),
+    }
 }
 
 fn compile_sandbox_permissions(
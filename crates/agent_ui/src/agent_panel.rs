@@ -4222,6 +4222,7 @@ impl AgentPanel {
         self.retain_running_thread(old_view, cx);
 
         if let BaseView::AgentThread { conversation_view } = &self.base_view {
+            conversation_view.update(cx, |view, cx| view.apply_pending_message_jump(cx));
             let conversation_view = conversation_view.read(cx);
             let thread_agent = conversation_view.agent_key().clone();
             if self.selected_agent != thread_agent {
@@ -4388,6 +4389,7 @@ impl AgentPanel {
         // Check if the active view already holds this thread.
         if let BaseView::AgentThread { conversation_view } = &self.base_view {
             if conversation_view.read(cx).thread_id == thread_id {
+                conversation_view.update(cx, |view, cx| view.apply_pending_message_jump(cx));
                 cx.emit(AgentPanelEvent::ActiveViewChanged);
                 return;
             }
@@ -7901,6 +7903,7 @@ mod tests {
                         worktree_paths: WorktreePaths::from_folder_paths(&PathList::default()),
                         remote_connection: None,
                         archived: false,
+                        forked_from: None,
                     },
                     cx,
                 );
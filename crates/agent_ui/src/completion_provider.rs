@@ -8,9 +8,13 @@ use crate::DEFAULT_THREAD_TITLE;
 use crate::thread_metadata_store::{ThreadMetadata, ThreadMetadataStore};
 use acp_thread::MentionUri;
 use agent_client_protocol::schema::v1 as acp;
-use anyhow::Result;
+use anyhow::{Context as _, Result};
+use context_server::ContextServerId;
+use context_server::protocol::{InitializedContextServerProtocol, ServerCapability};
+use context_server::types::requests;
 use editor::{CompletionProvider, Editor, code_context_menus::COMPLETION_MENU_MAX_WIDTH};
 use futures::FutureExt as _;
+use futures::future::{self, Shared};
 use fuzzy::{PathMatch, StringMatch, StringMatchCandidate};
 use gpui::{
     App, BackgroundExecutor, Entity, Focusable, Hsla, SharedString, Task, WeakEntity, Window,
@@ -163,6 +167,7 @@ pub(crate) enum PromptContextType {
     Skill,
     Diagnostics,
     BranchDiff,
+    McpResource,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -247,6 +252,7 @@ impl TryFrom<&str> for PromptContextType {
             "skill" => Ok(Self::Skill),
             "diagnostics" => Ok(Self::Diagnostics),
             "diff" => Ok(Self::BranchDiff),
+            "resource" => Ok(Self::McpResource),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -262,6 +268,7 @@ impl PromptContextType {
             Self::Skill => "skill",
             Self::Diagnostics => "diagnostics",
             Self::BranchDiff => "branch diff",
+            Self::McpResource => "resource",
         }
     }
 
@@ -274,6 +281,7 @@ impl PromptContextType {
             Self::Skill => "Skills",
             Self::Diagnostics => "Diagnostics",
             Self::BranchDiff => "Branch Diff",
+            Self::McpResource => "MCP Resources",
         }
     }
 
@@ -286,6 +294,7 @@ impl PromptContextType {
             Self::Skill => IconName::Sparkle,
             Self::Diagnostics => IconName::Warning,
             Self::BranchDiff => IconName::GitBranch,
+            Self::McpResource => IconName::ToolHammer,
         }
     }
 }
@@ -299,6 +308,7 @@ pub(crate) enum Match {
     Skill(AvailableSkill),
     Entry(EntryMatch),
     BranchDiff(BranchDiffMatch),
+    McpResource(McpResourceMatch),
 }
 
 #[derive(Debug, Clone)]
@@ -317,6 +327,7 @@ impl Match {
             Match::Skill(_) => 1.,
             Match::Fetch(_) => 1.,
             Match::BranchDiff(_) => 1.,
+            Match::McpResource(_) => 1.,
         }
     }
 }
@@ -640,6 +651,85 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
         }
     }
 
+    fn completion_for_mcp_resource(
+        resource: McpResourceMatch,
+        source_range: Range<Anchor>,
+        source: Arc<T>,
+        editor: WeakEntity<Editor>,
+        mention_set: WeakEntity<MentionSet>,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Completion {
+        let source_highlight_id = cx
+            .theme()
+            .syntax()
+            .highlight_id("variable")
+            .map(HighlightId::new);
+        let server_name = SharedString::from(resource.server_id.0.to_string());
+        let label = build_slash_item_label(
+            &Arc::from(resource.name.as_str()),
+            Some(&server_name),
+            source_highlight_id,
+        );
+        let documentation = resource
+            .description
+            .clone()
+            .map(CompletionDocumentation::MultiLinePlainText);
+
+        if resource.is_template {
+            return Completion {
+                replace_range: source_range,
+                new_text: format!(
+                    "@{} {}",
+                    PromptContextType::McpResource.keyword(),
+                    resource.uri
+                ),
+                label,
+                icon_path: Some(IconName::ToolHammer.path().into()),
+                icon_color: None,
+                documentation,
+                source: project::CompletionSource::Custom,
+                match_start: None,
+                snippet_deduplication_key: None,
+                insert_text_mode: None,
+                // Keep the menu open so the template's next parameter can be completed.
+                confirm: Some(Arc::new(|_, _, _| true)),
+                group: None,
+            };
+        }
+
+        let uri = MentionUri::McpResource {
+            server_id: resource.server_id.0.to_string(),
+            uri: resource.uri,
+            name: resource.name.to_string(),
+        };
+        let new_text = format!("{} ", uri.as_link());
+        let new_text_len = new_text.len();
+        Completion {
+            replace_range: source_range.clone(),
+            new_text,
+            label,
+            documentation,
+            insert_text_mode: None,
+            source: project::CompletionSource::Custom,
+            match_start: None,
+            snippet_deduplication_key: None,
+            icon_path: Some(uri.icon_path(cx)),
+            icon_color: None,
+            confirm: Some(confirm_completion_callback(
+                resource.name,
+                source_range.start,
+                new_text_len - 1,
+                uri,
+                source,
+                editor,
+                mention_set,
+                workspace,
+            )),
+            group: None,
+        }
+    }
+
     pub(crate) fn completion_for_path(
         project_path: ProjectPath,
         path_prefix: &RelPath,
@@ -1159,6 +1249,21 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
 
             Some(PromptContextType::BranchDiff) => Task::ready(Vec::new()),
 
+            Some(PromptContextType::McpResource) => {
+                let listings = self
+                    .mention_set
+                    .update(cx, |mention_set, cx| mention_set.mcp_resource_listings(cx));
+                let search_resources_task =
+                    search_mcp_resources(query, cancellation_flag, listings, cx);
+                cx.background_spawn(async move {
+                    search_resources_task
+                        .await
+                        .into_iter()
+                        .map(Match::McpResource)
+                        .collect()
+                })
+            }
+
             None if query.is_empty() => {
                 let recent_task = self.recent_context_picker_entries(&workspace, cx);
                 let entries = self
@@ -1391,6 +1496,14 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
             entries.push(PromptContextEntry::Mode(PromptContextType::Fetch));
         }
 
+        if self
+            .source
+            .supports_context(PromptContextType::McpResource, cx)
+            && !resource_servers(workspace.read(cx).project(), cx).is_empty()
+        {
+            entries.push(PromptContextEntry::Mode(PromptContextType::McpResource));
+        }
+
         if self
             .source
             .supports_context(PromptContextType::Diagnostics, cx)
@@ -1840,6 +1953,17 @@ impl<T: PromptCompletionProviderDelegate> CompletionProvider for PromptCompletio
                                         workspace.clone(),
                                         cx,
                                     )),
+                                    Match::McpResource(resource) => {
+                                        Some(Self::completion_for_mcp_resource(
+                                            resource,
+                                            source_range.clone(),
+                                            source.clone(),
+                                            editor.clone(),
+                                            mention_set.clone(),
+                                            workspace.clone(),
+                                            cx,
+                                        ))
+                                    }
                                     Match::Fetch(url) => Self::completion_for_fetch(
                                         source_range.clone(),
                                         url,
@@ -2528,6 +2652,360 @@ pub(crate) fn search_skills(
     })
 }
 
+#[derive(Debug, Clone)]
+pub struct McpResourceMatch {
+    pub server_id: ContextServerId,
+    pub name: SharedString,
+    pub description: Option<SharedString>,
+    pub uri: String,
+    /// Set when `uri` is the start of a resource template that still needs
+    /// values for some of its parameters.
+    pub is_template: bool,
+}
+
+/// A resource template as listed by a context server.
+#[derive(Debug, Clone)]
+pub struct McpResourceTemplate {
+    pub uri_template: String,
+    pub name: SharedString,
+    pub description: Option<SharedString>,
+}
+
+/// The resources and resource templates a context server lists.
+#[derive(Debug, Default)]
+pub struct McpResourceListing {
+    pub resources: Vec<McpResourceMatch>,
+    pub templates: Vec<McpResourceTemplate>,
+}
+
+/// The listing of a context server's resources, along with the client to
+/// complete its templates with.
+pub type McpResourceListingTask = (
+    ContextServerId,
+    Arc<InitializedContextServerProtocol>,
+    Shared<Task<Arc<McpResourceListing>>>,
+);
+
+/// The running context servers that expose resources.
+pub(crate) fn resource_servers(
+    project: &Entity<Project>,
+    cx: &App,
+) -> Vec<(ContextServerId, Arc<InitializedContextServerProtocol>)> {
+    project
+        .read(cx)
+        .context_server_store()
+        .read(cx)
+        .running_servers()
+        .into_iter()
+        .filter_map(|server| {
+            let client = server.client()?;
+            client
+                .capable(ServerCapability::Resources)
+                .then(|| (server.id(), client))
+        })
+        .collect()
+}
+
+/// Lists the resources and resource templates of a context server.
+pub(crate) async fn list_mcp_resources(
+    server_id: ContextServerId,
+    client: &InitializedContextServerProtocol,
+) -> Result<McpResourceListing> {
+    let (resources, templates) = futures::join!(
+        client.request::<requests::ResourcesList>(()),
+        client.request::<requests::ListResourceTemplates>(())
+    );
+    let resources = resources
+        .with_context(|| format!("failed to list resources of context server {server_id}"))?
+        .resources
+        .into_iter()
+        .map(|resource| McpResourceMatch {
+            server_id: server_id.clone(),
+            name: resource.title.unwrap_or(resource.name).into(),
+            description: resource.description.map(Into::into),
+            uri: resource.uri.to_string(),
+            is_template: false,
+        })
+        .collect();
+    // Servers without templates may not implement listing them at all.
+    let templates = match templates {
+        Ok(response) => response.resource_templates,
+        Err(error) => {
+            log::debug!("failed to list resource templates of context server {server_id}: {error}");
+            Vec::new()
+        }
+    };
+    let templates = templates
+        .into_iter()
+        .map(|template| McpResourceTemplate {
+            uri_template: template.uri_template,
+            name: template.title.unwrap_or(template.name).into(),
+            description: template.description.map(Into::into),
+        })
+        .collect();
+    Ok(McpResourceListing {
+        resources,
+        templates,
+    })
+}
+
+pub(crate) fn search_mcp_resources(
+    query: String,
+    cancellation_flag: Arc<AtomicBool>,
+    listings: Vec<McpResourceListingTask>,
+    cx: &mut App,
+) -> Task<Vec<McpResourceMatch>> {
+    if listings.is_empty() {
+        return Task::ready(Vec::new());
+    }
+    let executor = cx.background_executor().clone();
+    cx.background_spawn(async move {
+        let listings =
+            future::join_all(
+                listings
+                    .into_iter()
+                    .map(|(server_id, client, listing)| async move {
+                        (server_id, client, listing.await)
+                    }),
+            )
+            .await;
+
+        let mut candidates = Vec::new();
+        let mut template_matches = Vec::new();
+        for (server_id, client, listing) in &listings {
+            candidates.extend(listing.resources.iter().cloned());
+            for template in &listing.templates {
+                let input = if query.is_empty() {
+                    None
+                } else {
+                    resource_template_input(&template.uri_template, &query)
+                };
+                match input {
+                    None => candidates.push(McpResourceMatch {
+                        server_id: server_id.clone(),
+                        name: template.name.clone(),
+                        description: template.description.clone(),
+                        uri: resource_template_prefix(&template.uri_template).to_string(),
+                        is_template: true,
+                    }),
+                    Some(ResourceTemplateInput::Complete) => {
+                        template_matches.push(
+                            future::ready(vec![McpResourceMatch {
+                                server_id: server_id.clone(),
+                                name: query.clone().into(),
+                                description: template.description.clone(),
+                                uri: query.clone(),
+                                is_template: false,
+                            }])
+                            .boxed(),
+                        );
+                    }
+                    Some(ResourceTemplateInput::Parameter(parameter)) => {
+                        template_matches.push(
+                            complete_resource_template_parameter(
+                                server_id,
+                                client,
+                                &template.uri_template,
+                                parameter,
+                            )
+                            .boxed(),
+                        );
+                    }
+                }
+            }
+        }
+        let mut template_matches = future::join_all(template_matches)
+            .await
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let candidate_strings = candidates
+            .iter()
+            .enumerate()
+            .map(|(id, resource)| {
+                StringMatchCandidate::new(id, &format!("{} {}", resource.name, resource.uri))
+            })
+            .collect::<Vec<_>>();
+        let matches = fuzzy::match_strings(
+            &candidate_strings,
+            &query,
+            false,
+            true,
+            100,
+            &cancellation_flag,
+            executor,
+        )
+        .await;
+        template_matches.extend(
+            matches
+                .into_iter()
+                .map(|mat| candidates[mat.candidate_id].clone()),
+        );
+        template_matches
+    })
+}
+
+/// Asks the server for values of the template parameter being typed.
+async fn complete_resource_template_parameter(
+    server_id: &ContextServerId,
+    client: &InitializedContextServerProtocol,
+    uri_template: &str,
+    parameter: ResourceTemplateParameter,
+) -> Vec<McpResourceMatch> {
+    let response = client
+        .request::<requests::CompletionComplete>(context_server::types::CompletionCompleteParams {
+            reference: context_server::types::CompletionReference::Resource(
+                context_server::types::ResourceReference {
+                    ty: context_server::types::PromptReferenceType::Resource,
+                    uri: uri_template.to_string(),
+                },
+            ),
+            argument: context_server::types::CompletionArgument {
+                name: parameter.name.clone(),
+                value: parameter.value.clone(),
+            },
+            context: (!parameter.arguments.is_empty()).then(|| {
+                context_server::types::CompletionContext {
+                    arguments: Some(parameter.arguments.into_iter().collect()),
+                }
+            }),
+            meta: None,
+        })
+        .await;
+    let mut values = match response {
+        Ok(response) => response.completion.values,
+        Err(error) => {
+            log::debug!("context server {server_id} can't complete {uri_template}: {error}");
+            Vec::new()
+        }
+    };
+    // Whatever has been typed for the last parameter is a resource in its own right.
+    if parameter.is_last && !parameter.value.is_empty() && !values.contains(&parameter.value) {
+        values.push(parameter.value.clone());
+    }
+
+    values
+        .into_iter()
+        .map(|value| {
+            let uri = format!("{}{}{}", parameter.prefix, value, parameter.suffix);
+            McpResourceMatch {
+                server_id: server_id.clone(),
+                name: if parameter.is_last {
+                    uri.clone().into()
+                } else {
+                    value.into()
+                },
+                description: None,
+                uri,
+                is_template: !parameter.is_last,
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+enum ResourceTemplatePart<'a> {
+    Literal(&'a str),
+    Parameter(&'a str),
+}
+
+/// Splits a URI template like `repo://{owner}/{name}` into its literal text
+/// and parameters. Expression operators such as `+` or `/` are ignored.
+fn resource_template_parts(uri_template: &str) -> Vec<ResourceTemplatePart<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = uri_template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        if start > 0 {
+            parts.push(ResourceTemplatePart::Literal(&rest[..start]));
+        }
+        let expression = &rest[start + 1..start + len];
+        parts.push(ResourceTemplatePart::Parameter(
+            expression.trim_start_matches(['+', '#', '.', '/', ';', '?', '&']),
+        ));
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(ResourceTemplatePart::Literal(rest));
+    }
+    parts
+}
+
+/// The text of a URI template before its first parameter.
+fn resource_template_prefix(uri_template: &str) -> &str {
+    match resource_template_parts(uri_template).first() {
+        Some(ResourceTemplatePart::Literal(literal)) => literal,
+        _ => "",
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ResourceTemplateInput {
+    /// The input is the value of one of the template's parameters.
+    Parameter(ResourceTemplateParameter),
+    /// The input fills in every parameter of the template.
+    Complete,
+}
+
+#[derive(Debug, PartialEq)]
+struct ResourceTemplateParameter {
+    name: String,
+    /// The text typed for this parameter so far.
+    value: String,
+    /// The input before `value`.
+    prefix: String,
+    /// The template text between this parameter and the next one.
+    suffix: String,
+    /// Values of the parameters before this one.
+    arguments: Vec<(String, String)>,
+    is_last: bool,
+}
+
+/// Returns how far `input` has got in filling in `uri_template`, or `None`
+/// if it doesn't follow the template.
+fn resource_template_input(uri_template: &str, input: &str) -> Option<ResourceTemplateInput> {
+    let parts = resource_template_parts(uri_template);
+    let mut arguments = Vec::new();
+    let mut consumed = 0;
+    for (ix, part) in parts.iter().enumerate() {
+        let rest = &input[consumed..];
+        match part {
+            ResourceTemplatePart::Literal(literal) => {
+                rest.strip_prefix(literal)?;
+                consumed += literal.len();
+            }
+            ResourceTemplatePart::Parameter(name) => {
+                let suffix = match parts.get(ix + 1) {
+                    Some(ResourceTemplatePart::Literal(literal)) => *literal,
+                    _ => "",
+                };
+                match rest.find(suffix).filter(|_| !suffix.is_empty()) {
+                    Some(len) => {
+                        arguments.push((name.to_string(), rest[..len].to_string()));
+                        consumed += len;
+                    }
+                    None => {
+                        return Some(ResourceTemplateInput::Parameter(
+                            ResourceTemplateParameter {
+                                name: name.to_string(),
+                                value: rest.to_string(),
+                                prefix: input[..consumed].to_string(),
+                                suffix: suffix.to_string(),
+                                arguments,
+                                is_last: ix + 1 == parts.len(),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    (consumed == input.len()).then_some(ResourceTemplateInput::Complete)
+}
+
 pub struct SymbolMatch {
     pub symbol: Symbol,
 }
@@ -3026,6 +3504,58 @@ mod tests {
         assert_eq!(order, vec!["skill-a", "compact"]);
     }
 
+    #[test]
+    fn test_resource_template_input() {
+        let template = "repo://{owner}/{name}/issues";
+        assert_eq!(resource_template_prefix(template), "repo://");
+        assert_eq!(resource_template_input(template, "rep"), None);
+        assert_eq!(
+            resource_template_input(template, "repo://zed-ind"),
+            Some(ResourceTemplateInput::Parameter(
+                ResourceTemplateParameter {
+                    name: "owner".into(),
+                    value: "zed-ind".into(),
+                    prefix: "repo://".into(),
+                    suffix: "/".into(),
+                    arguments: Vec::new(),
+                    is_last: false,
+                }
+            ))
+        );
+        assert_eq!(
+            resource_template_input(template, "repo://zed-industries/zed"),
+            Some(ResourceTemplateInput::Parameter(
+                ResourceTemplateParameter {
+                    name: "name".into(),
+                    value: "zed".into(),
+                    prefix: "repo://zed-industries/".into(),
+                    suffix: "/issues".into(),
+                    arguments: vec![("owner".into(), "zed-industries".into())],
+                    is_last: false,
+                }
+            ))
+        );
+        assert_eq!(
+            resource_template_input(template, "repo://zed-industries/zed/issues"),
+            Some(ResourceTemplateInput::Complete)
+        );
+
+        let template = "file:///{+path}";
+        assert_eq!(
+            resource_template_input(template, "file:///src/main.rs"),
+            Some(ResourceTemplateInput::Parameter(
+                ResourceTemplateParameter {
+                    name: "path".into(),
+                    value: "src/main.rs".into(),
+                    prefix: "file:///".into(),
+                    suffix: String::new(),
+                    arguments: Vec::new(),
+                    is_last: true,
+                }
+            ))
+        );
+    }
+
     #[test]
     fn test_mention_completion_parse() {
         let supported_modes = vec![PromptContextType::File, PromptContextType::Symbol];
@@ -1,9 +1,10 @@
-use std::sync::Arc;
+use std::{rc::Rc, sync::Arc};
 
+use agent::{ContextServerSamplingPrompt, SamplingApproval, SamplingApprovalRequest};
 use context_server::ContextServerId;
 use extension::ExtensionManifest;
 use fs::Fs;
-use gpui::WeakEntity;
+use gpui::{PromptLevel, Task, WeakEntity};
 use language::LanguageRegistry;
 use settings::update_settings_file;
 use ui::prelude::*;
@@ -13,6 +14,8 @@ use workspace::{MultiWorkspace, Workspace};
 use crate::agent_configuration::ConfigureContextServerModal;
 
 pub(crate) fn init(language_registry: Arc<LanguageRegistry>, fs: Arc<dyn Fs>, cx: &mut App) {
+    cx.set_global(ContextServerSamplingPrompt(Rc::new(prompt_for_sampling)));
+
     let Some(extension_events) = extension::ExtensionEvents::try_global(cx) else {
         log::info!(
             "No extension events global found. Skipping context server configuration wizard"
@@ -53,6 +56,58 @@ pub(crate) fn init(language_registry: Arc<LanguageRegistry>, fs: Arc<dyn Fs>, cx
     .detach();
 }
 
+/// Asks the user in the active window whether a context server may use the
+/// language model. The request is denied when there is no window to ask in.
+fn prompt_for_sampling(request: SamplingApprovalRequest, cx: &mut App) -> Task<SamplingApproval> {
+    let Some(multi_workspace) = cx
+        .active_window()
+        .and_then(|window| window.downcast::<MultiWorkspace>())
+    else {
+        return Task::ready(SamplingApproval::Deny);
+    };
+
+    let message = format!(
+        "Allow the MCP server \"{}\" to use {}?",
+        request.server_id, request.model_name
+    );
+    let mut detail = String::new();
+    if let Some(system_prompt) = &request.system_prompt {
+        detail.push_str(&format!("System prompt: {}\n\n", truncate(system_prompt)));
+    }
+    if let Some(last_message) = &request.last_message {
+        detail.push_str(&format!("Message: {}\n\n", truncate(last_message)));
+    }
+    detail.push_str(&format!(
+        "Up to {} output tokens. {} tokens used by this server so far.",
+        request.max_tokens,
+        request.token_usage.total_tokens()
+    ));
+
+    let answer = multi_workspace.update(cx, |_, window, cx| {
+        window.prompt(
+            PromptLevel::Info,
+            &message,
+            Some(&detail),
+            &["Allow", "Always Allow", "Deny"],
+            cx,
+        )
+    });
+    let Ok(answer) = answer else {
+        return Task::ready(SamplingApproval::Deny);
+    };
+    cx.background_spawn(async move {
+        match answer.await {
+            Ok(0) => SamplingApproval::Allow,
+            Ok(1) => SamplingApproval::AlwaysAllow,
+            _ => SamplingApproval::Deny,
+        }
+    })
+}
+
+fn truncate(text: &str) -> String {
+    util::truncate_and_trailoff(text.trim(), 400)
+}
+
 fn remove_context_server_settings(
     context_server_ids: Vec<Arc<str>>,
     fs: Arc<dyn Fs>,
@@ -7,7 +7,7 @@ use acp_thread::{
 };
 use acp_thread::{AgentConnection, Plan};
 use action_log::{ActionLog, ActionLogTelemetry, DiffStats};
-use agent::{NativeAgentServer, NoModelConfiguredError, ThreadStore};
+use agent::{BudgetExceededError, NativeAgentServer, NoModelConfiguredError, ThreadStore};
 use agent_client_protocol::schema::v1 as acp;
 #[cfg(test)]
 use agent_servers::AgentServerDelegate;
@@ -28,7 +28,7 @@ use fs::Fs;
 use futures::FutureExt as _;
 use gpui::{
     Action, Animation, AnimationExt, App, ClickEvent, ClipboardItem, CursorStyle, ElementId, Empty,
-    Entity, EventEmitter, FocusHandle, Focusable, Hsla, ListOffset, ListState, ObjectFit,
+    Entity, EventEmitter, FocusHandle, Focusable, Global, Hsla, ListOffset, ListState, ObjectFit,
     PlatformDisplay, ScrollHandle, SharedString, StyledText, Subscription, Task, TextRun,
     TextStyle, WeakEntity, Window, WindowHandle, div, ease_in_out, img, linear_color_stop,
     linear_gradient, list, pulsating_between,
@@ -149,6 +149,9 @@ pub(crate) enum ThreadError {
     RequestFailed,
     MaxOutputTokens,
     NoModelSelected,
+    BudgetExceeded {
+        message: SharedString,
+    },
     ApiError {
         provider: SharedString,
     },
@@ -164,6 +167,10 @@ impl From<anyhow::Error> for ThreadError {
             Self::MaxOutputTokens
         } else if error.is::<NoModelConfiguredError>() {
             Self::NoModelSelected
+        } else if let Some(BudgetExceededError(message)) = error.downcast_ref() {
+            Self::BudgetExceeded {
+                message: message.clone(),
+            }
         } else if let Some(acp_error) = error.downcast_ref::<acp::Error>()
             && acp_error.code == acp::ErrorCode::AuthRequired
         {
@@ -588,6 +595,24 @@ pub enum AcpServerViewEvent {
 
 impl EventEmitter<AcpServerViewEvent> for ConversationView {}
 
+/// User messages to scroll to the next time a thread is shown, keyed by thread
+/// and given as an index among the thread's user messages. Set when a thread
+/// is opened from a search result, which may first need to load it.
+#[derive(Default)]
+struct PendingMessageJumps(HashMap<ThreadId, usize>);
+
+impl Global for PendingMessageJumps {}
+
+pub(crate) fn jump_to_user_message_when_shown(
+    thread_id: ThreadId,
+    user_message_ix: usize,
+    cx: &mut App,
+) {
+    cx.default_global::<PendingMessageJumps>()
+        .0
+        .insert(thread_id, user_message_ix);
+}
+
 pub struct ConversationView {
     agent: Rc<dyn AgentServer>,
     connection_store: Entity<AgentConnectionStore>,
@@ -665,6 +690,26 @@ impl ConversationView {
             .map(|view| view.read(cx).thread.clone())
     }
 
+    /// Scrolls to the user message requested with
+    /// [`jump_to_user_message_when_shown`], once the thread has loaded.
+    pub(crate) fn apply_pending_message_jump(&self, cx: &mut App) {
+        let Some(thread_view) = self.root_thread_view() else {
+            return;
+        };
+        let Some(user_message_ix) = cx
+            .try_global::<PendingMessageJumps>()
+            .and_then(|jumps| jumps.0.get(&self.thread_id).copied())
+        else {
+            return;
+        };
+        cx.global_mut::<PendingMessageJumps>()
+            .0
+            .remove(&self.thread_id);
+        thread_view.update(cx, |thread_view, cx| {
+            thread_view.scroll_to_nth_user_message(user_message_ix, cx)
+        });
+    }
+
     pub fn root_thread_view(&self) -> Option<Entity<ThreadView>> {
         self.root_session_id
             .as_ref()
@@ -1204,6 +1249,7 @@ impl ConversationView {
                             }),
                             cx,
                         );
+                        this.apply_pending_message_jump(cx);
                     }
                     Err(err) => {
                         this.handle_load_error(
@@ -4671,6 +4717,7 @@ pub(crate) mod tests {
                         worktree_paths: WorktreePaths::from_folder_paths(&PathList::default()),
                         remote_connection: None,
                         archived: false,
+                        forked_from: None,
                     },
                     cx,
                 );
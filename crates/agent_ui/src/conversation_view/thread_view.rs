@@ -3,7 +3,7 @@ use crate::{
     agent_configuration::configure_context_server_modal::default_markdown_style,
     conversation_view::thread_search_bar::{ThreadSearchBar, ThreadSearchBarEvent},
     open_abs_path_at_point,
-    thread_metadata_store::{ThreadId, ThreadMetadataStore},
+    thread_metadata_store::{ThreadId, ThreadMetadata, ThreadMetadataStore},
 };
 use agent_client_protocol::schema::v1 as acp;
 use std::cell::RefCell;
@@ -587,6 +587,7 @@ pub struct ThreadView {
     pub(super) thread_error: Option<ThreadError>,
     pub thread_error_markdown: Option<Entity<Markdown>>,
     pub token_limit_callout_dismissed: bool,
+    budget_warning_dismissed: bool,
     pub last_token_limit_telemetry: Option<acp_thread::TokenUsageRatio>,
     thread_feedback: ThreadFeedbackState,
     pub list_state: ListState,
@@ -1007,6 +1008,7 @@ impl ThreadView {
             thread_error: None,
             thread_error_markdown: None,
             token_limit_callout_dismissed: false,
+            budget_warning_dismissed: false,
             last_token_limit_telemetry: None,
             thread_feedback: Default::default(),
             expanded_tool_call_raw_inputs: HashSet::default(),
@@ -1941,6 +1943,9 @@ impl ThreadView {
                 ThreadError::NoModelSelected => {
                     ("no_model_selected", None, "No model selected.".into())
                 }
+                ThreadError::BudgetExceeded { message } => {
+                    ("budget_exceeded", None, message.clone())
+                }
                 ThreadError::ApiError { provider } => (
                     "api_error",
                     None,
@@ -2972,6 +2977,103 @@ impl ThreadView {
             .detach_and_log_err(cx);
     }
 
+    /// Saves a copy of the thread up to (but not including) the given message
+    /// and opens it, with that message restored as the fork's draft prompt.
+    /// With `into_worktree`, the git checkpoint taken before the message is
+    /// first restored into a new detached worktree, and the fork opens there.
+    pub fn fork_from_message(
+        &mut self,
+        client_id: &ClientUserMessageId,
+        into_worktree: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let worktree_creation = if into_worktree {
+            let Some(checkpoint) = self.thread.read(cx).user_message_checkpoint(client_id) else {
+                log::error!("Can't fork into a worktree: the message has no checkpoint");
+                return;
+            };
+            let commits = checkpoint
+                .commit_shas()
+                .map(|(work_dir, sha)| (work_dir.clone(), sha.to_string()))
+                .collect();
+            let Ok(creation) = self.workspace.update(cx, |workspace, cx| {
+                git_ui_core::worktree_service::create_worktree_workspace_at_commits(
+                    workspace, None, commits, window, cx,
+                )
+            }) else {
+                return;
+            };
+            Some(creation)
+        } else {
+            None
+        };
+        let thread = self.thread.clone();
+        let client_id = client_id.clone();
+        let parent_thread_id = self.root_thread_id;
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_this, cx| {
+            let (workspace, worktree_paths) = match worktree_creation {
+                Some(creation) => {
+                    let created = creation.await?;
+                    let worktree_paths = created.workspace.read_with(cx, |workspace, cx| {
+                        workspace.project().read(cx).worktree_paths(cx)
+                    });
+                    (created.workspace.downgrade(), Some(worktree_paths))
+                }
+                None => (workspace, None),
+            };
+            let fork_session_id = thread
+                .update(cx, |thread, cx| {
+                    thread.fork(
+                        client_id,
+                        worktree_paths
+                            .as_ref()
+                            .map(|paths| paths.folder_path_list().clone()),
+                        cx,
+                    )
+                })
+                .await?;
+            cx.update(|window, cx| {
+                let store = ThreadMetadataStore::global(cx);
+                let parent = store
+                    .read(cx)
+                    .entry(parent_thread_id)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Thread metadata not found"))?;
+                let title = parent
+                    .title()
+                    .map(|title| SharedString::from(format!("{title} (fork)")));
+                let now = chrono::Utc::now();
+                let metadata = ThreadMetadata {
+                    thread_id: ThreadId::new(),
+                    session_id: Some(fork_session_id.clone()),
+                    agent_id: parent.agent_id.clone(),
+                    title: title.clone(),
+                    title_override: None,
+                    updated_at: now,
+                    created_at: Some(now),
+                    interacted_at: None,
+                    worktree_paths: worktree_paths.unwrap_or(parent.worktree_paths),
+                    remote_connection: parent.remote_connection.clone(),
+                    archived: false,
+                    forked_from: Some(parent_thread_id),
+                };
+                let work_dirs = metadata.folder_paths().clone();
+                store.update(cx, |store, cx| store.save(metadata, cx));
+
+                workspace.update(cx, |workspace, cx| {
+                    if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                        panel.update(cx, |panel, cx| {
+                            panel.open_thread(fork_session_id, Some(work_dirs), title, window, cx)
+                        });
+                    }
+                })
+            })?
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn clear_thread_error(&mut self, cx: &mut Context<Self>) {
         self.thread_error = None;
         self.thread_error_markdown = None;
@@ -6147,6 +6249,7 @@ impl ThreadView {
                 let is_subagent = self.is_subagent();
                 let can_rewind = self.thread.read(cx).supports_truncate(cx);
                 let is_editable = can_rewind && message.client_id.is_some() && !is_subagent;
+                let can_fork = is_editable && self.thread.read(cx).supports_fork(cx);
                 let agent_name = if is_subagent {
                     "subagents".into()
                 } else {
@@ -6245,6 +6348,43 @@ impl ThreadView {
                                                     .icon_size(IconSize::XSmall)
                                                     .on_click(cx.listener(Self::cancel_editing))
                                             )
+                                            .when_some(
+                                                message.client_id.clone().filter(|_| can_fork),
+                                                |this, client_id| {
+                                                    this.child(
+                                                        IconButton::new("fork", IconName::GitBranch)
+                                                            .disabled(is_loading_contents)
+                                                            .icon_color(Color::Muted)
+                                                            .icon_size(IconSize::XSmall)
+                                                            .tooltip(Tooltip::text(
+                                                                "Fork the thread from this message, keeping the original."
+                                                            ))
+                                                            .on_click(cx.listener(move |this, _, window, cx| {
+                                                                this.fork_from_message(&client_id, false, window, cx);
+                                                            }))
+                                                    )
+                                                },
+                                            )
+                                            .when_some(
+                                                message
+                                                    .client_id
+                                                    .clone()
+                                                    .filter(|_| can_fork && message.checkpoint.is_some()),
+                                                |this, client_id| {
+                                                    this.child(
+                                                        IconButton::new("fork-into-worktree", IconName::GitWorktree)
+                                                            .disabled(is_loading_contents)
+                                                            .icon_color(Color::Muted)
+                                                            .icon_size(IconSize::XSmall)
+                                                            .tooltip(Tooltip::text(
+                                                                "Fork the thread from this message into a new worktree with the files restored to this point."
+                                                            ))
+                                                            .on_click(cx.listener(move |this, _, window, cx| {
+                                                                this.fork_from_message(&client_id, true, window, cx);
+                                                            }))
+                                                    )
+                                                },
+                                            )
                                             .child(
                                                 if is_loading_contents {
                                                     div()
@@ -7004,6 +7144,21 @@ impl ThreadView {
         }
     }
 
+    /// Scrolls to the `n`th user message (counting from zero), or to the most
+    /// recent one if there aren't that many.
+    pub(crate) fn scroll_to_nth_user_message(&mut self, n: usize, cx: &mut Context<Self>) {
+        let entry_ix = self
+            .thread
+            .read(cx)
+            .entries()
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| matches!(entry, AgentThreadEntry::UserMessage(_)))
+            .nth(n)
+            .map(|(ix, _)| ix);
+        self.scroll_to_user_message_index(entry_ix, cx);
+    }
+
     pub fn scroll_to_end(&mut self, cx: &mut Context<Self>) {
         self.list_state.scroll_to_end();
         cx.notify();
@@ -11103,6 +11258,14 @@ impl ThreadView {
                         cx,
                     )
                 }),
+            ThreadError::BudgetExceeded { message } => self.render_error_callout(
+                "Budget Reached",
+                format!("{message} Raise the limit in the `agent.budgets` setting to continue.")
+                    .into(),
+                true,
+                false,
+                cx,
+            ),
             ThreadError::ApiError { provider } => self.render_error_callout(
                 "API Error",
                 format!(
@@ -11808,6 +11971,42 @@ impl ThreadView {
         )
     }
 
+    fn render_budget_warning_callout(&self, cx: &mut Context<Self>) -> Option<Callout> {
+        if self.budget_warning_dismissed {
+            return None;
+        }
+        let thread = self.as_native_thread(cx)?;
+        let Some(agent::BudgetStatus::Warning(message)) = thread.read(cx).budget_status().cloned()
+        else {
+            return None;
+        };
+
+        Some(
+            Callout::new()
+                .border_position(self.callout_border_position())
+                .severity(Severity::Warning)
+                .icon(IconName::Warning)
+                .title("Approaching budget")
+                .description(message)
+                .actions_slot(
+                    Button::new("open-usage-report", "View Usage")
+                        .label_size(LabelSize::Small)
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(crate::OpenUsageReport.boxed_clone(), cx);
+                        }),
+                )
+                .dismiss_action(
+                    IconButton::new("dismiss-budget-warning", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Dismiss"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.budget_warning_dismissed = true;
+                            cx.notify();
+                        })),
+                ),
+        )
+    }
+
     fn render_token_limit_callout(&self, cx: &mut Context<Self>) -> Option<Callout> {
         if self.token_limit_callout_dismissed || self.as_native_thread(cx).is_none() {
             return None;
@@ -12463,6 +12662,7 @@ impl Render for ThreadView {
                 |this, version| this.child(self.render_new_version_callout(&version, cx)),
             )
             .children(self.render_token_limit_callout(cx))
+            .children(self.render_budget_warning_callout(cx))
             .children(self.render_request_elicitations(cx))
             .child(self.render_message_editor(window, cx))
     }
@@ -12587,6 +12787,7 @@ pub(crate) fn open_link(
             MentionUri::TerminalSelection { .. } => {}
             MentionUri::GitDiff { .. } => {}
             MentionUri::MergeConflict { .. } => {}
+            MentionUri::McpResource { .. } => {}
             MentionUri::Rule { name, .. } => {
                 crate::ui::open_migrated_rule(workspace, &name, window, cx);
             }
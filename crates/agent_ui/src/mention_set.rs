@@ -5,6 +5,15 @@ use agent_client_protocol::schema::v1 as acp;
 use agent_servers::{AgentServer, AgentServerDelegate};
 use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
+use context_server::{
+    ContextServerId,
+    client::NotificationSubscription,
+    protocol::InitializedContextServerProtocol,
+    types::{
+        ResourceContentsType, ResourcesReadParams, ResourcesSubscribeParams,
+        ResourcesUnsubscribeParams, ResourcesUpdatedParams, requests,
+    },
+};
 use editor::{
     Anchor, Editor, EditorSnapshot, FoldPlaceholder, ToOffset,
     display_map::{Crease, CreaseId, CreaseMetadata, FoldId},
@@ -12,8 +21,8 @@ use editor::{
 };
 use futures::{AsyncReadExt as _, FutureExt as _, future::Shared};
 use gpui::{
-    AppContext, ClipboardEntry, Context, Empty, Entity, EntityId, Image, ImageFormat, Img,
-    SharedString, Task, WeakEntity,
+    AppContext, BackgroundExecutor, ClipboardEntry, Context, Empty, Entity, EntityId, Image,
+    ImageFormat, Img, SharedString, Task, WeakEntity,
 };
 use http_client::{AsyncBody, HttpClientWithUrl};
 use itertools::Either;
@@ -37,6 +46,9 @@ use ui::{Disclosure, Toggleable, prelude::*};
 use util::{ResultExt, debug_panic, rel_path::RelPath};
 use workspace::{Workspace, notifications::NotifyResultExt as _};
 
+use crate::completion_provider::{
+    McpResourceListing, McpResourceListingTask, list_mcp_resources, resource_servers,
+};
 use crate::ui::MentionCrease;
 
 pub type MentionTask = Shared<Task<Result<Mention, String>>>;
@@ -62,6 +74,52 @@ pub struct MentionSet {
     thread_store: Option<Entity<ThreadStore>>,
     mentions: HashMap<CreaseId, (MentionUri, MentionTask)>,
     crease_entities: HashMap<CreaseId, Entity<LoadingContext>>,
+    /// Mentioned context server resources we get update notifications for, by server.
+    mcp_resource_subscriptions: HashMap<ContextServerId, McpResourceSubscription>,
+    /// What each context server listed for resource completions, kept until
+    /// the server says its list changed.
+    mcp_resource_listings: HashMap<ContextServerId, CachedMcpResourceListing>,
+}
+
+struct McpResourceSubscription {
+    client: Arc<InitializedContextServerProtocol>,
+    executor: BackgroundExecutor,
+    uris: HashSet<String>,
+    _updates: NotificationSubscription,
+}
+
+impl McpResourceSubscription {
+    fn unsubscribe(&self, uri: &str) {
+        let Ok(uri) = url::Url::parse(uri) else {
+            return;
+        };
+        let client = self.client.clone();
+        self.executor
+            .spawn(async move {
+                client
+                    .request::<requests::ResourcesUnsubscribe>(ResourcesUnsubscribeParams {
+                        uri,
+                        meta: None,
+                    })
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+}
+
+impl Drop for McpResourceSubscription {
+    fn drop(&mut self) {
+        for uri in &self.uris {
+            self.unsubscribe(uri);
+        }
+    }
+}
+
+struct CachedMcpResourceListing {
+    client: Arc<InitializedContextServerProtocol>,
+    listing: Shared<Task<Arc<McpResourceListing>>>,
+    _list_changed: NotificationSubscription,
 }
 
 impl MentionSet {
@@ -71,6 +129,8 @@ impl MentionSet {
             thread_store,
             mentions: HashMap::default(),
             crease_entities: HashMap::default(),
+            mcp_resource_subscriptions: HashMap::default(),
+            mcp_resource_listings: HashMap::default(),
         }
     }
 
@@ -108,6 +168,7 @@ impl MentionSet {
                 self.crease_entities.remove(&crease_id);
             }
         }
+        self.unsubscribe_from_unmentioned_mcp_resources();
     }
 
     pub fn insert_mention(
@@ -156,6 +217,9 @@ impl MentionSet {
             MentionUri::GitDiff { base_ref } => {
                 self.confirm_mention_for_git_diff(base_ref.into(), cx)
             }
+            MentionUri::McpResource { server_id, uri, .. } => {
+                self.confirm_mention_for_mcp_resource(ContextServerId(server_id.into()), uri, cx)
+            }
             MentionUri::Selection {
                 abs_path: Some(abs_path),
                 line_range,
@@ -176,6 +240,7 @@ impl MentionSet {
     pub fn remove_mention(&mut self, crease_id: &CreaseId, cx: &mut App) {
         self.mentions.remove(crease_id);
         self.crease_entities.remove(crease_id);
+        self.unsubscribe_from_unmentioned_mcp_resources();
         self.recompute_disambiguation(cx);
     }
 
@@ -209,10 +274,12 @@ impl MentionSet {
         self.crease_entities
             .retain(|id, _| mentions.contains_key(id));
         self.mentions = mentions;
+        self.unsubscribe_from_unmentioned_mcp_resources();
     }
 
     pub fn clear(&mut self) -> impl Iterator<Item = (CreaseId, (MentionUri, MentionTask))> {
         self.crease_entities.clear();
+        self.mcp_resource_subscriptions.clear();
         self.mentions.drain()
     }
 
@@ -344,6 +411,9 @@ impl MentionSet {
             MentionUri::GitDiff { base_ref } => {
                 self.confirm_mention_for_git_diff(base_ref.into(), cx)
             }
+            MentionUri::McpResource { server_id, uri, .. } => {
+                self.confirm_mention_for_mcp_resource(ContextServerId(server_id.into()), uri, cx)
+            }
             MentionUri::MergeConflict { .. } => {
                 debug_panic!("unexpected merge conflict URI");
                 Task::ready(Err(anyhow!("unexpected merge conflict URI")))
@@ -375,6 +445,7 @@ impl MentionSet {
                     });
                     this.mentions.remove(&crease_id);
                     this.crease_entities.remove(&crease_id);
+                    this.unsubscribe_from_unmentioned_mcp_resources();
                 })
                 .ok();
             }
@@ -520,6 +591,246 @@ impl MentionSet {
         })
     }
 
+    fn confirm_mention_for_mcp_resource(
+        &mut self,
+        server_id: ContextServerId,
+        uri: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Mention>> {
+        let Some(client) = self.mcp_resource_client(&server_id, cx) else {
+            return Task::ready(Err(anyhow!("Context server {server_id} is not running")));
+        };
+        self.subscribe_to_mcp_resource(server_id, &uri, &client, cx);
+        read_mcp_resource(client, uri, cx)
+    }
+
+    fn mcp_resource_client(
+        &self,
+        server_id: &ContextServerId,
+        cx: &App,
+    ) -> Option<Arc<InitializedContextServerProtocol>> {
+        let project = self.project.upgrade()?;
+        let server = project
+            .read(cx)
+            .context_server_store()
+            .read(cx)
+            .get_running_server(server_id)?;
+        server.client()
+    }
+
+    /// Asks the server to tell us when the resource changes, so the mention
+    /// can be re-read before the message is sent.
+    fn subscribe_to_mcp_resource(
+        &mut self,
+        server_id: ContextServerId,
+        uri: &str,
+        client: &Arc<InitializedContextServerProtocol>,
+        cx: &mut Context<Self>,
+    ) {
+        let supports_subscriptions = client
+            .initialize
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false);
+        if !supports_subscriptions {
+            return;
+        }
+        let Ok(url) = url::Url::parse(uri) else {
+            return;
+        };
+
+        let this = cx.entity().downgrade();
+        let subscription = self
+            .mcp_resource_subscriptions
+            .entry(server_id.clone())
+            .or_insert_with(|| McpResourceSubscription {
+                client: client.clone(),
+                executor: cx.background_executor().clone(),
+                uris: HashSet::default(),
+                _updates: client.on_notification(
+                    "notifications/resources/updated",
+                    Box::new(move |params, cx| {
+                        let Some(params) =
+                            serde_json::from_value::<ResourcesUpdatedParams>(params).log_err()
+                        else {
+                            return;
+                        };
+                        let this = this.clone();
+                        let server_id = server_id.clone();
+                        cx.spawn(async move |cx| {
+                            this.update(cx, |this, cx| {
+                                this.reload_mcp_resource(&server_id, &params.uri, cx)
+                            })
+                        })
+                        .detach();
+                    }),
+                ),
+            });
+        if subscription.uris.insert(uri.to_string()) {
+            let client = client.clone();
+            cx.background_spawn(async move {
+                client
+                    .request::<requests::ResourcesSubscribe>(ResourcesSubscribeParams {
+                        uri: url,
+                        meta: None,
+                    })
+                    .await
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    fn reload_mcp_resource(
+        &mut self,
+        server_id: &ContextServerId,
+        uri: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(client) = self.mcp_resource_client(server_id, cx) else {
+            return;
+        };
+
+        let mut reload_task = None;
+        for (mention, task) in self.mentions.values_mut() {
+            if let MentionUri::McpResource {
+                server_id: mention_server_id,
+                uri: mention_resource_uri,
+                ..
+            } = &*mention
+                && mention_server_id.as_str() == server_id.0.as_ref()
+                && mention_resource_uri == uri
+            {
+                *task = reload_task
+                    .get_or_insert_with(|| {
+                        let read = read_mcp_resource(client.clone(), uri.to_string(), cx);
+                        cx.spawn(async move |_, _| read.await.map_err(|e| e.to_string()))
+                            .shared()
+                    })
+                    .clone();
+            }
+        }
+
+        // The resource is no longer mentioned, so stop listening for its updates.
+        if reload_task.is_none() {
+            self.unsubscribe_from_unmentioned_mcp_resources();
+        }
+    }
+
+    /// Stops listening for updates to resources that are no longer mentioned.
+    fn unsubscribe_from_unmentioned_mcp_resources(&mut self) {
+        if self.mcp_resource_subscriptions.is_empty() {
+            return;
+        }
+        let mentioned = self
+            .mentions
+            .values()
+            .filter_map(|(mention, _)| match mention {
+                MentionUri::McpResource { server_id, uri, .. } => {
+                    Some((server_id.as_str(), uri.as_str()))
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        self.mcp_resource_subscriptions
+            .retain(|server_id, subscription| {
+                let unmentioned = subscription
+                    .uris
+                    .iter()
+                    .filter(|uri| !mentioned.contains(&(server_id.0.as_ref(), uri.as_str())))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                for uri in unmentioned {
+                    subscription.uris.remove(&uri);
+                    subscription.unsubscribe(&uri);
+                }
+                !subscription.uris.is_empty()
+            });
+    }
+
+    /// Lists the resources of the running context servers that have some,
+    /// reusing earlier listings until a server says its list changed.
+    pub fn mcp_resource_listings(&mut self, cx: &mut Context<Self>) -> Vec<McpResourceListingTask> {
+        let Some(project) = self.project.upgrade() else {
+            return Vec::new();
+        };
+        let servers = resource_servers(&project, cx);
+        // Forget the servers that stopped or were restarted since.
+        self.mcp_resource_listings.retain(|server_id, cached| {
+            servers
+                .iter()
+                .any(|(id, client)| id == server_id && Arc::ptr_eq(client, &cached.client))
+        });
+
+        servers
+            .into_iter()
+            .map(|(server_id, client)| {
+                let listing = match self.mcp_resource_listings.get(&server_id) {
+                    Some(cached) => cached.listing.clone(),
+                    None => {
+                        let cached = self.list_mcp_resources(server_id.clone(), client.clone(), cx);
+                        let listing = cached.listing.clone();
+                        self.mcp_resource_listings.insert(server_id.clone(), cached);
+                        listing
+                    }
+                };
+                (server_id, client, listing)
+            })
+            .collect()
+    }
+
+    fn list_mcp_resources(
+        &self,
+        server_id: ContextServerId,
+        client: Arc<InitializedContextServerProtocol>,
+        cx: &mut Context<Self>,
+    ) -> CachedMcpResourceListing {
+        let this = cx.entity().downgrade();
+        let list_changed = client.on_notification("notifications/resources/list_changed", {
+            let server_id = server_id.clone();
+            Box::new(move |_, cx| {
+                let this = this.clone();
+                let server_id = server_id.clone();
+                cx.spawn(async move |cx| {
+                    this.update(cx, |this, _| {
+                        this.mcp_resource_listings.remove(&server_id);
+                    })
+                })
+                .detach();
+            })
+        });
+
+        let listing = cx
+            .spawn({
+                let client = client.clone();
+                async move |this, cx| match list_mcp_resources(server_id.clone(), &client).await {
+                    Ok(listing) => Arc::new(listing),
+                    Err(error) => {
+                        log::warn!("{error:#}");
+                        // List again on the next completion rather than caching the failure.
+                        this.update(cx, |this, _| {
+                            if this
+                                .mcp_resource_listings
+                                .get(&server_id)
+                                .is_some_and(|cached| Arc::ptr_eq(&cached.client, &client))
+                            {
+                                this.mcp_resource_listings.remove(&server_id);
+                            }
+                        })
+                        .ok();
+                        Arc::default()
+                    }
+                }
+            })
+            .shared();
+        CachedMcpResourceListing {
+            client,
+            listing,
+            _list_changed: list_changed,
+        }
+    }
+
     pub fn confirm_mention_for_selection(
         &mut self,
         source_range: Range<text::Anchor>,
@@ -709,6 +1020,44 @@ impl MentionSet {
     }
 }
 
+fn read_mcp_resource(
+    client: Arc<InitializedContextServerProtocol>,
+    uri: String,
+    cx: &App,
+) -> Task<Result<Mention>> {
+    cx.background_spawn(async move {
+        let response = client
+            .request::<requests::ResourcesRead>(ResourcesReadParams {
+                uri: url::Url::parse(&uri)?,
+                meta: None,
+            })
+            .await?;
+
+        let mut content = String::new();
+        for contents in response.contents {
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            match contents {
+                ResourceContentsType::Text(text) => content.push_str(&text.text),
+                ResourceContentsType::Blob(blob) => {
+                    write!(
+                        &mut content,
+                        "[Binary content of {} ({})]",
+                        blob.uri,
+                        blob.mime_type.as_deref().unwrap_or("unknown type")
+                    )
+                    .ok();
+                }
+            }
+        }
+        Ok(Mention::Text {
+            content,
+            tracked_buffers: Vec::new(),
+        })
+    })
+}
+
 /// Computes disambiguated labels for a set of mentions, so that mentions sharing
 /// a base name get extra context (parent path components, skill source) to tell
 /// them apart. Same approach as buffer tab titles and the sidebar.
@@ -103,6 +103,7 @@ impl SessionCapabilities {
                 PromptContextType::Fetch,
                 PromptContextType::Skill,
                 PromptContextType::BranchDiff,
+                PromptContextType::McpResource,
             ]);
         }
         supported
@@ -5769,4 +5770,259 @@ mod tests {
             "Expected text to start with 'prefix text\\n\\n', got: {text:?}"
         );
     }
+
+    #[gpui::test]
+    async fn test_mcp_resource_mentions(cx: &mut TestAppContext) {
+        use context_server::{
+            ContextServer, ContextServerCommand, ContextServerId,
+            types::{
+                EmptyResponse, ListResourceTemplatesResponse, Resource, ResourceContentsType,
+                ResourcesCapabilities, ResourcesListResponse, ResourcesReadResponse,
+                ServerCapabilities, TextResourceContents, requests,
+            },
+        };
+        use project::project_settings::{ContextServerSettings, ProjectSettings};
+        use settings::Settings as _;
+        use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+        init_test(cx);
+        cx.update(|cx| {
+            let mut settings = ProjectSettings::get_global(cx).clone();
+            settings.context_servers.insert(
+                "docs".into(),
+                ContextServerSettings::Stdio {
+                    enabled: true,
+                    remote: false,
+                    command: ContextServerCommand {
+                        path: "somebinary".into(),
+                        args: Vec::new(),
+                        env: None,
+                        timeout: None,
+                    },
+                },
+            );
+            ProjectSettings::override_global(settings, cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/project", json!({"file": ""})).await;
+        let project = Project::test(fs, [Path::new(path!("/project"))], cx).await;
+
+        let list_count = Arc::new(AtomicUsize::new(0));
+        let readme = Arc::new(parking_lot::Mutex::new("Version 1".to_string()));
+        let (subscribe_tx, mut subscribe_rx) = futures::channel::mpsc::unbounded();
+        let (unsubscribe_tx, mut unsubscribe_rx) = futures::channel::mpsc::unbounded();
+        let transport = Arc::new(
+            context_server::test::create_fake_transport("docs", cx.executor())
+                .on_request::<requests::Initialize, _>(|_| async {
+                    context_server::types::InitializeResponse {
+                        protocol_version: context_server::types::ProtocolVersion(
+                            context_server::types::LATEST_PROTOCOL_VERSION.to_string(),
+                        ),
+                        server_info: context_server::types::Implementation {
+                            name: "docs".into(),
+                            title: None,
+                            version: "1.0.0".to_string(),
+                            description: None,
+                        },
+                        capabilities: ServerCapabilities {
+                            resources: Some(ResourcesCapabilities {
+                                subscribe: Some(true),
+                                list_changed: Some(true),
+                            }),
+                            ..Default::default()
+                        },
+                        meta: None,
+                    }
+                })
+                .on_request::<requests::ResourcesList, _>({
+                    let list_count = list_count.clone();
+                    move |_| {
+                        list_count.fetch_add(1, SeqCst);
+                        async {
+                            ResourcesListResponse {
+                                resources: vec![Resource {
+                                    uri: "docs://readme".parse().unwrap(),
+                                    name: "README".into(),
+                                    title: None,
+                                    description: None,
+                                    mime_type: None,
+                                }],
+                                next_cursor: None,
+                                meta: None,
+                            }
+                        }
+                    }
+                })
+                .on_request::<requests::ListResourceTemplates, _>(|_| async {
+                    ListResourceTemplatesResponse {
+                        resource_templates: Vec::new(),
+                        next_cursor: None,
+                        meta: None,
+                    }
+                })
+                .on_request::<requests::ResourcesRead, _>({
+                    let readme = readme.clone();
+                    move |params| {
+                        let text = readme.lock().clone();
+                        async move {
+                            ResourcesReadResponse {
+                                contents: vec![ResourceContentsType::Text(TextResourceContents {
+                                    uri: params.uri,
+                                    mime_type: None,
+                                    text,
+                                })],
+                                meta: None,
+                            }
+                        }
+                    }
+                })
+                .on_request::<requests::ResourcesSubscribe, _>(move |params| {
+                    subscribe_tx.unbounded_send(params.uri.to_string()).unwrap();
+                    async { EmptyResponse::default() }
+                })
+                .on_request::<requests::ResourcesUnsubscribe, _>(move |params| {
+                    unsubscribe_tx
+                        .unbounded_send(params.uri.to_string())
+                        .unwrap();
+                    async { EmptyResponse::default() }
+                }),
+        );
+        project.update(cx, |project, cx| {
+            project.context_server_store().update(cx, |store, cx| {
+                store.start_server(
+                    Arc::new(ContextServer::new(
+                        ContextServerId("docs".into()),
+                        transport.clone(),
+                    )),
+                    cx,
+                );
+            });
+        });
+        cx.run_until_parked();
+
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        let (message_editor, editor) = workspace.update_in(cx, |workspace, window, cx| {
+            let message_editor = cx.new(|cx| {
+                MessageEditor::new(
+                    workspace.weak_handle(),
+                    project.downgrade(),
+                    None,
+                    Default::default(),
+                    "Test Agent".into(),
+                    "Test",
+                    EditorMode::AutoHeight {
+                        max_lines: None,
+                        min_lines: 1,
+                    },
+                    window,
+                    cx,
+                )
+            });
+            workspace.active_pane().update(cx, |pane, cx| {
+                pane.add_item(
+                    Box::new(cx.new(|_| MessageEditorItem(message_editor.clone()))),
+                    true,
+                    true,
+                    None,
+                    window,
+                    cx,
+                );
+            });
+            message_editor.read(cx).focus_handle(cx).focus(window, cx);
+            let editor = message_editor.read(cx).editor().clone();
+            (message_editor, editor)
+        });
+        message_editor.update(cx, |message_editor, _| {
+            message_editor
+                .session_capabilities
+                .write()
+                .set_prompt_capabilities(acp::PromptCapabilities::new().embedded_context(true));
+        });
+
+        // The listing is requested once and reused while the query is typed.
+        cx.simulate_input("@resource ");
+        editor.update(cx, |editor, _| {
+            assert_eq!(current_completion_labels(editor), ["README docs"]);
+        });
+        cx.simulate_input("read");
+        editor.update(cx, |editor, _| {
+            assert_eq!(current_completion_labels(editor), ["README docs"]);
+        });
+        assert_eq!(list_count.load(SeqCst), 1);
+
+        // Until the server says the list changed.
+        transport.notify("notifications/resources/list_changed", json!({}));
+        cx.run_until_parked();
+        cx.simulate_input("m");
+        editor.update(cx, |editor, _| {
+            assert_eq!(current_completion_labels(editor), ["README docs"]);
+        });
+        assert_eq!(list_count.load(SeqCst), 2);
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.confirm_completion(&editor::actions::ConfirmCompletion::default(), window, cx);
+        });
+        cx.run_until_parked();
+        let uri = MentionUri::McpResource {
+            server_id: "docs".into(),
+            uri: "docs://readme".into(),
+            name: "README".into(),
+        };
+        editor.update(cx, |editor, cx| {
+            assert_eq!(editor.text(cx), format!("{} ", uri.as_link()));
+        });
+        assert_eq!(
+            subscribe_rx.try_next().unwrap(),
+            Some("docs://readme".into())
+        );
+
+        let mention_contents = |cx: &mut VisualTestContext| {
+            let contents = message_editor.update(cx, |message_editor, cx| {
+                message_editor
+                    .mention_set()
+                    .update(cx, |mention_set, cx| mention_set.contents(false, cx))
+            });
+            async move {
+                contents
+                    .await
+                    .unwrap()
+                    .into_values()
+                    .map(|(uri, mention)| match mention {
+                        Mention::Text { content, .. } => (uri, content),
+                        mention => panic!("unexpected mention {mention:?}"),
+                    })
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(
+            mention_contents(cx).await,
+            [(uri.clone(), "Version 1".to_string())]
+        );
+
+        // The mention is read again when the server says the resource changed.
+        *readme.lock() = "Version 2".to_string();
+        transport.notify(
+            "notifications/resources/updated",
+            json!({ "uri": "docs://readme" }),
+        );
+        cx.run_until_parked();
+        assert_eq!(
+            mention_contents(cx).await,
+            [(uri.clone(), "Version 2".to_string())]
+        );
+
+        // Removing the mention stops listening for its updates.
+        assert!(unsubscribe_rx.try_next().is_err());
+        editor.update_in(cx, |editor, window, cx| {
+            editor.set_text("", window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            unsubscribe_rx.try_next().unwrap(),
+            Some("docs://readme".into())
+        );
+    }
 }
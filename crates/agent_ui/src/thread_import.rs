@@ -882,6 +882,7 @@ fn collect_importable_threads(
                 worktree_paths: WorktreePaths::from_folder_paths(&folder_paths),
                 remote_connection: remote_connection.clone(),
                 archived: true,
+                forked_from: None,
             });
         }
     }
@@ -1,15 +1,17 @@
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::agent_connection_store::AgentConnectionStore;
 
+use crate::conversation_view::jump_to_user_message_when_shown;
 use crate::thread_metadata_store::{
     ThreadId, ThreadMetadata, ThreadMetadataStore, worktree_info_from_thread_paths,
 };
 use crate::{Agent, ArchiveSelectedThread, DEFAULT_THREAD_TITLE, RemoveSelectedThread};
 
-use agent::ThreadStore;
+use agent::{DbThreadSearchMatch, ThreadStore};
 use agent_client_protocol::schema::v1 as acp;
 use agent_settings::AgentSettings;
 use chrono::{DateTime, Datelike as _, Local, NaiveDate, TimeDelta, Utc};
@@ -59,6 +61,10 @@ enum ArchiveListItem {
     Entry {
         thread: ThreadMetadata,
         highlight_positions: Vec<usize>,
+        /// The best message in the thread matching the filter text, if any.
+        content_match: Option<DbThreadSearchMatch>,
+        /// How many forks deep this thread is below the thread it's listed under.
+        fork_depth: usize,
     },
 }
 
@@ -127,6 +133,51 @@ pub fn fuzzy_match_positions(query: &str, candidate: &str) -> Option<Vec<usize>>
     None
 }
 
+/// Searching thread contents starts once the filter text is this long, since
+/// shorter prefixes match too much to be useful.
+const MIN_CONTENT_SEARCH_QUERY_LEN: usize = 3;
+const CONTENT_SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+const CONTENT_SEARCH_LIMIT: usize = 500;
+
+/// Orders threads so that each fork directly follows the thread it was forked
+/// from, pairing every thread with its fork depth. Forks whose parent isn't
+/// among `threads` stay where they are, at the top level.
+fn nest_forks(threads: Vec<ThreadMetadata>) -> Vec<(ThreadMetadata, usize)> {
+    let present = threads
+        .iter()
+        .map(|thread| thread.thread_id)
+        .collect::<HashSet<_>>();
+    let mut forks_by_parent: HashMap<ThreadId, Vec<ThreadMetadata>> = HashMap::default();
+    let mut roots = Vec::new();
+    for thread in threads {
+        match thread.forked_from.filter(|parent| present.contains(parent)) {
+            Some(parent) => forks_by_parent.entry(parent).or_default().push(thread),
+            None => roots.push(thread),
+        }
+    }
+
+    let mut nested = Vec::with_capacity(present.len());
+    let mut stack = roots
+        .into_iter()
+        .rev()
+        .map(|thread| (thread, 0))
+        .collect::<Vec<_>>();
+    while let Some((thread, depth)) = stack.pop() {
+        if let Some(forks) = forks_by_parent.remove(&thread.thread_id) {
+            stack.extend(forks.into_iter().rev().map(|fork| (fork, depth + 1)));
+        }
+        nested.push((thread, depth));
+    }
+    // Only reachable with a cycle in `forked_from`, but don't drop threads.
+    nested.extend(
+        forks_by_parent
+            .into_values()
+            .flatten()
+            .map(|thread| (thread, 0)),
+    );
+    nested
+}
+
 pub enum ThreadsArchiveViewEvent {
     Close,
     Activate { thread: ThreadMetadata },
@@ -156,6 +207,9 @@ pub struct ThreadsArchiveView {
     archived_branch_names: HashMap<ThreadId, HashMap<PathBuf, String>>,
     _load_branch_names_task: Task<()>,
     thread_filter: ThreadFilter,
+    /// The best full-text match for the filter text in each saved thread.
+    content_matches: HashMap<acp::SessionId, DbThreadSearchMatch>,
+    _content_search_task: Task<()>,
 }
 
 impl ThreadsArchiveView {
@@ -178,6 +232,7 @@ impl ThreadsArchiveView {
             cx.subscribe(&filter_editor, |this: &mut Self, _, event, cx| {
                 if let editor::EditorEvent::BufferEdited = event {
                     this.update_items(cx);
+                    this.search_thread_contents(cx);
                 }
             });
 
@@ -230,6 +285,8 @@ impl ThreadsArchiveView {
             archived_branch_names: HashMap::default(),
             _load_branch_names_task: Task::ready(()),
             thread_filter: ThreadFilter::All,
+            content_matches: HashMap::default(),
+            _content_search_task: Task::ready(()),
         };
 
         this.update_items(cx);
@@ -297,7 +354,24 @@ impl ThreadsArchiveView {
         let mut items = Vec::with_capacity(sessions.len() + 5);
         let mut current_bucket: Option<TimeBucket> = None;
 
-        for session in sessions {
+        // Nesting forks under their parent only makes sense for the full
+        // list; while filtering, a fork may match without its parent.
+        let sessions = if query.is_empty() {
+            nest_forks(sessions)
+        } else {
+            sessions.into_iter().map(|session| (session, 0)).collect()
+        };
+
+        for (session, fork_depth) in sessions {
+            let content_match = if query.is_empty() {
+                None
+            } else {
+                session
+                    .session_id
+                    .as_ref()
+                    .and_then(|session_id| self.content_matches.get(session_id))
+                    .cloned()
+            };
             let highlight_positions = if !query.is_empty() {
                 let title = session
                     .title
@@ -316,7 +390,7 @@ impl ThreadsArchiveView {
                             .and_then(|name| name.to_str())
                             .is_some_and(|name| fuzzy_match_positions(&query, name).is_some())
                     });
-                    if !worktree_matched {
+                    if !worktree_matched && content_match.is_none() {
                         continue;
                     }
                     Vec::new()
@@ -335,7 +409,8 @@ impl ThreadsArchiveView {
                 TimeBucket::from_dates(today, entry_date)
             };
 
-            if Some(entry_bucket) != current_bucket {
+            // Forks stay with their parent even if created on another day.
+            if fork_depth == 0 && Some(entry_bucket) != current_bucket {
                 current_bucket = Some(entry_bucket);
                 items.push(ArchiveListItem::BucketSeparator(entry_bucket));
             }
@@ -343,6 +418,8 @@ impl ThreadsArchiveView {
             items.push(ArchiveListItem::Entry {
                 thread: session,
                 highlight_positions,
+                content_match,
+                fork_depth,
             });
         }
 
@@ -380,6 +457,44 @@ impl ThreadsArchiveView {
         cx.notify();
     }
 
+    /// Full-text searches the saved threads for the filter text, so threads
+    /// can be found by what was said in them and not just by title.
+    fn search_thread_contents(&mut self, cx: &mut Context<Self>) {
+        let query = self.filter_editor.read(cx).text(cx);
+        let thread_store = ThreadStore::try_global(cx)
+            .filter(|_| query.trim().chars().count() >= MIN_CONTENT_SEARCH_QUERY_LEN);
+        let Some(thread_store) = thread_store else {
+            self._content_search_task = Task::ready(());
+            if !self.content_matches.is_empty() {
+                self.content_matches.clear();
+                self.update_items(cx);
+            }
+            return;
+        };
+
+        self._content_search_task = cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(CONTENT_SEARCH_DEBOUNCE)
+                .await;
+            let search = thread_store.update(cx, |store, cx| {
+                store.search_threads(query, CONTENT_SEARCH_LIMIT, cx)
+            });
+            let matches = search.await.log_err().unwrap_or_default();
+            this.update(cx, |this, cx| {
+                this.content_matches.clear();
+                // Matches come best first, so keep the first one per thread.
+                for content_match in matches {
+                    this.content_matches
+                        .entry(content_match.id.clone())
+                        .or_insert(content_match);
+                }
+                this.preserve_selection_on_next_update = true;
+                this.update_items(cx);
+            })
+            .ok();
+        });
+    }
+
     fn reload_branch_names_if_threads_changed(&mut self, cx: &mut Context<Self>) {
         let current_ids: HashSet<ThreadId> = self
             .items
@@ -450,6 +565,13 @@ impl ThreadsArchiveView {
             return;
         }
 
+        let user_message_ix = self
+            .selected_content_match(&thread)
+            .and_then(|m| m.user_message_ix);
+        if let Some(user_message_ix) = user_message_ix {
+            jump_to_user_message_when_shown(thread.thread_id, user_message_ix, cx);
+        }
+
         if thread.folder_paths().is_empty() {
             self.show_project_picker_for_thread(thread, window, cx);
             return;
@@ -461,6 +583,17 @@ impl ThreadsArchiveView {
         cx.emit(ThreadsArchiveViewEvent::Activate { thread });
     }
 
+    fn selected_content_match(&self, thread: &ThreadMetadata) -> Option<&DbThreadSearchMatch> {
+        self.items.iter().find_map(|item| match item {
+            ArchiveListItem::Entry {
+                thread: entry,
+                content_match,
+                ..
+            } if entry.thread_id == thread.thread_id => content_match.as_ref(),
+            _ => None,
+        })
+    }
+
     fn show_project_picker_for_thread(
         &mut self,
         thread: ThreadMetadata,
@@ -616,6 +749,8 @@ impl ThreadsArchiveView {
             ArchiveListItem::Entry {
                 thread,
                 highlight_positions,
+                content_match,
+                fork_depth,
             } => {
                 let id = SharedString::from(format!("archive-entry-{}", ix));
 
@@ -687,7 +822,7 @@ impl ThreadsArchiveView {
                         }
                     }));
 
-                if is_restoring {
+                let entry = if is_restoring {
                     base.status(AgentThreadStatus::Running)
                         .action_slot(
                             IconButton::new("cancel-restore", IconName::Close)
@@ -779,6 +914,30 @@ impl ThreadsArchiveView {
                         })
                     })
                     .into_any_element()
+                };
+
+                let entry = match content_match {
+                    Some(content_match) => v_flex()
+                        .child(entry)
+                        .child(
+                            h_flex().pl_8().pr_2().pb_1().child(
+                                Label::new(content_match.snippet.clone())
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            ),
+                        )
+                        .into_any_element(),
+                    None => entry,
+                };
+
+                if *fork_depth > 0 {
+                    div()
+                        .pl(rems(0.75 * *fork_depth as f32))
+                        .child(entry)
+                        .into_any_element()
+                } else {
+                    entry
                 }
             }
         }
@@ -1635,6 +1794,51 @@ impl PickerDelegate for ProjectPickerDelegate {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::thread_metadata_store::WorktreePaths;
+
+    #[test]
+    fn test_nest_forks() {
+        let thread = |forked_from: Option<ThreadId>| ThreadMetadata {
+            thread_id: ThreadId::new(),
+            session_id: None,
+            agent_id: AgentId::new("agent"),
+            title: None,
+            title_override: None,
+            updated_at: Utc::now(),
+            created_at: None,
+            interacted_at: None,
+            worktree_paths: WorktreePaths::from_folder_paths(&PathList::default()),
+            remote_connection: None,
+            archived: false,
+            forked_from,
+        };
+        let parent = thread(None);
+        let other = thread(None);
+        let fork = thread(Some(parent.thread_id));
+        let fork_of_fork = thread(Some(fork.thread_id));
+        let orphan = thread(Some(ThreadId::new()));
+
+        let nested = nest_forks(vec![
+            fork_of_fork.clone(),
+            other.clone(),
+            fork.clone(),
+            orphan.clone(),
+            parent.clone(),
+        ]);
+        assert_eq!(
+            nested
+                .iter()
+                .map(|(thread, depth)| (thread.thread_id, *depth))
+                .collect::<Vec<_>>(),
+            vec![
+                (other.thread_id, 0),
+                (orphan.thread_id, 0),
+                (parent.thread_id, 0),
+                (fork.thread_id, 1),
+                (fork_of_fork.thread_id, 2),
+            ]
+        );
+    }
 
     #[test]
     fn test_fuzzy_match_positions_returns_byte_indices() {
@@ -0,0 +1,261 @@
+use agent::{DbUsageReportRow, ThreadStore};
+use collections::HashMap;
+use gpui::{
+    App, Context, DismissEvent, EventEmitter, FocusHandle, Focusable, Render, SharedString, Task,
+    Window,
+};
+use ui::{Modal, ModalHeader, Section, SectionHeader, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::{OpenUsageReport, humanize_token_count};
+
+/// Spend recorded by the agent, summed by model and by project.
+pub struct UsageReportModal {
+    focus_handle: FocusHandle,
+    by_model: Vec<UsageSummary>,
+    by_project: Vec<UsageSummary>,
+    is_loading: bool,
+    _load_task: Task<()>,
+}
+
+#[derive(Debug, PartialEq)]
+struct UsageSummary {
+    label: SharedString,
+    requests: u64,
+    tokens: u64,
+    /// `None` when none of the requests were made with a model that reports
+    /// its prices.
+    cost: Option<f64>,
+}
+
+impl UsageReportModal {
+    pub fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _cx: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(|workspace, _: &OpenUsageReport, window, cx| {
+            workspace.toggle_modal(window, cx, Self::new);
+        });
+    }
+
+    fn new(_window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let report = ThreadStore::global(cx).update(cx, |store, cx| store.usage_report(cx));
+        let load_task = cx.spawn(async move |this, cx| {
+            let rows = report.await.log_err().unwrap_or_default();
+            this.update(cx, |this, cx| {
+                this.by_model = summarize(&rows, |row| row.model.clone());
+                this.by_project = summarize(&rows, project_label);
+                this.is_loading = false;
+                cx.notify();
+            })
+            .ok();
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            by_model: Vec::new(),
+            by_project: Vec::new(),
+            is_loading: true,
+            _load_task: load_task,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_section(&self, title: &'static str, summaries: &[UsageSummary]) -> Section {
+        Section::new()
+            .header(SectionHeader::new(title))
+            .child(v_flex().gap_1().children(summaries.iter().map(|summary| {
+                let cost = summary
+                    .cost
+                    .map_or_else(|| "—".to_string(), |cost| format!("${cost:.2}"));
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .justify_between()
+                    .child(Label::new(summary.label.clone()).truncate())
+                    .child(
+                        h_flex()
+                            .flex_none()
+                            .gap_3()
+                            .child(
+                                Label::new(format!("{} requests", summary.requests))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                Label::new(format!(
+                                    "{} tokens",
+                                    humanize_token_count(summary.tokens)
+                                ))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                            )
+                            .child(Label::new(cost).size(LabelSize::Small)),
+                    )
+            })))
+    }
+}
+
+fn project_label(row: &DbUsageReportRow) -> SharedString {
+    if row.folder_paths.is_empty() {
+        return "No Project".into();
+    }
+    row.folder_paths
+        .paths()
+        .iter()
+        .map(|path| {
+            path.file_name()
+                .map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy())
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+        .into()
+}
+
+/// Sums the rows that share a label, most expensive first.
+fn summarize(
+    rows: &[DbUsageReportRow],
+    label: impl Fn(&DbUsageReportRow) -> SharedString,
+) -> Vec<UsageSummary> {
+    let mut summaries = HashMap::<SharedString, UsageSummary>::default();
+    for row in rows {
+        let label = label(row);
+        let summary = summaries
+            .entry(label.clone())
+            .or_insert_with(|| UsageSummary {
+                label,
+                requests: 0,
+                tokens: 0,
+                cost: None,
+            });
+        summary.requests += row.requests;
+        summary.tokens += row.total_tokens();
+        if let Some(cost) = row.cost {
+            *summary.cost.get_or_insert(0.0) += cost;
+        }
+    }
+    let mut summaries = summaries.into_values().collect::<Vec<_>>();
+    summaries.sort_by(|a, b| {
+        b.cost
+            .unwrap_or(0.0)
+            .total_cmp(&a.cost.unwrap_or(0.0))
+            .then(b.tokens.cmp(&a.tokens))
+            .then(a.label.cmp(&b.label))
+    });
+    summaries
+}
+
+impl EventEmitter<DismissEvent> for UsageReportModal {}
+
+impl Focusable for UsageReportModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ModalView for UsageReportModal {}
+
+impl Render for UsageReportModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_empty = self.by_model.is_empty();
+
+        v_flex()
+            .id("agent-usage-report")
+            .key_context("UsageReportModal")
+            .w(rems(34.))
+            .max_h(rems(40.))
+            .elevation_3(cx)
+            .overflow_hidden()
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cancel))
+            .child(
+                Modal::new("agent-usage-report-modal", None)
+                    .header(
+                        ModalHeader::new()
+                            .headline("Agent Usage")
+                            .description(
+                                "Tokens and spend from every request the agent has made. \
+                                Costs are only known for models that report their prices.",
+                            )
+                            .show_dismiss_button(true),
+                    )
+                    .map(|modal| {
+                        if self.is_loading || is_empty {
+                            let message = if self.is_loading {
+                                "Loading usage…"
+                            } else {
+                                "No usage recorded yet."
+                            };
+                            modal.section(
+                                Section::new().child(
+                                    Label::new(message)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                            )
+                        } else {
+                            modal
+                                .section(self.render_section("By Model", &self.by_model))
+                                .section(self.render_section("By Project", &self.by_project))
+                        }
+                    }),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::path_list::PathList;
+
+    fn row(model: &str, project: &str, tokens: u64, cost: Option<f64>) -> DbUsageReportRow {
+        DbUsageReportRow {
+            model: model.to_string().into(),
+            folder_paths: PathList::new(&[project]),
+            requests: 1,
+            input_tokens: tokens,
+            output_tokens: 0,
+            cache_tokens: 0,
+            cost,
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let rows = vec![
+            row("anthropic/sonnet", "/zed", 1_000, Some(1.0)),
+            row("ollama/llama", "/zed", 5_000, None),
+            row("anthropic/sonnet", "/other", 2_000, Some(2.0)),
+        ];
+
+        assert_eq!(
+            summarize(&rows, |row| row.model.clone()),
+            vec![
+                UsageSummary {
+                    label: "anthropic/sonnet".into(),
+                    requests: 2,
+                    tokens: 3_000,
+                    cost: Some(3.0),
+                },
+                UsageSummary {
+                    label: "ollama/llama".into(),
+                    requests: 1,
+                    tokens: 5_000,
+                    cost: None,
+                },
+            ]
+        );
+        assert_eq!(
+            summarize(&rows, project_label)
+                .into_iter()
+                .map(|summary| (summary.label, summary.cost))
+                .collect::<Vec<_>>(),
+            vec![("other".into(), Some(2.0)), ("zed".into(), Some(1.0))]
+        );
+    }
+}
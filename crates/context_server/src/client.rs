@@ -54,6 +54,7 @@ pub(crate) struct Client {
     name: Arc<str>,
     subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
     io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
@@ -148,12 +149,22 @@ struct AnyNotification<'a> {
     params: Option<Value>,
 }
 
+/// A JSON-RPC error, either received from the context server or sent in
+/// response to one of its requests.
 #[derive(Debug, Serialize, Deserialize)]
-pub(crate) struct Error {
+pub struct Error {
     pub message: String,
     pub code: i32,
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ModelContextServerBinary {
     pub executable: PathBuf,
@@ -212,12 +223,14 @@ impl Client {
             let response_handlers = response_handlers.clone();
             let request_handlers = request_handlers.clone();
             let transport = transport.clone();
+            let outbound_tx = outbound_tx.clone();
             async move |cx| {
                 Self::handle_input(
                     transport,
                     subscription_set,
                     request_handlers,
                     response_handlers,
+                    outbound_tx,
                     cx,
                 )
                 .log_err()
@@ -251,6 +264,7 @@ impl Client {
             server_id,
             subscription_set,
             response_handlers,
+            request_handlers,
             name: server_name,
             next_id: Default::default(),
             outbound_tx,
@@ -274,6 +288,7 @@ impl Client {
         subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
         request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: async_channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
@@ -288,6 +303,17 @@ impl Client {
                         request.params.unwrap_or(RawValue::NULL),
                         cx.clone(),
                     );
+                } else {
+                    // Servers wait for a response to every request, so reply even when we
+                    // don't support the method.
+                    let response = error_response(
+                        request.id,
+                        Error {
+                            message: format!("Method not found: {}", request.method),
+                            code: METHOD_NOT_FOUND,
+                        },
+                    );
+                    outbound_tx.try_send(response).log_err();
                 }
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut()
@@ -505,6 +531,53 @@ impl Client {
         self.transport.set_protocol_version(version);
     }
 
+    /// Registers a handler for requests sent by the context server, replacing
+    /// any previous handler for the same method.
+    ///
+    /// The task returned by the handler resolves to the response's result. An
+    /// [`Error`] returned from it is sent to the server as is; any other error
+    /// is reported as an internal error.
+    pub fn on_request(
+        &self,
+        method: &'static str,
+        mut f: Box<dyn 'static + Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>,
+    ) {
+        let outbound_tx = self.outbound_tx.clone();
+        self.request_handlers.lock().insert(
+            method,
+            Box::new(move |id, params, cx| {
+                let result = match serde_json::from_str(params.get()) {
+                    Ok(params) => f(params, cx.clone()),
+                    Err(error) => Task::ready(Err(Error {
+                        message: error.to_string(),
+                        code: INVALID_PARAMS,
+                    }
+                    .into())),
+                };
+                let outbound_tx = outbound_tx.clone();
+                cx.spawn(async move |_| {
+                    let response = match result.await {
+                        Ok(result) => serde_json::to_string(&Response {
+                            jsonrpc: JSON_RPC_VERSION,
+                            id,
+                            value: CspResult::Ok(Some(result)),
+                        })
+                        .unwrap(),
+                        Err(error) => {
+                            let error = error.downcast::<Error>().unwrap_or_else(|error| Error {
+                                message: error.to_string(),
+                                code: INTERNAL_ERROR,
+                            });
+                            error_response(id, error)
+                        }
+                    };
+                    outbound_tx.try_send(response).log_err();
+                })
+                .detach();
+            }),
+        );
+    }
+
     #[must_use]
     pub fn on_notification(
         &self,
@@ -520,6 +593,15 @@ impl Client {
     }
 }
 
+fn error_response(id: RequestId, error: Error) -> String {
+    serde_json::to_string(&Response::<()> {
+        jsonrpc: JSON_RPC_VERSION,
+        id,
+        value: CspResult::Error(Some(error)),
+    })
+    .unwrap()
+}
+
 #[derive(Debug)]
 pub struct RequestCanceled;
 
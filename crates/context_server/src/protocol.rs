@@ -9,7 +9,7 @@ use std::time::Duration;
 
 use anyhow::Result;
 use futures::{channel::oneshot, future::BoxFuture};
-use gpui::AsyncApp;
+use gpui::{AppContext as _, AsyncApp, Task};
 use serde_json::Value;
 
 use crate::client::{Client, NotificationSubscription};
@@ -42,7 +42,8 @@ impl ModelContextProtocol {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             capabilities: types::ClientCapabilities {
                 experimental: None,
-                sampling: None,
+                sampling: Some(serde_json::json!({})),
+                elicitation: Some(serde_json::json!({})),
                 roots: None,
             },
             meta: None,
@@ -144,4 +145,28 @@ impl InitializedContextServerProtocol {
     ) -> NotificationSubscription {
         self.inner.on_notification(method, f)
     }
+
+    /// Handles requests of type `T` sent by the server.
+    pub fn on_request<T: Request>(
+        &self,
+        mut f: impl 'static + Send + FnMut(T::Params, AsyncApp) -> Task<Result<T::Response>>,
+    ) {
+        self.inner.on_request(
+            T::METHOD,
+            Box::new(move |params, cx| {
+                let params = match serde_json::from_value(params) {
+                    Ok(params) => params,
+                    Err(error) => {
+                        return Task::ready(Err(crate::client::Error {
+                            message: error.to_string(),
+                            code: crate::client::INVALID_PARAMS,
+                        }
+                        .into()));
+                    }
+                };
+                let response = f(params, cx.clone());
+                cx.background_spawn(async move { Ok(serde_json::to_value(response.await?)?) })
+            }),
+        );
+    }
 }
@@ -1,8 +1,16 @@
 use anyhow::Context as _;
 use collections::HashMap;
-use futures::{FutureExt, Stream, StreamExt as _, future::BoxFuture, lock::Mutex};
+use futures::{
+    FutureExt, Stream, StreamExt as _, channel::oneshot, future::BoxFuture, lock::Mutex,
+};
 use gpui::BackgroundExecutor;
-use std::{pin::Pin, sync::Arc};
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering::SeqCst},
+    },
+};
 
 use crate::{
     transport::Transport,
@@ -43,6 +51,8 @@ pub struct FakeTransport {
     >,
     tx: futures::channel::mpsc::UnboundedSender<String>,
     rx: Arc<Mutex<futures::channel::mpsc::UnboundedReceiver<String>>>,
+    pending_responses: parking_lot::Mutex<HashMap<u64, oneshot::Sender<serde_json::Value>>>,
+    next_request_id: AtomicU64,
     executor: BackgroundExecutor,
 }
 
@@ -53,10 +63,45 @@ impl FakeTransport {
             request_handlers: Default::default(),
             tx,
             rx: Arc::new(Mutex::new(rx)),
+            pending_responses: Default::default(),
+            next_request_id: Default::default(),
             executor,
         }
     }
 
+    /// Sends a request from the server to the client, resolving to the
+    /// client's JSON-RPC response.
+    pub fn request<T: crate::types::Request>(
+        &self,
+        params: T::Params,
+    ) -> BoxFuture<'static, serde_json::Value> {
+        let id = self.next_request_id.fetch_add(1, SeqCst);
+        let (response_tx, response_rx) = oneshot::channel();
+        self.pending_responses.lock().insert(id, response_tx);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": T::METHOD,
+            "params": params,
+        });
+        self.tx
+            .unbounded_send(request.to_string())
+            .expect("transport was dropped");
+        async move { response_rx.await.expect("client dropped the request") }.boxed()
+    }
+
+    /// Sends a notification from the server to the client.
+    pub fn notify(&self, method: &str, params: serde_json::Value) {
+        let notification = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        });
+        self.tx
+            .unbounded_send(notification.to_string())
+            .expect("transport was dropped");
+    }
+
     pub fn on_request<T, Fut>(
         mut self,
         handler: impl 'static + Send + Sync + Fn(T::Params) -> Fut,
@@ -103,6 +148,8 @@ impl Transport for FakeTransport {
                 } else {
                     log::debug!("No handler registered for MCP request '{method}'");
                 }
+            } else if let Some(response_tx) = self.pending_responses.lock().remove(&id) {
+                response_tx.send(msg).ok();
             }
         }
         Ok(())
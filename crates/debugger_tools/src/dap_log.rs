@@ -1090,6 +1090,8 @@ impl SearchableItem for DapLogView {
             case: true,
             word: true,
             regex: true,
+            structural: false,
+            scopes: false,
             find_in_results: true,
             // DAP log is read-only.
             replacement: false,
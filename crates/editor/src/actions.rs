@@ -828,6 +828,8 @@ actions!(
         ShowCompletions,
         /// Shows the system character palette.
         ShowCharacterPalette,
+        /// Shows a color picker for the color under the cursor.
+        ShowColorPicker,
         /// Shows edit prediction at cursor.
         ShowEditPrediction,
         /// Shows signature help for the current function.
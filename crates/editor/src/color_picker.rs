@@ -0,0 +1,1223 @@
+use std::{
+    ops::Range,
+    sync::{Arc, LazyLock},
+};
+
+use collections::HashMap;
+use gpui::{
+    Bounds, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Hsla, MouseButton,
+    MouseDownEvent, MouseMoveEvent, Pixels, Point as GpuiPoint, Rgba, Subscription, canvas,
+    linear_color_stop, linear_gradient,
+};
+use language::{Buffer, point_from_lsp};
+use multi_buffer::{Anchor, MultiBufferRow, MultiBufferSnapshot, ToPoint as _};
+use project::{ColorPresentation, DocumentColor};
+use regex::Regex;
+use text::{Bias, BufferId, Point};
+use ui::{ToggleButtonGroup, ToggleButtonGroupStyle, ToggleButtonSimple, prelude::*};
+use util::ResultExt as _;
+
+use crate::{
+    Editor, EditorEvent, PointForPosition,
+    actions::{SelectAll, ShowColorPicker},
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+    scroll::Autoscroll,
+};
+
+/// The number of lines the inline color picker occupies below the color.
+const COLOR_PICKER_BLOCK_HEIGHT: u32 = 8;
+
+static COLOR_LITERAL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)#[0-9a-f]{3,8}\b|\b(?:rgba?|hsla?|oklch)\([^()]*\)")
+        .expect("Failed to create COLOR_LITERAL_REGEX")
+});
+
+/// The syntaxes a color can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorFormat {
+    Hex,
+    Rgb,
+    Hsl,
+    Oklch,
+}
+
+impl ColorFormat {
+    const ALL: [Self; 4] = [Self::Hex, Self::Rgb, Self::Hsl, Self::Oklch];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Hex => "Hex",
+            Self::Rgb => "RGB",
+            Self::Hsl => "HSL",
+            Self::Oklch => "OKLCH",
+        }
+    }
+}
+
+/// Parses a CSS color literal, such as `#ff8800`, `rgb(255 136 0 / 50%)`,
+/// `hsl(32, 100%, 50%)` or `oklch(75% 0.18 55)`.
+pub fn parse_color_literal(text: &str) -> Option<(Rgba, ColorFormat)> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix('#') {
+        if !matches!(hex.len(), 3 | 4 | 6 | 8) {
+            return None;
+        }
+        return Some((Rgba::try_from(text).ok()?, ColorFormat::Hex));
+    }
+
+    let (name, arguments) = text.strip_suffix(')')?.split_once('(')?;
+    let arguments = arguments
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|argument| !argument.is_empty())
+        .collect::<Vec<_>>();
+    if !matches!(arguments.len(), 3 | 4) {
+        return None;
+    }
+    let alpha = match arguments.get(3) {
+        Some(alpha) => parse_fraction(alpha, 1.0)?,
+        None => 1.0,
+    };
+
+    match name.trim().to_ascii_lowercase().as_str() {
+        "rgb" | "rgba" => Some((
+            Rgba {
+                r: parse_fraction(arguments[0], 255.0)?,
+                g: parse_fraction(arguments[1], 255.0)?,
+                b: parse_fraction(arguments[2], 255.0)?,
+                a: alpha,
+            },
+            ColorFormat::Rgb,
+        )),
+        "hsl" | "hsla" => {
+            let hsla = Hsla {
+                h: parse_hue(arguments[0])? / 360.0,
+                s: parse_fraction(arguments[1], 100.0)?,
+                l: parse_fraction(arguments[2], 100.0)?,
+                a: alpha,
+            };
+            Some((Rgba::from(hsla), ColorFormat::Hsl))
+        }
+        "oklch" => Some((
+            oklch_to_rgba(
+                parse_fraction(arguments[0], 1.0)?,
+                // Percentages are relative to 0.4, the chroma of the most saturated sRGB colors.
+                match arguments[1].strip_suffix('%') {
+                    Some(percentage) => percentage.parse::<f32>().ok()? / 100.0 * 0.4,
+                    None => arguments[1].parse::<f32>().ok()?,
+                },
+                parse_hue(arguments[2])?,
+                alpha,
+            ),
+            ColorFormat::Oklch,
+        )),
+        _ => None,
+    }
+}
+
+/// Parses a number or percentage, dividing plain numbers by `scale`.
+fn parse_fraction(text: &str, scale: f32) -> Option<f32> {
+    let value = match text.strip_suffix('%') {
+        Some(percentage) => percentage.parse::<f32>().ok()? / 100.0,
+        None => text.parse::<f32>().ok()? / scale,
+    };
+    value.is_finite().then_some(value.clamp(0.0, 1.0))
+}
+
+fn parse_hue(text: &str) -> Option<f32> {
+    let hue = text
+        .strip_suffix("deg")
+        .unwrap_or(text)
+        .parse::<f32>()
+        .ok()?;
+    hue.is_finite().then_some(hue.rem_euclid(360.0))
+}
+
+/// Writes `color` in the given syntax.
+pub fn format_color(color: Rgba, format: ColorFormat) -> String {
+    let is_opaque = color.a >= 1.0;
+    let alpha = format_number(color.a, 2);
+    match format {
+        ColorFormat::Hex => {
+            let [r, g, b, a] = [color.r, color.g, color.b, color.a]
+                .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+            if is_opaque {
+                format!("#{r:02x}{g:02x}{b:02x}")
+            } else {
+                format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+            }
+        }
+        ColorFormat::Rgb => {
+            let [r, g, b] = [color.r, color.g, color.b]
+                .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+            if is_opaque {
+                format!("rgb({r}, {g}, {b})")
+            } else {
+                format!("rgba({r}, {g}, {b}, {alpha})")
+            }
+        }
+        ColorFormat::Hsl => {
+            let hsla = Hsla::from(color);
+            let h = (hsla.h * 360.0).round() as u32 % 360;
+            let s = (hsla.s * 100.0).round() as u32;
+            let l = (hsla.l * 100.0).round() as u32;
+            if is_opaque {
+                format!("hsl({h}, {s}%, {l}%)")
+            } else {
+                format!("hsla({h}, {s}%, {l}%, {alpha})")
+            }
+        }
+        ColorFormat::Oklch => {
+            let (l, c, h) = rgba_to_oklch(color);
+            let (l, c, h) = (
+                format_number(l * 100.0, 1),
+                format_number(c, 3),
+                format_number(h, 1),
+            );
+            if is_opaque {
+                format!("oklch({l}% {c} {h})")
+            } else {
+                format!("oklch({l}% {c} {h} / {alpha})")
+            }
+        }
+    }
+}
+
+fn format_number(value: f32, decimals: usize) -> String {
+    let formatted = format!("{value:.decimals$}");
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        formatted
+    }
+}
+
+fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(channel: f32) -> f32 {
+    let channel = if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    };
+    channel.clamp(0.0, 1.0)
+}
+
+/// Converts an sRGB color to OKLCH lightness (0 to 1), chroma, and hue in degrees.
+fn rgba_to_oklch(color: Rgba) -> (f32, f32, f32) {
+    let (r, g, b) = (
+        srgb_to_linear(color.r),
+        srgb_to_linear(color.g),
+        srgb_to_linear(color.b),
+    );
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    let lightness = 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s;
+    let a = 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s;
+    let b = 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s;
+
+    let chroma = (a * a + b * b).sqrt();
+    let hue = if chroma < 1e-4 {
+        0.0
+    } else {
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+    };
+    (lightness, chroma, hue)
+}
+
+/// Converts an OKLCH color to sRGB, clipping colors outside of the sRGB gamut.
+fn oklch_to_rgba(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Rgba {
+    let (a, b) = (
+        chroma * hue.to_radians().cos(),
+        chroma * hue.to_radians().sin(),
+    );
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    Rgba {
+        r: linear_to_srgb(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+        g: linear_to_srgb(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+        b: linear_to_srgb(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+        a: alpha,
+    }
+}
+
+/// Finds the color literal in `line` that contains the byte offset `column`.
+fn find_color_literal(line: &str, column: usize) -> Option<Range<usize>> {
+    COLOR_LITERAL_REGEX
+        .find_iter(line)
+        .find(|found| {
+            found.start() <= column
+                && column <= found.end()
+                && parse_color_literal(found.as_str()).is_some()
+        })
+        .map(|found| found.range())
+}
+
+/// Picks the language server presentation to apply for `format`.
+///
+/// Presentations written as CSS colors are only used when they match `format`,
+/// so that formats the server doesn't offer can still be chosen: `None` means
+/// the color should be written as CSS instead. Presentations in a
+/// language-specific syntax are used as they are, starting with the first one.
+fn pick_presentation(
+    presentations: &[ColorPresentation],
+    format: ColorFormat,
+) -> Option<&ColorPresentation> {
+    let mut has_css_presentations = false;
+    for presentation in presentations {
+        if let Some((_, presentation_format)) = parse_color_literal(&presentation.label) {
+            if presentation_format == format {
+                return Some(presentation);
+            }
+            has_css_presentations = true;
+        }
+    }
+    if has_css_presentations {
+        None
+    } else {
+        presentations.first()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum ColorChannel {
+    Hue,
+    Saturation,
+    Lightness,
+    Alpha,
+}
+
+impl ColorChannel {
+    const ALL: [Self; 4] = [Self::Hue, Self::Saturation, Self::Lightness, Self::Alpha];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Hue => "H",
+            Self::Saturation => "S",
+            Self::Lightness => "L",
+            Self::Alpha => "A",
+        }
+    }
+
+    fn value(self, color: Hsla) -> f32 {
+        match self {
+            Self::Hue => color.h,
+            Self::Saturation => color.s,
+            Self::Lightness => color.l,
+            Self::Alpha => color.a,
+        }
+    }
+
+    fn set_value(self, color: &mut Hsla, value: f32) {
+        let value = value.clamp(0.0, 1.0);
+        match self {
+            // A hue of 1.0 wraps around to red, which would make the slider jump back.
+            Self::Hue => color.h = value.min(0.999),
+            Self::Saturation => color.s = value,
+            Self::Lightness => color.l = value,
+            Self::Alpha => color.a = value,
+        }
+    }
+
+    fn display_value(self, color: Hsla) -> String {
+        match self {
+            Self::Hue => format!("{}°", (color.h * 360.0).round()),
+            _ => format!("{}%", (self.value(color) * 100.0).round()),
+        }
+    }
+
+    /// The gradient segments drawn behind the slider for `color`.
+    fn gradient(self, color: Hsla) -> Vec<(Hsla, Hsla)> {
+        match self {
+            Self::Hue => (0..6)
+                .map(|segment| {
+                    (
+                        Hsla {
+                            h: segment as f32 / 6.0,
+                            s: 1.0,
+                            l: 0.5,
+                            a: 1.0,
+                        },
+                        Hsla {
+                            h: ((segment + 1) as f32 / 6.0).min(0.999),
+                            s: 1.0,
+                            l: 0.5,
+                            a: 1.0,
+                        },
+                    )
+                })
+                .collect(),
+            Self::Saturation => vec![(Hsla { s: 0.0, ..color }, Hsla { s: 1.0, ..color })],
+            Self::Lightness => vec![
+                (Hsla { l: 0.0, ..color }, Hsla { l: 0.5, ..color }),
+                (Hsla { l: 0.5, ..color }, Hsla { l: 1.0, ..color }),
+            ],
+            Self::Alpha => vec![(Hsla { a: 0.0, ..color }, Hsla { a: 1.0, ..color })],
+        }
+    }
+}
+
+pub(crate) enum ColorPickerEvent {
+    Confirmed,
+}
+
+/// An inline editor for a single color value.
+pub(crate) struct ColorPicker {
+    color: Hsla,
+    format: ColorFormat,
+    value_editor: Entity<Editor>,
+    focus_handle: FocusHandle,
+    slider_bounds: HashMap<ColorChannel, Bounds<Pixels>>,
+    dragged_channel: Option<ColorChannel>,
+    updating_value_editor: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EventEmitter<ColorPickerEvent> for ColorPicker {}
+impl EventEmitter<DismissEvent> for ColorPicker {}
+
+impl Focusable for ColorPicker {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ColorPicker {
+    fn new(color: Hsla, format: ColorFormat, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let focus_handle = cx.focus_handle();
+        let value_editor = cx.new(|cx| Editor::single_line(window, cx));
+        let subscriptions = vec![
+            cx.subscribe_in(&value_editor, window, Self::on_value_editor_event),
+            cx.on_focus_out(&focus_handle, window, |_, _, _, cx| cx.emit(DismissEvent)),
+        ];
+
+        let mut this = Self {
+            color,
+            format,
+            value_editor,
+            focus_handle,
+            slider_bounds: HashMap::default(),
+            dragged_channel: None,
+            updating_value_editor: false,
+            _subscriptions: subscriptions,
+        };
+        this.update_value_editor(window, cx);
+        this
+    }
+
+    fn rgba(&self) -> Rgba {
+        Rgba::from(self.color)
+    }
+
+    fn update_value_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = format_color(self.rgba(), self.format);
+        self.updating_value_editor = true;
+        self.value_editor.update(cx, |editor, cx| {
+            editor.set_text(text, window, cx);
+            editor.select_all(&SelectAll, window, cx);
+        });
+        self.updating_value_editor = false;
+        cx.notify();
+    }
+
+    fn on_value_editor_event(
+        &mut self,
+        value_editor: &Entity<Editor>,
+        event: &EditorEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.updating_value_editor || !matches!(event, EditorEvent::BufferEdited) {
+            return;
+        }
+        if let Some((color, format)) = parse_color_literal(&value_editor.read(cx).text(cx)) {
+            self.color = color.into();
+            self.format = format;
+            cx.notify();
+        }
+    }
+
+    fn set_format(&mut self, format: ColorFormat, window: &mut Window, cx: &mut Context<Self>) {
+        self.format = format;
+        self.update_value_editor(window, cx);
+    }
+
+    fn set_channel_from_position(
+        &mut self,
+        channel: ColorChannel,
+        position: GpuiPoint<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(bounds) = self.slider_bounds.get(&channel) else {
+            return;
+        };
+        if bounds.size.width <= Pixels::ZERO {
+            return;
+        }
+        let value = (position.x - bounds.left()) / bounds.size.width;
+        channel.set_value(&mut self.color, value);
+        self.update_value_editor(window, cx);
+    }
+
+    fn handle_mouse_move(
+        &mut self,
+        event: &MouseMoveEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(channel) = self.dragged_channel else {
+            return;
+        };
+        if event.pressed_button == Some(MouseButton::Left) {
+            self.set_channel_from_position(channel, event.position, window, cx);
+        } else {
+            self.dragged_channel = None;
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(ColorPickerEvent::Confirmed);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_slider(&self, channel: ColorChannel, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let last_bounds = self.slider_bounds.get(&channel).copied();
+        let handle_color = if cx.theme().appearance().is_light() {
+            gpui::black()
+        } else {
+            gpui::white()
+        };
+
+        h_flex()
+            .gap_2()
+            .child(
+                div().w_3().child(
+                    Label::new(channel.label())
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                ),
+            )
+            .child(
+                div()
+                    .id(SharedString::from(format!(
+                        "color-picker-{}",
+                        channel.label()
+                    )))
+                    .relative()
+                    .flex_1()
+                    .h_3()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        h_flex()
+                            .size_full()
+                            .rounded_sm()
+                            .overflow_hidden()
+                            .children(channel.gradient(self.color).into_iter().map(
+                                |(from, to)| {
+                                    div().h_full().flex_1().bg(linear_gradient(
+                                        90.,
+                                        linear_color_stop(from, 0.),
+                                        linear_color_stop(to, 1.),
+                                    ))
+                                },
+                            )),
+                    )
+                    .child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .bottom_0()
+                            .left(relative(channel.value(self.color)))
+                            .ml(px(-2.))
+                            .w_1()
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(handle_color)
+                            .bg(handle_color.opacity(0.5)),
+                    )
+                    .child(
+                        canvas(
+                            move |bounds, window, cx| {
+                                if last_bounds == Some(bounds) {
+                                    return;
+                                }
+                                window.defer(cx, move |_, cx| {
+                                    view.update(cx, |this, cx| {
+                                        this.slider_bounds.insert(channel, bounds);
+                                        cx.notify();
+                                    })
+                                    .ok();
+                                });
+                            },
+                            |_, _, _, _| {},
+                        )
+                        .absolute()
+                        .size_full(),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                            this.dragged_channel = Some(channel);
+                            this.set_channel_from_position(channel, event.position, window, cx);
+                        }),
+                    ),
+            )
+            .child(
+                div().w_10().child(
+                    Label::new(channel.display_value(self.color))
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
+
+impl Render for ColorPicker {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let formats = ColorFormat::ALL.map(|format| {
+            ToggleButtonSimple::new(
+                format.label(),
+                cx.listener(move |this, _, window, cx| this.set_format(format, window, cx)),
+            )
+        });
+        let selected_format = ColorFormat::ALL
+            .iter()
+            .position(|format| *format == self.format)
+            .unwrap_or_default();
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .key_context("ColorPicker")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_mouse_move(cx.listener(Self::handle_mouse_move))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, _, _| this.dragged_channel = None),
+            )
+            .w(rems(20.))
+            .p_1p5()
+            .gap_1()
+            .elevation_2(cx)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .size_5()
+                            .flex_none()
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(cx.theme().colors().border)
+                            .bg(self.color),
+                    )
+                    .child(
+                        ToggleButtonGroup::single_row("color-picker-format", formats)
+                            .style(ToggleButtonGroupStyle::Outlined)
+                            .label_size(LabelSize::XSmall)
+                            .auto_width()
+                            .selected_index(selected_format),
+                    ),
+            )
+            .children(
+                ColorChannel::ALL.map(|channel| self.render_slider(channel, cx).into_any_element()),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex_1()
+                            .px_1()
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(cx.theme().colors().border_variant)
+                            .child(self.value_editor.clone()),
+                    )
+                    .child(
+                        Button::new("apply-color", "Apply")
+                            .label_size(LabelSize::Small)
+                            .on_click(
+                                cx.listener(|_, _, _, cx| cx.emit(ColorPickerEvent::Confirmed)),
+                            ),
+                    ),
+            )
+    }
+}
+
+enum ColorPickerTarget {
+    /// A color reported by a language server, rewritten using its color presentations.
+    DocumentColor {
+        buffer_id: BufferId,
+        color: DocumentColor,
+    },
+    /// A color literal found in the text, for languages without a color provider.
+    Text,
+}
+
+pub(crate) struct ColorPickerState {
+    picker: Entity<ColorPicker>,
+    block_id: CustomBlockId,
+    range: Range<Anchor>,
+    target: ColorPickerTarget,
+    _subscriptions: [Subscription; 2],
+}
+
+impl Editor {
+    pub fn show_color_picker(
+        &mut self,
+        _: &ShowColorPicker,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let head = self.selections.newest_anchor().head();
+        if let Some((buffer_id, range, color)) = self
+            .colors
+            .as_ref()
+            .and_then(|colors| colors.color_at(head, &snapshot))
+        {
+            let target = ColorPickerTarget::DocumentColor { buffer_id, color };
+            self.open_color_picker(range, target, window, cx);
+        } else if let Some(range) = color_literal_at(&snapshot, head) {
+            self.open_color_picker(range, ColorPickerTarget::Text, window, cx);
+        }
+    }
+
+    /// Opens the color picker when an inlay color swatch is clicked.
+    pub(crate) fn show_color_picker_for_swatch(
+        &mut self,
+        point_for_position: &PointForPosition,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if point_for_position.as_valid().is_some()
+            || point_for_position.column_overshoot_after_line_end > 0
+        {
+            return false;
+        }
+        let Some(colors) = self.colors.as_ref() else {
+            return false;
+        };
+
+        let display_snapshot = self.display_snapshot(cx);
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let previous_valid = snapshot.anchor_at(
+            point_for_position
+                .previous_valid
+                .to_point(&display_snapshot),
+            Bias::Left,
+        );
+        let next_valid = snapshot.anchor_at(
+            point_for_position.next_valid.to_point(&display_snapshot),
+            Bias::Right,
+        );
+        let Some((buffer_id, range, color)) =
+            colors.color_for_swatch(previous_valid..next_valid, &snapshot)
+        else {
+            return false;
+        };
+
+        let target = ColorPickerTarget::DocumentColor { buffer_id, color };
+        self.open_color_picker(range, target, window, cx);
+        true
+    }
+
+    fn open_color_picker(
+        &mut self,
+        range: Range<Anchor>,
+        target: ColorPickerTarget,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        self.dismiss_color_picker(window, cx);
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let text = snapshot.text_for_range(range.clone()).collect::<String>();
+        let parsed = parse_color_literal(&text);
+        let color = match &target {
+            ColorPickerTarget::DocumentColor { color, .. } => Rgba {
+                r: color.color.red,
+                g: color.color.green,
+                b: color.color.blue,
+                a: color.color.alpha,
+            },
+            ColorPickerTarget::Text => match parsed {
+                Some((color, _)) => color,
+                None => return,
+            },
+        };
+        let format = parsed.map_or(ColorFormat::Hex, |(_, format)| format);
+
+        let picker = cx.new(|cx| ColorPicker::new(color.into(), format, window, cx));
+        let subscriptions = [
+            cx.subscribe_in(
+                &picker,
+                window,
+                |editor, _, _: &ColorPickerEvent, window, cx| {
+                    editor.apply_color_picker(window, cx);
+                },
+            ),
+            cx.subscribe_in(
+                &picker,
+                window,
+                |editor, _, _: &DismissEvent, window, cx| {
+                    editor.dismiss_color_picker(window, cx);
+                },
+            ),
+        ];
+        let block_id = self.insert_blocks(
+            [BlockProperties {
+                style: BlockStyle::Flex,
+                placement: BlockPlacement::Below(range.start),
+                height: Some(COLOR_PICKER_BLOCK_HEIGHT),
+                render: Arc::new({
+                    let picker = picker.clone();
+                    move |cx: &mut BlockContext| {
+                        div()
+                            .block_mouse_except_scroll()
+                            .pl(cx.anchor_x)
+                            .py_1()
+                            .child(picker.clone())
+                            .into_any_element()
+                    }
+                }),
+                priority: 0,
+            }],
+            Some(Autoscroll::fit()),
+            cx,
+        )[0];
+        let value_editor = picker.read(cx).value_editor.clone();
+        window.focus(&value_editor.focus_handle(cx), cx);
+
+        self.color_picker = Some(ColorPickerState {
+            picker,
+            block_id,
+            range,
+            target,
+            _subscriptions: subscriptions,
+        });
+    }
+
+    fn take_color_picker(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<ColorPickerState> {
+        let state = self.color_picker.take()?;
+        if state.picker.focus_handle(cx).contains_focused(window, cx) {
+            window.focus(&self.focus_handle, cx);
+        }
+        self.remove_blocks([state.block_id].into_iter().collect(), None, cx);
+        Some(state)
+    }
+
+    pub(crate) fn dismiss_color_picker(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.take_color_picker(window, cx).is_some()
+    }
+
+    fn apply_color_picker(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(state) = self.take_color_picker(window, cx) else {
+            return;
+        };
+        let (color, format) = {
+            let picker = state.picker.read(cx);
+            (picker.rgba(), picker.format)
+        };
+        let replacement = format_color(color, format);
+
+        let (buffer_id, document_color) = match state.target {
+            ColorPickerTarget::DocumentColor { buffer_id, color } => (buffer_id, color),
+            ColorPickerTarget::Text => {
+                self.transact(window, cx, |editor, _, cx| {
+                    editor.edit([(state.range, replacement)], cx);
+                });
+                return;
+            }
+        };
+        let buffer = self.buffer.read(cx).buffer(buffer_id);
+        let presentations = buffer
+            .clone()
+            .zip(self.project.clone())
+            .map(|(buffer, project)| {
+                project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+                    lsp_store.color_presentations(
+                        buffer,
+                        &document_color,
+                        lsp::Color {
+                            red: color.r,
+                            green: color.g,
+                            blue: color.b,
+                            alpha: color.a,
+                        },
+                        cx,
+                    )
+                })
+            });
+
+        let range = state.range;
+        cx.spawn_in(window, async move |editor, cx| {
+            let presentations = match presentations {
+                Some(presentations) => presentations.await.log_err().unwrap_or_default(),
+                None => Vec::new(),
+            };
+            editor.update_in(cx, |editor, window, cx| {
+                let Some(buffer) = buffer else {
+                    return;
+                };
+                // Without any presentation, the server's syntax for the color is unknown.
+                if presentations.is_empty() {
+                    return;
+                }
+                let edits = match pick_presentation(&presentations, format) {
+                    Some(presentation) => {
+                        editor.color_presentation_edits(&buffer, &range, presentation, cx)
+                    }
+                    None => vec![(range, replacement)],
+                };
+                if !edits.is_empty() {
+                    editor.transact(window, cx, |editor, _, cx| editor.edit(edits, cx));
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn color_presentation_edits(
+        &self,
+        buffer: &Entity<Buffer>,
+        color_range: &Range<Anchor>,
+        presentation: &ColorPresentation,
+        cx: &App,
+    ) -> Vec<(Range<Anchor>, String)> {
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        match &presentation.text_edit {
+            Some(text_edit) => edits.extend(lsp_edit_to_anchor_range(
+                &snapshot,
+                &buffer_snapshot,
+                text_edit,
+            )),
+            None => edits.push((color_range.clone(), presentation.label.to_string())),
+        }
+        edits.extend(
+            presentation
+                .additional_text_edits
+                .iter()
+                .filter_map(|edit| lsp_edit_to_anchor_range(&snapshot, &buffer_snapshot, edit)),
+        );
+        edits
+    }
+}
+
+fn lsp_edit_to_anchor_range(
+    snapshot: &MultiBufferSnapshot,
+    buffer_snapshot: &language::BufferSnapshot,
+    edit: &lsp::TextEdit,
+) -> Option<(Range<Anchor>, String)> {
+    let start = buffer_snapshot.clip_point_utf16(point_from_lsp(edit.range.start), Bias::Left);
+    let end = buffer_snapshot.clip_point_utf16(point_from_lsp(edit.range.end), Bias::Right);
+    let range = snapshot
+        .buffer_anchor_range_to_anchor_range(buffer_snapshot.anchor_range_outside(start..end))?;
+    Some((range, edit.new_text.clone()))
+}
+
+fn color_literal_at(snapshot: &MultiBufferSnapshot, position: Anchor) -> Option<Range<Anchor>> {
+    let point = position.to_point(snapshot);
+    let line_start = Point::new(point.row, 0);
+    let line_end = Point::new(point.row, snapshot.line_len(MultiBufferRow(point.row)));
+    let line = snapshot
+        .text_for_range(line_start..line_end)
+        .collect::<String>();
+    let range = find_color_literal(&line, point.column as usize)?;
+    Some(
+        snapshot.anchor_after(Point::new(point.row, range.start as u32))
+            ..snapshot.anchor_before(Point::new(point.row, range.end as u32)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use futures::StreamExt as _;
+    use gpui::{TestAppContext, VisualTestContext};
+    use language::FakeLspAdapter;
+    use languages::rust_lang;
+    use parking_lot::Mutex;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use util::path;
+    use workspace::{MultiWorkspace, OpenOptions};
+
+    use super::*;
+    use crate::{DisplayPoint, DisplayRow, LSP_REQUEST_DEBOUNCE_TIMEOUT, editor_tests::init_test};
+
+    fn assert_color_eq(actual: Rgba, expected: Rgba) {
+        let channels = |color: Rgba| [color.r, color.g, color.b, color.a];
+        for (actual_channel, expected_channel) in
+            channels(actual).into_iter().zip(channels(expected))
+        {
+            assert!(
+                (actual_channel - expected_channel).abs() < 0.01,
+                "expected {expected:?}, got {actual:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_color_literals() {
+        let orange = Rgba {
+            r: 1.0,
+            g: 136.0 / 255.0,
+            b: 0.0,
+            a: 1.0,
+        };
+        for (literal, format) in [
+            ("#ff8800", ColorFormat::Hex),
+            ("#F80", ColorFormat::Hex),
+            ("rgb(255, 136, 0)", ColorFormat::Rgb),
+            ("rgb(100% 53.33% 0%)", ColorFormat::Rgb),
+            ("hsl(32, 100%, 50%)", ColorFormat::Hsl),
+            ("oklch(75.16% 0.174 60.35)", ColorFormat::Oklch),
+        ] {
+            let (color, parsed_format) = parse_color_literal(literal).unwrap();
+            assert_eq!(parsed_format, format, "{literal}");
+            assert_color_eq(color, orange);
+        }
+
+        let (translucent, _) = parse_color_literal("rgba(0, 0, 255, 0.5)").unwrap();
+        assert_eq!(translucent.a, 0.5);
+        let (translucent, _) = parse_color_literal("rgb(0 0 255 / 25%)").unwrap();
+        assert_eq!(translucent.a, 0.25);
+
+        assert!(parse_color_literal("#f808").is_some());
+        assert!(parse_color_literal("#ff88f").is_none());
+        assert!(parse_color_literal("rgb(1, 2)").is_none());
+        assert!(parse_color_literal("lab(50% 40 59)").is_none());
+    }
+
+    #[test]
+    fn test_format_colors_round_trip() {
+        let color = Rgba {
+            r: 0.2,
+            g: 0.4,
+            b: 0.8,
+            a: 0.5,
+        };
+        assert_eq!(format_color(color, ColorFormat::Hex), "#3366cc80");
+        assert_eq!(
+            format_color(color, ColorFormat::Rgb),
+            "rgba(51, 102, 204, 0.5)"
+        );
+        assert_eq!(
+            format_color(color, ColorFormat::Hsl),
+            "hsla(220, 60%, 50%, 0.5)"
+        );
+        for format in ColorFormat::ALL {
+            let formatted = format_color(color, format);
+            let (parsed, parsed_format) = parse_color_literal(&formatted).unwrap();
+            assert_eq!(parsed_format, format, "{formatted}");
+            assert_color_eq(parsed, Rgba { a: 0.5, ..color });
+        }
+        assert_eq!(
+            format_color(Rgba { a: 1.0, ..color }, ColorFormat::Hex),
+            "#3366cc"
+        );
+    }
+
+    #[test]
+    fn test_find_color_literal() {
+        let line = "  color: #fff; background: rgba(0, 0, 0, 0.5);";
+        assert_eq!(find_color_literal(line, 10), Some(9..13));
+        assert_eq!(find_color_literal(line, 13), Some(9..13));
+        assert_eq!(find_color_literal(line, 30), Some(27..45));
+        assert_eq!(find_color_literal(line, 3), None);
+    }
+
+    #[test]
+    fn test_pick_presentation() {
+        let presentation = |label: &str| ColorPresentation {
+            label: label.to_string().into(),
+            text_edit: None,
+            additional_text_edits: Vec::new(),
+        };
+        let css = [presentation("#ff0000"), presentation("rgb(255, 0, 0)")];
+        assert_eq!(
+            pick_presentation(&css, ColorFormat::Rgb).map(|p| p.label.as_ref()),
+            Some("rgb(255, 0, 0)")
+        );
+        assert!(pick_presentation(&css, ColorFormat::Oklch).is_none());
+
+        let rust = [presentation("Color::rgb(255, 0, 0)")];
+        assert_eq!(
+            pick_presentation(&rust, ColorFormat::Oklch).map(|p| p.label.as_ref()),
+            Some("Color::rgb(255, 0, 0)")
+        );
+    }
+
+    #[gpui::test]
+    async fn test_apply_color_presentation_from_swatch(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/a"),
+            json!({
+                "main.rs": "let red = Color::RED;",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(rust_lang());
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    color_provider: Some(lsp::ColorProviderCapability::Simple(true)),
+                    ..lsp::ServerCapabilities::default()
+                },
+                ..FakeLspAdapter::default()
+            },
+        );
+
+        let editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_abs_path(
+                    PathBuf::from(path!("/a/main.rs")),
+                    OpenOptions::default(),
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        let fake_language_server = fake_servers.next().await.unwrap();
+
+        let color_range = lsp::Range::new(lsp::Position::new(0, 10), lsp::Position::new(0, 20));
+        let mut color_requests = fake_language_server
+            .set_request_handler::<lsp::request::DocumentColor, _, _>(move |_, _| async move {
+                Ok(vec![lsp::ColorInformation {
+                    range: color_range,
+                    color: lsp::Color {
+                        red: 1.0,
+                        green: 0.0,
+                        blue: 0.0,
+                        alpha: 1.0,
+                    },
+                }])
+            });
+        let presentations = Arc::new(Mutex::new(Vec::new()));
+        let mut presentation_requests = fake_language_server
+            .set_request_handler::<lsp::request::ColorPresentationRequest, _, _>({
+                let presentations = presentations.clone();
+                move |params, _| {
+                    assert_eq!(params.range, color_range);
+                    assert_eq!(params.color.green, 1.0);
+                    let presentations = presentations.lock().clone();
+                    async move { Ok(presentations) }
+                }
+            });
+        cx.executor().advance_clock(LSP_REQUEST_DEBOUNCE_TIMEOUT);
+        color_requests.next().await.unwrap();
+        cx.run_until_parked();
+
+        let pick_green_from_swatch = |cx: &mut VisualTestContext| {
+            editor.update_in(cx, |editor, window, cx| {
+                let snapshot = editor.display_snapshot(cx);
+                let inside_swatch = DisplayPoint::new(DisplayRow(0), 11);
+                let point_for_position = PointForPosition {
+                    previous_valid: snapshot.clip_point(inside_swatch, Bias::Left),
+                    next_valid: snapshot.clip_point(inside_swatch, Bias::Right),
+                    nearest_valid: snapshot.clip_point(inside_swatch, Bias::Left),
+                    exact_unclipped: inside_swatch,
+                    column_overshoot_after_line_end: 0,
+                };
+                assert!(
+                    editor.show_color_picker_for_swatch(&point_for_position, window, cx),
+                    "Clicking the swatch should open the color picker"
+                );
+                let picker = editor.color_picker.as_ref().unwrap().picker.clone();
+                picker.update(cx, |picker, cx| {
+                    picker.color = Rgba {
+                        r: 0.0,
+                        g: 1.0,
+                        b: 0.0,
+                        a: 1.0,
+                    }
+                    .into();
+                    cx.emit(ColorPickerEvent::Confirmed);
+                });
+            });
+        };
+
+        pick_green_from_swatch(cx);
+        presentation_requests.next().await.unwrap();
+        cx.run_until_parked();
+        editor.update(cx, |editor, cx| {
+            assert!(editor.color_picker.is_none());
+            assert_eq!(
+                editor.text(cx),
+                "let red = Color::RED;",
+                "Without presentations, the color should not be rewritten as CSS"
+            );
+        });
+
+        *presentations.lock() = vec![lsp::ColorPresentation {
+            label: "Color::rgb(0, 255, 0)".to_string(),
+            text_edit: Some(lsp::TextEdit {
+                range: color_range,
+                new_text: "Color::rgb(0, 255, 0)".to_string(),
+            }),
+            additional_text_edits: Some(vec![lsp::TextEdit {
+                range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+                new_text: "use color::Color;\n".to_string(),
+            }]),
+        }];
+        pick_green_from_swatch(cx);
+        presentation_requests.next().await.unwrap();
+        cx.run_until_parked();
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor.text(cx),
+                "use color::Color;\nlet red = Color::rgb(0, 255, 0);",
+                "The server's presentation should replace the color"
+            );
+        });
+    }
+}
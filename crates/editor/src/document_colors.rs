@@ -5,7 +5,7 @@ use futures::future::join_all;
 use gpui::{Hsla, Rgba};
 use itertools::Itertools;
 use language::point_from_lsp;
-use multi_buffer::Anchor;
+use multi_buffer::{Anchor, MultiBufferSnapshot};
 use project::{DocumentColor, InlayId};
 use settings::Settings as _;
 use text::{Bias, BufferId};
@@ -109,6 +109,49 @@ impl LspColorData {
         true
     }
 
+    /// Returns the color whose inlay swatch lies within `swatch_range`.
+    pub(crate) fn color_for_swatch(
+        &self,
+        swatch_range: Range<Anchor>,
+        snapshot: &MultiBufferSnapshot,
+    ) -> Option<(BufferId, Range<Anchor>, DocumentColor)> {
+        if self.render_mode != DocumentColorsRenderMode::Inlay {
+            return None;
+        }
+        self.find_color(snapshot, |range| {
+            range.start.cmp(&swatch_range.start, snapshot).is_ge()
+                && range.start.cmp(&swatch_range.end, snapshot).is_le()
+        })
+    }
+
+    /// Returns the color whose text contains `position`.
+    pub(crate) fn color_at(
+        &self,
+        position: Anchor,
+        snapshot: &MultiBufferSnapshot,
+    ) -> Option<(BufferId, Range<Anchor>, DocumentColor)> {
+        self.find_color(snapshot, |range| {
+            range.start.cmp(&position, snapshot).is_le()
+                && range.end.cmp(&position, snapshot).is_ge()
+        })
+    }
+
+    fn find_color(
+        &self,
+        snapshot: &MultiBufferSnapshot,
+        mut predicate: impl FnMut(&Range<Anchor>) -> bool,
+    ) -> Option<(BufferId, Range<Anchor>, DocumentColor)> {
+        self.buffer_colors
+            .iter()
+            .find_map(|(buffer_id, buffer_colors)| {
+                buffer_colors
+                    .colors
+                    .iter()
+                    .find(|(range, _, _)| snapshot.can_resolve(&range.start) && predicate(range))
+                    .map(|(range, color, _)| (*buffer_id, range.clone(), color.clone()))
+            })
+    }
+
     pub fn editor_display_highlights(
         &self,
         snapshot: &EditorSnapshot,
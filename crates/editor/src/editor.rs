@@ -17,6 +17,7 @@ mod bracket_colorization;
 mod clangd_ext;
 pub mod code_context_menus;
 mod code_lens;
+mod color_picker;
 pub mod display_map;
 mod document_colors;
 mod document_links;
@@ -142,6 +143,7 @@ use code_context_menus::{
 };
 use code_lens::CodeLensState;
 use collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use color_picker::ColorPickerState;
 use convert_case::{Case, Casing};
 use dap::TelemetrySpawnLocation;
 use display_map::*;
@@ -948,6 +950,7 @@ pub struct Editor {
     autoclose_regions: Vec<AutocloseRegion>,
     snippet_stack: InvalidationStack<SnippetState>,
     select_syntax_node_history: SelectSyntaxNodeHistory,
+    selection_ranges_task: Option<Task<()>>,
     ime_transaction: Option<TransactionId>,
     pub diagnostics_max_severity: DiagnosticSeverity,
     active_diagnostics: ActiveDiagnostic,
@@ -1143,6 +1146,7 @@ pub struct Editor {
 
     selection_drag_state: SelectionDragState,
     colors: Option<LspColorData>,
+    color_picker: Option<ColorPickerState>,
     code_lens: Option<CodeLensState>,
     post_scroll_update: Task<()>,
     refresh_colors_task: Task<()>,
@@ -2264,6 +2268,7 @@ impl Editor {
             autoclose_regions: Vec::new(),
             snippet_stack: InvalidationStack::default(),
             select_syntax_node_history: SelectSyntaxNodeHistory::default(),
+            selection_ranges_task: None,
             ime_transaction: None,
             active_diagnostics: ActiveDiagnostic::None,
             show_inline_diagnostics: ProjectSettings::get_global(cx).diagnostics.inline.enabled,
@@ -2433,6 +2438,7 @@ impl Editor {
             runnables: RunnableData::new(),
             pull_diagnostics_task: Task::ready(()),
             colors: None,
+            color_picker: None,
             code_lens: None,
             refresh_colors_task: Task::ready(()),
             refresh_code_lens_task: Task::ready(()),
@@ -11322,6 +11328,15 @@ pub trait SemanticsProvider {
         new_name: String,
         cx: &mut App,
     ) -> Option<Task<Result<ProjectTransaction>>>;
+
+    fn supports_selection_ranges(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool;
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>>;
 }
 
 impl SemanticsProvider for WeakEntity<Project> {
@@ -11502,6 +11517,25 @@ impl SemanticsProvider for WeakEntity<Project> {
         })
         .ok()
     }
+
+    fn supports_selection_ranges(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool {
+        self.read_with(cx, |project, cx| {
+            project.supports_selection_ranges(buffer, cx)
+        })
+        .unwrap_or(false)
+    }
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>> {
+        self.update(cx, |project, cx| {
+            project.selection_ranges(buffer, positions, cx)
+        })
+        .ok()
+    }
 }
 
 fn consume_contiguous_rows(
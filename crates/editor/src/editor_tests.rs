@@ -12418,6 +12418,60 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_select_larger_smaller_syntax_node_with_lsp(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state("fn f() { foo(ˇa, b) }");
+    let range =
+        |start, end| lsp::Range::new(lsp::Position::new(0, start), lsp::Position::new(0, end));
+    let mut selection_range_requests = cx
+        .set_request_handler::<lsp::request::SelectionRangeRequest, _, _>(
+            move |_, params, _| async move {
+                assert_eq!(params.positions, vec![lsp::Position::new(0, 13)]);
+                // The argument list without its parentheses isn't a tree-sitter node.
+                Ok(Some(vec![lsp::SelectionRange {
+                    range: range(13, 14),
+                    parent: Some(Box::new(lsp::SelectionRange {
+                        range: range(13, 17),
+                        parent: Some(Box::new(lsp::SelectionRange {
+                            range: range(9, 18),
+                            parent: None,
+                        })),
+                    })),
+                }]))
+            },
+        );
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+    });
+    selection_range_requests.next().await;
+    cx.run_until_parked();
+    cx.assert_editor_state("fn f() { foo(«aˇ», b) }");
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+    });
+    selection_range_requests.next().await;
+    cx.run_until_parked();
+    cx.assert_editor_state("fn f() { foo(«a, bˇ») }");
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, window, cx);
+    });
+    cx.assert_editor_state("fn f() { foo(«aˇ», b) }");
+}
+
 #[gpui::test]
 async fn test_select_larger_syntax_node_for_cursor_at_end(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
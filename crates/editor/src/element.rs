@@ -532,6 +532,7 @@ impl EditorElement {
         register_action(editor, window, Editor::restart_language_server);
         register_action(editor, window, Editor::stop_language_server);
         register_action(editor, window, Editor::show_character_palette);
+        register_action(editor, window, Editor::show_color_picker);
         register_action(editor, window, |editor, action, window, cx| {
             if let Some(task) = editor.compose_completion(action, window, cx) {
                 editor.detach_and_notify_err(task, window, cx);
@@ -622,6 +622,14 @@ impl EditorElement {
             return;
         }
 
+        if click_count == 1
+            && !modifiers.modified()
+            && editor.show_color_picker_for_swatch(&point_for_position, window, cx)
+        {
+            cx.stop_propagation();
+            return;
+        }
+
         if EditorSettings::get_global(cx)
             .drag_and_drop_selection
             .enabled
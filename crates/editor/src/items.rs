@@ -1740,6 +1740,8 @@ impl SearchableItem for Editor {
                 case: true,
                 word: true,
                 regex: true,
+                structural: true,
+                scopes: true,
                 replacement: false,
                 selection: false,
                 select_all: true,
@@ -1750,6 +1752,8 @@ impl SearchableItem for Editor {
                 case: true,
                 word: true,
                 regex: true,
+                structural: true,
+                scopes: true,
                 replacement: true,
                 selection: true,
                 select_all: true,
@@ -1839,6 +1843,14 @@ impl SearchableItem for Editor {
     ) {
         let text = self.buffer.read(cx);
         let text = text.snapshot(cx);
+        if query.is_structural() {
+            if let Some(replacement) = structural_replacement(&text, identifier, query) {
+                self.transact(window, cx, |this, _, cx| {
+                    this.edit([(identifier.clone(), replacement)], cx);
+                });
+            }
+            return;
+        }
         let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
         let text: Cow<_> = if text.len() == 1 {
             text.first().cloned().unwrap().into()
@@ -1865,9 +1877,17 @@ impl SearchableItem for Editor {
         let text = text.snapshot(cx);
         let mut edits = vec![];
 
-        // A regex might have replacement variables so we cannot apply
-        // the same replacement to all matches
-        if query.is_regex() {
+        // Regex and structural replacements can refer to what each match
+        // captured, and case-preserving replacements depend on each match's
+        // casing, so we cannot apply the same replacement to all matches
+        if query.is_structural() {
+            edits = matches
+                .filter_map(|m| {
+                    structural_replacement(&text, m, query)
+                        .map(|replacement| (m.clone(), replacement))
+                })
+                .collect();
+        } else if query.is_regex() || query.preserve_case() {
             edits = matches
                 .filter_map(|m| {
                     let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
@@ -2306,6 +2326,22 @@ fn deserialize_path_key(path_key: proto::PathKey) -> Option<PathKey> {
     })
 }
 
+/// The replacement for a structural search match, which depends on the syntax
+/// of the buffer containing it.
+fn structural_replacement(
+    snapshot: &MultiBufferSnapshot,
+    range: &Range<Anchor>,
+    query: &SearchQuery,
+) -> Option<Arc<str>> {
+    let buffer_ranges = snapshot.range_to_buffer_ranges(range.clone());
+    let [(buffer, buffer_range, _)] = buffer_ranges.as_slice() else {
+        return None;
+    };
+    query
+        .structural_replacement(buffer, buffer_range.start.0..buffer_range.end.0)
+        .map(Arc::from)
+}
+
 fn chunk_search_range(
     buffer: BufferSnapshot,
     query: &SearchQuery,
@@ -2318,11 +2354,13 @@ fn chunk_search_range(
     }
 
     let summary: TextSummary = buffer.text_summary_for_range(initial_range);
-    let num_chunks = if !query.is_regex() && !query.as_str().contains('\n') {
-        NonZeroU32::new(summary.lines.row.saturating_add(1).min(num_cpus.max(1)))
-    } else {
-        NonZeroU32::new(1)
-    };
+    // Regex and structural matches can span lines, so they're searched in one chunk.
+    let num_chunks =
+        if !query.is_regex() && !query.is_structural() && !query.as_str().contains('\n') {
+            NonZeroU32::new(summary.lines.row.saturating_add(1).min(num_cpus.max(1)))
+        } else {
+            NonZeroU32::new(1)
+        };
 
     let Some(num_chunks) = num_chunks else {
         return Box::new(std::iter::empty());
@@ -654,9 +654,9 @@ impl Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let Some(visible_row_count) = self.visible_row_count() else {
+        if self.visible_row_count().is_none() {
             return;
-        };
+        }
         let old_selections: Box<[_]> = self
             .selections
             .all::<MultiBufferOffset>(&self.display_snapshot(cx))
@@ -665,51 +665,19 @@ impl Editor {
             return;
         }
 
+        // Language servers know about constructs that tree-sitter grammars may not expose,
+        // so prefer their ranges and only fall back to the syntax tree where they have none.
+        if self.select_larger_syntax_node_with_lsp(&old_selections, window, cx) {
+            return;
+        }
+
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
-
-        let mut selected_larger_node = false;
-        let mut new_selections = old_selections
+        let new_selections = old_selections
             .iter()
             .map(|selection| {
-                let old_range = selection.start..selection.end;
-
-                if let Some((node, _)) = buffer.syntax_ancestor(old_range.clone()) {
-                    // manually select word at selection
-                    if ["string_content", "inline"].contains(&node.kind()) {
-                        let (word_range, _) = buffer.surrounding_word(old_range.start, None);
-                        // ignore if word is already selected
-                        if !word_range.is_empty() && old_range != word_range {
-                            let (last_word_range, _) = buffer.surrounding_word(old_range.end, None);
-                            // only select word if start and end point belongs to same word
-                            if word_range == last_word_range {
-                                selected_larger_node = true;
-                                return Selection {
-                                    id: selection.id,
-                                    start: word_range.start,
-                                    end: word_range.end,
-                                    goal: SelectionGoal::None,
-                                    reversed: selection.reversed,
-                                };
-                            }
-                        }
-                    }
-                }
-
-                let mut new_range = old_range.clone();
-                while let Some((node, range)) = buffer.syntax_ancestor(new_range.clone()) {
-                    new_range = range;
-                    if !node.is_named() {
-                        continue;
-                    }
-                    if !display_map.intersects_fold(new_range.start)
-                        && !display_map.intersects_fold(new_range.end)
-                    {
-                        break;
-                    }
-                }
-
-                selected_larger_node |= new_range != old_range;
+                let new_range =
+                    larger_syntax_node_range(&buffer, &display_map, selection.start..selection.end);
                 Selection {
                     id: selection.id,
                     start: new_range.start,
@@ -718,12 +686,137 @@ impl Editor {
                     reversed: selection.reversed,
                 }
             })
+            .collect();
+        self.push_larger_syntax_node_selections(old_selections, new_selections, window, cx);
+    }
+
+    /// Requests selection ranges for every selection from the language servers that support them,
+    /// returning `false` if no selection is in a buffer with such a server.
+    fn select_larger_syntax_node_with_lsp(
+        &mut self,
+        old_selections: &[Selection<MultiBufferOffset>],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(provider) = self.semantics_provider.clone() else {
+            return false;
+        };
+
+        let mut positions_by_buffer =
+            HashMap::<BufferId, (Entity<Buffer>, Vec<(usize, text::Anchor)>)>::default();
+        for (ix, selection) in old_selections.iter().enumerate() {
+            if let Some((buffer, position)) = self
+                .buffer
+                .read(cx)
+                .text_anchor_for_position(selection.start, cx)
+            {
+                positions_by_buffer
+                    .entry(buffer.read(cx).remote_id())
+                    .or_insert_with(|| (buffer, Vec::new()))
+                    .1
+                    .push((ix, position));
+            }
+        }
+
+        let requests = positions_by_buffer
+            .into_values()
+            .filter(|(buffer, _)| provider.supports_selection_ranges(buffer, cx))
+            .filter_map(|(buffer, positions)| {
+                let (selection_indices, positions): (Vec<_>, Vec<_>) =
+                    positions.into_iter().unzip();
+                let request = provider.selection_ranges(&buffer, positions, cx)?;
+                Some(async move { (selection_indices, request.await) })
+            })
             .collect::<Vec<_>>();
+        if requests.is_empty() {
+            return false;
+        }
+
+        let old_selections: Box<[_]> = old_selections.into();
+        self.selection_ranges_task = Some(cx.spawn_in(window, async move |editor, cx| {
+            let responses = future::join_all(requests).await;
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    let display_map = editor.display_map.update(cx, |map, cx| map.snapshot(cx));
+                    let current_selections = editor
+                        .selections
+                        .all::<MultiBufferOffset>(&display_map)
+                        .into_iter()
+                        .map(|selection| selection.start..selection.end);
+                    if !current_selections.eq(old_selections
+                        .iter()
+                        .map(|selection| selection.start..selection.end))
+                    {
+                        return;
+                    }
 
+                    let mut selection_ranges = vec![Vec::new(); old_selections.len()];
+                    for (selection_indices, response) in responses {
+                        let Some(response) = response.log_err() else {
+                            continue;
+                        };
+                        for (ix, ranges) in selection_indices.into_iter().zip(response) {
+                            selection_ranges[ix] = ranges;
+                        }
+                    }
+
+                    let buffer = editor.buffer.read(cx).snapshot(cx);
+                    let new_selections = old_selections
+                        .iter()
+                        .zip(&selection_ranges)
+                        .map(|(selection, ranges)| {
+                            let old_range = selection.start..selection.end;
+                            let new_range = larger_lsp_selection_range(
+                                &buffer,
+                                &display_map,
+                                &old_range,
+                                ranges,
+                            )
+                            .unwrap_or_else(|| {
+                                larger_syntax_node_range(&buffer, &display_map, old_range)
+                            });
+                            Selection {
+                                id: selection.id,
+                                start: new_range.start,
+                                end: new_range.end,
+                                goal: SelectionGoal::None,
+                                reversed: selection.reversed,
+                            }
+                        })
+                        .collect();
+                    editor.push_larger_syntax_node_selections(
+                        old_selections,
+                        new_selections,
+                        window,
+                        cx,
+                    );
+                })
+                .ok();
+        }));
+        true
+    }
+
+    fn push_larger_syntax_node_selections(
+        &mut self,
+        old_selections: Box<[Selection<MultiBufferOffset>]>,
+        mut new_selections: Vec<Selection<MultiBufferOffset>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(visible_row_count) = self.visible_row_count() else {
+            return;
+        };
+        let selected_larger_node = old_selections
+            .iter()
+            .zip(&new_selections)
+            .any(|(old, new)| old.start != new.start || old.end != new.end);
         if !selected_larger_node {
             return; // don't put this call in the history
         }
 
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer = self.buffer.read(cx).snapshot(cx);
+
         // scroll based on transformation done to the last selection created by the user
         let (last_old, last_new) = old_selections
             .last()
@@ -2406,3 +2499,61 @@ impl Editor {
         });
     }
 }
+
+fn larger_syntax_node_range(
+    buffer: &MultiBufferSnapshot,
+    display_map: &DisplaySnapshot,
+    old_range: Range<MultiBufferOffset>,
+) -> Range<MultiBufferOffset> {
+    if let Some((node, _)) = buffer.syntax_ancestor(old_range.clone()) {
+        // manually select word at selection
+        if ["string_content", "inline"].contains(&node.kind()) {
+            let (word_range, _) = buffer.surrounding_word(old_range.start, None);
+            // ignore if word is already selected
+            if !word_range.is_empty() && old_range != word_range {
+                let (last_word_range, _) = buffer.surrounding_word(old_range.end, None);
+                // only select word if start and end point belongs to same word
+                if word_range == last_word_range {
+                    return word_range;
+                }
+            }
+        }
+    }
+
+    let mut new_range = old_range;
+    while let Some((node, range)) = buffer.syntax_ancestor(new_range.clone()) {
+        new_range = range;
+        if !node.is_named() {
+            continue;
+        }
+        if !display_map.intersects_fold(new_range.start)
+            && !display_map.intersects_fold(new_range.end)
+        {
+            break;
+        }
+    }
+    new_range
+}
+
+/// Picks the innermost language server selection range that is larger than `old_range`.
+///
+/// `ranges` are ordered from innermost to outermost, as returned by the server.
+fn larger_lsp_selection_range(
+    buffer: &MultiBufferSnapshot,
+    display_map: &DisplaySnapshot,
+    old_range: &Range<MultiBufferOffset>,
+    ranges: &[Range<text::Anchor>],
+) -> Option<Range<MultiBufferOffset>> {
+    ranges
+        .iter()
+        .filter_map(|range| {
+            let range = buffer.buffer_anchor_range_to_anchor_range(range.clone())?;
+            Some(range.start.to_offset(buffer)..range.end.to_offset(buffer))
+        })
+        .filter(|range| {
+            range.start <= old_range.start && old_range.end <= range.end && range != old_range
+        })
+        .find(|range| {
+            !display_map.intersects_fold(range.start) && !display_map.intersects_fold(range.end)
+        })
+}
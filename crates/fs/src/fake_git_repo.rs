@@ -11,11 +11,11 @@ use git::{
     Oid, RunHook,
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitData, CommitDataReader, CommitDetails, CommitOptions,
-        CreateWorktreeTarget, FetchOptions, FileHistoryChangedFileSets, GRAPH_CHUNK_SIZE,
-        GitRepository, GitRepositoryCheckpoint, InitialGraphCommitData, LogOrder, LogSource,
-        PushOptions, RefEdit, Remote, RepoPath, ResetMode, SearchCommitArgs, Worktree,
-        commit_hash_search_query,
+        AskPassDelegate, Branch, CommitData, CommitDataReader, CommitDetails, CommitDiff,
+        CommitFile, CommitOptions, CommitSearchTarget, CreateWorktreeTarget, FetchOptions,
+        FileHistoryChangedFileSets, GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint,
+        InitialGraphCommitData, LogOrder, LogSource, PushOptions, RefEdit, Remote, RepoPath,
+        ResetMode, SearchCommitArgs, Worktree, commit_hash_search_query,
     },
     stash::GitStash,
     status::{
@@ -78,6 +78,8 @@ pub struct FakeGitRepositoryState {
     pub refs: HashMap<String, String>,
     pub graph_commits: Vec<Arc<InitialGraphCommitData>>,
     pub commit_data: HashMap<Oid, FakeCommitDataEntry>,
+    /// The files changed by each commit, newest commit first, like `git log`.
+    pub commit_diffs: Vec<(Oid, Vec<CommitFile>)>,
     pub stash_entries: GitStash,
     pub commit_template: Option<GitCommitTemplate>,
 }
@@ -103,6 +105,7 @@ impl FakeGitRepositoryState {
             remotes: HashMap::default(),
             graph_commits: Vec::new(),
             commit_data: Default::default(),
+            commit_diffs: Vec::new(),
             commit_history: Vec::new(),
             stash_entries: Default::default(),
             commit_template: None,
@@ -175,12 +178,31 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
-    fn load_commit(
-        &self,
-        _commit: String,
-        _cx: AsyncApp,
-    ) -> BoxFuture<'_, Result<git::repository::CommitDiff>> {
-        async { Ok(git::repository::CommitDiff { files: Vec::new() }) }.boxed()
+    fn load_blob_bytes(&self, revision: String) -> BoxFuture<'_, Result<Vec<u8>>> {
+        self.with_state_async(false, move |state| {
+            let content = match revision.split_once(':') {
+                Some(("", path)) => state.index_contents.get(&RepoPath::new(path)?).cloned(),
+                Some(("HEAD", path)) => state.head_contents.get(&RepoPath::new(path)?).cloned(),
+                Some(_) => None,
+                None => state.oids.get(&revision.parse::<git::Oid>()?).cloned(),
+            };
+            content
+                .map(String::into_bytes)
+                .with_context(|| format!("blob {revision} does not exist"))
+        })
+        .boxed()
+    }
+
+    fn load_commit(&self, commit: String, _cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
+        self.with_state_async(false, move |state| {
+            let files = state
+                .commit_diffs
+                .iter()
+                .find(|(sha, _)| sha.to_string() == commit)
+                .map(|(_, files)| files.clone())
+                .unwrap_or_default();
+            Ok(CommitDiff { files })
+        })
     }
 
     fn set_index_text(
@@ -291,9 +313,17 @@ impl GitRepository for FakeGitRepository {
                     commit
                 }
             };
+            let message = sha
+                .parse::<Oid>()
+                .ok()
+                .and_then(|oid| match state.commit_data.get(&oid)? {
+                    FakeCommitDataEntry::Success(data) => Some(data.message.clone()),
+                    FakeCommitDataEntry::Fail(_) => None,
+                })
+                .unwrap_or_else(|| "initial commit".into());
             Ok(CommitDetails {
                 sha: sha.into(),
-                message: "initial commit".into(),
+                message,
                 ..Default::default()
             })
         })
@@ -1538,6 +1568,19 @@ impl GitRepository for FakeGitRepository {
         request_tx: Sender<Oid>,
     ) -> BoxFuture<'_, Result<()>> {
         async move {
+            if search_args.target != CommitSearchTarget::Message {
+                let matching_shas =
+                    self.fs
+                        .with_git_state(&self.dot_git_path, false, |state| {
+                            search_commit_diffs(&state.commit_diffs, &search_args)
+                        })??;
+                for sha in matching_shas {
+                    if request_tx.send(sha).await.is_err() {
+                        break;
+                    }
+                }
+                return Ok(());
+            }
             let hash_query = commit_hash_search_query(search_args.query.as_str())
                 .map(|query| query.to_ascii_lowercase());
             let message_query = if search_args.case_sensitive {
@@ -1632,3 +1675,51 @@ impl GitRepository for FakeGitRepository {
         self.is_trusted.load(std::sync::atomic::Ordering::Acquire)
     }
 }
+
+/// The commits whose diffs `git log -S` or `git log -G` would select, limited
+/// to the files in `search_args.paths` when it's non-empty.
+fn search_commit_diffs(
+    commit_diffs: &[(Oid, Vec<CommitFile>)],
+    search_args: &SearchCommitArgs,
+) -> Result<Vec<Oid>> {
+    anyhow::ensure!(!search_args.query.is_empty(), "the search query is empty");
+    let pattern = match search_args.target {
+        CommitSearchTarget::ChangedLines => search_args.query.to_string(),
+        CommitSearchTarget::Message | CommitSearchTarget::Occurrences => {
+            regex::escape(&search_args.query)
+        }
+    };
+    let regex = regex::RegexBuilder::new(&pattern)
+        .case_insensitive(!search_args.case_sensitive)
+        .build()?;
+    let file_matches = |file: &CommitFile| {
+        let old_text = file.old_text.as_deref().unwrap_or_default();
+        let new_text = file.new_text.as_deref().unwrap_or_default();
+        match search_args.target {
+            CommitSearchTarget::ChangedLines => {
+                let old_lines = old_text.lines().collect::<HashSet<_>>();
+                let new_lines = new_text.lines().collect::<HashSet<_>>();
+                old_lines
+                    .symmetric_difference(&new_lines)
+                    .any(|line| regex.is_match(line))
+            }
+            CommitSearchTarget::Message | CommitSearchTarget::Occurrences => {
+                regex.find_iter(old_text).count() != regex.find_iter(new_text).count()
+            }
+        }
+    };
+    Ok(commit_diffs
+        .iter()
+        .filter(|(_, files)| {
+            files.iter().any(|file| {
+                (search_args.paths.is_empty()
+                    || search_args
+                        .paths
+                        .iter()
+                        .any(|path| file.path.starts_with(path)))
+                    && file_matches(file)
+            })
+        })
+        .map(|(sha, _)| *sha)
+        .collect())
+}
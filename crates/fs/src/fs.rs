@@ -58,7 +58,7 @@ use collections::{BTreeMap, btree_map};
 use fake_git_repo::{FakeCommitDataEntry, FakeGitRepositoryState};
 #[cfg(feature = "test-support")]
 use git::{
-    repository::{CommitData, InitialGraphCommitData, RepoPath, Worktree, repo_path},
+    repository::{CommitData, CommitFile, InitialGraphCommitData, RepoPath, Worktree, repo_path},
     status::{FileStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
 #[cfg(feature = "test-support")]
@@ -2396,6 +2396,19 @@ impl FakeFs {
         .unwrap();
     }
 
+    /// Sets the files changed by each commit, newest commit first, which are
+    /// loaded for commit views and searched by `git log -S` and `-G` searches.
+    pub fn set_commit_diffs(
+        &self,
+        dot_git: &Path,
+        commit_diffs: impl IntoIterator<Item = (git::Oid, Vec<CommitFile>)>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.commit_diffs = commit_diffs.into_iter().collect();
+        })
+        .unwrap();
+    }
+
     /// Put the given git repository into a state with the given status,
     /// by mutating the head, index, and unmerged state.
     pub fn set_status_for_repo(&self, dot_git: &Path, statuses: &[(&str, FileStatus)]) {
@@ -61,6 +61,9 @@ actions!(
         Blame,
         /// Shows the git history for the selected file, folder, or project.
         FileHistory,
+        /// Searches the repository history for commits that added or removed a
+        /// string.
+        SearchHistory,
         /// Opens the selected file in the editor without a diff view.
         ViewFile,
         /// Stages the current file.
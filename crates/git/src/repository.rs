@@ -544,7 +544,7 @@ pub enum CommitFileStatus {
     Deleted,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct CommitFile {
     pub path: RepoPath,
     pub old_text: Option<String>,
@@ -731,6 +731,27 @@ impl LogSource {
 pub struct SearchCommitArgs {
     pub query: SharedString,
     pub case_sensitive: bool,
+    pub target: CommitSearchTarget,
+    /// Restricts the search to commits touching these paths, when non-empty.
+    pub paths: Vec<RepoPath>,
+    /// Restricts the search to commits after this date, in any format
+    /// accepted by `git log --since`, such as `2.weeks` or `2024-01-31`.
+    pub since: Option<SharedString>,
+    /// Restricts the search to commits before this date, see `since`.
+    pub until: Option<SharedString>,
+}
+
+/// What a commit search matches its query against.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CommitSearchTarget {
+    /// Commit messages, or commit hashes for queries that look like one.
+    #[default]
+    Message,
+    /// Commits that add or remove occurrences of the query, as `git log -S`.
+    Occurrences,
+    /// Commits whose diff adds or removes a line matching the query as a
+    /// regex, as `git log -G`.
+    ChangedLines,
 }
 
 pub fn commit_hash_search_query(query: &str) -> Option<&str> {
@@ -768,6 +789,11 @@ pub trait GitRepository: Send + Sync {
     }
     fn load_blob_content(&self, oid: Oid) -> BoxFuture<'_, Result<String>>;
 
+    /// Returns the raw bytes of the blob named by `revision`, which may be an oid or a
+    /// `<commit>:<path>` spec. Unlike [`Self::load_blob_content`], the contents are not
+    /// required to be valid UTF-8, so this is suitable for images and other binary files.
+    fn load_blob_bytes(&self, revision: String) -> BoxFuture<'_, Result<Vec<u8>>>;
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -1548,6 +1574,17 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn load_blob_bytes(&self, revision: String) -> BoxFuture<'_, Result<Vec<u8>>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                git_binary
+                    .run_raw_bytes(&["cat-file", "blob", &revision])
+                    .await
+            })
+            .boxed()
+    }
+
     fn load_commit_template(&self) -> BoxFuture<'_, Result<Option<GitCommitTemplate>>> {
         let working_directory = self.working_directory();
         let git_binary = self.git_binary_in_worktree();
@@ -3295,23 +3332,65 @@ impl GitRepository for RealGitRepository {
         let git = self.git_binary();
 
         async move {
+            // A bare `-S` or `-G` would take the next argument as its pattern.
+            anyhow::ensure!(
+                search_args.target == CommitSearchTarget::Message || !search_args.query.is_empty(),
+                "the search query is empty"
+            );
             let log_source_args = log_source.get_args();
-            let mut args = vec!["log", SEARCH_COMMIT_FORMAT];
-            let hash_query = commit_hash_search_query(search_args.query.as_str())
-                .map(|query| query.to_ascii_lowercase());
+            let mut args = vec![Cow::Borrowed("log"), Cow::Borrowed(SEARCH_COMMIT_FORMAT)];
+            let hash_query = match search_args.target {
+                CommitSearchTarget::Message => commit_hash_search_query(search_args.query.as_str())
+                    .map(|query| query.to_ascii_lowercase()),
+                CommitSearchTarget::Occurrences | CommitSearchTarget::ChangedLines => None,
+            };
 
             if hash_query.is_none() {
-                args.push("--fixed-strings");
-
                 if !search_args.case_sensitive {
-                    args.push("--regexp-ignore-case");
+                    args.push(Cow::Borrowed("--regexp-ignore-case"));
                 }
 
-                args.push("--grep");
-                args.push(search_args.query.as_str());
+                match search_args.target {
+                    CommitSearchTarget::Message => {
+                        args.push(Cow::Borrowed("--fixed-strings"));
+                        args.push(Cow::Borrowed("--grep"));
+                        args.push(Cow::Borrowed(search_args.query.as_str()));
+                    }
+                    CommitSearchTarget::Occurrences => {
+                        args.push(Cow::Owned(format!("-S{}", search_args.query)));
+                    }
+                    CommitSearchTarget::ChangedLines => {
+                        args.push(Cow::Owned(format!("-G{}", search_args.query)));
+                    }
+                }
+            }
+
+            if let Some(since) = &search_args.since {
+                args.push(Cow::Owned(format!("--since={since}")));
+            }
+            if let Some(until) = &search_args.until {
+                args.push(Cow::Owned(format!("--until={until}")));
             }
 
-            args.extend(log_source_args.iter().map(|arg| arg.as_ref()));
+            // A path log source already ends with a pathspec.
+            let has_pathspec = matches!(log_source, LogSource::Path(_));
+            args.extend(
+                log_source_args
+                    .iter()
+                    .map(|arg| Cow::Borrowed(arg.as_ref())),
+            );
+            if !search_args.paths.is_empty() {
+                if !has_pathspec {
+                    args.push(Cow::Borrowed("--"));
+                }
+                args.extend(
+                    search_args
+                        .paths
+                        .iter()
+                        .map(|path| Cow::Borrowed(path.as_unix_str())),
+                );
+            }
+            let args = args.iter().map(|arg| arg.as_ref()).collect::<Vec<&str>>();
             let mut command = git.build_command(&args);
             command.stdout(Stdio::piped());
             command.stderr(Stdio::null());
@@ -3781,6 +3860,13 @@ impl GitBinary {
 
     /// Returns the result of the command without trimming the trailing newline.
     pub async fn run_raw<S>(&self, args: &[S]) -> Result<String>
+    where
+        S: AsRef<OsStr>,
+    {
+        Ok(String::from_utf8(self.run_raw_bytes(args).await?)?)
+    }
+
+    pub async fn run_raw_bytes<S>(&self, args: &[S]) -> Result<Vec<u8>>
     where
         S: AsRef<OsStr>,
     {
@@ -3794,7 +3880,7 @@ impl GitBinary {
                 status: output.status,
             }
         );
-        Ok(String::from_utf8(output.stdout)?)
+        Ok(output.stdout)
     }
 
     #[allow(clippy::disallowed_methods)]
@@ -4985,6 +5071,102 @@ mod tests {
         assert_eq!(graph_data[0].sha, commit_sha);
     }
 
+    #[gpui::test]
+    async fn test_search_commits_by_occurrences(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+
+        git_init_repo(repo_dir.path());
+        fs::create_dir(repo_dir.path().join("src")).unwrap();
+        fs::write(repo_dir.path().join("src/lib.rs"), "fn old_name() {}\n").unwrap();
+        fs::write(repo_dir.path().join("README"), "old_name\n").unwrap();
+        git_command(repo_dir.path(), ["add", "."]);
+        git_command(repo_dir.path(), ["commit", "-m", "Add old_name"]);
+        let added_sha: Oid = git_command_output(repo_dir.path(), ["rev-parse", "HEAD"])
+            .parse()
+            .unwrap();
+
+        fs::write(repo_dir.path().join("src/lib.rs"), "pub fn old_name() {}\n").unwrap();
+        git_command(
+            repo_dir.path(),
+            ["commit", "-am", "Make the function public"],
+        );
+        let public_sha: Oid = git_command_output(repo_dir.path(), ["rev-parse", "HEAD"])
+            .parse()
+            .unwrap();
+
+        fs::write(repo_dir.path().join("src/lib.rs"), "fn new_name() {}\n").unwrap();
+        git_command(repo_dir.path(), ["commit", "-am", "Rename old_name"]);
+        let renamed_sha: Oid = git_command_output(repo_dir.path(), ["rev-parse", "HEAD"])
+            .parse()
+            .unwrap();
+
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        let search = |target: CommitSearchTarget, paths: Vec<RepoPath>| {
+            let (request_tx, request_rx) = async_channel::unbounded();
+            let search = repo.search_commits(
+                LogSource::All,
+                SearchCommitArgs {
+                    query: "old_name".into(),
+                    case_sensitive: true,
+                    target,
+                    paths,
+                    since: None,
+                    until: None,
+                },
+                request_tx,
+            );
+            async move {
+                search.await.unwrap();
+                let mut shas = Vec::new();
+                while let Ok(sha) = request_rx.recv().await {
+                    shas.push(sha);
+                }
+                shas
+            }
+        };
+
+        // `-S` only matches commits that change the number of occurrences.
+        assert_eq!(
+            search(CommitSearchTarget::Occurrences, Vec::new()).await,
+            vec![renamed_sha, added_sha]
+        );
+        // `-G` matches any commit whose added or removed lines match.
+        assert_eq!(
+            search(CommitSearchTarget::ChangedLines, Vec::new()).await,
+            vec![renamed_sha, public_sha, added_sha]
+        );
+        assert_eq!(
+            search(CommitSearchTarget::ChangedLines, vec![repo_path("README")]).await,
+            vec![added_sha]
+        );
+
+        let (request_tx, _request_rx) = async_channel::unbounded();
+        let empty_search = repo.search_commits(
+            LogSource::All,
+            SearchCommitArgs {
+                query: "".into(),
+                case_sensitive: true,
+                target: CommitSearchTarget::Occurrences,
+                paths: Vec::new(),
+                since: None,
+                until: None,
+            },
+            request_tx,
+        );
+        assert!(empty_search.await.is_err());
+    }
+
     #[gpui::test]
     async fn test_build_command_untrusted_includes_both_safety_args(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
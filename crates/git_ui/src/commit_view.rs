@@ -23,7 +23,7 @@ use language::{
     ReplicaId, Rope, TextBuffer,
 };
 use markdown::{Markdown, MarkdownElement};
-use multi_buffer::PathKey;
+use multi_buffer::{ExcerptBoundaryInfo, PathKey};
 use project::{Project, ProjectPath, WorktreeId, git_store::Repository};
 use settings::{DiffViewStyle, Settings};
 use std::{
@@ -47,6 +47,7 @@ use workspace::{
 
 use crate::commit_tooltip::CommitAvatar;
 use crate::git_panel::GitPanel;
+use crate::image_diff::{self, ImageDiffSide};
 
 actions!(
     git,
@@ -87,12 +88,12 @@ pub struct CommitView {
     remote: Option<GitRemote>,
 }
 
-struct GitBlob {
-    path: RepoPath,
-    worktree_id: WorktreeId,
-    is_deleted: bool,
-    is_binary: bool,
-    display_name: String,
+pub(crate) struct GitBlob {
+    pub(crate) path: RepoPath,
+    pub(crate) worktree_id: WorktreeId,
+    pub(crate) is_deleted: bool,
+    pub(crate) is_binary: bool,
+    pub(crate) display_name: String,
 }
 
 struct CommitDiffAddon {
@@ -113,6 +114,33 @@ impl Addon for CommitDiffAddon {
         self.file_statuses.get(&buffer_id).copied()
     }
 
+    fn render_buffer_header_controls(
+        &self,
+        _excerpt_info: &ExcerptBoundaryInfo,
+        buffer: &language::BufferSnapshot,
+        _window: &Window,
+        _cx: &App,
+    ) -> Option<AnyElement> {
+        let repo_path = RepoPath::from_rel_path(buffer.file()?.path());
+        if !image_diff::is_image_path(&repo_path) {
+            return None;
+        }
+        let commit_view = self.commit_view.clone();
+        Some(
+            Button::new("compare-images", "Compare Images")
+                .label_size(LabelSize::Small)
+                .start_icon(Icon::new(IconName::Image).size(IconSize::Small))
+                .on_click(move |_, window, cx| {
+                    commit_view
+                        .update(cx, |view, cx| {
+                            view.open_image_diff(repo_path.clone(), window, cx)
+                        })
+                        .log_err();
+                })
+                .into_any_element(),
+        )
+    }
+
     fn extend_buffer_header_context_menu(
         &self,
         menu: ContextMenu,
@@ -533,6 +561,25 @@ impl CommitView {
             .log_err();
     }
 
+    fn open_image_diff(&self, repo_path: RepoPath, window: &mut Window, cx: &mut Context<Self>) {
+        let sha = self.commit.sha.to_string();
+        let short_sha = sha.get(0..git::SHORT_SHA_LENGTH).unwrap_or(&sha);
+        let file_name = repo_path.file_name().unwrap_or_default();
+        let title = format!("{short_sha} - {file_name}").into();
+
+        image_diff::open_image_diff(
+            repo_path,
+            ImageDiffSide::Revision(format!("{sha}^")),
+            ImageDiffSide::Revision(sha),
+            title,
+            self.repository.clone(),
+            self.workspace.clone(),
+            window,
+            cx,
+        )
+        .detach_and_notify_err(self.workspace.clone(), window, cx);
+    }
+
     fn open_file_at_head_action(
         &mut self,
         _: &OpenFileAtHead,
@@ -957,7 +1004,7 @@ impl language::File for GitBlob {
     }
 }
 
-async fn build_buffer(
+pub(crate) async fn build_buffer(
     mut text: String,
     blob: Arc<dyn File>,
     language_registry: &Arc<language::LanguageRegistry>,
@@ -991,7 +1038,7 @@ async fn build_buffer(
     Ok(buffer)
 }
 
-async fn build_buffer_diff(
+pub(crate) async fn build_buffer_diff(
     mut old_text: Option<String>,
     buffer: &Entity<Buffer>,
     language_registry: &Arc<LanguageRegistry>,
@@ -2,6 +2,7 @@ use crate::{
     conflict_view,
     git_panel::{GitPanel, GitStatusEntry},
     git_panel_settings::GitPanelSettings,
+    image_diff,
 };
 use anyhow::Result;
 use buffer_diff::BufferDiff;
@@ -442,6 +443,8 @@ impl DiffMultibuffer {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<BufferId> {
+        // Images are compared visually, so their text is hidden.
+        let is_image = image_diff::is_image_path(&repo_path);
         let diff_subscription = cx.subscribe_in(&diff, window, {
             let repo_path = repo_path.clone();
             let path_key = path_key.clone();
@@ -559,7 +562,8 @@ impl DiffMultibuffer {
                     );
                 }
                 if is_excerpt_newly_added
-                    && (file_status.is_deleted()
+                    && (is_image
+                        || file_status.is_deleted()
                         || (file_status.is_untracked()
                             && GitPanelSettings::get_global(cx).collapse_untracked_diff))
                 {
@@ -12,8 +12,8 @@ use git::{
     BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, Oid, ParsedGitRemote,
     parse_git_remote_url,
     repository::{
-        CommitDiff, CommitFile, InitialGraphCommitData, LogOrder, LogSource, RepoPath,
-        SearchCommitArgs,
+        CommitDiff, CommitFile, CommitSearchTarget, InitialGraphCommitData, LogOrder, LogSource,
+        RepoPath, SearchCommitArgs,
     },
     status::{FileStatus, StatusCode, TrackedStatus},
 };
@@ -2033,6 +2033,10 @@ impl GitGraph {
                 SearchCommitArgs {
                     query: query.clone(),
                     case_sensitive: self.search_state.case_sensitive,
+                    target: CommitSearchTarget::Message,
+                    paths: Vec::new(),
+                    since: None,
+                    until: None,
                 },
                 request_tx,
                 cx,
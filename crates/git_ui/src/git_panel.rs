@@ -8,6 +8,7 @@ use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::{CommitAvatar, CommitTooltip};
 use crate::commit_view::CommitView;
 use crate::git_panel_settings::GitPanelScrollbarAccessor;
+use crate::image_diff::{self, ImageDiffSide};
 use crate::project_diff::{DeployBranchDiff, Diff, ProjectDiff};
 use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::solo_diff_view::SoloDiffView;
@@ -535,7 +536,7 @@ impl StageIntent {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
-enum DiffTarget {
+pub(crate) enum DiffTarget {
     Uncommitted,
     Staged,
     Unstaged,
@@ -1900,6 +1901,11 @@ impl GitPanel {
             let target =
                 Self::diff_target_for_section(self.section_for_entry_index(selected_index));
 
+            if image_diff::is_image_path(&entry.repo_path) {
+                self.open_image_diff(entry.clone(), target, window, cx);
+                return None;
+            }
+
             if target == DiffTarget::Uncommitted
                 && let Some(project_diff) = workspace.read(cx).active_item_as::<ProjectDiff>(cx)
                 && let Some(project_path) = project_diff.read(cx).active_project_path(cx)
@@ -1947,6 +1953,11 @@ impl GitPanel {
                 .clone();
             let repository = self.active_repository.clone()?;
 
+            if image_diff::is_image_path(&entry.repo_path) {
+                self.open_image_diff(entry, DiffTarget::Uncommitted, window, cx);
+                return Some(());
+            }
+
             SoloDiffView::open_or_focus(entry, repository, self.workspace.clone(), window, cx)
                 .detach_and_notify_err(self.workspace.clone(), window, cx);
 
@@ -1954,6 +1965,50 @@ impl GitPanel {
         });
     }
 
+    /// Images can't be shown in a text diff, so they get a visual comparison instead.
+    fn open_image_diff(
+        &self,
+        entry: GitStatusEntry,
+        target: DiffTarget,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repository) = self.active_repository.clone() else {
+            return;
+        };
+        let (old, new, description) = match target {
+            DiffTarget::Uncommitted => (
+                ImageDiffSide::Revision("HEAD".into()),
+                ImageDiffSide::WorkingTree,
+                "Uncommitted",
+            ),
+            DiffTarget::Staged => (
+                ImageDiffSide::Revision("HEAD".into()),
+                ImageDiffSide::Revision(String::new()),
+                "Staged",
+            ),
+            DiffTarget::Unstaged => (
+                ImageDiffSide::Revision(String::new()),
+                ImageDiffSide::WorkingTree,
+                "Unstaged",
+            ),
+        };
+        let file_name = entry.repo_path.file_name().unwrap_or_default().to_string();
+        let title = format!("{file_name} ({description})").into();
+
+        image_diff::open_image_diff(
+            entry.repo_path,
+            old,
+            new,
+            title,
+            repository,
+            self.workspace.clone(),
+            window,
+            cx,
+        )
+        .detach_and_notify_err(self.workspace.clone(), window, cx);
+    }
+
     fn view_file(&mut self, _: &ViewFile, window: &mut Window, cx: &mut Context<Self>) {
         maybe!({
             let entry = self.entries.get(self.selected_entry?)?.status_entry()?;
@@ -6900,6 +6955,7 @@ impl GitPanel {
         &self,
         entity: &Entity<Self>,
         file: &Arc<dyn File>,
+        diff_target: DiffTarget,
         _: &Window,
         cx: &App,
     ) -> Option<AnyElement> {
@@ -6938,15 +6994,37 @@ impl GitPanel {
                         .ok();
                 }
             });
+        let compare_images = entry
+            .status_entry()
+            .filter(|_| image_diff::is_image_path(&repo_path))
+            .map(|status_entry| {
+                let status_entry = status_entry.clone();
+                let git_panel = entity.downgrade();
+                Button::new("compare-images", "Compare Images")
+                    .label_size(LabelSize::Small)
+                    .start_icon(Icon::new(IconName::Image).size(IconSize::Small))
+                    .on_click(move |_, window, cx| {
+                        git_panel
+                            .update(cx, |this, cx| {
+                                this.open_image_diff(status_entry.clone(), diff_target, window, cx)
+                            })
+                            .ok();
+                    })
+            });
         Some(
             h_flex()
-                .id("start-slot")
-                .text_lg()
-                .child(checkbox)
-                .on_mouse_down(MouseButton::Left, |_, _, cx| {
-                    // prevent the list item active state triggering when toggling checkbox
-                    cx.stop_propagation();
-                })
+                .gap_2()
+                .children(compare_images)
+                .child(
+                    h_flex()
+                        .id("start-slot")
+                        .text_lg()
+                        .child(checkbox)
+                        .on_mouse_down(MouseButton::Left, |_, _, cx| {
+                            // prevent the list item active state triggering when toggling checkbox
+                            cx.stop_propagation();
+                        }),
+                )
                 .into_any_element(),
         )
     }
@@ -8176,6 +8254,8 @@ impl EventEmitter<PanelEvent> for GitPanel {}
 
 pub(crate) struct GitPanelAddon {
     pub(crate) workspace: WeakEntity<Workspace>,
+    /// The changes shown by the editor, which image comparisons are opened for.
+    pub(crate) diff_target: DiffTarget,
 }
 
 impl editor::Addon for GitPanelAddon {
@@ -8193,9 +8273,13 @@ impl editor::Addon for GitPanelAddon {
         let file = buffer.file()?;
         let git_panel = self.workspace.upgrade()?.read(cx).panel::<GitPanel>(cx)?;
 
-        git_panel
-            .read(cx)
-            .render_buffer_header_controls(&git_panel, file, window, cx)
+        git_panel.read(cx).render_buffer_header_controls(
+            &git_panel,
+            file,
+            self.diff_target,
+            window,
+            cx,
+        )
     }
 }
 
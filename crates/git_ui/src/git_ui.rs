@@ -46,6 +46,8 @@ pub mod git_panel;
 mod git_panel_settings;
 pub mod git_picker;
 mod git_runtime_diagnostics;
+pub mod history_search;
+mod image_diff;
 pub mod multi_diff_view;
 pub mod picker_prompt;
 pub mod project_diff;
@@ -78,6 +80,7 @@ pub fn init(cx: &mut App) {
     editor::set_blame_renderer(blame_ui::GitBlameRenderer, cx);
     commit_view::init(cx);
     git_graph::init(cx);
+    history_search::init(cx);
 
     git_ui_core::set_branch_picker_builder(
         |workspace, repository, window, cx| {
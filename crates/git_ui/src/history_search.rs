@@ -0,0 +1,946 @@
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use editor::{Addon, Editor, EditorEvent, MultiBuffer, multibuffer_context_lines};
+use git::repository::{
+    CommitSearchTarget, LogSource, RepoPath, SearchCommitArgs, is_binary_content,
+};
+use git::status::{FileStatus, StatusCode, TrackedStatus};
+use git::{Oid, SearchHistory};
+use gpui::{
+    AnyElement, App, AppContext as _, AsyncWindowContext, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement, Render, SharedString,
+    Styled, Task, WeakEntity, Window,
+};
+use language::{BufferId, Capability, OffsetRangeExt as _};
+use multi_buffer::{ExcerptBoundaryInfo, PathKey};
+use project::{ProjectPath, git_store::Repository};
+use regex::{Regex, RegexBuilder};
+use std::{
+    any::{Any, TypeId},
+    sync::Arc,
+};
+use theme::ActiveTheme;
+use ui::{Tooltip, prelude::*};
+use util::paths::{PathMatcher, PathStyle};
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, Workspace,
+    item::{ItemEvent, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+use crate::commit_view::{CommitView, GitBlob, build_buffer, build_buffer_diff};
+
+/// Walking the history of a large repository can yield thousands of commits, so
+/// only the most recent ones are loaded into the results.
+const MAX_COMMITS: usize = 200;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+        workspace.register_action(HistorySearch::deploy);
+    })
+    .detach();
+}
+
+/// Searches the repository history with `git log -S` or `git log -G`, showing
+/// the hunks of each matching commit that added or removed the query.
+pub struct HistorySearch {
+    query_editor: Entity<Editor>,
+    paths_editor: Entity<Editor>,
+    since_editor: Entity<Editor>,
+    until_editor: Entity<Editor>,
+    target: CommitSearchTarget,
+    case_sensitive: bool,
+    editor: Entity<Editor>,
+    multibuffer: Entity<MultiBuffer>,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    results: HashMap<BufferId, HistorySearchResult>,
+    state: SearchState,
+}
+
+#[derive(Clone)]
+struct HistorySearchResult {
+    sha: SharedString,
+    subject: SharedString,
+    status: FileStatus,
+}
+
+enum SearchState {
+    Idle,
+    Searching(Task<()>),
+    Finished {
+        commit_count: usize,
+        truncated: bool,
+    },
+    Error(SharedString),
+}
+
+struct HistorySearchAddon {
+    history_search: WeakEntity<HistorySearch>,
+}
+
+impl Addon for HistorySearchAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn override_status_for_buffer_id(&self, buffer_id: BufferId, cx: &App) -> Option<FileStatus> {
+        let history_search = self.history_search.upgrade()?;
+        let result = history_search.read(cx).results.get(&buffer_id)?;
+        Some(result.status)
+    }
+
+    fn render_buffer_header_controls(
+        &self,
+        _excerpt_info: &ExcerptBoundaryInfo,
+        buffer: &language::BufferSnapshot,
+        _window: &Window,
+        cx: &App,
+    ) -> Option<AnyElement> {
+        let buffer_id = buffer.remote_id();
+        let subject = self
+            .history_search
+            .upgrade()?
+            .read(cx)
+            .results
+            .get(&buffer_id)?
+            .subject
+            .clone();
+        let history_search = self.history_search.clone();
+        Some(
+            h_flex()
+                .gap_2()
+                .child(
+                    Label::new(subject)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .truncate(),
+                )
+                .child(
+                    Button::new(
+                        SharedString::from(format!("open-commit-{}", buffer.remote_id())),
+                        "Open Commit",
+                    )
+                    .label_size(LabelSize::Small)
+                    .start_icon(Icon::new(IconName::GitCommit).size(IconSize::Small))
+                    .on_click(move |_, window, cx| {
+                        if let Some(history_search) = history_search.upgrade() {
+                            history_search.read(cx).open_commit(buffer_id, window, cx);
+                        }
+                    }),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+impl HistorySearch {
+    fn deploy(
+        workspace: &mut Workspace,
+        _: &SearchHistory,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items()
+            .filter_map(|item| item.downcast::<Self>())
+            .find(|view| view.read(cx).repository == repository);
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            existing.update(cx, |view, cx| view.focus_query_editor(window, cx));
+            return;
+        }
+
+        let workspace_handle = cx.weak_entity();
+        let project = workspace.project().clone();
+        let view = cx.new(|cx| {
+            let multibuffer = cx.new(|cx| {
+                let mut multibuffer = MultiBuffer::new(Capability::ReadOnly);
+                multibuffer.set_all_diff_hunks_expanded(cx);
+                multibuffer
+            });
+            let history_search = cx.weak_entity();
+            let editor = cx.new(|cx| {
+                let mut editor =
+                    Editor::for_multibuffer(multibuffer.clone(), Some(project), window, cx);
+                editor.disable_diagnostics(cx);
+                editor.set_expand_all_diff_hunks(cx);
+                editor.set_show_bookmarks(false, cx);
+                editor.set_show_breakpoints(false, cx);
+                editor.register_addon(HistorySearchAddon { history_search });
+                editor
+            });
+
+            let single_line_editor = |placeholder: &str, window: &mut Window, cx: &mut App| {
+                cx.new(|cx| {
+                    let mut editor = Editor::single_line(window, cx);
+                    editor.set_placeholder_text(placeholder, window, cx);
+                    editor
+                })
+            };
+
+            Self {
+                query_editor: single_line_editor("Search added or removed text…", window, cx),
+                paths_editor: single_line_editor(
+                    "Paths in the repository, comma-separated (e.g. src, Cargo.toml)",
+                    window,
+                    cx,
+                ),
+                since_editor: single_line_editor("Since (e.g. 2 weeks ago)", window, cx),
+                until_editor: single_line_editor("Until (e.g. 2024-01-31)", window, cx),
+                target: CommitSearchTarget::Occurrences,
+                case_sensitive: false,
+                editor,
+                multibuffer,
+                repository,
+                workspace: workspace_handle,
+                results: HashMap::default(),
+                state: SearchState::Idle,
+            }
+        });
+
+        workspace.add_item_to_active_pane(Box::new(view.clone()), None, true, window, cx);
+        view.update(cx, |view, cx| view.focus_query_editor(window, cx));
+    }
+
+    /// Opens the commit that the result shown in the given buffer is from.
+    fn open_commit(&self, buffer_id: BufferId, window: &mut Window, cx: &mut App) {
+        let Some(result) = self.results.get(&buffer_id) else {
+            return;
+        };
+        CommitView::open(
+            result.sha.to_string(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+    }
+
+    fn focus_query_editor(&self, window: &mut Window, cx: &mut Context<Self>) {
+        self.query_editor.update(cx, |editor, cx| {
+            editor.select_all(&Default::default(), window, cx);
+        });
+        window.focus(&self.query_editor.focus_handle(cx), cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.search(window, cx);
+    }
+
+    fn toggle_regex(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.target = match self.target {
+            CommitSearchTarget::ChangedLines => CommitSearchTarget::Occurrences,
+            _ => CommitSearchTarget::ChangedLines,
+        };
+        self.search(window, cx);
+    }
+
+    fn toggle_case_sensitive(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.case_sensitive = !self.case_sensitive;
+        self.search(window, cx);
+    }
+
+    fn search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.results.clear();
+        self.multibuffer
+            .update(cx, |multibuffer, cx| multibuffer.clear(cx));
+        self.state = SearchState::Idle;
+        cx.emit(EditorEvent::TitleChanged);
+        cx.notify();
+
+        let query = self.query_editor.read(cx).text(cx);
+        if query.is_empty() {
+            return;
+        }
+
+        let matcher = match hunk_matcher(&query, self.target, self.case_sensitive) {
+            Ok(matcher) => matcher,
+            Err(error) => {
+                self.state = SearchState::Error(error.to_string().into());
+                return;
+            }
+        };
+        let paths = match parse_paths(&self.paths_editor.read(cx).text(cx)) {
+            Ok(paths) => paths,
+            Err(error) => {
+                self.state = SearchState::Error(error.to_string().into());
+                return;
+            }
+        };
+        // `git log` selects commits touching any of the paths, but their diffs
+        // also contain the files outside of them.
+        let path_matcher = match (!paths.is_empty())
+            .then(|| PathMatcher::new(paths.iter().map(|path| path.as_unix_str()), PathStyle::Unix))
+            .transpose()
+        {
+            Ok(path_matcher) => path_matcher,
+            Err(error) => {
+                self.state = SearchState::Error(error.to_string().into());
+                return;
+            }
+        };
+        let date = |editor: &Entity<Editor>, cx: &App| {
+            let text = editor.read(cx).text(cx);
+            let text = text.trim();
+            (!text.is_empty()).then(|| SharedString::from(text.to_string()))
+        };
+
+        let args = SearchCommitArgs {
+            query: query.into(),
+            case_sensitive: self.case_sensitive,
+            target: self.target,
+            paths,
+            since: date(&self.since_editor, cx),
+            until: date(&self.until_editor, cx),
+        };
+        let (request_tx, request_rx) = async_channel::unbounded::<Oid>();
+        self.repository.update(cx, |repository, cx| {
+            repository.search_commits(LogSource::All, args, request_tx, cx);
+        });
+
+        let task = cx.spawn_in(window, async move |this, cx| {
+            let mut commit_count = 0;
+            let mut truncated = false;
+            while let Ok(oid) = request_rx.recv().await {
+                if commit_count == MAX_COMMITS {
+                    truncated = true;
+                    break;
+                }
+                match Self::add_commit(
+                    &this,
+                    oid,
+                    commit_count,
+                    &matcher,
+                    path_matcher.as_ref(),
+                    cx,
+                )
+                .await
+                {
+                    Ok(true) => commit_count += 1,
+                    Ok(false) => {}
+                    Err(error) => {
+                        log::error!("failed to load commit {oid} for history search: {error:#}");
+                    }
+                }
+            }
+            this.update(cx, |this, cx| {
+                this.state = SearchState::Finished {
+                    commit_count,
+                    truncated,
+                };
+                cx.notify();
+            })
+            .ok();
+        });
+        self.state = SearchState::Searching(task);
+    }
+
+    /// Adds the hunks of a commit that match the query, returning whether any
+    /// were found.
+    async fn add_commit(
+        this: &WeakEntity<Self>,
+        oid: Oid,
+        commit_ix: usize,
+        matcher: &Regex,
+        path_matcher: Option<&PathMatcher>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<bool> {
+        let sha = oid.to_string();
+        let (commit_diff, commit_details, project) = this.update(cx, |this, cx| {
+            let (commit_diff, commit_details) = this.repository.update(cx, |repository, _| {
+                (
+                    repository.load_commit_diff(sha.clone()),
+                    repository.show(sha.clone()),
+                )
+            });
+            let project = this
+                .workspace
+                .upgrade()
+                .map(|workspace| workspace.read(cx).project().clone());
+            (commit_diff, commit_details, project)
+        })?;
+        let project = project.context("workspace was dropped")?;
+        let (commit_diff, commit_details) = futures::join!(commit_diff, commit_details);
+        let commit_diff = commit_diff??;
+        let commit_details = commit_details??;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        let first_worktree_id = project.read_with(cx, |project, cx| {
+            project
+                .worktrees(cx)
+                .next()
+                .map(|worktree| worktree.read(cx).id())
+        });
+        let short_sha = sha.get(0..git::SHORT_SHA_LENGTH).unwrap_or(&sha);
+        let subject = SharedString::from(
+            commit_details
+                .message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        );
+
+        let mut found = false;
+        for file in commit_diff.files {
+            let new_text = file.new_text.as_deref().unwrap_or_default();
+            let old_text = file.old_text.as_deref().unwrap_or_default();
+            if path_matcher.is_some_and(|path_matcher| !path_matcher.is_match(&*file.path))
+                || file.is_binary
+                || is_binary_content(new_text.as_bytes())
+                || is_binary_content(old_text.as_bytes())
+                || !(matcher.is_match(new_text) || matcher.is_match(old_text))
+            {
+                continue;
+            }
+
+            let status_code = if file.old_text.is_none() {
+                StatusCode::Added
+            } else if file.new_text.is_none() {
+                StatusCode::Deleted
+            } else {
+                StatusCode::Modified
+            };
+            let worktree_id = this
+                .update(cx, |this, cx| {
+                    this.repository
+                        .read(cx)
+                        .repo_path_to_project_path(&file.path, cx)
+                        .map(|path| path.worktree_id)
+                        .or(first_worktree_id)
+                })?
+                .context("project has no worktrees")?;
+            let file_name = file
+                .path
+                .file_name()
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| file.path.display(PathStyle::local()).to_string());
+            let blob = Arc::new(GitBlob {
+                path: file.path.clone(),
+                worktree_id,
+                is_deleted: file.new_text.is_none(),
+                is_binary: false,
+                display_name: format!("{short_sha} - {file_name}"),
+            }) as Arc<dyn language::File>;
+
+            let buffer = build_buffer(
+                file.new_text.unwrap_or_default(),
+                blob,
+                &language_registry,
+                cx,
+            )
+            .await?;
+            let buffer_diff =
+                build_buffer_diff(file.old_text, &buffer, &language_registry, cx).await?;
+
+            let added = this.update(cx, |this, cx| {
+                let snapshot = buffer.read(cx).snapshot();
+                let diff_snapshot = buffer_diff.read(cx).snapshot(cx);
+                let base_text = diff_snapshot.base_text();
+                let ranges = diff_snapshot
+                    .hunks(&snapshot)
+                    .filter(|hunk| {
+                        let added = snapshot
+                            .text_for_range(hunk.buffer_range.clone())
+                            .collect::<String>();
+                        let removed = base_text
+                            .text_for_range(hunk.diff_base_byte_range.clone())
+                            .collect::<String>();
+                        matcher.is_match(&added) || matcher.is_match(&removed)
+                    })
+                    .map(|hunk| hunk.buffer_range.to_point(&snapshot))
+                    .collect::<Vec<_>>();
+                if ranges.is_empty() {
+                    return false;
+                }
+
+                let path_key = PathKey::with_sort_prefix(
+                    commit_ix as u64,
+                    snapshot.file().unwrap().path().clone(),
+                );
+                this.multibuffer.update(cx, |multibuffer, cx| {
+                    multibuffer.set_excerpts_for_path(
+                        path_key,
+                        buffer.clone(),
+                        ranges,
+                        multibuffer_context_lines(cx),
+                        cx,
+                    );
+                    multibuffer.add_diff(buffer_diff.clone(), cx);
+                });
+                this.results.insert(
+                    snapshot.remote_id(),
+                    HistorySearchResult {
+                        sha: sha.clone().into(),
+                        subject: subject.clone(),
+                        status: FileStatus::Tracked(TrackedStatus {
+                            index_status: status_code,
+                            worktree_status: StatusCode::Unmodified,
+                        }),
+                    },
+                );
+                cx.notify();
+                true
+            })?;
+            found |= added;
+        }
+
+        Ok(found)
+    }
+
+    fn render_input(&self, editor: &Entity<Editor>, cx: &Context<Self>) -> Div {
+        let colors = cx.theme().colors();
+        h_flex()
+            .h_8()
+            .min_w_0()
+            .px_1p5()
+            .gap_1()
+            .border_1()
+            .border_color(colors.border_variant)
+            .rounded_md()
+            .bg(colors.toolbar_background)
+            .child(editor.clone())
+    }
+
+    fn render_search_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_regex = self.target == CommitSearchTarget::ChangedLines;
+        let status = match &self.state {
+            SearchState::Idle => None,
+            SearchState::Searching(_) => Some((SharedString::from("Searching…"), Color::Muted)),
+            SearchState::Finished {
+                commit_count: 0, ..
+            } => Some((SharedString::from("No matching commits"), Color::Muted)),
+            SearchState::Finished {
+                commit_count,
+                truncated,
+            } => {
+                let noun = if *commit_count == 1 {
+                    "commit"
+                } else {
+                    "commits"
+                };
+                let label = if *truncated {
+                    format!("First {commit_count} {noun}")
+                } else {
+                    format!("{commit_count} {noun}")
+                };
+                Some((SharedString::from(label), Color::Muted))
+            }
+            SearchState::Error(error) => Some((error.clone(), Color::Error)),
+        };
+
+        v_flex()
+            .key_context("GitHistorySearchBar")
+            .on_action(cx.listener(Self::confirm))
+            .w_full()
+            .p_1p5()
+            .gap_1p5()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .child(
+                        self.render_input(&self.query_editor, cx)
+                            .flex_1()
+                            .child(
+                                IconButton::new("history-search-regex", IconName::Regex)
+                                    .shape(ui::IconButtonShape::Square)
+                                    .toggle_state(is_regex)
+                                    .tooltip(Tooltip::text("Match Changed Lines with Regex (-G)"))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_regex(window, cx)
+                                    })),
+                            )
+                            .child(
+                                IconButton::new(
+                                    "history-search-case-sensitive",
+                                    IconName::CaseSensitive,
+                                )
+                                .shape(ui::IconButtonShape::Square)
+                                .toggle_state(self.case_sensitive)
+                                .tooltip(Tooltip::text("Match Case Sensitivity"))
+                                .on_click(cx.listener(
+                                    |this, _, window, cx| this.toggle_case_sensitive(window, cx),
+                                )),
+                            ),
+                    )
+                    .child(h_flex().min_w_48().justify_end().when_some(
+                        status,
+                        |this, (label, color)| {
+                            this.child(Label::new(label).size(LabelSize::Small).color(color))
+                        },
+                    )),
+            )
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .child(self.render_input(&self.paths_editor, cx).flex_1())
+                    .child(self.render_input(&self.since_editor, cx).w_48())
+                    .child(self.render_input(&self.until_editor, cx).w_48()),
+            )
+    }
+}
+
+/// Matches the hunks that `git log` selected the commit for: those containing
+/// the literal query for `-S`, or lines matching the regex for `-G`.
+///
+/// `git log -G` uses POSIX extended regexes, while the hunks are matched with
+/// Rust's `regex` syntax. The two agree on the common subset of literals, `.`,
+/// bracket expressions (including classes like `[[:digit:]]`), `*`, `+`, `?`,
+/// `{n,m}`, `|`, groups and anchors. Syntax outside of it, like `\d` or lazy
+/// quantifiers, may select commits for which no hunk is shown, and those
+/// commits are left out of the results.
+fn hunk_matcher(query: &str, target: CommitSearchTarget, case_sensitive: bool) -> Result<Regex> {
+    let pattern = match target {
+        CommitSearchTarget::ChangedLines => query.to_string(),
+        CommitSearchTarget::Message | CommitSearchTarget::Occurrences => regex::escape(query),
+    };
+    Ok(RegexBuilder::new(&pattern)
+        .multi_line(true)
+        .case_insensitive(!case_sensitive)
+        .build()?)
+}
+
+fn parse_paths(text: &str) -> Result<Vec<RepoPath>> {
+    text.split(',')
+        .map(|path| path.trim().trim_end_matches('/'))
+        .filter(|path| !path.is_empty())
+        .map(|path| RepoPath::new(path).with_context(|| format!("invalid path {path:?}")))
+        .collect()
+}
+
+impl EventEmitter<EditorEvent> for HistorySearch {}
+
+impl Focusable for HistorySearch {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        if self.results.is_empty() {
+            self.query_editor.focus_handle(cx)
+        } else {
+            self.editor.focus_handle(cx)
+        }
+    }
+}
+
+impl Item for HistorySearch {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::MagnifyingGlass).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        let query = self.query_editor.read(cx).text(cx);
+        if query.is_empty() {
+            "History Search".into()
+        } else {
+            format!("History — {}", util::truncate_and_trailoff(&query, 20)).into()
+        }
+    }
+
+    fn to_item_events(event: &EditorEvent, f: &mut dyn FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Git History Search Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn active_project_path(&self, cx: &App) -> Option<ProjectPath> {
+        self.editor.read(cx).active_project_path(cx)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl Render for HistorySearch {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("GitHistorySearch")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_search_bar(cx))
+            .child(div().flex_1().min_h_0().child(self.editor.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::{CommitData, CommitFile, repo_path};
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+    use workspace::MultiWorkspace;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme_settings::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn commit(byte: u8, message: &str) -> (Oid, CommitData) {
+        let sha = Oid::from_bytes(&[byte; 20]).unwrap();
+        let data = CommitData {
+            sha,
+            parents: Default::default(),
+            author_name: "Author".into(),
+            author_email: "author@example.com".into(),
+            commit_timestamp: 0,
+            subject: message.to_string().into(),
+            message: message.to_string().into(),
+        };
+        (sha, data)
+    }
+
+    fn file(path: &str, old_text: Option<&str>, new_text: Option<&str>) -> CommitFile {
+        CommitFile {
+            path: repo_path(path),
+            old_text: old_text.map(ToString::to_string),
+            new_text: new_text.map(ToString::to_string),
+            is_binary: false,
+        }
+    }
+
+    /// Opens a history search in a repository whose newest commit renames
+    /// `old_name` in `src/lib.rs`, and whose first commit added it there and
+    /// to the `README`.
+    async fn open_history_search(
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<Workspace>,
+        Entity<HistorySearch>,
+        &mut VisualTestContext,
+    ) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "README": "old_name\n",
+                "src": { "lib.rs": "fn new_name() {}\n" },
+            }),
+        )
+        .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        let (renamed_sha, renamed) = commit(2, "Rename old_name");
+        let (added_sha, added) = commit(1, "Add old_name");
+        fs.set_commit_data(dot_git, [(renamed, false), (added, false)]);
+        fs.set_commit_diffs(
+            dot_git,
+            [
+                (
+                    renamed_sha,
+                    vec![file(
+                        "src/lib.rs",
+                        Some("fn old_name() {}\n"),
+                        Some("fn new_name() {}\n"),
+                    )],
+                ),
+                (
+                    added_sha,
+                    vec![
+                        file("README", None, Some("old_name\n")),
+                        file("src/lib.rs", None, Some("fn old_name() {}\n")),
+                        file("src/unrelated.rs", None, Some("fn other() {}\n")),
+                    ],
+                ),
+            ],
+        );
+
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project, window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        cx.run_until_parked();
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            HistorySearch::deploy(workspace, &SearchHistory, window, cx);
+        });
+        cx.run_until_parked();
+        let history_search = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<HistorySearch>(cx).unwrap()
+        });
+        (workspace, history_search, cx)
+    }
+
+    fn search(
+        history_search: &Entity<HistorySearch>,
+        query: &str,
+        paths: &str,
+        cx: &mut VisualTestContext,
+    ) {
+        history_search.update_in(cx, |history_search, window, cx| {
+            history_search.query_editor.update(cx, |editor, cx| {
+                editor.set_text(query, window, cx);
+            });
+            history_search.paths_editor.update(cx, |editor, cx| {
+                editor.set_text(paths, window, cx);
+            });
+            history_search.search(window, cx);
+        });
+        cx.run_until_parked();
+    }
+
+    /// The subject of the commit and the path of the file of each excerpt, in
+    /// the order they're shown.
+    fn excerpts(history_search: &Entity<HistorySearch>, cx: &VisualTestContext) -> Vec<String> {
+        history_search.read_with(cx, |history_search, cx| {
+            let snapshot = history_search.multibuffer.read(cx).snapshot(cx);
+            snapshot
+                .excerpts()
+                .map(|excerpt| {
+                    let buffer_id = excerpt.context.start.buffer_id;
+                    format!(
+                        "{}: {}",
+                        history_search.results[&buffer_id].subject,
+                        snapshot
+                            .path_for_buffer(buffer_id)
+                            .unwrap()
+                            .path
+                            .as_unix_str()
+                    )
+                })
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_history_search_shows_matching_hunks(cx: &mut TestAppContext) {
+        let (_, history_search, cx) = open_history_search(cx).await;
+
+        search(&history_search, "old_name", "", cx);
+        assert_eq!(
+            excerpts(&history_search, cx),
+            [
+                "Rename old_name: src/lib.rs",
+                "Add old_name: README",
+                "Add old_name: src/lib.rs",
+            ]
+        );
+        history_search.read_with(cx, |history_search, cx| {
+            assert!(matches!(
+                history_search.state,
+                SearchState::Finished {
+                    commit_count: 2,
+                    truncated: false
+                }
+            ));
+            let text = history_search.editor.read(cx).text(cx);
+            assert!(text.contains("fn new_name() {}"), "{text}");
+            assert!(!text.contains("fn other() {}"), "{text}");
+        });
+
+        // The path filter applies to the files shown, not only to the commits.
+        search(&history_search, "old_name", "src", cx);
+        assert_eq!(
+            excerpts(&history_search, cx),
+            ["Rename old_name: src/lib.rs", "Add old_name: src/lib.rs"]
+        );
+
+        search(&history_search, "old_name", "README", cx);
+        assert_eq!(excerpts(&history_search, cx), ["Add old_name: README"]);
+
+        search(&history_search, "", "", cx);
+        assert_eq!(excerpts(&history_search, cx), Vec::<String>::new());
+    }
+
+    #[gpui::test]
+    async fn test_history_search_opens_commit(cx: &mut TestAppContext) {
+        let (workspace, history_search, cx) = open_history_search(cx).await;
+        search(&history_search, "old_name", "README", cx);
+
+        history_search.update_in(cx, |history_search, window, cx| {
+            let buffer_id = *history_search.results.keys().next().unwrap();
+            history_search.open_commit(buffer_id, window, cx);
+        });
+        cx.run_until_parked();
+
+        let title = workspace.update(cx, |workspace, cx| {
+            let commit_view = workspace.active_item_as::<CommitView>(cx).unwrap();
+            commit_view.read(cx).tab_content_text(0, cx)
+        });
+        assert_eq!(title, "0101010 — Add old_name");
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use git::repository::RepoPath;
+use gpui::{App, AppContext as _, Entity, SharedString, Task, WeakEntity, Window};
+use image_viewer::ImageDiffView;
+use project::{Project, git_store::Repository, image_store};
+use util::paths::PathStyle;
+use workspace::Workspace;
+
+/// One side of an image diff.
+pub(crate) enum ImageDiffSide {
+    /// The blob at `<revision>:<path>`. An empty revision refers to the index.
+    Revision(String),
+    /// The file as it currently exists in the project.
+    WorkingTree,
+}
+
+pub(crate) fn is_image_path(path: &RepoPath) -> bool {
+    path.extension()
+        .is_some_and(|ext| image_store::is_image_extension(&ext.to_lowercase()))
+}
+
+/// Loads both sides of an image change and opens them in an [`ImageDiffView`].
+///
+/// A side that cannot be loaded is shown as missing, since added and deleted
+/// files only exist on one side of the diff.
+pub(crate) fn open_image_diff(
+    repo_path: RepoPath,
+    old: ImageDiffSide,
+    new: ImageDiffSide,
+    title: SharedString,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<Result<Entity<ImageDiffView>>> {
+    let Some(project) = workspace
+        .upgrade()
+        .map(|workspace| workspace.read(cx).project().clone())
+    else {
+        return Task::ready(Err(anyhow!("workspace was dropped")));
+    };
+
+    let old_image = load_image(&old, &repo_path, &repository, &project, cx);
+    let new_image = load_image(&new, &repo_path, &repository, &project, cx);
+    let tooltip = SharedString::from(repo_path.display(PathStyle::local()).to_string());
+
+    window.spawn(cx, async move |cx| {
+        let (old_image, new_image) = futures::join!(old_image, new_image);
+        let (old_image, new_image) = (old_image.ok(), new_image.ok());
+        if old_image.is_none() && new_image.is_none() {
+            anyhow::bail!("could not load either version of {tooltip}");
+        }
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            let view =
+                cx.new(|cx| ImageDiffView::new(title, Some(tooltip), old_image, new_image, cx));
+            workspace.add_item_to_active_pane(Box::new(view.clone()), None, true, window, cx);
+            view
+        })
+    })
+}
+
+fn load_image(
+    side: &ImageDiffSide,
+    repo_path: &RepoPath,
+    repository: &Entity<Repository>,
+    project: &Entity<Project>,
+    cx: &mut App,
+) -> Task<Result<Arc<gpui::Image>>> {
+    match side {
+        ImageDiffSide::Revision(revision) => {
+            let revision = format!("{revision}:{}", repo_path.as_unix_str());
+            let bytes = repository.update(cx, |repository, cx| {
+                repository.load_blob_bytes(revision, cx)
+            });
+            cx.background_spawn(async move { image_store::create_gpui_image(bytes.await?) })
+        }
+        ImageDiffSide::WorkingTree => {
+            let Some(project_path) = repository.read(cx).repo_path_to_project_path(repo_path, cx)
+            else {
+                return Task::ready(Err(anyhow!(
+                    "could not resolve repository path {repo_path:?}"
+                )));
+            };
+            let image_item = project.update(cx, |project, cx| project.open_image(project_path, cx));
+            cx.spawn(async move |cx| {
+                let image_item = image_item.await?;
+                Ok(image_item.read_with(cx, |image_item, _| image_item.image.clone()))
+            })
+        }
+    }
+}
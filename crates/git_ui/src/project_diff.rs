@@ -1,7 +1,7 @@
 use crate::{
     branch_diff::BranchDiff,
     diff_multibuffer::DiffMultibuffer,
-    git_panel::{GitPanel, GitPanelAddon, GitStatusEntry},
+    git_panel::{DiffTarget, GitPanel, GitPanelAddon, GitStatusEntry},
     staged_diff::StagedDiff,
     unstaged_diff::UnstagedDiff,
 };
@@ -233,6 +233,7 @@ impl ProjectDiff {
                         rhs_editor.set_read_only(false);
                         rhs_editor.register_addon(GitPanelAddon {
                             workspace: workspace_handle,
+                            diff_target: DiffTarget::Uncommitted,
                         });
                     });
                 },
@@ -1649,6 +1650,59 @@ mod tests {
         assert_eq!(text, "foo\n");
     }
 
+    #[gpui::test]
+    async fn test_image_changes_are_folded(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "foo.txt": "FOO\n",
+                "logo.png": "new pixels\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        fs.set_head_and_index_for_repo(
+            path!("/project/.git").as_ref(),
+            &[
+                ("foo.txt", "foo\n".into()),
+                ("logo.png", "old pixels\n".into()),
+            ],
+        );
+
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        let diff = cx.new_window_entity(|window, cx| {
+            ProjectDiff::new(project.clone(), workspace, window, cx)
+        });
+        cx.run_until_parked();
+
+        let editor = diff.read_with(cx, |diff, cx| diff.editor(cx).read(cx).rhs_editor().clone());
+        editor.read_with(cx, |editor, cx| {
+            let folded_paths = editor
+                .buffer()
+                .read(cx)
+                .all_buffers()
+                .into_iter()
+                .filter(|buffer| editor.is_buffer_folded(buffer.read(cx).remote_id(), cx))
+                .map(|buffer| {
+                    buffer
+                        .read(cx)
+                        .file()
+                        .unwrap()
+                        .path()
+                        .as_unix_str()
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(folded_paths, ["logo.png"]);
+        });
+    }
+
     #[gpui::test]
     async fn test_scroll_to_beginning_with_deletion(cx: &mut TestAppContext) {
         init_test(cx);
@@ -1,6 +1,6 @@
 use crate::{
     diff_multibuffer::DiffMultibuffer,
-    git_panel::{GitPanel, GitPanelAddon, GitStatusEntry},
+    git_panel::{DiffTarget, GitPanel, GitPanelAddon, GitStatusEntry},
 };
 use anyhow::{Context as _, Result};
 use buffer_diff::DiffHunkStatus;
@@ -233,6 +233,7 @@ impl StagedDiff {
                         rhs_editor.set_read_only(true);
                         rhs_editor.register_addon(GitPanelAddon {
                             workspace: workspace_handle,
+                            diff_target: DiffTarget::Staged,
                         });
                     });
                 },
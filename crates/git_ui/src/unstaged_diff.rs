@@ -1,6 +1,6 @@
 use crate::{
     diff_multibuffer::DiffMultibuffer,
-    git_panel::{GitPanel, GitPanelAddon, GitStatusEntry},
+    git_panel::{DiffTarget, GitPanel, GitPanelAddon, GitStatusEntry},
 };
 use anyhow::{Context as _, Result};
 use buffer_diff::DiffHunkStatus;
@@ -287,6 +287,7 @@ impl UnstagedDiff {
                         rhs_editor.set_read_only(false);
                         rhs_editor.register_addon(GitPanelAddon {
                             workspace: workspace_handle,
+                            diff_target: DiffTarget::Unstaged,
                         });
                     });
                 },
@@ -1,11 +1,11 @@
 use std::error::Error;
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::anyhow;
 use askpass::AskPassDelegate;
-use collections::HashSet;
+use collections::{HashMap, HashSet};
 use fs::Fs;
 use gpui::{
     AsyncWindowContext, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, SharedString,
@@ -272,6 +272,7 @@ impl Render for WorktreeFetchFailedToast {
                                     worktree_name: worktree_name.clone(),
                                     branch_target: branch_target.clone(),
                                 },
+                                HashMap::default(),
                                 window,
                                 focused_dock,
                                 RemoteBranchFetchMode::UseLocal,
@@ -443,6 +444,9 @@ async fn fetch_remote_for_worktree_base(
 /// - `creation_infos`: a vec of `(repo, new_path, receiver)` tuples.
 /// - `path_remapping`: `(old_work_dir, new_worktree_path)` pairs for remapping editor tabs.
 ///
+/// Repositories with an entry in `base_shas` (keyed by working directory) are
+/// checked out at that commit instead of `base_ref`.
+///
 /// Multiple entries in `git_repos` can be linked worktrees of the *same*
 /// underlying repository (e.g. a project that has both the main checkout and
 /// one of its linked worktrees open as separate Zed worktrees). Those entries
@@ -456,6 +460,7 @@ fn start_worktree_creations(
     existing_worktree_names: &[String],
     existing_worktree_paths: &HashSet<PathBuf>,
     base_ref: Option<String>,
+    base_shas: &HashMap<Arc<Path>, String>,
     worktree_directory_setting: &str,
     rng: &mut impl rand::Rng,
     cx: &mut gpui::App,
@@ -492,7 +497,10 @@ fn start_worktree_creations(
                 None
             } else {
                 let target = git::repository::CreateWorktreeTarget::Detached {
-                    base_sha: base_ref.clone(),
+                    base_sha: base_shas
+                        .get(&work_dir)
+                        .cloned()
+                        .or_else(|| base_ref.clone()),
                 };
                 Some(repo.create_worktree(target, new_path.clone()))
             };
@@ -685,6 +693,7 @@ pub fn handle_create_worktree(
     let task = create_worktree_workspace_inner(
         workspace,
         action,
+        HashMap::default(),
         window,
         fallback_focused_dock,
         RemoteBranchFetchMode::Fetch,
@@ -733,6 +742,7 @@ pub fn create_worktree_workspace(
     create_worktree_workspace_inner(
         workspace,
         action,
+        HashMap::default(),
         window,
         fallback_focused_dock,
         RemoteBranchFetchMode::Fetch,
@@ -742,9 +752,38 @@ pub fn create_worktree_workspace(
     )
 }
 
+/// Same as [`create_worktree_workspace`], but checks each repository out at
+/// the given commit (keyed by the repository's working directory) in a
+/// detached worktree, falling back to the current HEAD for repositories
+/// without one. Used to restore an agent thread's git checkpoint when forking
+/// the thread into a new worktree.
+pub fn create_worktree_workspace_at_commits(
+    workspace: &mut Workspace,
+    worktree_name: Option<String>,
+    commits: HashMap<Arc<Path>, String>,
+    window: &mut gpui::Window,
+    cx: &mut gpui::Context<Workspace>,
+) -> Task<anyhow::Result<CreatedWorktreeWorkspace>> {
+    create_worktree_workspace_inner(
+        workspace,
+        &zed_actions::CreateWorktree {
+            worktree_name,
+            branch_target: NewWorktreeBranchTarget::CurrentBranch,
+        },
+        commits,
+        window,
+        None,
+        RemoteBranchFetchMode::UseLocal,
+        // The user explicitly asked for the fork, so foreground it.
+        true,
+        cx,
+    )
+}
+
 fn create_worktree_workspace_inner(
     workspace: &mut Workspace,
     action: &zed_actions::CreateWorktree,
+    base_shas: HashMap<Arc<Path>, String>,
     window: &mut gpui::Window,
     fallback_focused_dock: Option<DockPosition>,
     remote_branch_fetch_mode: RemoteBranchFetchMode,
@@ -845,6 +884,7 @@ fn create_worktree_workspace_inner(
             non_git_paths,
             worktree_name.clone(),
             branch_target.clone(),
+            base_shas,
             fetch_askpass_delegates,
             remote_branch_fetch_mode,
             previous_state,
@@ -959,6 +999,7 @@ async fn do_create_worktree(
     non_git_paths: Vec<PathBuf>,
     worktree_name: Option<String>,
     branch_target: NewWorktreeBranchTarget,
+    base_shas: HashMap<Arc<Path>, String>,
     fetch_askpass_delegates: Vec<AskPassDelegate>,
     remote_branch_fetch_mode: RemoteBranchFetchMode,
     previous_state: PreviousWorkspaceState,
@@ -1039,6 +1080,7 @@ async fn do_create_worktree(
             &existing_worktree_names,
             &existing_worktree_paths,
             base_ref,
+            &base_shas,
             &worktree_directory_setting,
             &mut rng,
             cx,
@@ -1700,6 +1742,85 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_create_worktree_workspace_at_commits(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        cx.update(|cx| <dyn Fs>::set_global(fs.clone(), cx));
+        fs.insert_tree(
+            "/root",
+            json!({
+                "project": {
+                    ".git": {},
+                    "src": {
+                        "main.rs": "fn main() {}",
+                    },
+                },
+            }),
+        )
+        .await;
+
+        let main_project_root = PathBuf::from(path!("/root/project"));
+        let project = Project::test(fs.clone(), [main_project_root.as_path()], cx).await;
+        project
+            .update(cx, |project, cx| project.git_scans_complete(cx))
+            .await;
+
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        multi_workspace.update(cx, |multi_workspace, cx| {
+            multi_workspace.retain_active_workspace(cx);
+        });
+
+        let main_workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        let creation = main_workspace.update_in(cx, |workspace, window, cx| {
+            create_worktree_workspace_at_commits(
+                workspace,
+                Some("fork".to_string()),
+                HashMap::from_iter([(
+                    Arc::from(main_project_root.as_path()),
+                    "checkpoint-sha".to_string(),
+                )]),
+                window,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+        let created = creation.await.expect("worktree creation should succeed");
+
+        let active_workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        assert_eq!(active_workspace, created.workspace);
+
+        let worktree_path = created.workspace.read_with(cx, |workspace, cx| {
+            workspace
+                .project()
+                .read(cx)
+                .visible_worktrees(cx)
+                .next()
+                .expect("new workspace should have a worktree")
+                .read(cx)
+                .abs_path()
+                .to_path_buf()
+        });
+        assert_ne!(worktree_path, main_project_root);
+        let dot_git = fs
+            .load(&worktree_path.join(".git"))
+            .await
+            .expect("worktree should have a .git file");
+        let git_dir = dot_git
+            .strip_prefix("gitdir: ")
+            .expect(".git file should point to the worktree's git dir");
+        let head = fs
+            .load(&Path::new(git_dir).join("HEAD"))
+            .await
+            .expect("worktree should have a HEAD");
+        assert_eq!(
+            head, "checkpoint-sha",
+            "the worktree should be detached at the given commit"
+        );
+    }
+
     #[test]
     fn test_remote_branch_name_parse() {
         assert_eq!(
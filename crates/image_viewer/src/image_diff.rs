@@ -0,0 +1,509 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use gpui::{
+    App, Bounds, Context, EventEmitter, FocusHandle, Focusable, MouseButton, MouseDownEvent,
+    MouseMoveEvent, ObjectFit, Pixels, Point, Render, StyledImage as _, Task, Window, canvas, img,
+};
+use image::{Rgba, RgbaImage};
+use ui::{ToggleButtonGroup, ToggleButtonGroupStyle, ToggleButtonSimple, prelude::*};
+use workspace::item::{Item, ItemBufferKind};
+
+/// Color used to mark pixels that differ between the two images.
+const DIFFERENCE_HIGHLIGHT: Rgba<u8> = Rgba([255, 0, 255, 255]);
+/// Alpha applied to unchanged pixels so that the highlighted ones stand out.
+const UNCHANGED_ALPHA: u8 = 64;
+
+/// How the two sides of an [`ImageDiffView`] are presented.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImageDiffMode {
+    /// The old and new images next to each other.
+    #[default]
+    SideBySide,
+    /// The new image drawn over the old one, revealed up to a draggable divider.
+    Swipe,
+    /// The new image drawn over the old one with adjustable opacity.
+    OnionSkin,
+    /// A single image highlighting every pixel that changed.
+    Difference,
+}
+
+impl ImageDiffMode {
+    const ALL: [Self; 4] = [
+        Self::SideBySide,
+        Self::Swipe,
+        Self::OnionSkin,
+        Self::Difference,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::SideBySide => "Side by Side",
+            Self::Swipe => "Swipe",
+            Self::OnionSkin => "Onion Skin",
+            Self::Difference => "Difference",
+        }
+    }
+}
+
+/// The pixel-level comparison of two images.
+pub struct ImageDifference {
+    /// An image the size of the larger input, with changed pixels highlighted.
+    pub image: Arc<gpui::Image>,
+    pub changed_pixels: u64,
+    pub total_pixels: u64,
+}
+
+impl ImageDifference {
+    pub fn changed_fraction(&self) -> f64 {
+        if self.total_pixels == 0 {
+            0.
+        } else {
+            self.changed_pixels as f64 / self.total_pixels as f64
+        }
+    }
+}
+
+/// Compares two encoded images pixel by pixel.
+///
+/// Pixels that only exist in one of the images (because the dimensions changed)
+/// are counted as changed.
+pub fn compute_image_difference(old: &gpui::Image, new: &gpui::Image) -> Result<ImageDifference> {
+    let old = decode_rgba(old).context("decoding old image")?;
+    let new = decode_rgba(new).context("decoding new image")?;
+    let width = old.width().max(new.width());
+    let height = old.height().max(new.height());
+
+    let mut changed_pixels = 0;
+    let mut output = RgbaImage::new(width, height);
+    for (x, y, pixel) in output.enumerate_pixels_mut() {
+        let old_pixel = old.get_pixel_checked(x, y);
+        let new_pixel = new.get_pixel_checked(x, y);
+        match (old_pixel, new_pixel) {
+            (Some(old_pixel), Some(new_pixel)) if pixels_match(old_pixel, new_pixel) => {
+                let [red, green, blue, _] = new_pixel.0;
+                let luma = (0.299 * red as f32 + 0.587 * green as f32 + 0.114 * blue as f32) as u8;
+                *pixel = Rgba([luma, luma, luma, UNCHANGED_ALPHA]);
+            }
+            _ => {
+                changed_pixels += 1;
+                *pixel = DIFFERENCE_HIGHLIGHT;
+            }
+        }
+    }
+
+    let mut bytes = Vec::new();
+    output
+        .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .context("encoding difference image")?;
+
+    Ok(ImageDifference {
+        image: Arc::new(gpui::Image::from_bytes(gpui::ImageFormat::Png, bytes)),
+        changed_pixels,
+        total_pixels: width as u64 * height as u64,
+    })
+}
+
+fn decode_rgba(image: &gpui::Image) -> Result<RgbaImage> {
+    Ok(image::load_from_memory(&image.bytes)?.to_rgba8())
+}
+
+fn pixels_match(old: &Rgba<u8>, new: &Rgba<u8>) -> bool {
+    // Fully transparent pixels look the same regardless of their color channels.
+    old == new || (old.0[3] == 0 && new.0[3] == 0)
+}
+
+/// A read-only item comparing two versions of an image, e.g. a binary file in a
+/// commit or an uncommitted change.
+///
+/// Either side may be missing when the image was added or deleted, in which case
+/// only the side-by-side mode is available.
+pub struct ImageDiffView {
+    title: SharedString,
+    tooltip: Option<SharedString>,
+    old_image: Option<Arc<gpui::Image>>,
+    new_image: Option<Arc<gpui::Image>>,
+    difference: Option<Result<ImageDifference, SharedString>>,
+    mode: ImageDiffMode,
+    /// Position of the swipe divider, or opacity of the new image in onion skin mode.
+    blend: f32,
+    container_bounds: Option<Bounds<Pixels>>,
+    is_dragging: bool,
+    focus_handle: FocusHandle,
+    _difference_task: Task<()>,
+}
+
+impl ImageDiffView {
+    pub fn new(
+        title: SharedString,
+        tooltip: Option<SharedString>,
+        old_image: Option<Arc<gpui::Image>>,
+        new_image: Option<Arc<gpui::Image>>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let difference_task = match (old_image.clone(), new_image.clone()) {
+            (Some(old_image), Some(new_image)) => cx.spawn(async move |this, cx| {
+                let difference = cx
+                    .background_spawn(
+                        async move { compute_image_difference(&old_image, &new_image) },
+                    )
+                    .await
+                    .map_err(|error| SharedString::from(format!("{error:#}")));
+                this.update(cx, |this, cx| {
+                    this.difference = Some(difference);
+                    cx.notify();
+                })
+                .ok();
+            }),
+            _ => Task::ready(()),
+        };
+
+        Self {
+            title,
+            tooltip,
+            old_image,
+            new_image,
+            difference: None,
+            mode: ImageDiffMode::default(),
+            blend: 0.5,
+            container_bounds: None,
+            is_dragging: false,
+            focus_handle: cx.focus_handle(),
+            _difference_task: difference_task,
+        }
+    }
+
+    pub fn mode(&self) -> ImageDiffMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: ImageDiffMode, cx: &mut Context<Self>) {
+        if mode != ImageDiffMode::SideBySide && !self.has_both_sides() {
+            return;
+        }
+        self.mode = mode;
+        self.is_dragging = false;
+        cx.notify();
+    }
+
+    pub fn difference(&self) -> Option<&ImageDifference> {
+        self.difference.as_ref()?.as_ref().ok()
+    }
+
+    fn has_both_sides(&self) -> bool {
+        self.old_image.is_some() && self.new_image.is_some()
+    }
+
+    fn update_blend(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let Some(bounds) = self.container_bounds else {
+            return;
+        };
+        if bounds.size.width <= Pixels::ZERO {
+            return;
+        }
+        self.blend = ((position.x - bounds.left()) / bounds.size.width).clamp(0., 1.);
+        cx.notify();
+    }
+
+    fn handle_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.is_dragging = true;
+        self.update_blend(event.position, cx);
+    }
+
+    fn handle_mouse_move(
+        &mut self,
+        event: &MouseMoveEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_dragging && event.pressed_button == Some(MouseButton::Left) {
+            self.update_blend(event.position, cx);
+        } else {
+            self.is_dragging = false;
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let modes = ImageDiffMode::ALL.map(|mode| {
+            ToggleButtonSimple::new(
+                mode.label(),
+                cx.listener(move |this, _, _, cx| this.set_mode(mode, cx)),
+            )
+        });
+        let selected_index = ImageDiffMode::ALL
+            .iter()
+            .position(|mode| *mode == self.mode)
+            .unwrap_or_default();
+
+        let summary = match &self.difference {
+            Some(Ok(difference)) => Some(format!(
+                "{} of {} pixels changed ({:.2}%)",
+                difference.changed_pixels,
+                difference.total_pixels,
+                difference.changed_fraction() * 100.
+            )),
+            Some(Err(_)) => Some("Unable to compare images".to_string()),
+            None if self.has_both_sides() => Some("Comparing…".to_string()),
+            None => None,
+        };
+
+        h_flex()
+            .w_full()
+            .p_2()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(div().when(self.has_both_sides(), |this| {
+                this.child(
+                    ToggleButtonGroup::single_row("image-diff-modes", modes)
+                        .style(ToggleButtonGroupStyle::Outlined)
+                        .label_size(LabelSize::Small)
+                        .auto_width()
+                        .selected_index(selected_index),
+                )
+            }))
+            .when_some(summary, |this, summary| {
+                this.child(
+                    Label::new(summary)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+    }
+
+    fn render_side(
+        &self,
+        label: &'static str,
+        color: Color,
+        image: Option<&Arc<gpui::Image>>,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .gap_1()
+            .child(Label::new(label).size(LabelSize::Small).color(color))
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .border_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .map(|this| match image {
+                        Some(image) => this.child(render_image(image)),
+                        None => this
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .child(Label::new("No image").color(Color::Muted)),
+                    }),
+            )
+    }
+
+    fn render_overlay(
+        &self,
+        old_image: &Arc<gpui::Image>,
+        new_image: &Arc<gpui::Image>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let container_width = self
+            .container_bounds
+            .map_or(Pixels::ZERO, |bounds| bounds.size.width);
+        let view = cx.entity().downgrade();
+        let last_bounds = self.container_bounds;
+
+        div()
+            .id("image-diff-overlay")
+            .relative()
+            .size_full()
+            .overflow_hidden()
+            .cursor(gpui::CursorStyle::ResizeLeftRight)
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::handle_mouse_down))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, _, _| this.is_dragging = false),
+            )
+            .on_mouse_move(cx.listener(Self::handle_mouse_move))
+            .child(div().absolute().size_full().child(render_image(old_image)))
+            .map(|this| match self.mode {
+                ImageDiffMode::Swipe => this
+                    .child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .left_0()
+                            .h_full()
+                            .w(relative(self.blend))
+                            .overflow_hidden()
+                            .child(
+                                div()
+                                    .h_full()
+                                    .w(container_width)
+                                    .child(render_image(new_image)),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .h_full()
+                            .w(px(2.))
+                            .left(container_width * self.blend)
+                            .bg(cx.theme().colors().text_accent),
+                    ),
+                _ => this.child(
+                    div()
+                        .absolute()
+                        .size_full()
+                        .opacity(self.blend)
+                        .child(render_image(new_image)),
+                ),
+            })
+            .child(
+                canvas(
+                    move |bounds, window, cx| {
+                        if last_bounds == Some(bounds) {
+                            return;
+                        }
+                        window.defer(cx, move |_, cx| {
+                            view.update(cx, |this, cx| {
+                                this.container_bounds = Some(bounds);
+                                cx.notify();
+                            })
+                            .ok();
+                        });
+                    },
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
+    }
+
+    fn render_difference(&self) -> AnyElement {
+        match &self.difference {
+            Some(Ok(difference)) => render_image(&difference.image).into_any_element(),
+            Some(Err(error)) => Label::new(error.clone())
+                .color(Color::Error)
+                .into_any_element(),
+            None => Label::new("Comparing…")
+                .color(Color::Muted)
+                .into_any_element(),
+        }
+    }
+}
+
+fn render_image(image: &Arc<gpui::Image>) -> impl IntoElement {
+    img(image.clone())
+        .size_full()
+        .object_fit(ObjectFit::Contain)
+}
+
+impl Render for ImageDiffView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = match (self.mode, &self.old_image, &self.new_image) {
+            (ImageDiffMode::Swipe | ImageDiffMode::OnionSkin, Some(old), Some(new)) => {
+                let (old, new) = (old.clone(), new.clone());
+                self.render_overlay(&old, &new, cx).into_any_element()
+            }
+            (ImageDiffMode::Difference, Some(_), Some(_)) => div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(self.render_difference())
+                .into_any_element(),
+            _ => h_flex()
+                .size_full()
+                .gap_2()
+                .child(self.render_side("Before", Color::Deleted, self.old_image.as_ref(), cx))
+                .child(self.render_side("After", Color::Created, self.new_image.as_ref(), cx))
+                .into_any_element(),
+        };
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .key_context("ImageDiffView")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(div().flex_1().min_h_0().p_2().child(content))
+    }
+}
+
+impl Focusable for ImageDiffView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<()> for ImageDiffView {}
+
+impl Item for ImageDiffView {
+    type Event = ();
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.title.clone()
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        self.tooltip.clone()
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Image))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Image Diff Opened")
+    }
+
+    fn buffer_kind(&self, _cx: &App) -> ItemBufferKind {
+        ItemBufferKind::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_image(pixels: &[[u8; 3]]) -> gpui::Image {
+        let mut ppm = format!("P3\n{} 1\n255\n", pixels.len());
+        for [red, green, blue] in pixels {
+            ppm.push_str(&format!("{red} {green} {blue}\n"));
+        }
+        gpui::Image::from_bytes(gpui::ImageFormat::Pnm, ppm.into_bytes())
+    }
+
+    #[test]
+    fn test_identical_images_have_no_difference() {
+        let image = test_image(&[[10, 20, 30], [40, 50, 60]]);
+        let difference = compute_image_difference(&image, &image).unwrap();
+        assert_eq!(difference.changed_pixels, 0);
+        assert_eq!(difference.total_pixels, 2);
+    }
+
+    #[test]
+    fn test_changed_and_resized_pixels_are_counted() {
+        let old = test_image(&[[0, 0, 0], [255, 255, 255]]);
+        let new = test_image(&[[0, 0, 0], [255, 0, 0], [0, 0, 255]]);
+        let difference = compute_image_difference(&old, &new).unwrap();
+        assert_eq!(difference.changed_pixels, 2);
+        assert_eq!(difference.total_pixels, 3);
+
+        let rendered = image::load_from_memory(&difference.image.bytes)
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(rendered.dimensions(), (3, 1));
+        assert_eq!(*rendered.get_pixel(1, 0), DIFFERENCE_HIGHLIGHT);
+        assert_eq!(rendered.get_pixel(0, 0).0[3], UNCHANGED_ALPHA);
+    }
+}
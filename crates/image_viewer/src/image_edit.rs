@@ -0,0 +1,345 @@
+use std::{io::Cursor, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use editor::Editor;
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString,
+    Subscription, WeakEntity,
+};
+use image::{DynamicImage, ImageFormat, imageops::FilterType};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::ImageView;
+
+/// The largest image, in pixels, that the resize modal will produce. Resizing
+/// allocates the whole result up front, so larger sizes could exhaust memory.
+const MAX_RESIZE_PIXELS: u64 = 8_192 * 8_192;
+
+/// A transformation that can be applied to the image shown in an [`ImageView`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageEdit {
+    RotateClockwise,
+    RotateCounterclockwise,
+    FlipHorizontal,
+    FlipVertical,
+    /// Keeps only the given rectangle, in image pixels.
+    Crop {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    /// Scales the image to exactly the given size, in image pixels.
+    Resize {
+        width: u32,
+        height: u32,
+    },
+}
+
+impl ImageEdit {
+    pub fn apply(self, image: DynamicImage) -> DynamicImage {
+        match self {
+            ImageEdit::RotateClockwise => image.rotate90(),
+            ImageEdit::RotateCounterclockwise => image.rotate270(),
+            ImageEdit::FlipHorizontal => image.fliph(),
+            ImageEdit::FlipVertical => image.flipv(),
+            ImageEdit::Crop {
+                x,
+                y,
+                width,
+                height,
+            } => image.crop_imm(x, y, width, height),
+            ImageEdit::Resize { width, height } => {
+                image.resize_exact(width, height, FilterType::Lanczos3)
+            }
+        }
+    }
+}
+
+/// The formats an edited image can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageExportFormat {
+    Png,
+    Jpeg,
+    WebP,
+}
+
+impl ImageExportFormat {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::WebP),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Png => "PNG",
+            Self::Jpeg => "JPEG",
+            Self::WebP => "WebP",
+        }
+    }
+}
+
+pub fn decode_image(image: &gpui::Image) -> Result<DynamicImage> {
+    image::load_from_memory(&image.bytes).context("decoding image")
+}
+
+/// Encodes `image` in the given format.
+///
+/// `jpeg_quality` ranges from 1 to 100 and is only used for JPEG. WebP images
+/// are always written losslessly, since the `image` crate has no lossy WebP
+/// encoder.
+pub fn encode_image(
+    image: &DynamicImage,
+    format: ImageExportFormat,
+    jpeg_quality: u8,
+) -> Result<Vec<u8>> {
+    let mut bytes = Cursor::new(Vec::new());
+    match format {
+        ImageExportFormat::Png => image.write_to(&mut bytes, ImageFormat::Png)?,
+        ImageExportFormat::WebP => image
+            .to_rgba8()
+            .write_with_encoder(image::codecs::webp::WebPEncoder::new_lossless(&mut bytes))?,
+        ImageExportFormat::Jpeg => {
+            // JPEG has no alpha channel, so transparent pixels would otherwise be rejected.
+            image.to_rgb8().write_with_encoder(
+                image::codecs::jpeg::JpegEncoder::new_with_quality(
+                    &mut bytes,
+                    jpeg_quality.clamp(1, 100),
+                ),
+            )?
+        }
+    }
+    Ok(bytes.into_inner())
+}
+
+/// Applies `edit` to `image`, returning the result along with its size.
+///
+/// The result is encoded as a PNG so that repeated edits don't lose quality.
+pub fn apply_image_edit(
+    image: &gpui::Image,
+    edit: ImageEdit,
+) -> Result<(Arc<gpui::Image>, (u32, u32))> {
+    let edited = edit.apply(decode_image(image)?);
+    let bytes = encode_image(&edited, ImageExportFormat::Png, 100)?;
+    let image = Arc::new(gpui::Image::from_bytes(gpui::ImageFormat::Png, bytes));
+    Ok((image, (edited.width(), edited.height())))
+}
+
+/// Parses the size typed into the resize modal.
+///
+/// Accepts `<width>x<height>`, a lone width that keeps the aspect ratio, or
+/// a percentage of the current size such as `50%`. Sizes of more than
+/// [`MAX_RESIZE_PIXELS`] are rejected.
+pub fn parse_resize_query(query: &str, current_size: (u32, u32)) -> Option<(u32, u32)> {
+    let query = query.trim();
+    let (current_width, current_height) = current_size;
+    if current_width == 0 || current_height == 0 {
+        return None;
+    }
+
+    let (width, height) = if let Some(percentage) = query.strip_suffix('%') {
+        let scale = percentage.trim().parse::<f64>().ok()? / 100.0;
+        (
+            (current_width as f64 * scale).round(),
+            (current_height as f64 * scale).round(),
+        )
+    } else if let Some((width, height)) = query.split_once(['x', 'X', '×']) {
+        (
+            width.trim().parse::<u32>().ok()? as f64,
+            height.trim().parse::<u32>().ok()? as f64,
+        )
+    } else {
+        let width = query.parse::<u32>().ok()? as f64;
+        (
+            width,
+            (width * current_height as f64 / current_width as f64).round(),
+        )
+    };
+
+    (width >= 1.0 && height >= 1.0 && width * height <= MAX_RESIZE_PIXELS as f64)
+        .then_some((width as u32, height as u32))
+}
+
+pub struct ResizeImageModal {
+    size_editor: Entity<Editor>,
+    image_view: WeakEntity<ImageView>,
+    current_size: (u32, u32),
+    _subscription: Subscription,
+}
+
+impl ModalView for ResizeImageModal {}
+
+impl EventEmitter<DismissEvent> for ResizeImageModal {}
+
+impl Focusable for ResizeImageModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.size_editor.focus_handle(cx)
+    }
+}
+
+impl ResizeImageModal {
+    pub fn new(
+        image_view: WeakEntity<ImageView>,
+        current_size: (u32, u32),
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let (width, height) = current_size;
+        let size_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(&format!("{width}x{height}"), window, cx);
+            editor
+        });
+        let subscription =
+            cx.subscribe_in(
+                &size_editor,
+                window,
+                |_, _, event, window, cx| match event {
+                    editor::EditorEvent::Blurred if window.is_window_active() => {
+                        cx.emit(DismissEvent)
+                    }
+                    editor::EditorEvent::BufferEdited => cx.notify(),
+                    _ => {}
+                },
+            );
+
+        Self {
+            size_editor,
+            image_view,
+            current_size,
+            _subscription: subscription,
+        }
+    }
+
+    fn target_size(&self, cx: &App) -> Option<(u32, u32)> {
+        parse_resize_query(&self.size_editor.read(cx).text(cx), self.current_size)
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((width, height)) = self.target_size(cx) else {
+            return;
+        };
+        if let Some(image_view) = self.image_view.upgrade() {
+            image_view.update(cx, |image_view, cx| {
+                image_view.apply_edit(ImageEdit::Resize { width, height }, cx);
+                image_view.focus_handle.focus(window, cx);
+            });
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for ResizeImageModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (width, height) = self.current_size;
+        let help_text: SharedString = match self.target_size(cx) {
+            Some((new_width, new_height)) => {
+                format!("Resize from {width}×{height} to {new_width}×{new_height}").into()
+            }
+            None => format!(
+                "Current size: {width}×{height}. Enter a width, a size like 640x480, or a percentage, of up to {} megapixels.",
+                MAX_RESIZE_PIXELS / 1_000_000
+            )
+            .into(),
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("ResizeImageModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.size_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(help_text).color(Color::Muted)),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView as _, Rgba, RgbaImage};
+
+    fn test_image() -> DynamicImage {
+        let mut image = RgbaImage::new(3, 2);
+        image.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        image.put_pixel(2, 1, Rgba([0, 0, 255, 128]));
+        DynamicImage::ImageRgba8(image)
+    }
+
+    #[test]
+    fn test_edits_transform_pixels() {
+        let rotated = ImageEdit::RotateClockwise.apply(test_image());
+        assert_eq!(rotated.dimensions(), (2, 3));
+        assert_eq!(rotated.get_pixel(1, 0), Rgba([255, 0, 0, 255]));
+
+        let flipped = ImageEdit::FlipHorizontal.apply(test_image());
+        assert_eq!(flipped.get_pixel(2, 0), Rgba([255, 0, 0, 255]));
+
+        let cropped = ImageEdit::Crop {
+            x: 1,
+            y: 1,
+            width: 2,
+            height: 1,
+        }
+        .apply(test_image());
+        assert_eq!(cropped.dimensions(), (2, 1));
+        assert_eq!(cropped.get_pixel(1, 0), Rgba([0, 0, 255, 128]));
+
+        let resized = ImageEdit::Resize {
+            width: 6,
+            height: 4,
+        }
+        .apply(test_image());
+        assert_eq!(resized.dimensions(), (6, 4));
+    }
+
+    #[test]
+    fn test_encoded_images_round_trip() {
+        for format in [
+            ImageExportFormat::Png,
+            ImageExportFormat::Jpeg,
+            ImageExportFormat::WebP,
+        ] {
+            let bytes = encode_image(&test_image(), format, 80).unwrap();
+            let decoded = image::load_from_memory(&bytes).unwrap();
+            assert_eq!(decoded.dimensions(), (3, 2), "{}", format.label());
+        }
+    }
+
+    #[test]
+    fn test_parse_resize_query() {
+        assert_eq!(parse_resize_query("640x480", (100, 50)), Some((640, 480)));
+        assert_eq!(parse_resize_query(" 200 ", (100, 50)), Some((200, 100)));
+        assert_eq!(parse_resize_query("50%", (100, 50)), Some((50, 25)));
+        assert_eq!(parse_resize_query("0x10", (100, 50)), None);
+        assert_eq!(parse_resize_query("wide", (100, 50)), None);
+        assert_eq!(parse_resize_query("4294967295x4294967295", (100, 50)), None);
+        assert_eq!(parse_resize_query("100000", (100, 50)), None);
+        assert_eq!(
+            parse_resize_query("8192x8192", (100, 50)),
+            Some((8_192, 8_192))
+        );
+    }
+}
@@ -1,21 +1,23 @@
+mod image_diff;
+mod image_edit;
 mod image_info;
 mod image_viewer_settings;
 
 use std::{path::Path, sync::Arc};
 
-use anyhow::Context as _;
+use anyhow::{Context as _, Result, anyhow};
 use editor::{
     Editor, EditorEvent, EditorSettings, RevealInFileManager, actions::SelectAll,
     items::entry_git_aware_label_color,
 };
 use file_icons::FileIcons;
 use gpui::{
-    AnyElement, App, Bounds, Context, DispatchPhase, Element, ElementId, Entity, EventEmitter,
-    FocusHandle, Focusable, Font, GlobalElementId, InspectorElementId, InteractiveElement,
-    IntoElement, LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    ParentElement, PinchEvent, Pixels, Point, Render, RenderImage, ScrollDelta, ScrollWheelEvent,
-    Style, Styled, Subscription, Task, WeakEntity, Window, actions, checkerboard, div, img, point,
-    px, size,
+    Action as _, Anchor, AnyElement, App, Bounds, ClipboardItem, Context, DispatchPhase, Element,
+    ElementId, Entity, EventEmitter, FocusHandle, Focusable, Font, GlobalElementId,
+    InspectorElementId, InteractiveElement, IntoElement, LayoutId, MouseButton, MouseDownEvent,
+    MouseMoveEvent, MouseUpEvent, ParentElement, PinchEvent, Pixels, Point, Render, RenderImage,
+    ScrollDelta, ScrollWheelEvent, Style, Styled, Subscription, Task, WeakEntity, Window, actions,
+    checkerboard, div, img, point, px, size,
 };
 use language::File as _;
 use persistence::ImageViewerDb;
@@ -24,15 +26,20 @@ use project::{
 };
 use settings::Settings;
 use theme_settings::ThemeSettings;
-use ui::{Tooltip, prelude::*};
+use ui::{ContextMenu, Divider, PopoverMenu, Tooltip, prelude::*};
 use util::{ResultExt as _, paths::PathExt};
 use workspace::{
     ItemId, ItemSettings, Pane, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
     WorkspaceId, delete_unloaded_items,
     invalid_item_view::InvalidItemView,
-    item::{HighlightedText, Item, ItemHandle, ProjectItem, SerializableItem, TabContentParams},
+    item::{
+        HighlightedText, Item, ItemHandle, ProjectItem, SaveOptions, SerializableItem,
+        TabContentParams,
+    },
 };
 
+pub use crate::image_diff::*;
+pub use crate::image_edit::*;
 pub use crate::image_info::*;
 pub use crate::image_viewer_settings::*;
 
@@ -48,7 +55,21 @@ actions!(
         /// Fit the image to view.
         FitToView,
         /// Zoom to actual size (100%).
-        ZoomToActualSize
+        ZoomToActualSize,
+        /// Rotate the image 90 degrees clockwise.
+        RotateClockwise,
+        /// Rotate the image 90 degrees counterclockwise.
+        RotateCounterclockwise,
+        /// Mirror the image horizontally.
+        FlipHorizontal,
+        /// Mirror the image vertically.
+        FlipVertical,
+        /// Start selecting a region of the image to crop to.
+        Crop,
+        /// Resize the image to new dimensions.
+        Resize,
+        /// Copy the image to the clipboard.
+        CopyImage
     ]
 );
 
@@ -73,6 +94,37 @@ pub struct ImageView {
     image_size: Option<(u32, u32)>,
     pending_image: Option<Arc<gpui::Image>>,
     displayed_image: Option<DisplayedImage>,
+    edits: Entity<ImageEdits>,
+    crop_selection: Option<CropSelection>,
+    edit_task: Option<Task<()>>,
+    _image_item_subscription: Option<Subscription>,
+    _edits_subscription: Subscription,
+}
+
+/// Unsaved edits to an image, shared by a view and the views split from it.
+#[derive(Default)]
+struct ImageEdits {
+    /// The result of the edits, shown in place of the file's contents.
+    image: Option<Arc<gpui::Image>>,
+    size: Option<(u32, u32)>,
+}
+
+/// The region being selected while cropping, in image pixels.
+#[derive(Default)]
+struct CropSelection {
+    corners: Option<(Point<f32>, Point<f32>)>,
+    is_dragging: bool,
+}
+
+impl CropSelection {
+    fn rect(&self) -> Option<(u32, u32, u32, u32)> {
+        let (start, end) = self.corners?;
+        let left = start.x.min(end.x).round() as u32;
+        let top = start.y.min(end.y).round() as u32;
+        let right = start.x.max(end.x).round() as u32;
+        let bottom = start.y.max(end.y).round() as u32;
+        (right > left && bottom > top).then_some((left, top, right - left, bottom - top))
+    }
 }
 
 struct DisplayedImage {
@@ -149,7 +201,7 @@ impl ImageView {
         let pending_image = image_item.read(cx).image.clone();
         let _render_image = pending_image.clone().get_render_image(window, cx);
 
-        cx.subscribe(&image_item, Self::on_image_event).detach();
+        let image_item_subscription = cx.subscribe(&image_item, Self::on_image_event);
         let git_store = project.read(cx).git_store().clone();
         cx.subscribe(&git_store, |_, _, event, cx| {
             if matches!(event, GitStoreEvent::DiffBaseChanged(_)) {
@@ -170,6 +222,8 @@ impl ImageView {
             .read(cx)
             .image_metadata
             .map(|m| (m.width, m.height));
+        let edits = cx.new(|_| ImageEdits::default());
+        let edits_subscription = cx.observe(&edits, Self::on_edits_changed);
 
         Self {
             image_item,
@@ -182,9 +236,33 @@ impl ImageView {
             image_size,
             pending_image: Some(pending_image),
             displayed_image: None,
+            edits,
+            crop_selection: None,
+            edit_task: None,
+            _image_item_subscription: Some(image_item_subscription),
+            _edits_subscription: edits_subscription,
         }
     }
 
+    fn on_edits_changed(&mut self, edits: Entity<ImageEdits>, cx: &mut Context<Self>) {
+        self.image_size = edits.read(cx).size.or_else(|| {
+            self.image_item
+                .read(cx)
+                .image_metadata
+                .map(|m| (m.width, m.height))
+        });
+        self.pan_offset = Point::default();
+        cx.emit(ImageViewEvent::Edited);
+        cx.notify();
+    }
+
+    fn set_edited_image(&self, edited: Option<(Arc<gpui::Image>, (u32, u32))>, cx: &mut App) {
+        self.edits.update(cx, |edits, cx| {
+            (edits.image, edits.size) = edited.unzip();
+            cx.notify();
+        });
+    }
+
     fn on_image_event(
         &mut self,
         _: Entity<ImageItem>,
@@ -195,11 +273,13 @@ impl ImageView {
             ImageItemEvent::MetadataUpdated
             | ImageItemEvent::FileHandleChanged
             | ImageItemEvent::Reloaded => {
-                self.image_size = self
-                    .image_item
-                    .read(cx)
-                    .image_metadata
-                    .map(|m| (m.width, m.height));
+                if self.edits.read(cx).image.is_none() {
+                    self.image_size = self
+                        .image_item
+                        .read(cx)
+                        .image_metadata
+                        .map(|m| (m.width, m.height));
+                }
                 cx.emit(ImageViewEvent::TitleChanged);
                 cx.notify();
             }
@@ -319,7 +399,14 @@ impl ImageView {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if event.button == MouseButton::Left || event.button == MouseButton::Middle {
+        if event.button == MouseButton::Left
+            && let Some(position) = self.image_position(event.position)
+            && let Some(crop_selection) = self.crop_selection.as_mut()
+        {
+            crop_selection.corners = Some((position, position));
+            crop_selection.is_dragging = true;
+            cx.notify();
+        } else if event.button == MouseButton::Left || event.button == MouseButton::Middle {
             self.last_mouse_position = Some(event.position);
             cx.notify();
         }
@@ -331,8 +418,7 @@ impl ImageView {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.last_mouse_position = None;
-        cx.notify();
+        self.end_drag(cx);
     }
 
     fn handle_mouse_move(
@@ -341,7 +427,21 @@ impl ImageView {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if self.is_dragging() {
+        if self
+            .crop_selection
+            .as_ref()
+            .is_some_and(|crop_selection| crop_selection.is_dragging)
+        {
+            if let Some(position) = self.image_position(event.position)
+                && let Some((_, end)) = self
+                    .crop_selection
+                    .as_mut()
+                    .and_then(|crop_selection| crop_selection.corners.as_mut())
+            {
+                *end = position;
+                cx.notify();
+            }
+        } else if self.is_dragging() {
             if let Some(last_pos) = self.last_mouse_position {
                 let delta = event.position - last_pos;
                 self.pan_offset += delta;
@@ -355,6 +455,209 @@ impl ImageView {
         let zoom_factor = 1.0 + event.delta;
         self.set_zoom(self.zoom_level * zoom_factor, Some(event.position), cx);
     }
+
+    fn current_image(&self, cx: &App) -> Arc<gpui::Image> {
+        self.edits
+            .read(cx)
+            .image
+            .clone()
+            .unwrap_or_else(|| self.image_item.read(cx).image.clone())
+    }
+
+    fn apply_edit(&mut self, edit: ImageEdit, cx: &mut Context<Self>) {
+        let previous_edit = self.edit_task.take();
+        self.edit_task = Some(cx.spawn(async move |this, cx| {
+            // Each edit builds on the result of the previous one.
+            if let Some(previous_edit) = previous_edit {
+                previous_edit.await;
+            }
+            let Ok(source) = this.read_with(cx, |this, cx| this.current_image(cx)) else {
+                return;
+            };
+            let edited = cx
+                .background_spawn(async move { apply_image_edit(&source, edit) })
+                .await;
+            this.update(cx, |this, cx| match edited {
+                Ok(edited) => this.set_edited_image(Some(edited), cx),
+                Err(error) => log::error!("Failed to edit image: {error:#}"),
+            })
+            .log_err();
+        }));
+    }
+
+    fn rotate_clockwise(&mut self, _: &RotateClockwise, _: &mut Window, cx: &mut Context<Self>) {
+        self.apply_edit(ImageEdit::RotateClockwise, cx);
+    }
+
+    fn rotate_counterclockwise(
+        &mut self,
+        _: &RotateCounterclockwise,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.apply_edit(ImageEdit::RotateCounterclockwise, cx);
+    }
+
+    fn flip_horizontal(&mut self, _: &FlipHorizontal, _: &mut Window, cx: &mut Context<Self>) {
+        self.apply_edit(ImageEdit::FlipHorizontal, cx);
+    }
+
+    fn flip_vertical(&mut self, _: &FlipVertical, _: &mut Window, cx: &mut Context<Self>) {
+        self.apply_edit(ImageEdit::FlipVertical, cx);
+    }
+
+    fn crop(&mut self, _: &Crop, _: &mut Window, cx: &mut Context<Self>) {
+        self.crop_selection = Some(CropSelection::default());
+        cx.notify();
+    }
+
+    fn confirm_crop(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some((x, y, width, height)) = self
+            .crop_selection
+            .take()
+            .and_then(|crop_selection| crop_selection.rect())
+        {
+            self.apply_edit(
+                ImageEdit::Crop {
+                    x,
+                    y,
+                    width,
+                    height,
+                },
+                cx,
+            );
+        }
+        cx.notify();
+    }
+
+    fn cancel_crop(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        self.crop_selection = None;
+        cx.notify();
+    }
+
+    fn resize(&mut self, _: &Resize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(image_size) = self.image_size else {
+            return;
+        };
+        let Some(workspace) = Workspace::for_window(window, cx) else {
+            return;
+        };
+        let image_view = cx.entity().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                ResizeImageModal::new(image_view, image_size, window, cx)
+            });
+        });
+    }
+
+    fn copy_image(&mut self, _: &CopyImage, _: &mut Window, cx: &mut Context<Self>) {
+        let image = self.current_image(cx);
+        cx.write_to_clipboard(ClipboardItem::new_image(&image));
+    }
+
+    /// Converts a window position to a position within the image, in image pixels.
+    fn image_position(&self, position: Point<Pixels>) -> Option<Point<f32>> {
+        let bounds = self.container_bounds?;
+        let (width, height) = self.image_size?;
+        let image_origin = point(
+            bounds.origin.x + bounds.size.width / 2.0 - px(width as f32 * self.zoom_level) / 2.0
+                + self.pan_offset.x,
+            bounds.origin.y + bounds.size.height / 2.0 - px(height as f32 * self.zoom_level) / 2.0
+                + self.pan_offset.y,
+        );
+        let x: f32 = ((position.x - image_origin.x) / self.zoom_level).into();
+        let y: f32 = ((position.y - image_origin.y) / self.zoom_level).into();
+        Some(point(
+            x.clamp(0.0, width as f32),
+            y.clamp(0.0, height as f32),
+        ))
+    }
+
+    fn end_drag(&mut self, cx: &mut Context<Self>) {
+        self.last_mouse_position = None;
+        if let Some(crop_selection) = self.crop_selection.as_mut() {
+            crop_selection.is_dragging = false;
+        }
+        cx.notify();
+    }
+
+    /// Writes the image, including any unsaved edits, to `project_path`, in the
+    /// format implied by its extension.
+    ///
+    /// Saving to a different path switches the view over to the new file.
+    fn save_image(
+        &mut self,
+        project: Entity<Project>,
+        project_path: ProjectPath,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let edited_image = self.edits.read(cx).image.clone();
+        if edited_image.is_none() && self.image_item.read(cx).project_path(cx) == project_path {
+            return Task::ready(Ok(()));
+        }
+        let image = self.current_image(cx);
+        let Some(format) = project_path
+            .path
+            .extension()
+            .and_then(ImageExportFormat::from_extension)
+        else {
+            return Task::ready(Err(anyhow!(
+                "Images can only be saved as PNG, JPEG, or WebP"
+            )));
+        };
+        let Some(worktree) = project
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)
+        else {
+            return Task::ready(Err(anyhow!("No worktree for path {project_path:?}")));
+        };
+        let jpeg_quality = ImageViewerSettings::get_global(cx).jpeg_quality;
+
+        cx.spawn(async move |this, cx| {
+            let bytes = cx
+                .background_spawn(async move {
+                    encode_image(&decode_image(&image)?, format, jpeg_quality)
+                })
+                .await
+                .with_context(|| format!("encoding image as {}", format.label()))?;
+            // Worktrees write through the project's file system, so this works for remote projects too.
+            worktree
+                .update(cx, |worktree, cx| {
+                    worktree.create_entry(project_path.path.clone(), false, Some(bytes), cx)
+                })
+                .await?;
+
+            let is_same_file = this.read_with(cx, |this, cx| {
+                this.image_item.read(cx).project_path(cx) == project_path
+            })?;
+            if !is_same_file {
+                let image_item = project
+                    .update(cx, |project, cx| project.open_image(project_path, cx))
+                    .await?;
+                this.update(cx, |this, cx| {
+                    this._image_item_subscription =
+                        Some(cx.subscribe(&image_item, Self::on_image_event));
+                    this.image_item = image_item;
+                })?;
+            }
+
+            this.update(cx, |this, cx| {
+                if let Some(edited_image) = &edited_image
+                    && this
+                        .edits
+                        .read(cx)
+                        .image
+                        .as_ref()
+                        .is_some_and(|image| Arc::ptr_eq(image, edited_image))
+                {
+                    this.set_edited_image(None, cx);
+                }
+                cx.emit(ImageViewEvent::Edited);
+                cx.emit(ImageViewEvent::TitleChanged);
+                cx.notify();
+            })
+        })
+    }
 }
 
 struct ImageContentElement {
@@ -417,7 +720,7 @@ impl Element for ImageContentElement {
         cx: &mut App,
     ) -> Self::PrepaintState {
         let image_view = self.image_view.read(cx);
-        let image = image_view.image_item.read(cx).image.clone();
+        let image = image_view.current_image(cx);
 
         let first_layout = image_view.container_bounds.is_none();
 
@@ -434,7 +737,16 @@ impl Element for ImageContentElement {
         let pan_offset = image_view.pan_offset;
         let border_color = cx.theme().colors().border;
 
-        let is_dragging = image_view.is_dragging();
+        let is_dragging = image_view.is_dragging()
+            || image_view
+                .crop_selection
+                .as_ref()
+                .is_some_and(|crop_selection| crop_selection.is_dragging);
+        let crop_rect = image_view
+            .crop_selection
+            .as_ref()
+            .and_then(|crop_selection| crop_selection.rect());
+        let accent_color = cx.theme().colors().text_accent;
 
         let scaled_size = image_view
             .image_size
@@ -491,6 +803,19 @@ impl Element for ImageContentElement {
                         img(image)
                             .id(("image-viewer-image", self.image_view.entity_id()))
                             .size_full()
+                    })
+                    .when_some(crop_rect, |this, (x, y, width, height)| {
+                        this.child(
+                            div()
+                                .absolute()
+                                .left(px(x as f32 * zoom_level))
+                                .top(px(y as f32 * zoom_level))
+                                .w(px(width as f32 * zoom_level))
+                                .h(px(height as f32 * zoom_level))
+                                .border_1()
+                                .border_color(accent_color)
+                                .bg(accent_color.opacity(0.15)),
+                        )
                     }),
             )
             .into_any_element();
@@ -519,10 +844,7 @@ impl Element for ImageContentElement {
                 if phase == DispatchPhase::Bubble
                     && let Some(entity) = image_view.upgrade()
                 {
-                    entity.update(cx, |this, cx| {
-                        this.last_mouse_position = None;
-                        cx.notify();
-                    });
+                    entity.update(cx, |this, cx| this.end_drag(cx));
                 }
             });
         }
@@ -533,6 +855,7 @@ impl Element for ImageContentElement {
 
 pub enum ImageViewEvent {
     TitleChanged,
+    Edited,
 }
 
 impl EventEmitter<ImageViewEvent> for ImageView {}
@@ -546,6 +869,10 @@ impl Item for ImageView {
                 f(workspace::item::ItemEvent::UpdateTab);
                 f(workspace::item::ItemEvent::UpdateBreadcrumbs);
             }
+            ImageViewEvent::Edited => {
+                f(workspace::item::ItemEvent::Edit);
+                f(workspace::item::ItemEvent::UpdateTab);
+            }
         }
     }
 
@@ -658,12 +985,68 @@ impl Item for ImageView {
             image_size: self.image_size,
             pending_image: None,
             displayed_image: None,
+            edits: self.edits.clone(),
+            crop_selection: None,
+            edit_task: None,
+            _image_item_subscription: None,
+            _edits_subscription: cx.observe(&self.edits, Self::on_edits_changed),
         })))
     }
 
     fn has_deleted_file(&self, cx: &App) -> bool {
         self.image_item.read(cx).file.disk_state().is_deleted()
     }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.edits.read(cx).image.is_some()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.image_item
+            .read(cx)
+            .file
+            .path()
+            .extension()
+            .and_then(ImageExportFormat::from_extension)
+            .is_some()
+    }
+
+    fn can_save_as(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let project_path = self.image_item.read(cx).project_path(cx);
+        self.save_image(project, project_path, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        project: Entity<Project>,
+        path: ProjectPath,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.save_image(project, path, cx)
+    }
+
+    fn reload(
+        &mut self,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.crop_selection = None;
+        self.edit_task = None;
+        self.set_edited_image(None, cx);
+        Task::ready(Ok(()))
+    }
     fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
         workspace::item::ItemBufferKind::Singleton
     }
@@ -768,13 +1151,28 @@ impl Render for ImageView {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .track_focus(&self.focus_handle(cx))
-            .key_context("ImageViewer")
+            .key_context(if self.crop_selection.is_some() {
+                "ImageViewer cropping"
+            } else {
+                "ImageViewer"
+            })
             .on_action(cx.listener(Self::zoom_in))
             .on_action(cx.listener(Self::zoom_out))
             .on_action(cx.listener(Self::reset_zoom))
             .on_action(cx.listener(Self::fit_to_view))
             .on_action(cx.listener(Self::zoom_to_actual_size))
             .on_action(cx.listener(Self::reveal_in_file_manager))
+            .on_action(cx.listener(Self::rotate_clockwise))
+            .on_action(cx.listener(Self::rotate_counterclockwise))
+            .on_action(cx.listener(Self::flip_horizontal))
+            .on_action(cx.listener(Self::flip_vertical))
+            .on_action(cx.listener(Self::crop))
+            .on_action(cx.listener(Self::resize))
+            .on_action(cx.listener(Self::copy_image))
+            .when(self.crop_selection.is_some(), |this| {
+                this.on_action(cx.listener(Self::confirm_crop))
+                    .on_action(cx.listener(Self::cancel_crop))
+            })
             .size_full()
             .relative()
             .bg(cx.theme().colors().editor_background)
@@ -783,7 +1181,9 @@ impl Render for ImageView {
                     .id("image-container")
                     .size_full()
                     .overflow_hidden()
-                    .cursor(if self.is_dragging() {
+                    .cursor(if self.crop_selection.is_some() {
+                        gpui::CursorStyle::Crosshair
+                    } else if self.is_dragging() {
                         gpui::CursorStyle::ClosedHand
                     } else {
                         gpui::CursorStyle::OpenHand
@@ -932,6 +1332,87 @@ impl ImageViewToolbarControls {
         self._zoom_subscription = None;
         cx.notify();
     }
+
+    fn render_edit_controls(&self, image_view: &Entity<ImageView>, cx: &App) -> AnyElement {
+        let focus_handle = image_view.read(cx).focus_handle.clone();
+
+        if image_view.read(cx).crop_selection.is_some() {
+            return h_flex()
+                .gap_1()
+                .child(
+                    IconButton::new("apply-crop", IconName::Check)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Apply Crop"))
+                        .on_click({
+                            let focus_handle = focus_handle.clone();
+                            move |_, window, cx| {
+                                focus_handle.dispatch_action(&menu::Confirm, window, cx)
+                            }
+                        }),
+                )
+                .child(
+                    IconButton::new("cancel-crop", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Cancel Crop"))
+                        .on_click(move |_, window, cx| {
+                            focus_handle.dispatch_action(&menu::Cancel, window, cx)
+                        }),
+                )
+                .into_any_element();
+        }
+
+        h_flex()
+            .gap_1()
+            .child(
+                IconButton::new("rotate-counterclockwise", IconName::RotateCcw)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Rotate Counterclockwise", &RotateCounterclockwise, cx)
+                    })
+                    .on_click({
+                        let focus_handle = focus_handle.clone();
+                        move |_, window, cx| {
+                            focus_handle.dispatch_action(&RotateCounterclockwise, window, cx)
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("rotate-clockwise", IconName::RotateCw)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Rotate Clockwise", &RotateClockwise, cx)
+                    })
+                    .on_click({
+                        let focus_handle = focus_handle.clone();
+                        move |_, window, cx| {
+                            focus_handle.dispatch_action(&RotateClockwise, window, cx)
+                        }
+                    }),
+            )
+            .child(
+                PopoverMenu::new("image-edit-menu")
+                    .trigger_with_tooltip(
+                        IconButton::new("image-edit-menu-trigger", IconName::Ellipsis)
+                            .icon_size(IconSize::Small),
+                        Tooltip::text("Edit Image"),
+                    )
+                    .anchor(Anchor::TopRight)
+                    .menu(move |window, cx| {
+                        let focus_handle = focus_handle.clone();
+                        Some(ContextMenu::build(window, cx, |menu, _, _| {
+                            menu.context(focus_handle)
+                                .action("Flip Horizontally", FlipHorizontal.boxed_clone())
+                                .action("Flip Vertically", FlipVertical.boxed_clone())
+                                .action("Crop", Crop.boxed_clone())
+                                .action("Resize…", Resize.boxed_clone())
+                                .separator()
+                                .action("Copy Image", CopyImage.boxed_clone())
+                                .action("Save As…", workspace::SaveAs.boxed_clone())
+                        }))
+                    }),
+            )
+            .into_any_element()
+    }
 }
 
 impl Render for ImageViewToolbarControls {
@@ -942,6 +1423,8 @@ impl Render for ImageViewToolbarControls {
 
         h_flex()
             .gap_1()
+            .child(self.render_edit_controls(&image_view, cx))
+            .child(Divider::vertical())
             .child(
                 IconButton::new("zoom-out", IconName::Dash)
                     .icon_size(IconSize::Small)
@@ -1091,6 +1574,7 @@ mod tests {
     use fs::{FakeFs, Fs as _};
     use gpui::{TestAppContext, VisualTestContext};
     use settings::SettingsStore;
+    use std::{cell::Cell, rc::Rc};
     use util::rel_path::rel_path;
 
     fn init_test(cx: &mut TestAppContext) {
@@ -1348,6 +1832,181 @@ mod tests {
             split_image_view.clone().into_any_element()
         });
     }
+
+    #[gpui::test]
+    async fn test_saving_edited_image_as_another_format(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (project, image_item) = open_test_image(cx).await;
+        let worktree_id = cx.read(|cx| image_item.read(cx).project_path(cx).worktree_id);
+
+        let (image_view, cx) = cx.add_window_view(|window, cx| {
+            ImageView::new(image_item.clone(), project.clone(), window, cx)
+        });
+        image_view.update(cx, |image_view, cx| {
+            image_view.apply_edit(
+                ImageEdit::Resize {
+                    width: 4,
+                    height: 2,
+                },
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        assert!(cx.read(|cx| image_view.read(cx).is_dirty(cx)));
+        assert_eq!(cx.read(|cx| image_view.read(cx).image_size), Some((4, 2)));
+
+        image_view
+            .update_in(cx, |image_view, window, cx| {
+                image_view.save_as(
+                    project.clone(),
+                    ProjectPath {
+                        worktree_id,
+                        path: rel_path("resized.jpg").into(),
+                    },
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .expect("edited image should be saved");
+
+        assert!(!cx.read(|cx| image_view.read(cx).is_dirty(cx)));
+        assert_eq!(
+            cx.read(|cx| image_view
+                .read(cx)
+                .image_item
+                .read(cx)
+                .project_path(cx)
+                .path),
+            rel_path("resized.jpg").into(),
+            "the view should switch to the newly written file"
+        );
+        let fs = cx.read(|cx| project.read(cx).fs().clone());
+        let bytes = fs
+            .load_bytes(Path::new("/root/resized.jpg"))
+            .await
+            .expect("resized image should be written");
+        assert_eq!(
+            image::guess_format(&bytes).ok(),
+            Some(image::ImageFormat::Jpeg)
+        );
+        assert_eq!(
+            image::load_from_memory(&bytes)
+                .map(|image| (image.width(), image.height()))
+                .ok(),
+            Some((4, 2))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_split_views_share_edits(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (project, image_item) = open_test_image(cx).await;
+
+        let cx = cx.add_empty_window();
+        let image_view = cx.update(|window, cx| {
+            cx.new(|cx| ImageView::new(image_item.clone(), project.clone(), window, cx))
+        });
+        let split_image_view = image_view
+            .update_in(cx, |image_view, window, cx| {
+                image_view.clone_on_split(None, window, cx)
+            })
+            .await
+            .expect("image view should support splitting");
+
+        image_view.update(cx, |image_view, cx| {
+            image_view.apply_edit(
+                ImageEdit::Resize {
+                    width: 4,
+                    height: 2,
+                },
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        for view in [&image_view, &split_image_view] {
+            assert!(cx.read(|cx| view.read(cx).is_dirty(cx)));
+            assert_eq!(cx.read(|cx| view.read(cx).image_size), Some((4, 2)));
+        }
+
+        split_image_view
+            .update_in(cx, |image_view, window, cx| {
+                image_view.reload(project.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert!(!cx.read(|cx| image_view.read(cx).is_dirty(cx)));
+        assert_eq!(
+            cx.read(|cx| image_view.read(cx).image_size),
+            cx.read(|cx| image_item
+                .read(cx)
+                .image_metadata
+                .map(|m| (m.width, m.height)))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_saving_unedited_image_as_another_format(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (project, image_item) = open_test_image(cx).await;
+        let worktree_id = cx.read(|cx| image_item.read(cx).project_path(cx).worktree_id);
+
+        let (image_view, cx) = cx.add_window_view(|window, cx| {
+            ImageView::new(image_item.clone(), project.clone(), window, cx)
+        });
+        assert!(!cx.read(|cx| image_view.read(cx).is_dirty(cx)));
+
+        image_view
+            .update_in(cx, |image_view, window, cx| {
+                image_view.save_as(
+                    project.clone(),
+                    ProjectPath {
+                        worktree_id,
+                        path: rel_path("copy.png").into(),
+                    },
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .expect("image should be saved");
+
+        let fs = cx.read(|cx| project.read(cx).fs().clone());
+        let bytes = fs
+            .load_bytes(Path::new("/root/copy.png"))
+            .await
+            .expect("image should be written");
+        assert_eq!(
+            image::guess_format(&bytes).ok(),
+            Some(image::ImageFormat::Png)
+        );
+        assert_eq!(
+            cx.read(|cx| image_view
+                .read(cx)
+                .image_item
+                .read(cx)
+                .project_path(cx)
+                .path),
+            rel_path("copy.png").into(),
+        );
+
+        // Events from the file the view used to show no longer reach it.
+        let title_changes = Rc::new(Cell::new(0));
+        let _subscription = cx.update(|_, cx| {
+            let title_changes = title_changes.clone();
+            cx.subscribe(&image_view, move |_, event, _| {
+                if matches!(event, ImageViewEvent::TitleChanged) {
+                    title_changes.set(title_changes.get() + 1);
+                }
+            })
+        });
+        cx.update(|_, cx| {
+            image_item.update(cx, |_, cx| cx.emit(ImageItemEvent::FileHandleChanged));
+        });
+        cx.run_until_parked();
+        assert_eq!(title_changes.get(), 0);
+    }
 }
 
 mod persistence {
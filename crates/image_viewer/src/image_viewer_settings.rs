@@ -8,12 +8,18 @@ pub struct ImageViewerSettings {
     ///
     /// Default: "binary"
     pub unit: ImageFileSizeUnit,
+    /// The quality, from 1 to 100, used when saving edited images as JPEG.
+    ///
+    /// Default: 90
+    pub jpeg_quality: u8,
 }
 
 impl Settings for ImageViewerSettings {
     fn from_settings(content: &settings::SettingsContent) -> Self {
+        let image_viewer = content.image_viewer.clone().unwrap();
         Self {
-            unit: content.image_viewer.clone().unwrap().unit.unwrap(),
+            unit: image_viewer.unit.unwrap(),
+            jpeg_quality: image_viewer.jpeg_quality.unwrap().clamp(1, 100) as u8,
         }
     }
 }
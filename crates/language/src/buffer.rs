@@ -587,6 +587,27 @@ pub enum CharKind {
     Word,
 }
 
+/// A kind of syntactic region, as classified by the captures of a
+/// language's highlights query.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum SyntacticRegion {
+    /// Text captured as `comment`, or a sub-capture such as `comment.doc`.
+    Comment,
+    /// Text captured as `string`, or a sub-capture such as `string.escape`.
+    String,
+}
+
+impl SyntacticRegion {
+    fn from_capture_name(name: &str) -> Option<Self> {
+        let kind = name.split('.').next()?;
+        match kind {
+            "comment" => Some(Self::Comment),
+            "string" => Some(Self::String),
+            _ => None,
+        }
+    }
+}
+
 /// Context for character classification within a specific scope.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum CharScopeContext {
@@ -3882,6 +3903,45 @@ impl BufferSnapshot {
         self.syntax.captures(range, &self.text, query)
     }
 
+    /// Returns the comments and string literals that intersect the given
+    /// range, ordered by their start offset. Regions from injected languages
+    /// are included, so regions may be nested.
+    pub fn syntactic_regions(&self, range: Range<usize>) -> Vec<(Range<usize>, SyntacticRegion)> {
+        let captures = self.syntax.captures(range, &self.text, |grammar| {
+            grammar
+                .highlights_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+        let regions_by_capture = captures
+            .grammars()
+            .iter()
+            .map(|grammar| {
+                grammar
+                    .highlights_config
+                    .as_ref()
+                    .map(|config| {
+                        config
+                            .query
+                            .capture_names()
+                            .iter()
+                            .map(|name| SyntacticRegion::from_capture_name(name))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        captures
+            .filter_map(|capture| {
+                let region = regions_by_capture[capture.grammar_index]
+                    .get(capture.index as usize)
+                    .copied()
+                    .flatten()?;
+                Some((capture.node.byte_range(), region))
+            })
+            .collect()
+    }
+
     #[ztracing::instrument(skip_all)]
     fn get_highlights(&self, range: Range<usize>) -> (SyntaxMapCaptures<'_>, Vec<HighlightMap>) {
         let captures = self.syntax.captures(range, &self.text, |grammar| {
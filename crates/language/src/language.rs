@@ -19,6 +19,7 @@ pub mod modeline;
 mod outline;
 pub mod proto;
 mod runnable;
+mod structural_search;
 mod syntax_map;
 mod task_context;
 mod text_diff;
@@ -81,6 +82,7 @@ use std::{
     str,
     sync::{Arc, LazyLock},
 };
+pub use structural_search::{StructuralMatch, StructuralPattern};
 use syntax_map::{QueryCursorHandle, SyntaxSnapshot};
 use task::RunnableTag;
 pub use task_context::{ContextLocation, ContextProvider};
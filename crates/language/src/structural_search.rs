@@ -0,0 +1,590 @@
+//! Matching code by its syntax rather than its text.
+//!
+//! A [`StructuralPattern`] is either a snippet of code containing
+//! metavariables, like `foo($A, $$$REST)`, or a raw Tree-sitter query, like
+//! `(call_expression function: (identifier) @name) @match`.
+//!
+//! Code patterns are parsed with the grammar of each syntax layer they're
+//! searched in, and match any node whose tokens are the pattern's tokens,
+//! ignoring whitespace and comments. A metavariable like `$A` matches exactly
+//! one node, so it can't end partway through a nested expression, and
+//! `$$$REST` matches any run of sibling nodes, including none. A metavariable
+//! used more than once must match the same text each time, except for `$_`,
+//! which matches anything.
+
+use crate::{
+    BufferSnapshot, Language, LanguageId,
+    syntax_map::{QueryCursorHandle, TextProvider},
+    with_parser,
+};
+use anyhow::{Result, ensure};
+use collections::HashMap;
+use parking_lot::Mutex;
+use regex::{Captures, Regex};
+use std::{
+    fmt,
+    ops::Range,
+    sync::{Arc, LazyLock},
+};
+use streaming_iterator::StreamingIterator as _;
+use tree_sitter::{Node, Query};
+
+/// Matches `$$$NAME` and `$NAME` metavariables in code patterns.
+static METAVARIABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$\$\$([A-Z_][A-Z0-9_]*)|\$([A-Z_][A-Z0-9_]*)").unwrap());
+
+/// Matches the names substituted in replacement templates, which may also be
+/// the lowercase names of query captures.
+static TEMPLATE_VARIABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\$\$([A-Za-z_][A-Za-z0-9_]*)|\$([A-Za-z_][A-Za-z0-9_]*)").unwrap()
+});
+
+static WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[A-Za-z_][A-Za-z0-9_]*").unwrap());
+
+/// Metavariables are replaced with identifiers starting with this prefix
+/// before a pattern is parsed, so that they're parsed as single tokens.
+const PLACEHOLDER_PREFIX: &str = "zed_metavariable_";
+
+/// The query capture that sets the range of a match. Without one, a match
+/// spans all of its captures.
+const MATCH_CAPTURE: &str = "match";
+
+const WILDCARD: &str = "_";
+
+#[derive(Clone)]
+pub struct StructuralPattern {
+    source: Arc<str>,
+    /// Each language's compiled pattern, or `None` when the pattern can't be
+    /// used with that language.
+    compiled: Arc<Mutex<HashMap<LanguageId, Option<Arc<CompiledPattern>>>>>,
+}
+
+enum CompiledPattern {
+    Code(Vec<PatternToken>),
+    Query(Query),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PatternToken {
+    Literal(String),
+    /// A metavariable that matches one node.
+    Single(String),
+    /// A metavariable that matches a run of sibling nodes.
+    Multiple(String),
+}
+
+/// A match of a [`StructuralPattern`] in a buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuralMatch {
+    pub range: Range<usize>,
+    /// The range matched by each metavariable or query capture, by name.
+    pub captures: Vec<(String, Range<usize>)>,
+}
+
+impl fmt::Debug for StructuralPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StructuralPattern")
+            .field(&self.source)
+            .finish()
+    }
+}
+
+impl StructuralPattern {
+    pub fn new(source: &str) -> Result<Self> {
+        let source = source.trim();
+        ensure!(!source.is_empty(), "structural search pattern is empty");
+        Ok(Self {
+            source: source.into(),
+            compiled: Arc::default(),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the pattern is tried as a Tree-sitter query before being
+    /// treated as code.
+    fn may_be_query(&self) -> bool {
+        self.source.starts_with('(') || self.source.starts_with('[')
+    }
+
+    /// Words that occur in the text of every match, so that files lacking
+    /// them can be skipped without being parsed.
+    pub fn required_words(&self) -> Vec<String> {
+        if self.may_be_query() {
+            return Vec::new();
+        }
+        let source = METAVARIABLE.replace_all(&self.source, " ");
+        WORD.find_iter(&source)
+            .map(|word| word.as_str().to_string())
+            .collect()
+    }
+
+    /// The non-overlapping matches of the pattern that lie within `range`,
+    /// in every syntax layer of the buffer. When matches overlap, the
+    /// outermost one is kept.
+    pub fn matches(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<StructuralMatch> {
+        let mut matches = Vec::new();
+        let mut text = None;
+        for layer in buffer.syntax_layers() {
+            let root = layer.node();
+            if root.end_byte() <= range.start || root.start_byte() >= range.end {
+                continue;
+            }
+            let Some(pattern) = self.compiled_for(layer.language) else {
+                continue;
+            };
+            match pattern.as_ref() {
+                CompiledPattern::Code(tokens) => {
+                    let text =
+                        text.get_or_insert_with(|| buffer.text_for_range(range.clone()).collect());
+                    code_matches(tokens, root, text, range.clone(), &mut matches);
+                }
+                CompiledPattern::Query(query) => {
+                    query_matches(query, root, buffer, range.clone(), &mut matches);
+                }
+            }
+        }
+
+        matches.sort_by(|a, b| {
+            a.range
+                .start
+                .cmp(&b.range.start)
+                .then(b.range.end.cmp(&a.range.end))
+        });
+        let mut end = range.start;
+        matches.retain(|mat| {
+            let keep = mat.range.start >= end && !mat.range.is_empty();
+            if keep {
+                end = mat.range.end;
+            }
+            keep
+        });
+        matches
+    }
+
+    fn compiled_for(&self, language: &Arc<Language>) -> Option<Arc<CompiledPattern>> {
+        self.compiled
+            .lock()
+            .entry(language.id())
+            .or_insert_with(|| self.compile(language).map(Arc::new))
+            .clone()
+    }
+
+    fn compile(&self, language: &Language) -> Option<CompiledPattern> {
+        let grammar = language.grammar()?;
+        if self.may_be_query()
+            && let Ok(query) = Query::new(&grammar.ts_language, &self.source)
+        {
+            return Some(CompiledPattern::Query(query));
+        }
+
+        let mut metavariables = Vec::new();
+        let source = METAVARIABLE.replace_all(&self.source, |captures: &Captures| {
+            let (name, multiple) = match captures.get(1) {
+                Some(name) => (name.as_str(), true),
+                None => (&captures[2], false),
+            };
+            metavariables.push((name.to_string(), multiple));
+            format!("{PLACEHOLDER_PREFIX}{}", metavariables.len() - 1)
+        });
+        let tree = with_parser(|parser| {
+            parser.set_language(&grammar.ts_language).ok()?;
+            parser.parse(source.as_bytes(), None)
+        })?;
+
+        let mut tokens = Vec::new();
+        for leaf in leaves(tree.root_node(), 0..source.len()) {
+            let text = &source[leaf.byte_range()];
+            let metavariable = text
+                .strip_prefix(PLACEHOLDER_PREFIX)
+                .and_then(|index| metavariables.get(index.parse::<usize>().ok()?));
+            tokens.push(match metavariable {
+                Some((name, true)) => PatternToken::Multiple(name.clone()),
+                Some((name, false)) => PatternToken::Single(name.clone()),
+                None => PatternToken::Literal(text.to_string()),
+            });
+        }
+        (!tokens.is_empty()).then_some(CompiledPattern::Code(tokens))
+    }
+}
+
+impl StructuralMatch {
+    /// Expands a replacement template, substituting each `$NAME` or
+    /// `$$$NAME` with the text its metavariable or capture matched. Names
+    /// that weren't captured are left as they are.
+    pub fn expand_replacement(&self, template: &str, buffer: &BufferSnapshot) -> String {
+        TEMPLATE_VARIABLE
+            .replace_all(template, |captures: &Captures| {
+                let name = captures
+                    .get(1)
+                    .or_else(|| captures.get(2))
+                    .map_or("", |name| name.as_str());
+                match self.captures.iter().find(|(capture, _)| capture == name) {
+                    Some((_, range)) => buffer.text_for_range(range.clone()).collect(),
+                    None => captures[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+fn is_comment(node: &Node) -> bool {
+    node.kind().contains("comment")
+}
+
+/// The tokens of a tree within a range, skipping comments and the empty
+/// nodes inserted for recovering from syntax errors.
+fn leaves<'a>(root: Node<'a>, range: Range<usize>) -> Vec<Node<'a>> {
+    let mut leaves = Vec::new();
+    let mut cursor = root.walk();
+    loop {
+        let node = cursor.node();
+        let overlaps = node.start_byte() < range.end && node.end_byte() > range.start;
+        let descended = overlaps && !is_comment(&node) && cursor.goto_first_child();
+        if !descended {
+            if overlaps
+                && node.child_count() == 0
+                && !node.is_missing()
+                && node.start_byte() < node.end_byte()
+                && !is_comment(&node)
+                && node.start_byte() >= range.start
+                && node.end_byte() <= range.end
+            {
+                leaves.push(node);
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    return leaves;
+                }
+            }
+        }
+    }
+}
+
+fn code_matches(
+    tokens: &[PatternToken],
+    root: Node,
+    text: &str,
+    range: Range<usize>,
+    matches: &mut Vec<StructuralMatch>,
+) {
+    let leaves = leaves(root, range.clone());
+    let mut cursor = root.walk();
+    loop {
+        let node = cursor.node();
+        let overlaps = node.start_byte() < range.end && node.end_byte() > range.start;
+        if overlaps
+            && node.is_named()
+            && node.start_byte() >= range.start
+            && node.end_byte() <= range.end
+        {
+            let matcher = Matcher {
+                tokens,
+                leaves: &leaves,
+                text,
+                text_offset: range.start,
+                leaf_end: leaves.partition_point(|leaf| leaf.start_byte() < node.end_byte()),
+                byte_end: node.end_byte(),
+            };
+            let leaf_start = leaves.partition_point(|leaf| leaf.start_byte() < node.start_byte());
+            let mut captures = Vec::new();
+            if leaf_start < matcher.leaf_end && matcher.matches(0, leaf_start, &mut captures) {
+                matches.push(StructuralMatch {
+                    range: node.byte_range(),
+                    captures,
+                });
+            }
+        }
+
+        if !(overlaps && cursor.goto_first_child()) {
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Matches a code pattern's tokens against the tokens of one node.
+struct Matcher<'a, 'tree> {
+    tokens: &'a [PatternToken],
+    leaves: &'a [Node<'tree>],
+    text: &'a str,
+    text_offset: usize,
+    /// The index just past the node's last leaf.
+    leaf_end: usize,
+    byte_end: usize,
+}
+
+impl<'tree> Matcher<'_, 'tree> {
+    fn text(&self, range: Range<usize>) -> &str {
+        &self.text[range.start - self.text_offset..range.end - self.text_offset]
+    }
+
+    fn leaf_after(&self, byte: usize) -> usize {
+        self.leaves.partition_point(|leaf| leaf.start_byte() < byte)
+    }
+
+    fn matches(
+        &self,
+        token_ix: usize,
+        leaf_ix: usize,
+        captures: &mut Vec<(String, Range<usize>)>,
+    ) -> bool {
+        let Some(token) = self.tokens.get(token_ix) else {
+            return leaf_ix == self.leaf_end;
+        };
+        match token {
+            PatternToken::Literal(literal) => {
+                leaf_ix < self.leaf_end
+                    && self.text(self.leaves[leaf_ix].byte_range()) == literal
+                    && self.matches(token_ix + 1, leaf_ix + 1, captures)
+            }
+            PatternToken::Single(name) => {
+                if leaf_ix == self.leaf_end {
+                    return false;
+                }
+                self.nodes_starting_at(leaf_ix).any(|node| {
+                    self.try_bind(name, node.byte_range(), captures, |captures| {
+                        self.matches(token_ix + 1, self.leaf_after(node.end_byte()), captures)
+                    })
+                })
+            }
+            PatternToken::Multiple(name) => {
+                let start = self
+                    .leaves
+                    .get(leaf_ix)
+                    .filter(|_| leaf_ix < self.leaf_end)
+                    .map_or(self.byte_end, |leaf| leaf.start_byte());
+                if self.try_bind(name, start..start, captures, |captures| {
+                    self.matches(token_ix + 1, leaf_ix, captures)
+                }) {
+                    return true;
+                }
+                if leaf_ix == self.leaf_end {
+                    return false;
+                }
+                self.nodes_starting_at(leaf_ix).any(|first| {
+                    let mut last = first;
+                    loop {
+                        let range = first.start_byte()..last.end_byte();
+                        if self.try_bind(name, range, captures, |captures| {
+                            self.matches(token_ix + 1, self.leaf_after(last.end_byte()), captures)
+                        }) {
+                            return true;
+                        }
+                        match last.next_sibling() {
+                            Some(next) if next.end_byte() <= self.byte_end => last = next,
+                            _ => return false,
+                        }
+                    }
+                })
+            }
+        }
+    }
+
+    /// The leaf at the given index and its ancestors that start with it and
+    /// lie within the node being matched, smallest first.
+    fn nodes_starting_at(&self, leaf_ix: usize) -> impl Iterator<Item = Node<'tree>> + '_ {
+        let leaf = self.leaves[leaf_ix];
+        std::iter::successors(Some(leaf), |node| node.parent()).take_while(move |node| {
+            node.start_byte() == leaf.start_byte() && node.end_byte() <= self.byte_end
+        })
+    }
+
+    /// Binds a metavariable to a range and continues matching, undoing the
+    /// binding if the rest of the pattern doesn't match.
+    fn try_bind(
+        &self,
+        name: &str,
+        range: Range<usize>,
+        captures: &mut Vec<(String, Range<usize>)>,
+        rest: impl FnOnce(&mut Vec<(String, Range<usize>)>) -> bool,
+    ) -> bool {
+        let captures_len = captures.len();
+        if name != WILDCARD {
+            match captures.iter().find(|(capture, _)| capture == name) {
+                Some((_, bound)) => {
+                    if self.text(bound.clone()) != self.text(range) {
+                        return false;
+                    }
+                }
+                None => captures.push((name.to_string(), range)),
+            }
+        }
+        let matched = rest(captures);
+        if !matched {
+            captures.truncate(captures_len);
+        }
+        matched
+    }
+}
+
+fn query_matches(
+    query: &Query,
+    root: Node,
+    buffer: &BufferSnapshot,
+    range: Range<usize>,
+    matches: &mut Vec<StructuralMatch>,
+) {
+    let mut cursor = QueryCursorHandle::new();
+    cursor.set_byte_range(range.clone());
+    let capture_names = query.capture_names();
+    let mut query_matches = cursor.matches(query, root, TextProvider(buffer.as_rope()));
+    while let Some(query_match) = query_matches.next() {
+        let mut match_range = None;
+        let mut span: Option<Range<usize>> = None;
+        let mut captures = Vec::new();
+        for capture in query_match.captures {
+            let name = capture_names[capture.index as usize];
+            let capture_range = capture.node.byte_range();
+            span = Some(match span {
+                Some(span) => span.start.min(capture_range.start)..span.end.max(capture_range.end),
+                None => capture_range.clone(),
+            });
+            if name == MATCH_CAPTURE {
+                match_range = Some(capture_range);
+            } else {
+                captures.push((name.to_string(), capture_range));
+            }
+        }
+        if let Some(match_range) = match_range.or(span)
+            && match_range.start >= range.start
+            && match_range.end <= range.end
+        {
+            matches.push(StructuralMatch {
+                range: match_range,
+                captures,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Buffer, rust_lang};
+    use gpui::{AppContext as _, TestAppContext};
+    use pretty_assertions::assert_eq;
+
+    fn find(pattern: &str, text: &str, cx: &mut TestAppContext) -> Vec<(String, Vec<String>)> {
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(rust_lang(), cx));
+        cx.executor().run_until_parked();
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        StructuralPattern::new(pattern)
+            .unwrap()
+            .matches(&snapshot, 0..snapshot.len())
+            .into_iter()
+            .map(|mat| {
+                (
+                    snapshot.text_for_range(mat.range).collect(),
+                    mat.captures
+                        .into_iter()
+                        .map(|(name, range)| {
+                            format!(
+                                "{name}={}",
+                                snapshot.text_for_range(range).collect::<String>()
+                            )
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_code_patterns(cx: &mut TestAppContext) {
+        let text = "
+            fn main() {
+                foo(bar(1, 2), baz);
+                foo(1);
+                foo( x,  /* comment */ y );
+                other(foo(a, b), c);
+            }
+        ";
+
+        assert_eq!(
+            find("foo($A, $B)", text, cx),
+            vec![
+                (
+                    "foo(bar(1, 2), baz)".into(),
+                    vec!["A=bar(1, 2)".into(), "B=baz".into()]
+                ),
+                (
+                    "foo( x,  /* comment */ y )".into(),
+                    vec!["A=x".into(), "B=y".into()]
+                ),
+                ("foo(a, b)".into(), vec!["A=a".into(), "B=b".into()]),
+            ]
+        );
+
+        assert_eq!(
+            find("foo($$$ARGS)", text, cx)
+                .into_iter()
+                .map(|(text, captures)| (text, captures.join(" ")))
+                .collect::<Vec<_>>(),
+            vec![
+                ("foo(bar(1, 2), baz)".into(), "ARGS=bar(1, 2), baz".into()),
+                ("foo(1)".into(), "ARGS=1".into()),
+                (
+                    "foo( x,  /* comment */ y )".into(),
+                    "ARGS=x,  /* comment */ y".into()
+                ),
+                ("foo(a, b)".into(), "ARGS=a, b".into()),
+            ]
+        );
+
+        // A metavariable used twice must match the same text both times.
+        assert_eq!(
+            find(
+                "$A == $A",
+                "fn f() { a == a; a == b; (x + 1) == (x + 1); }",
+                cx
+            ),
+            vec![
+                ("a == a".into(), vec!["A=a".into()]),
+                ("(x + 1) == (x + 1)".into(), vec!["A=(x + 1)".into()]),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_query_patterns(cx: &mut TestAppContext) {
+        let text = "fn one() {} fn two() { one(); }";
+        assert_eq!(
+            find("(function_item name: (identifier) @name) @match", text, cx),
+            vec![
+                ("fn one() {}".into(), vec!["name=one".into()]),
+                ("fn two() { one(); }".into(), vec!["name=two".into()]),
+            ]
+        );
+        assert_eq!(
+            find(
+                r#"(call_expression function: (identifier) @callee (#eq? @callee "one"))"#,
+                text,
+                cx
+            ),
+            vec![("one".into(), vec!["callee=one".into()])]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_expand_replacement(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| {
+            Buffer::local("fn f() { foo(a, b + c); }", cx).with_language(rust_lang(), cx)
+        });
+        cx.executor().run_until_parked();
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let matches = StructuralPattern::new("foo($A, $B)")
+            .unwrap()
+            .matches(&snapshot, 0..snapshot.len());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0].expand_replacement("bar($B, $A, $UNKNOWN)", &snapshot),
+            "bar(b + c, a, $UNKNOWN)"
+        );
+    }
+}
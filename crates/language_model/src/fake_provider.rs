@@ -2,16 +2,23 @@ use crate::{
     AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
     LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolChoice,
+    LanguageModelToolChoice, http_fixture::HttpFixture,
+};
+use anyhow::{anyhow, ensure};
+use futures::{
+    AsyncReadExt as _, FutureExt, TryStreamExt as _, channel::mpsc, future::BoxFuture,
+    stream::BoxStream, stream::StreamExt,
 };
-use anyhow::anyhow;
-use futures::{FutureExt, channel::mpsc, future::BoxFuture, stream::BoxStream, stream::StreamExt};
 use gpui::{App, AsyncApp, Entity, Task};
-use http_client::Result;
+use http_client::{AsyncBody, HttpClient, Request, Response, Result, Url, http::HeaderValue};
 use parking_lot::Mutex;
-use std::sync::{
-    Arc,
-    atomic::{AtomicBool, AtomicU64, Ordering::SeqCst},
+use std::{
+    collections::VecDeque,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering::SeqCst},
+    },
 };
 
 #[derive(Clone)]
@@ -367,3 +374,89 @@ impl LanguageModel for FakeLanguageModel {
         self
     }
 }
+
+/// Serves recorded [`HttpFixture`]s in order, so that a real provider's
+/// request building, stream parsing and error handling can be tested without
+/// a live endpoint.
+///
+/// Each request must match the next fixture's method, URI and, when one was
+/// recorded, body. Anything else fails the request, so tests notice when a
+/// provider starts sending requests its fixtures weren't recorded for.
+pub struct ReplayHttpClient {
+    fixtures: Mutex<VecDeque<HttpFixture>>,
+}
+
+impl ReplayHttpClient {
+    pub fn new(fixtures: impl IntoIterator<Item = HttpFixture>) -> Arc<Self> {
+        Arc::new(Self {
+            fixtures: Mutex::new(fixtures.into_iter().collect()),
+        })
+    }
+
+    /// Replays the fixtures recorded into a directory, in recording order.
+    pub fn load_dir(dir: &Path) -> Result<Arc<Self>> {
+        Ok(Self::new(HttpFixture::load_dir(dir)?))
+    }
+
+    /// How many fixtures haven't been requested yet.
+    pub fn remaining_fixtures(&self) -> usize {
+        self.fixtures.lock().len()
+    }
+}
+
+impl HttpClient for ReplayHttpClient {
+    fn user_agent(&self) -> Option<&HeaderValue> {
+        None
+    }
+
+    fn proxy(&self) -> Option<&Url> {
+        None
+    }
+
+    fn send(&self, request: Request<AsyncBody>) -> BoxFuture<'static, Result<Response<AsyncBody>>> {
+        let fixture = self.fixtures.lock().pop_front();
+        async move {
+            let (parts, mut body) = request.into_parts();
+            let Some(fixture) = fixture else {
+                return Err(anyhow!(
+                    "no fixture left for {} {}",
+                    parts.method,
+                    parts.uri
+                ));
+            };
+            ensure!(
+                parts.method.as_str() == fixture.request.method
+                    && parts.uri.to_string() == fixture.request.uri,
+                "expected {} {}, got {} {}",
+                fixture.request.method,
+                fixture.request.uri,
+                parts.method,
+                parts.uri,
+            );
+            if let Some(expected_body) = &fixture.request.body {
+                let mut bytes = Vec::new();
+                body.read_to_end(&mut bytes).await?;
+                let body = serde_json::from_slice::<serde_json::Value>(&bytes)?;
+                ensure!(
+                    &body == expected_body,
+                    "request body differs from the fixture's:\n{}",
+                    serde_json::to_string_pretty(&body)?
+                );
+            }
+
+            let mut response = Response::builder().status(fixture.response.status);
+            for (name, value) in &fixture.response.headers {
+                response = response.header(name, value);
+            }
+            let chunks = fixture
+                .response
+                .chunks
+                .into_iter()
+                .map(|chunk| Ok::<_, std::io::Error>(chunk.into_bytes()));
+            Ok(response.body(AsyncBody::from_reader(
+                futures::stream::iter(chunks).into_async_read(),
+            ))?)
+        }
+        .boxed()
+    }
+}
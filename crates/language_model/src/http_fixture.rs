@@ -0,0 +1,72 @@
+//! Recorded exchanges between a language model provider and its API.
+//!
+//! Fixtures are captured by running Zed with `ZED_LANGUAGE_MODEL_RECORDING_DIR`
+//! set, and replayed in tests with `fake_provider::ReplayHttpClient`. Each fixture
+//! keeps the response body in the chunks it was streamed in, so that replaying
+//! it exercises the same partial-line and partial-JSON handling as the live
+//! endpoint did.
+
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// One request to a provider's API and the response it streamed back.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HttpFixture {
+    pub request: FixtureRequest,
+    pub response: FixtureResponse,
+}
+
+/// A recorded request. Request headers are never recorded, since they carry
+/// API keys.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FixtureRequest {
+    pub method: String,
+    pub uri: String,
+    /// The JSON body sent. When omitted, replay accepts any body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FixtureResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<(String, String)>,
+    /// The response body, split where the provider flushed it.
+    #[serde(default)]
+    pub chunks: Vec<String>,
+}
+
+impl HttpFixture {
+    /// The fixtures in a directory, in the order they were recorded.
+    pub fn load_dir(dir: &Path) -> Result<Vec<Self>> {
+        let mut paths = std::fs::read_dir(dir)
+            .with_context(|| format!("reading fixture directory {dir:?}"))?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<PathBuf>>>()?;
+        paths.retain(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        });
+        paths.sort();
+        paths.iter().map(|path| Self::load(path)).collect()
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json =
+            std::fs::read_to_string(path).with_context(|| format!("reading fixture {path:?}"))?;
+        serde_json::from_str(&json).with_context(|| format!("parsing fixture {path:?}"))
+    }
+
+    /// The name of the `index`th fixture recorded in a session. Names sort in
+    /// recording order.
+    pub fn file_name(index: usize) -> String {
+        format!("{index:04}.json")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json).with_context(|| format!("writing fixture {path:?}"))
+    }
+}
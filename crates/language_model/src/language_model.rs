@@ -1,4 +1,5 @@
 mod api_key;
+pub mod http_fixture;
 mod registry;
 mod request;
 
@@ -10,6 +10,7 @@ use provider::deepseek::DeepSeekLanguageModelProvider;
 
 pub mod extension;
 pub mod provider;
+pub mod recording;
 mod settings;
 
 pub use crate::extension::init_proxy as init_extension_proxy;
@@ -193,7 +194,7 @@ fn register_compatible_providers(
                 CompatibleProviderKind::OpenAi => registry.register_provider(
                     Arc::new(OpenAiCompatibleLanguageModelProvider::new(
                         provider_id.clone(),
-                        client.http_client(),
+                        recording::recording_http_client(client.http_client()),
                         credentials_provider.clone(),
                         cx,
                     )),
@@ -202,7 +203,7 @@ fn register_compatible_providers(
                 CompatibleProviderKind::Anthropic => registry.register_provider(
                     Arc::new(AnthropicCompatibleLanguageModelProvider::new(
                         provider_id.clone(),
-                        client.http_client(),
+                        recording::recording_http_client(client.http_client()),
                         credentials_provider.clone(),
                         cx,
                     )),
@@ -0,0 +1,466 @@
+//! Records the exchanges between providers and their APIs as fixtures.
+//!
+//! When `ZED_LANGUAGE_MODEL_RECORDING_DIR` is set, every request sent by the
+//! `openai_compatible` and `anthropic_compatible` providers is written to that
+//! directory along with the response streamed back, in the format replayed by
+//! `language_model::fake_provider::ReplayHttpClient`.
+
+use collections::HashMap;
+use futures::{AsyncRead, AsyncReadExt as _, FutureExt as _, future::BoxFuture};
+use http_client::{AsyncBody, HttpClient, Request, Response, Url, http::HeaderValue};
+use language_model::http_fixture::{FixtureRequest, FixtureResponse, HttpFixture};
+use parking_lot::Mutex;
+use std::{
+    mem,
+    path::PathBuf,
+    pin::Pin,
+    sync::{
+        Arc, LazyLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+};
+use util::ResultExt as _;
+
+const RECORDING_DIR_ENV_VAR: &str = "ZED_LANGUAGE_MODEL_RECORDING_DIR";
+
+/// The index of the next fixture recorded into each directory. Every provider
+/// has its own recorder, so recorders into the same directory share an index
+/// rather than overwriting each other's fixtures.
+static NEXT_INDICES: LazyLock<Mutex<HashMap<PathBuf, Arc<AtomicUsize>>>> =
+    LazyLock::new(Default::default);
+
+/// Wraps a provider's HTTP client so that its exchanges are recorded, if
+/// recording is enabled.
+pub(crate) fn recording_http_client(http_client: Arc<dyn HttpClient>) -> Arc<dyn HttpClient> {
+    match std::env::var_os(RECORDING_DIR_ENV_VAR) {
+        Some(dir) if !dir.is_empty() => match RecordingHttpClient::new(http_client.clone(), dir) {
+            Ok(client) => Arc::new(client),
+            Err(error) => {
+                log::error!("failed to start recording language model requests: {error:#}");
+                http_client
+            }
+        },
+        _ => http_client,
+    }
+}
+
+pub struct RecordingHttpClient {
+    inner: Arc<dyn HttpClient>,
+    dir: PathBuf,
+    next_index: Arc<AtomicUsize>,
+}
+
+impl RecordingHttpClient {
+    /// Records into `dir`, after any fixtures already recorded there.
+    pub fn new(inner: Arc<dyn HttpClient>, dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        let dir = dir.canonicalize()?;
+        let mut next_indices = NEXT_INDICES.lock();
+        let next_index = match next_indices.get(&dir) {
+            Some(next_index) => next_index.clone(),
+            None => {
+                let next_index = Arc::new(AtomicUsize::new(HttpFixture::load_dir(&dir)?.len()));
+                next_indices.insert(dir.clone(), next_index.clone());
+                next_index
+            }
+        };
+        Ok(Self {
+            inner,
+            dir,
+            next_index,
+        })
+    }
+}
+
+impl HttpClient for RecordingHttpClient {
+    fn user_agent(&self) -> Option<&HeaderValue> {
+        self.inner.user_agent()
+    }
+
+    fn proxy(&self) -> Option<&Url> {
+        self.inner.proxy()
+    }
+
+    fn send(
+        &self,
+        request: Request<AsyncBody>,
+    ) -> BoxFuture<'static, anyhow::Result<Response<AsyncBody>>> {
+        let inner = self.inner.clone();
+        let path = self.dir.join(HttpFixture::file_name(
+            self.next_index.fetch_add(1, Ordering::SeqCst),
+        ));
+        async move {
+            let (parts, mut body) = request.into_parts();
+            let mut bytes = Vec::new();
+            body.read_to_end(&mut bytes).await?;
+            let fixture_request = FixtureRequest {
+                method: parts.method.to_string(),
+                uri: parts.uri.to_string(),
+                body: serde_json::from_slice(&bytes).ok(),
+            };
+
+            let response = inner
+                .send(Request::from_parts(parts, AsyncBody::from(bytes)))
+                .await?;
+            let (parts, body) = response.into_parts();
+            let fixture_response = FixtureResponse {
+                status: parts.status.as_u16(),
+                headers: parts
+                    .headers
+                    .iter()
+                    .filter(|(name, _)| **name != http_client::http::header::SET_COOKIE)
+                    .filter_map(|(name, value)| {
+                        Some((name.to_string(), value.to_str().ok()?.to_string()))
+                    })
+                    .collect(),
+                chunks: Vec::new(),
+            };
+            let body = RecordingBody {
+                inner: body,
+                fixture: Some(HttpFixture {
+                    request: fixture_request,
+                    response: fixture_response,
+                }),
+                incomplete_char: Vec::new(),
+                path,
+            };
+            Ok(Response::from_parts(parts, AsyncBody::from_reader(body)))
+        }
+        .boxed()
+    }
+}
+
+/// A response body that's recorded as it's read, and saved once it's dropped,
+/// whether or not it was read to the end.
+struct RecordingBody {
+    inner: AsyncBody,
+    fixture: Option<HttpFixture>,
+    /// The start of a UTF-8 character split across reads.
+    incomplete_char: Vec<u8>,
+    path: PathBuf,
+}
+
+impl AsyncRead for RecordingBody {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(read)) = &poll
+            && *read > 0
+            && let Some(fixture) = &mut this.fixture
+        {
+            let mut bytes = mem::take(&mut this.incomplete_char);
+            bytes.extend_from_slice(&buf[..*read]);
+            let valid_len = match std::str::from_utf8(&bytes) {
+                Ok(text) => text.len(),
+                Err(error) => error.valid_up_to(),
+            };
+            this.incomplete_char = bytes.split_off(valid_len);
+            if let Ok(chunk) = String::from_utf8(bytes)
+                && !chunk.is_empty()
+            {
+                fixture.response.chunks.push(chunk);
+            }
+        }
+        poll
+    }
+}
+
+impl Drop for RecordingBody {
+    fn drop(&mut self) {
+        if let Some(mut fixture) = self.fixture.take() {
+            if !self.incomplete_char.is_empty() {
+                let rest = String::from_utf8_lossy(&self.incomplete_char).into_owned();
+                fixture.response.chunks.push(rest);
+            }
+            fixture.save(&self.path).log_err();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        anthropic_compatible::AnthropicCompatibleLanguageModelProvider,
+        open_ai_compatible::OpenAiCompatibleLanguageModelProvider,
+    };
+    use anyhow::Result;
+    use credentials_provider::CredentialsProvider;
+    use futures::StreamExt as _;
+    use gpui::{AsyncApp, BorrowAppContext as _, TestAppContext};
+    use http_client::FakeHttpClient;
+    use language_model::{
+        LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
+        LanguageModelProvider, LanguageModelRequest, LanguageModelToolUse,
+        LanguageModelToolUseInput, StopReason, TokenUsage, fake_provider::ReplayHttpClient,
+    };
+    use pretty_assertions::assert_eq;
+    use settings::SettingsStore;
+    use std::{future::Future, path::Path};
+
+    struct FakeCredentialsProvider;
+
+    impl CredentialsProvider for FakeCredentialsProvider {
+        fn read_credentials<'a>(
+            &'a self,
+            _url: &'a str,
+            _cx: &'a AsyncApp,
+        ) -> Pin<Box<dyn Future<Output = Result<Option<(String, Vec<u8>)>>> + 'a>> {
+            Box::pin(async { Ok(Some(("Bearer".to_string(), b"sk-test".to_vec()))) })
+        }
+
+        fn write_credentials<'a>(
+            &'a self,
+            _url: &'a str,
+            _username: &'a str,
+            _password: &'a [u8],
+            _cx: &'a AsyncApp,
+        ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn delete_credentials<'a>(
+            &'a self,
+            _url: &'a str,
+            _cx: &'a AsyncApp,
+        ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    /// Configures an `openai_compatible` and an `anthropic_compatible`
+    /// provider named "Example", matching the URLs the fixtures were recorded
+    /// against.
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            let content = serde_json::json!({
+                "language_models": {
+                    "openai_compatible": {
+                        "Example": {
+                            "api_url": "https://api.example.com/v1",
+                            "available_models": [{ "name": "custom-model", "max_tokens": 128000 }],
+                        }
+                    },
+                    "anthropic_compatible": {
+                        "Example": {
+                            "api_url": "https://api.example.com",
+                            "available_models": [{ "name": "custom-model", "max_tokens": 200000 }],
+                        }
+                    },
+                }
+            })
+            .to_string();
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store
+                    .set_user_settings(&content, cx)
+                    .expect("failed to parse test settings");
+            });
+        });
+    }
+
+    async fn authenticated_model(
+        provider: &dyn LanguageModelProvider,
+        cx: &mut TestAppContext,
+    ) -> Arc<dyn LanguageModel> {
+        cx.update(|cx| provider.authenticate(cx)).await.unwrap();
+        cx.update(|cx| provider.provided_models(cx).into_iter().next().unwrap())
+    }
+
+    fn fixture_dir(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("test_data")
+            .join(name)
+    }
+
+    fn tool_use(raw_input: &str, is_input_complete: bool) -> LanguageModelCompletionEvent {
+        LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+            id: "call_1".into(),
+            name: "read_file".into(),
+            raw_input: raw_input.into(),
+            input: LanguageModelToolUseInput::Json(
+                serde_json::from_str(&language_model::fix_streamed_json(raw_input)).unwrap(),
+            ),
+            is_input_complete,
+            thought_signature: None,
+        })
+    }
+
+    #[gpui::test]
+    async fn test_replay_openai_compatible_tool_call(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http_client =
+            ReplayHttpClient::load_dir(&fixture_dir("openai_compatible_tool_call")).unwrap();
+        let provider = cx.update(|cx| {
+            OpenAiCompatibleLanguageModelProvider::new(
+                "Example".into(),
+                http_client.clone(),
+                Arc::new(FakeCredentialsProvider),
+                cx,
+            )
+        });
+        let model = authenticated_model(&provider, cx).await;
+
+        let events = model
+            .stream_completion(LanguageModelRequest::default(), &cx.to_async())
+            .await
+            .unwrap()
+            .map(|event| event.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        let arguments = r#"{"path": "src/main.rs"}"#;
+        assert_eq!(
+            events,
+            vec![
+                LanguageModelCompletionEvent::Text("Let me read it.".into()),
+                tool_use("{", false),
+                tool_use(arguments, false),
+                tool_use(arguments, true),
+                LanguageModelCompletionEvent::Stop(StopReason::ToolUse),
+                LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                    input_tokens: 42,
+                    output_tokens: 17,
+                    cache_creation_input_tokens: 0,
+                    cache_read_input_tokens: 0,
+                }),
+            ]
+        );
+        assert_eq!(http_client.remaining_fixtures(), 0);
+    }
+
+    #[gpui::test]
+    async fn test_replay_anthropic_compatible_errors(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http_client =
+            ReplayHttpClient::load_dir(&fixture_dir("anthropic_compatible_errors")).unwrap();
+        let provider = cx.update(|cx| {
+            AnthropicCompatibleLanguageModelProvider::new(
+                "Example".into(),
+                http_client.clone(),
+                Arc::new(FakeCredentialsProvider),
+                cx,
+            )
+        });
+        let model = authenticated_model(&provider, cx).await;
+
+        let mut errors = Vec::new();
+        for _ in 0..2 {
+            let Err(error) = model
+                .stream_completion(LanguageModelRequest::default(), &cx.to_async())
+                .await
+            else {
+                panic!("expected the request to fail");
+            };
+            errors.push(error);
+        }
+
+        assert!(matches!(
+            &errors[0],
+            LanguageModelCompletionError::BadRequestFormat { message, .. }
+                if message.starts_with("max_tokens")
+        ));
+        assert!(matches!(
+            &errors[1],
+            LanguageModelCompletionError::ServerOverloaded {
+                retry_after: None,
+                ..
+            }
+        ));
+        assert_eq!(http_client.remaining_fixtures(), 0);
+    }
+
+    #[gpui::test]
+    async fn test_record_and_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let response_body = "data: {\"text\":\"héllo\"}\n\ndata: [DONE]\n\n";
+        let inner = FakeHttpClient::create(move |_| async move {
+            Ok(Response::builder()
+                .status(200)
+                .header("content-type", "text/event-stream")
+                .header("set-cookie", "session=secret")
+                .body(AsyncBody::from(response_body))?)
+        });
+        let recording = RecordingHttpClient::new(inner, dir.path()).unwrap();
+
+        let send = |client: &dyn HttpClient| {
+            client.send(
+                Request::post("https://api.example.com/v1/chat/completions")
+                    .header("Authorization", "Bearer sk-secret")
+                    .body(AsyncBody::from(r#"{"model":"custom-model"}"#))
+                    .unwrap(),
+            )
+        };
+        let mut response = send(&recording).await.unwrap();
+        let mut body = String::new();
+        response.body_mut().read_to_string(&mut body).await.unwrap();
+        assert_eq!(body, response_body);
+        drop(response);
+
+        let fixtures = HttpFixture::load_dir(dir.path()).unwrap();
+        assert_eq!(
+            fixtures,
+            vec![HttpFixture {
+                request: FixtureRequest {
+                    method: "POST".into(),
+                    uri: "https://api.example.com/v1/chat/completions".into(),
+                    body: Some(serde_json::json!({ "model": "custom-model" })),
+                },
+                response: FixtureResponse {
+                    status: 200,
+                    headers: vec![("content-type".into(), "text/event-stream".into())],
+                    chunks: vec![response_body.into()],
+                },
+            }]
+        );
+
+        let replay = ReplayHttpClient::new(fixtures);
+        let mut response = send(replay.as_ref()).await.unwrap();
+        let mut body = String::new();
+        response.body_mut().read_to_string(&mut body).await.unwrap();
+        assert_eq!(body, response_body);
+        assert_eq!(replay.remaining_fixtures(), 0);
+    }
+
+    #[gpui::test]
+    async fn test_providers_recording_into_one_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner = FakeHttpClient::create(|_| async {
+            Ok(Response::builder().status(200).body(AsyncBody::empty())?)
+        });
+        // Like the providers, each recorder is created before either records.
+        let openai = RecordingHttpClient::new(inner.clone(), dir.path()).unwrap();
+        let anthropic = RecordingHttpClient::new(inner, dir.path()).unwrap();
+
+        for (client, uri) in [
+            (&openai, "https://api.example.com/v1/chat/completions"),
+            (&anthropic, "https://api.example.com/v1/messages"),
+        ] {
+            let response = client
+                .send(Request::post(uri).body(AsyncBody::empty()).unwrap())
+                .await
+                .unwrap();
+            drop(response);
+        }
+
+        let uris = HttpFixture::load_dir(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|fixture| fixture.request.uri)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            uris,
+            vec![
+                "https://api.example.com/v1/chat/completions".to_string(),
+                "https://api.example.com/v1/messages".to_string(),
+            ]
+        );
+    }
+}
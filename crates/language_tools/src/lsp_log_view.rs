@@ -883,6 +883,8 @@ impl SearchableItem for LspLogView {
             case: true,
             word: true,
             regex: true,
+            structural: false,
+            scopes: false,
             find_in_results: false,
             // LSP log is read-only.
             replacement: false,
@@ -0,0 +1,249 @@
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Task,
+    WeakEntity, Window, actions,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr};
+
+actions!(
+    layout_selector,
+    [
+        /// Toggles the layout selector modal, to restore or save named layouts.
+        Toggle
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(LayoutSelector::register).detach();
+}
+
+pub struct LayoutSelector {
+    picker: Entity<Picker<LayoutSelectorDelegate>>,
+}
+
+impl LayoutSelector {
+    fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(move |workspace, _: &Toggle, window, cx| {
+            Self::toggle(workspace, window, cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let layouts = workspace.workspace_layouts(cx);
+        let workspace_handle = cx.weak_entity();
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            let delegate = LayoutSelectorDelegate::new(cx.entity().downgrade(), workspace_handle);
+            let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+
+            let picker_handle = picker.downgrade();
+            cx.spawn_in(window, async move |_, cx| {
+                let layout_names = layouts
+                    .await
+                    .log_err()
+                    .map(|layouts| layouts.into_keys().collect())
+                    .unwrap_or_default();
+                picker_handle
+                    .update_in(cx, |picker, window, cx| {
+                        picker.delegate.layout_names = layout_names;
+                        picker.refresh(window, cx);
+                    })
+                    .ok();
+            })
+            .detach();
+
+            LayoutSelector { picker }
+        });
+    }
+}
+
+impl Render for LayoutSelector {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl Focusable for LayoutSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for LayoutSelector {}
+impl ModalView for LayoutSelector {}
+
+pub struct LayoutSelectorDelegate {
+    layout_selector: WeakEntity<LayoutSelector>,
+    workspace: WeakEntity<Workspace>,
+    layout_names: Vec<String>,
+    matches: Vec<StringMatch>,
+    query: String,
+    selected_index: usize,
+}
+
+impl LayoutSelectorDelegate {
+    fn new(layout_selector: WeakEntity<LayoutSelector>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            layout_selector,
+            workspace,
+            layout_names: Vec::new(),
+            matches: Vec::new(),
+            query: String::new(),
+            selected_index: 0,
+        }
+    }
+
+    /// The name to save the current layout under, when the query doesn't name
+    /// an existing layout.
+    fn new_layout_name(&self) -> Option<&str> {
+        let name = self.query.trim();
+        (!name.is_empty() && !self.layout_names.iter().any(|layout| layout == name)).then_some(name)
+    }
+}
+
+impl PickerDelegate for LayoutSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn name() -> &'static str {
+        "layout selector"
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Restore a layout, or type a name to save the current one…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + usize::from(self.new_layout_name().is_some())
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .layout_names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| StringMatchCandidate::new(id, name))
+            .collect::<Vec<_>>();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.trim().is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    query.trim(),
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.query = query;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.match_count().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let (name, save) = match self.matches.get(self.selected_index) {
+            Some(mat) => (mat.string.clone(), false),
+            None => match self.new_layout_name() {
+                Some(name) => (name.to_string(), true),
+                None => return,
+            },
+        };
+
+        let task = self.workspace.update(cx, |workspace, cx| {
+            if save {
+                workspace.save_layout(name, window, cx)
+            } else {
+                workspace.restore_layout(name, window, cx)
+            }
+        });
+        if let Some(task) = task.log_err() {
+            let message = if save {
+                "Failed to save layout"
+            } else {
+                "Failed to restore layout"
+            };
+            task.detach_and_prompt_err(message, window, cx, |_, _, _| None);
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.layout_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let list_item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected);
+
+        if let Some(mat) = self.matches.get(ix) {
+            return Some(list_item.child(HighlightedLabel::new(
+                mat.string.clone(),
+                mat.positions.clone(),
+            )));
+        }
+
+        let name = self.new_layout_name()?;
+        Some(
+            list_item
+                .start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                .child(Label::new(format!("Save current layout as “{name}”"))),
+        )
+    }
+}
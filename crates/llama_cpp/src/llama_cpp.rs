@@ -580,6 +580,71 @@ pub async fn get_models(
     Ok(response.data)
 }
 
+#[derive(Serialize, Debug)]
+pub struct EmbeddingsRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<&'a str>,
+    pub input: &'a [String],
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EmbeddingsResponse {
+    pub data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EmbeddingData {
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+/// Embeds each of `request.input` via the OpenAI-compatible `POST /v1/embeddings`.
+/// The server has to be started with `--embeddings`.
+pub async fn embed(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: Option<&str>,
+    request: EmbeddingsRequest<'_>,
+    extra_headers: &CustomHeaders,
+) -> Result<Vec<Vec<f32>>> {
+    let uri = format!("{api_url}/v1/embeddings");
+    let request_builder = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .when_some(api_key, |builder, api_key| {
+            builder.header("Authorization", format!("Bearer {api_key}"))
+        })
+        .extra_headers(extra_headers);
+    let input_count = request.input.len();
+    let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
+
+    let mut response = client.send(request).await?;
+
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to connect to llama.cpp API: {} {}",
+        response.status(),
+        body,
+    );
+    let mut response: EmbeddingsResponse =
+        serde_json::from_str(&body).context("Unable to parse llama.cpp embeddings response")?;
+    anyhow::ensure!(
+        response.data.len() == input_count,
+        "llama.cpp returned {} embeddings for {input_count} inputs",
+        response.data.len(),
+    );
+    response.data.sort_by_key(|data| data.index);
+    Ok(response
+        .data
+        .into_iter()
+        .map(|data| data.embedding)
+        .collect())
+}
+
 /// Fetches `GET /props` to discover the loaded model's capabilities.
 ///
 /// In router mode `model` selects which model instance to query; passing
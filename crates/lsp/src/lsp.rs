@@ -1008,6 +1008,9 @@ impl LanguageServer {
                     color_provider: Some(DocumentColorClientCapabilities {
                         dynamic_registration: Some(true),
                     }),
+                    selection_range: Some(SelectionRangeClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
                     document_link: Some(DocumentLinkClientCapabilities {
                         dynamic_registration: Some(true),
                         tooltip_support: Some(true),
@@ -61,6 +61,7 @@ pub type CodeSpanLinkCallback = Arc<dyn Fn(&str, &App) -> Option<SharedString> +
 type UrlHoverCallback = Rc<dyn Fn(Option<SharedString>, &mut Window, &mut App)>;
 type SourceClickCallback = Box<dyn Fn(usize, usize, &mut Window, &mut App) -> bool>;
 type CheckboxToggleCallback = Rc<dyn Fn(Range<usize>, bool, &mut Window, &mut App)>;
+type HeadingAnchorCallback = Rc<dyn Fn(SharedString, &mut Window, &mut App)>;
 
 #[derive(Clone, Copy, Default)]
 pub struct BlockQuoteKindColors {
@@ -1300,6 +1301,7 @@ pub struct MarkdownElement {
     code_span_link: Option<CodeSpanLinkCallback>,
     on_source_click: Option<SourceClickCallback>,
     on_checkbox_toggle: Option<CheckboxToggleCallback>,
+    on_heading_anchor_click: Option<HeadingAnchorCallback>,
     image_resolver: Option<Box<dyn Fn(&str) -> Option<ImageSource>>>,
     show_root_block_markers: bool,
     autoscroll: AutoscrollBehavior,
@@ -1320,6 +1322,7 @@ impl MarkdownElement {
             code_span_link: None,
             on_source_click: None,
             on_checkbox_toggle: None,
+            on_heading_anchor_click: None,
             image_resolver: None,
             show_root_block_markers: false,
             autoscroll: AutoscrollBehavior::Propagate,
@@ -1392,6 +1395,18 @@ impl MarkdownElement {
         self
     }
 
+    /// Renders an anchor button next to every heading that has a slug. The
+    /// handler receives the slug of the heading whose anchor was clicked.
+    ///
+    /// Requires [`MarkdownOptions::parse_heading_slugs`] to be enabled.
+    pub fn on_heading_anchor_click(
+        mut self,
+        handler: impl Fn(SharedString, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_heading_anchor_click = Some(Rc::new(handler));
+        self
+    }
+
     pub fn image_resolver(
         mut self,
         resolver: impl Fn(&str) -> Option<ImageSource> + 'static,
@@ -1568,9 +1583,21 @@ impl MarkdownElement {
         range: &Range<usize>,
         markdown_end: usize,
         text_align_override: Option<TextAlign>,
+        anchor_slug: Option<SharedString>,
     ) {
         let align = text_align_override.unwrap_or(self.style.base_text_style.text_align);
         let mut heading = div().mt_4().mb_2();
+        if let Some((slug, on_click)) = anchor_slug.zip(self.on_heading_anchor_click.clone()) {
+            heading = heading
+                .relative()
+                .group("markdown-heading")
+                .child(render_heading_anchor(
+                    &self.markdown,
+                    range.start,
+                    slug,
+                    on_click,
+                ));
+        }
         heading = apply_heading_style(
             heading,
             level,
@@ -2193,6 +2220,16 @@ impl Element for MarkdownElement {
             0
         };
         let mut code_block_ids = HashSet::default();
+        let heading_slugs_by_source_index: BTreeMap<usize, SharedString> =
+            if self.on_heading_anchor_click.is_some() {
+                parsed_markdown
+                    .heading_slugs
+                    .iter()
+                    .map(|(slug, source_index)| (*source_index, slug.clone()))
+                    .collect()
+            } else {
+                BTreeMap::new()
+            };
 
         let mut current_img_block_range: Option<Range<usize>> = None;
         let mut handled_html_block = false;
@@ -2295,12 +2332,17 @@ impl Element for MarkdownElement {
                                 .table
                                 .current_cell_alignment()
                                 .and_then(alignment_to_text_align);
+                            let anchor_slug = heading_slugs_by_source_index
+                                .range(range.clone())
+                                .next()
+                                .map(|(_, slug)| slug.clone());
                             self.push_markdown_heading(
                                 &mut builder,
                                 *level,
                                 range,
                                 markdown_end,
                                 text_align_override,
+                                anchor_slug,
                             );
                         }
                         MarkdownTag::BlockQuote(kind) => {
@@ -3023,6 +3065,29 @@ fn apply_heading_style(
     heading
 }
 
+fn render_heading_anchor(
+    markdown: &Entity<Markdown>,
+    source_index: usize,
+    slug: SharedString,
+    on_click: HeadingAnchorCallback,
+) -> impl IntoElement {
+    let button_id = ElementId::NamedChild(
+        Arc::new(ElementId::from(("heading-anchor", markdown.entity_id()))),
+        source_index.to_string().into(),
+    );
+
+    div().absolute().top_0().right_0().child(
+        IconButton::new(button_id, IconName::Link)
+            .icon_size(IconSize::Small)
+            .icon_color(Color::Muted)
+            .tooltip(Tooltip::text("Copy Link to Heading"))
+            .visible_on_hover("markdown-heading")
+            .on_click(move |_event, window, cx| {
+                on_click(slug.clone(), window, cx);
+            }),
+    )
+}
+
 fn render_wrap_code_block_button(
     id: usize,
     is_wrapped: bool,
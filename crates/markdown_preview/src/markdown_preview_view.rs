@@ -1007,6 +1007,29 @@ impl MarkdownPreviewView {
                         })
                         .log_err();
                 }
+            })
+            .on_heading_anchor_click({
+                let view_handle = cx.entity().downgrade();
+                let workspace = self.workspace.clone();
+                let base_directory = self.base_directory.clone();
+                move |slug, window, cx| {
+                    let file_name = view_handle
+                        .read_with(cx, |view, cx| view.source_file_name(cx))
+                        .ok()
+                        .flatten();
+                    cx.write_to_clipboard(ClipboardItem::new_string(heading_link(
+                        file_name.as_deref(),
+                        &slug,
+                    )));
+                    handle_url_click(
+                        heading_link(None, &slug).into(),
+                        &view_handle,
+                        base_directory.clone(),
+                        &workspace,
+                        window,
+                        cx,
+                    );
+                }
             });
 
         if let Some(active_editor) = active_editor {
@@ -1039,6 +1062,13 @@ impl MarkdownPreviewView {
         markdown_element
     }
 
+    /// The file name of the previewed buffer, used to build shareable heading links.
+    fn source_file_name(&self, cx: &App) -> Option<String> {
+        let editor = self.active_editor.as_ref()?.editor.read(cx);
+        let file = editor.file_at(MultiBufferOffset(0), cx)?;
+        Some(file.file_name(cx).to_string())
+    }
+
     fn apply_checkbox_toggle_to_editor(
         editor: &Entity<Editor>,
         source_range: std::ops::Range<usize>,
@@ -1084,6 +1114,15 @@ impl MarkdownPreviewView {
     }
 }
 
+/// Builds a link to a heading, relative to the previewed file's directory so it
+/// can be pasted into sibling documents.
+fn heading_link(file_name: Option<&str>, slug: &str) -> String {
+    let file_name = file_name
+        .map(|file_name| urlencoding::encode(file_name).into_owned())
+        .unwrap_or_default();
+    format!("{file_name}#{slug}")
+}
+
 fn handle_url_click(
     url: SharedString,
     view: &WeakEntity<MarkdownPreviewView>,
@@ -1690,6 +1729,8 @@ impl SearchableItem for MarkdownPreviewView {
             case: true,
             word: true,
             regex: true,
+            structural: false,
+            scopes: false,
             replacement: false,
             selection: false,
             select_all: false,
@@ -2000,7 +2041,20 @@ mod tests {
         AppState, ItemId, MultiWorkspace, SaveIntent, Workspace, WorkspaceId, open_paths,
     };
 
-    use super::{MarkdownPreviewView, open_preview_url};
+    use super::{MarkdownPreviewView, heading_link, open_preview_url};
+
+    #[test]
+    fn builds_heading_links_relative_to_the_previewed_file() {
+        assert_eq!(
+            heading_link(Some("README.md"), "getting-started"),
+            "README.md#getting-started"
+        );
+        assert_eq!(
+            heading_link(Some("release notes.md"), "v2"),
+            "release%20notes.md#v2"
+        );
+        assert_eq!(heading_link(None, "overview"), "#overview");
+    }
 
     #[test]
     fn resolves_workspace_absolute_preview_image_path_and_rejects_missing() {
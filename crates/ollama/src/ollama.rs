@@ -399,6 +399,59 @@ pub async fn show_model(
     Ok(details)
 }
 
+#[derive(Serialize, Debug)]
+pub struct EmbedRequest<'a> {
+    pub model: &'a str,
+    pub input: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<KeepAlive>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EmbedResponse {
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+/// Embeds each of `request.input` with an embedding model, via `POST /api/embed`.
+pub async fn embed(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: Option<&str>,
+    request: EmbedRequest<'_>,
+    extra_headers: &CustomHeaders,
+) -> Result<Vec<Vec<f32>>> {
+    let uri = format!("{api_url}/api/embed");
+    let request_builder = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .when_some(api_key, |builder, api_key| {
+            builder.header("Authorization", format!("Bearer {api_key}"))
+        })
+        .extra_headers(extra_headers);
+    let input_count = request.input.len();
+    let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
+
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to connect to Ollama API: {} {}",
+        response.status(),
+        body,
+    );
+    let response: EmbedResponse =
+        serde_json::from_str(&body).context("Unable to parse Ollama embed response")?;
+    anyhow::ensure!(
+        response.embeddings.len() == input_count,
+        "Ollama returned {} embeddings for {input_count} inputs",
+        response.embeddings.len(),
+    );
+    Ok(response.embeddings)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
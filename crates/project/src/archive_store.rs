@@ -0,0 +1,439 @@
+//! Archives, like `.zip`, `.jar` or `.tar.gz` files, are browsed by unpacking them into a
+//! temporary directory, which is then added to the project as an invisible worktree. Buffers
+//! opened from these worktrees are read-only.
+//!
+//! Language servers may also point into archives with `jar:` or `zipfile:` URIs, for example
+//! when going to the definition of a symbol from a dependency; see [`parse_archive_uri`].
+
+use std::{
+    path::{Path, PathBuf},
+    pin::Pin,
+    str::FromStr as _,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result, anyhow};
+use async_compression::futures::bufread::GzipDecoder;
+use async_tar::Archive;
+use collections::HashMap;
+use fs::Fs;
+use futures::{
+    AsyncRead, AsyncReadExt as _, FutureExt as _, StreamExt as _, future::Shared, io::BufReader,
+};
+use gpui::{AppContext as _, Context, Entity, Subscription, Task};
+use percent_encoding::percent_decode_str;
+use util::{
+    paths::{PathStyle, UrlExt as _},
+    post_inc,
+    rel_path::RelPath,
+};
+use worktree::{Worktree, WorktreeId};
+
+use crate::worktree_store::{WorktreeStore, WorktreeStoreEvent};
+
+const ARCHIVE_WATCH_LATENCY: Duration = Duration::from_millis(100);
+/// Archives with more entries than this aren't unpacked.
+const MAX_ARCHIVE_ENTRIES: usize = 100_000;
+/// The largest file that's unpacked from an archive.
+const MAX_ARCHIVE_ENTRY_SIZE: u64 = 256 * 1024 * 1024;
+/// The most that's unpacked from a single archive.
+const MAX_UNPACKED_ARCHIVE_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// The archive formats that can be browsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// Detects the format of an archive from its file name.
+    pub fn for_path(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        let extension = if file_name.ends_with(".tar.gz") {
+            "tar.gz"
+        } else {
+            file_name.rsplit_once('.')?.1
+        };
+        match extension {
+            "zip" | "jar" | "war" | "ear" | "aar" | "whl" | "egg" | "vsix" | "nupkg" => {
+                Some(Self::Zip)
+            }
+            "tar" => Some(Self::Tar),
+            "tar.gz" | "tgz" | "crate" => Some(Self::TarGz),
+            _ => None,
+        }
+    }
+}
+
+pub struct ArchiveStore {
+    fs: Arc<dyn Fs>,
+    worktree_store: Entity<WorktreeStore>,
+    archives: HashMap<Arc<Path>, UnpackedArchive>,
+    /// The paths of the archives unpacked into each worktree.
+    archive_paths: HashMap<WorktreeId, Arc<Path>>,
+    next_archive_id: usize,
+    _subscription: Subscription,
+}
+
+/// An archive that was, or is being, unpacked. Its temporary directory is deleted once it is
+/// dropped, that is when the archive changes on disk, its worktree is removed, or the project
+/// is closed.
+struct UnpackedArchive {
+    id: usize,
+    #[allow(clippy::type_complexity)]
+    worktree: Shared<Task<Result<Entity<Worktree>, Arc<anyhow::Error>>>>,
+    temp_dir: Option<tempfile::TempDir>,
+    _watch: Task<()>,
+}
+
+impl ArchiveStore {
+    pub fn new(
+        fs: Arc<dyn Fs>,
+        worktree_store: Entity<WorktreeStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&worktree_store, |this, _, event, cx| {
+            if let WorktreeStoreEvent::WorktreeRemoved(_, worktree_id) = event
+                && let Some(abs_path) = this.archive_paths.get(worktree_id).cloned()
+            {
+                this.close_archive(&abs_path, cx);
+            }
+        });
+        Self {
+            fs,
+            worktree_store,
+            archives: HashMap::default(),
+            archive_paths: HashMap::default(),
+            next_archive_id: 0,
+            _subscription: subscription,
+        }
+    }
+
+    /// Unpacks the archive at the given path, returning the worktree its contents
+    /// can be browsed in. Each archive is only unpacked again after it changes on disk.
+    pub fn open_archive(
+        &mut self,
+        abs_path: Arc<Path>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Worktree>>> {
+        let task = match self.archives.get(&abs_path) {
+            Some(archive) => archive.worktree.clone(),
+            None => {
+                let archive = self.unpack_archive(abs_path.clone(), cx);
+                let task = archive.worktree.clone();
+                self.archives.insert(abs_path, archive);
+                task
+            }
+        };
+        cx.background_spawn(async move { task.await.map_err(|error| anyhow!("{error:#}")) })
+    }
+
+    fn unpack_archive(&mut self, abs_path: Arc<Path>, cx: &mut Context<Self>) -> UnpackedArchive {
+        let id = post_inc(&mut self.next_archive_id);
+        let fs = self.fs.clone();
+        let worktree_store = self.worktree_store.clone();
+        let worktree = cx
+            .spawn({
+                let abs_path = abs_path.clone();
+                async move |this, cx| {
+                    let result = async {
+                        let (temp_dir, root) = cx
+                            .background_spawn({
+                                let abs_path = abs_path.clone();
+                                async move { unpack_into_temp_dir(fs.as_ref(), &abs_path).await }
+                            })
+                            .await?;
+                        let worktree = worktree_store
+                            .update(cx, |worktree_store, cx| {
+                                worktree_store.create_worktree(&root, false, cx)
+                            })
+                            .await?;
+                        this.update(cx, |this, cx| {
+                            let worktree_id = worktree.read(cx).id();
+                            match this.archives.get_mut(&abs_path) {
+                                Some(archive) if archive.id == id => {
+                                    archive.temp_dir = Some(temp_dir);
+                                    this.archive_paths.insert(worktree_id, abs_path.clone());
+                                }
+                                // The archive changed while it was being unpacked.
+                                _ => this.worktree_store.update(cx, |worktree_store, cx| {
+                                    worktree_store.remove_worktree(worktree_id, cx)
+                                }),
+                            }
+                        })?;
+                        anyhow::Ok(worktree)
+                    }
+                    .await;
+                    if result.is_err() {
+                        // Let the archive be opened again once it is fixed.
+                        this.update(cx, |this, _| {
+                            if this
+                                .archives
+                                .get(&abs_path)
+                                .is_some_and(|archive| archive.id == id)
+                            {
+                                this.archives.remove(&abs_path);
+                            }
+                        })
+                        .ok();
+                    }
+                    result.map_err(Arc::new)
+                }
+            })
+            .shared();
+
+        let fs = self.fs.clone();
+        let watch = cx.spawn(async move |this, cx| {
+            // The parent directory is watched, so that the archive being replaced or
+            // deleted is noticed.
+            let Some(parent) = abs_path.parent() else {
+                return;
+            };
+            let (mut events, _watcher) = fs.watch(parent, ARCHIVE_WATCH_LATENCY).await;
+            while let Some(events) = events.next().await {
+                if events
+                    .iter()
+                    .any(|event| event.path.as_path() == abs_path.as_ref())
+                {
+                    this.update(cx, |this, cx| this.close_archive(&abs_path, cx))
+                        .ok();
+                    break;
+                }
+            }
+        });
+
+        UnpackedArchive {
+            id,
+            worktree,
+            temp_dir: None,
+            _watch: watch,
+        }
+    }
+
+    /// Removes the worktree of an archive and deletes its unpacked contents.
+    fn close_archive(&mut self, abs_path: &Arc<Path>, cx: &mut Context<Self>) {
+        let Some(archive) = self.archives.remove(abs_path) else {
+            return;
+        };
+        self.archive_paths.retain(|_, path| path != abs_path);
+        if let Some(Ok(worktree)) = archive.worktree.peek() {
+            let worktree_id = worktree.read(cx).id();
+            self.worktree_store.update(cx, |worktree_store, cx| {
+                worktree_store.remove_worktree(worktree_id, cx)
+            });
+        }
+    }
+
+    /// Returns the path of the archive that was unpacked into the given worktree.
+    pub fn archive_path(&self, worktree_id: WorktreeId) -> Option<&Arc<Path>> {
+        self.archive_paths.get(&worktree_id)
+    }
+
+    pub fn is_archive_worktree(&self, worktree_id: WorktreeId) -> bool {
+        self.archive_paths.contains_key(&worktree_id)
+    }
+
+    /// Opens the archive a `jar:` or `zipfile:` URI points into, returning its worktree
+    /// and the path of the URI's file within it.
+    pub fn open_archive_uri(
+        &mut self,
+        uri: &lsp::Uri,
+        path_style: PathStyle,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Result<(Entity<Worktree>, Arc<RelPath>)>>> {
+        let (archive_path, path) = parse_archive_uri(uri, path_style)?;
+        let worktree = self.open_archive(archive_path.into(), cx);
+        Some(cx.background_spawn(async move { Ok((worktree.await?, path)) }))
+    }
+}
+
+/// Splits a URI pointing into an archive, such as `jar:file:///libs/a.jar!/com/A.java`
+/// or `zipfile:///libs/a.zip::pkg/a.py`, into the archive's path and the path within it.
+pub fn parse_archive_uri(uri: &lsp::Uri, path_style: PathStyle) -> Option<(PathBuf, Arc<RelPath>)> {
+    let (archive_uri, path) = match uri.scheme() {
+        "jar" => uri.as_str().strip_prefix("jar:")?.split_once("!/")?,
+        "zipfile" => uri.as_str().split_once("::")?,
+        _ => return None,
+    };
+    let (_, archive_uri) = archive_uri.split_once(':')?;
+    let archive_path = lsp::Uri::from_str(&format!("file:{archive_uri}"))
+        .ok()?
+        .to_file_path_ext(path_style)
+        .ok()?;
+    let path = percent_decode_str(path).decode_utf8().ok()?;
+    let path = RelPath::new(Path::new(path.as_ref()), PathStyle::Unix).ok()?;
+    (!path.is_empty()).then(|| (archive_path, path.into_arc()))
+}
+
+/// Unpacks an archive into a new temporary directory, returning the directory and the
+/// path of the archive's contents within it.
+async fn unpack_into_temp_dir(
+    fs: &dyn Fs,
+    abs_path: &Path,
+) -> Result<(tempfile::TempDir, PathBuf)> {
+    let kind = ArchiveKind::for_path(abs_path)
+        .with_context(|| format!("{abs_path:?} is not a known archive"))?;
+    let file_name = abs_path
+        .file_name()
+        .with_context(|| format!("{abs_path:?} has no file name"))?;
+    let temp_dir = tempfile::tempdir()?;
+    // Unpacking into a directory named after the archive gives the
+    // worktree the archive's name.
+    let root = temp_dir.path().join(file_name);
+    let contents = fs.load_bytes(abs_path).await?;
+    fs.create_dir(&root).await?;
+    unpack(fs, kind, contents, &root)
+        .await
+        .with_context(|| format!("unpacking {abs_path:?}"))?;
+    Ok((temp_dir, root))
+}
+
+async fn unpack(
+    fs: &dyn Fs,
+    kind: ArchiveKind,
+    contents: Vec<u8>,
+    destination: &Path,
+) -> Result<()> {
+    match kind {
+        ArchiveKind::Zip => unpack_zip(fs, contents, destination).await,
+        ArchiveKind::Tar => unpack_tar(fs, contents.as_slice(), destination).await,
+        ArchiveKind::TarGz => {
+            let reader = GzipDecoder::new(BufReader::new(contents.as_slice()));
+            futures::pin_mut!(reader);
+            unpack_tar(fs, reader, destination).await
+        }
+    }
+}
+
+/// Keeps track of how much has been unpacked from an archive, so that archives
+/// that unpack to far more than their own size can't fill the disk.
+#[derive(Default)]
+struct UnpackedSize {
+    entries: usize,
+    bytes: u64,
+}
+
+impl UnpackedSize {
+    fn add_entry(&mut self) -> Result<()> {
+        self.entries += 1;
+        anyhow::ensure!(
+            self.entries <= MAX_ARCHIVE_ENTRIES,
+            "the archive has more than {MAX_ARCHIVE_ENTRIES} entries"
+        );
+        Ok(())
+    }
+
+    /// Writes the contents of the entry with the given name to a path as they're read.
+    async fn write_file(
+        &mut self,
+        fs: &dyn Fs,
+        name: &str,
+        path: &Path,
+        contents: impl AsyncRead + Send + Unpin,
+    ) -> Result<()> {
+        let limit = MAX_ARCHIVE_ENTRY_SIZE.min(MAX_UNPACKED_ARCHIVE_SIZE - self.bytes);
+        // Reading one byte more than allowed tells whether there's more.
+        let mut contents = contents.take(limit + 1);
+        fs.create_file_with(path, Pin::new(&mut contents))
+            .await
+            .with_context(|| format!("extracting {path:?}"))?;
+        let size = limit + 1 - contents.limit();
+        anyhow::ensure!(
+            size <= MAX_ARCHIVE_ENTRY_SIZE,
+            "{name} is larger than {} MiB, the most unpacked for a file in an archive",
+            MAX_ARCHIVE_ENTRY_SIZE / 1024 / 1024
+        );
+        anyhow::ensure!(
+            size <= limit,
+            "the archive unpacks to more than {} MiB",
+            MAX_UNPACKED_ARCHIVE_SIZE / 1024 / 1024
+        );
+        self.bytes += size;
+        Ok(())
+    }
+}
+
+/// Returns where an entry of an archive is unpacked to, or `None` for entries that
+/// would end up outside of the destination.
+fn entry_destination(destination: &Path, entry_path: &str) -> Option<PathBuf> {
+    let path = RelPath::new(Path::new(entry_path), PathStyle::Unix)
+        .ok()
+        .filter(|path| !path.is_empty())?;
+    Some(destination.join(path.as_std_path()))
+}
+
+async fn unpack_tar(
+    fs: &dyn Fs,
+    reader: impl AsyncRead + Send + Unpin,
+    destination: &Path,
+) -> Result<()> {
+    let mut size = UnpackedSize::default();
+    let mut entries = Archive::new(reader)
+        .entries()
+        .context("reading the tar archive")?;
+    while let Some(entry) = entries.next().await {
+        let entry = entry.context("reading tar entry")?;
+        size.add_entry()?;
+        let Some(name) = entry
+            .path()
+            .context("reading tar entry path")?
+            .to_str()
+            .map(ToString::to_string)
+        else {
+            continue;
+        };
+        let Some(path) = entry_destination(destination, &name) else {
+            continue;
+        };
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            fs.create_dir(&path).await?;
+        } else if entry_type.is_file() {
+            if let Some(parent) = path.parent() {
+                fs.create_dir(parent).await?;
+            }
+            futures::pin_mut!(entry);
+            size.write_file(fs, &name, &path, entry).await?;
+        }
+        // Links and special files aren't unpacked, so that the contents stay
+        // within the archive's directory.
+    }
+    Ok(())
+}
+
+async fn unpack_zip(fs: &dyn Fs, contents: Vec<u8>, destination: &Path) -> Result<()> {
+    let reader = async_zip::base::read::mem::ZipFileReader::new(contents)
+        .await
+        .context("reading the zip archive")?;
+    let mut size = UnpackedSize::default();
+    for (ix, entry) in reader.file().entries().iter().enumerate() {
+        size.add_entry()?;
+        let file_name = entry
+            .filename()
+            .as_str()
+            .context("reading zip entry file name")?;
+        let Some(path) = entry_destination(destination, file_name) else {
+            continue;
+        };
+        if entry
+            .dir()
+            .with_context(|| format!("reading zip entry metadata for path {path:?}"))?
+        {
+            fs.create_dir(&path).await?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs.create_dir(parent).await?;
+        }
+        let entry_reader = reader
+            .reader_with_entry(ix)
+            .await
+            .with_context(|| format!("reading entry for path {path:?}"))?;
+        futures::pin_mut!(entry_reader);
+        size.write_file(fs, file_name, &path, entry_reader).await?;
+    }
+    Ok(())
+}
@@ -35,11 +35,11 @@ use git::{
     parse_git_remote_url,
     repository::{
         Branch, BranchesScanResult, CommitData, CommitDetails, CommitDiff, CommitFile,
-        CommitOptions, CreateWorktreeTarget, DiffStatType, DiffType, FetchOptions,
-        FileHistoryChangedFileSets, GitCommitTemplate, GitRepository, GitRepositoryCheckpoint,
-        InitialGraphCommitData, LogOrder, LogSource, PushOptions, Remote, RemoteCommandOutput,
-        RepoPath, ResetMode, SearchCommitArgs, UpstreamTrackingStatus, Worktree as GitWorktree,
-        delete_branch_flag,
+        CommitOptions, CommitSearchTarget, CreateWorktreeTarget, DiffStatType, DiffType,
+        FetchOptions, FileHistoryChangedFileSets, GitCommitTemplate, GitRepository,
+        GitRepositoryCheckpoint, InitialGraphCommitData, LogOrder, LogSource, PushOptions, Remote,
+        RemoteCommandOutput, RepoPath, ResetMode, SearchCommitArgs, UpstreamTrackingStatus,
+        Worktree as GitWorktree, delete_branch_flag,
     },
     stash::{GitStash, StashEntry},
     status::{
@@ -322,6 +322,15 @@ pub struct GitStoreCheckpoint {
     checkpoints_by_work_dir_abs_path: HashMap<Arc<Path>, GitRepositoryCheckpoint>,
 }
 
+impl GitStoreCheckpoint {
+    /// The checkpoint commit of each repository, keyed by its working directory.
+    pub fn commit_shas(&self) -> impl Iterator<Item = (&Arc<Path>, Oid)> {
+        self.checkpoints_by_work_dir_abs_path
+            .iter()
+            .map(|(work_dir_abs_path, checkpoint)| (work_dir_abs_path, checkpoint.commit_sha))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StatusEntry {
     pub repo_path: RepoPath,
@@ -833,6 +842,7 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_git_diff);
         client.add_entity_request_handler(Self::handle_tree_diff);
         client.add_entity_request_handler(Self::handle_get_blob_content);
+        client.add_entity_request_handler(Self::handle_get_blob_bytes);
         client.add_entity_request_handler(Self::handle_load_commit_template);
         client.add_entity_request_handler(Self::handle_open_unstaged_diff);
         client.add_entity_request_handler(Self::handle_open_uncommitted_diff);
@@ -3535,6 +3545,15 @@ impl GitStore {
                 .context("missing search commit log source")?,
         )?;
         let search_args = SearchCommitArgs {
+            target: commit_search_target_from_proto(envelope.payload.target()),
+            paths: envelope
+                .payload
+                .paths
+                .iter()
+                .map(|path| RepoPath::from_proto(path))
+                .collect::<Result<_>>()?,
+            since: envelope.payload.since.map(SharedString::from),
+            until: envelope.payload.until.map(SharedString::from),
             query: SharedString::from(envelope.payload.query),
             case_sensitive: envelope.payload.case_sensitive,
         };
@@ -4206,6 +4225,24 @@ impl GitStore {
         Ok(proto::GetBlobContentResponse { content })
     }
 
+    async fn handle_get_blob_bytes(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::GetBlobBytes>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetBlobBytesResponse> {
+        let repository_id = RepositoryId(request.payload.repository_id);
+        let content = this
+            .update(&mut cx, |this, cx| {
+                let repository = this.repositories().get(&repository_id)?;
+                Some(repository.update(cx, |repo, cx| {
+                    repo.load_blob_bytes(request.payload.revision, cx)
+                }))
+            })
+            .context("missing repository")?
+            .await?;
+        Ok(proto::GetBlobBytesResponse { content })
+    }
+
     async fn handle_load_commit_template(
         this: Entity<Self>,
         request: TypedEnvelope<proto::LoadCommitTemplate>,
@@ -6566,6 +6603,14 @@ impl Repository {
                             log_source: Some(log_source_to_proto(&log_source)),
                             query: search_args.query.to_string(),
                             case_sensitive: search_args.case_sensitive,
+                            target: commit_search_target_to_proto(search_args.target).into(),
+                            paths: search_args
+                                .paths
+                                .iter()
+                                .map(|path| path.as_unix_str().to_owned())
+                                .collect(),
+                            since: search_args.since.as_ref().map(ToString::to_string),
+                            until: search_args.until.as_ref().map(ToString::to_string),
                         })
                         .await;
 
@@ -9532,6 +9577,29 @@ impl Repository {
         cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
     }
 
+    /// Loads the raw bytes of a blob, given either its oid or a `<commit>:<path>` spec.
+    pub fn load_blob_bytes(&mut self, revision: String, cx: &App) -> Task<Result<Vec<u8>>> {
+        let repository_id = self.snapshot.id;
+        let rx = self.send_job("load_blob_bytes", None, move |state, _| async move {
+            match state {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.load_blob_bytes(revision).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { client, project_id }) => {
+                    let response = client
+                        .request(proto::GetBlobBytes {
+                            project_id: project_id.to_proto(),
+                            repository_id: repository_id.0,
+                            revision,
+                        })
+                        .await?;
+                    Ok(response.content)
+                }
+            }
+        });
+        cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
+    }
+
     fn paths_changed(
         &mut self,
         paths: Vec<RepoPath>,
@@ -10197,6 +10265,22 @@ fn log_order_from_proto(log_order: proto::get_initial_graph_data::LogOrder) -> L
     }
 }
 
+fn commit_search_target_to_proto(target: CommitSearchTarget) -> proto::search_commits::Target {
+    match target {
+        CommitSearchTarget::Message => proto::search_commits::Target::Message,
+        CommitSearchTarget::Occurrences => proto::search_commits::Target::Occurrences,
+        CommitSearchTarget::ChangedLines => proto::search_commits::Target::ChangedLines,
+    }
+}
+
+fn commit_search_target_from_proto(target: proto::search_commits::Target) -> CommitSearchTarget {
+    match target {
+        proto::search_commits::Target::Message => CommitSearchTarget::Message,
+        proto::search_commits::Target::Occurrences => CommitSearchTarget::Occurrences,
+        proto::search_commits::Target::ChangedLines => CommitSearchTarget::ChangedLines,
+    }
+}
+
 fn initial_graph_commit_to_proto(commit: &InitialGraphCommitData) -> proto::InitialGraphCommit {
     proto::InitialGraphCommit {
         sha: commit.sha.to_string(),
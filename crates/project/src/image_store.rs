@@ -216,10 +216,12 @@ pub fn is_image_file(project: &Entity<Project>, path: &ProjectPath, cx: &App) ->
             .map(str::to_lowercase)
     });
 
-    match ext {
-        Some(ext) => Img::extensions().contains(&ext.as_str()) && !ext.contains("svg"),
-        None => false,
-    }
+    ext.is_some_and(|ext| is_image_extension(&ext))
+}
+
+/// Whether files with the given (lowercase) extension are opened as images.
+pub fn is_image_extension(ext: &str) -> bool {
+    Img::extensions().contains(&ext) && !ext.contains("svg")
 }
 
 impl ProjectItem for ImageItem {
@@ -890,7 +892,7 @@ impl LocalImageStore {
     }
 }
 
-fn create_gpui_image(content: Vec<u8>) -> anyhow::Result<Arc<gpui::Image>> {
+pub fn create_gpui_image(content: Vec<u8>) -> anyhow::Result<Arc<gpui::Image>> {
     let format = image::guess_format(&content)?;
 
     Ok(Arc::new(gpui::Image::from_bytes(
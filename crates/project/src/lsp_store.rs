@@ -35,6 +35,7 @@ use crate::{
     CoreCompletion, Hover, InlayHint, InlayId, LocationLink, LspAction, LspPullDiagnostics,
     ManifestProvidersStore, Project, ProjectItem, ProjectPath, ProjectTransaction,
     PulledDiagnostics, ResolveState, Symbol,
+    archive_store::ArchiveStore,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
     lsp_command::{self, *},
@@ -306,6 +307,7 @@ pub struct LocalLspStore {
     languages: Arc<LanguageRegistry>,
     language_server_ids: HashMap<LanguageServerSeed, UnifiedLanguageServer>,
     yarn: Entity<YarnPathStore>,
+    archives: Entity<ArchiveStore>,
     pub language_servers: HashMap<LanguageServerId, LanguageServerState>,
     buffers_being_formatted: HashSet<BufferId>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
@@ -4477,6 +4479,7 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
 
         client.add_entity_request_handler(Self::handle_lsp_ext_cancel_flycheck);
         client.add_entity_request_handler(Self::handle_lsp_ext_run_flycheck);
@@ -4534,6 +4537,7 @@ impl LspStore {
     pub fn new_local(
         buffer_store: Entity<BufferStore>,
         worktree_store: Entity<WorktreeStore>,
+        archive_store: Entity<ArchiveStore>,
         prettier_store: Entity<PrettierStore>,
         toolchain_store: Entity<LocalToolchainStore>,
         environment: Entity<ProjectEnvironment>,
@@ -4584,6 +4588,7 @@ impl LspStore {
                 http_client,
                 fs,
                 yarn,
+                archives: archive_store,
                 next_diagnostic_group_id: Default::default(),
                 diagnostics: Default::default(),
                 _subscription: cx.on_app_quit(|this, _| {
@@ -6201,6 +6206,33 @@ impl LspStore {
         )
     }
 
+    pub fn supports_selection_ranges(&self, buffer: &Entity<Buffer>, cx: &App) -> bool {
+        self.check_if_capable_for_proto_request(
+            buffer,
+            GetSelectionRanges::check_server_capabilities,
+            cx,
+        )
+    }
+
+    /// Queries the first capable language server for the ranges enclosing each of `positions`,
+    /// ordered from innermost to outermost.
+    pub fn selection_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Vec<Range<Anchor>>>>> {
+        if positions.is_empty() || !self.supports_selection_ranges(buffer, cx) {
+            return Task::ready(Ok(Vec::new()));
+        }
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
     fn apply_on_type_formatting(
         &mut self,
         buffer: Entity<Buffer>,
@@ -9332,7 +9364,43 @@ impl LspStore {
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Buffer>>> {
         let path_style = self.worktree_store.read(cx).path_style();
+        let archive_entry = self
+            .as_local()
+            .map(|local| local.archives.clone())
+            .and_then(|archives| {
+                archives.update(cx, |archives, cx| {
+                    archives.open_archive_uri(&abs_path, path_style, cx)
+                })
+            });
         cx.spawn(async move |lsp_store, cx| {
+            if let Some(archive_entry) = archive_entry {
+                let (worktree, path) = archive_entry.await?;
+                lsp_store.update(cx, |lsp_store, cx| {
+                    if let Some(local) = lsp_store.as_local_mut() {
+                        local.register_language_server_for_invisible_worktree(
+                            &worktree,
+                            language_server_id,
+                            cx,
+                        );
+                    }
+                })?;
+                let project_path = ProjectPath {
+                    worktree_id: worktree.read_with(cx, |worktree, _| worktree.id()),
+                    path,
+                };
+                let buffer = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.buffer_store().update(cx, |buffer_store, cx| {
+                            buffer_store.open_buffer(project_path, cx)
+                        })
+                    })?
+                    .await?;
+                buffer.update(cx, |buffer, cx| {
+                    buffer.set_capability(Capability::ReadOnly, cx)
+                });
+                return Ok(buffer);
+            }
+
             // Escape percent-encoded string.
             let current_scheme = abs_path.scheme().to_owned();
             // Uri is immutable, so we can't modify the scheme
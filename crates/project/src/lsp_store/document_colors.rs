@@ -340,6 +340,40 @@ impl LspStore {
         }
     }
 
+    /// Asks the language server that reported `color` how to write `new_color` in its place.
+    ///
+    /// Returns no presentations if no server reported `color` for `buffer`.
+    pub fn color_presentations(
+        &mut self,
+        buffer: Entity<Buffer>,
+        color: &DocumentColor,
+        new_color: lsp::Color,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<ColorPresentation>>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        let server_id = self
+            .current_lsp_data(buffer_id)
+            .and_then(|lsp_data| lsp_data.document_colors.as_ref())
+            .and_then(|document_colors| {
+                document_colors
+                    .colors
+                    .iter()
+                    .find_map(|(server_id, colors)| colors.contains(color).then_some(*server_id))
+            });
+        let Some(server_id) = server_id else {
+            return Task::ready(Ok(Vec::new()));
+        };
+
+        let color = DocumentColor {
+            lsp_range: color.lsp_range,
+            color: new_color,
+            resolved: false,
+            color_presentations: Vec::new(),
+        };
+        let resolve_task = self.resolve_color_presentation(color, buffer, server_id, cx);
+        cx.background_spawn(async move { Ok(resolve_task.await?.color_presentations) })
+    }
+
     pub(super) fn fetch_document_colors_for_buffer(
         &mut self,
         buffer: &Entity<Buffer>,
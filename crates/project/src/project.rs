@@ -1,5 +1,6 @@
 pub mod agent_registry_store;
 pub mod agent_server_store;
+pub mod archive_store;
 pub mod bookmark_store;
 pub mod buffer_store;
 pub mod color_extractor;
@@ -25,6 +26,7 @@ pub mod trusted_worktrees;
 pub mod worktree_store;
 
 mod environment;
+use archive_store::ArchiveStore;
 use buffer_diff::BufferDiff;
 use context_server_store::ContextServerStore;
 pub use environment::ProjectEnvironmentEvent;
@@ -236,6 +238,8 @@ pub struct Project {
     buffer_store: Entity<BufferStore>,
     context_server_store: Entity<ContextServerStore>,
     image_store: Entity<ImageStore>,
+    /// Only available in local projects.
+    archive_store: Option<Entity<ArchiveStore>>,
     lsp_store: Entity<LspStore>,
     _subscriptions: Vec<gpui::Subscription>,
     buffers_needing_diff: HashSet<WeakEntity<Buffer>>,
@@ -1310,10 +1314,14 @@ impl Project {
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
 
+            let archive_store =
+                cx.new(|cx| ArchiveStore::new(fs.clone(), worktree_store.clone(), cx));
+
             let lsp_store = cx.new(|cx| {
                 LspStore::new_local(
                     buffer_store.clone(),
                     worktree_store.clone(),
+                    archive_store.clone(),
                     prettier_store.clone(),
                     toolchain_store
                         .read(cx)
@@ -1347,6 +1355,7 @@ impl Project {
                 worktree_store,
                 buffer_store,
                 image_store,
+                archive_store: Some(archive_store),
                 lsp_store,
                 context_server_store,
                 join_project_response_message_id: 0,
@@ -1573,6 +1582,7 @@ impl Project {
                 worktree_store,
                 buffer_store,
                 image_store,
+                archive_store: None,
                 lsp_store,
                 context_server_store,
                 bookmark_store,
@@ -1874,6 +1884,7 @@ impl Project {
                 buffer_ordered_messages_tx: tx,
                 buffer_store: buffer_store.clone(),
                 image_store,
+                archive_store: None,
                 worktree_store: worktree_store.clone(),
                 lsp_store: lsp_store.clone(),
                 context_server_store,
@@ -2484,6 +2495,30 @@ impl Project {
             .map(|worktree| worktree.read(cx).id())
     }
 
+    /// Unpacks an archive, like a `.zip` or `.tar.gz` file, returning the invisible
+    /// worktree its contents can be browsed in.
+    pub fn open_archive(
+        &mut self,
+        abs_path: Arc<Path>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Worktree>>> {
+        let Some(archive_store) = &self.archive_store else {
+            return Task::ready(Err(anyhow!(
+                "Browsing archives is only supported in local projects"
+            )));
+        };
+        archive_store.update(cx, |archive_store, cx| {
+            archive_store.open_archive(abs_path, cx)
+        })
+    }
+
+    /// Checks if the worktree holds the contents of an archive opened with [`Self::open_archive`].
+    pub fn is_archive_worktree(&self, worktree_id: WorktreeId, cx: &App) -> bool {
+        self.archive_store
+            .as_ref()
+            .is_some_and(|archive_store| archive_store.read(cx).is_archive_worktree(worktree_id))
+    }
+
     /// Checks if the entry is the root of a worktree.
     #[inline]
     pub fn entry_is_worktree_root(&self, entry_id: ProjectEntryId, cx: &App) -> bool {
@@ -3608,6 +3643,16 @@ impl Project {
     ) {
         match event {
             BufferStoreEvent::BufferAdded(buffer) => {
+                if let Some(archive_store) = &self.archive_store
+                    && let Some(file) = buffer.read(cx).file()
+                    && archive_store
+                        .read(cx)
+                        .is_archive_worktree(file.worktree_id(cx))
+                {
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.set_capability(Capability::ReadOnly, cx)
+                    });
+                }
                 self.register_buffer(buffer, cx).log_err();
             }
             BufferStoreEvent::BufferDropped(buffer_id) => {
@@ -4537,6 +4582,23 @@ impl Project {
         })
     }
 
+    pub fn supports_selection_ranges(&self, buffer: &Entity<Buffer>, cx: &App) -> bool {
+        self.lsp_store
+            .read(cx)
+            .supports_selection_ranges(buffer, cx)
+    }
+
+    pub fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Vec<Range<Anchor>>>>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.selection_ranges(buffer, positions, cx)
+        })
+    }
+
     pub fn completions<T: ToOffset + ToPointUtf16>(
         &self,
         buffer: &Entity<Buffer>,
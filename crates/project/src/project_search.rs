@@ -340,6 +340,9 @@ impl Search {
                 };
 
                 let should_find_all_matches = !tx.is_closed();
+                // Structural and scoped queries match against syntax trees,
+                // which newly opened buffers may still be building.
+                let wait_for_parsing = query.needs_syntax_tree();
 
                 let _executor = executor.clone();
                 let worker_pool = executor.spawn(async move {
@@ -378,6 +381,7 @@ impl Search {
                             grab_buffer_snapshot_rx,
                             find_all_matches_tx,
                             sorted_matches_tx,
+                            wait_for_parsing,
                             cx.clone(),
                         )
                         .boxed_local(),
@@ -562,10 +566,15 @@ impl Search {
         rx: Receiver<(Entity<Buffer>, LineHint)>,
         find_all_matches_tx: Sender<FindAllMatchesRequest>,
         results: Sender<oneshot::Receiver<(Entity<Buffer>, Vec<Range<language::Anchor>>)>>,
+        wait_for_parsing: bool,
         mut cx: AsyncApp,
     ) {
         _ = maybe!(async move {
             while let Ok((buffer, line_hint)) = rx.recv().await {
+                if wait_for_parsing {
+                    let parsing_idle = buffer.read_with(&mut cx, |this, _| this.parsing_idle());
+                    parsing_idle.await;
+                }
                 let snapshot = buffer.read_with(&mut cx, |this, _| this.snapshot());
                 let (tx, rx) = oneshot::channel();
                 find_all_matches_tx
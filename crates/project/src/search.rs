@@ -4,7 +4,7 @@ use client::proto;
 use fancy_regex::{Captures, Regex, RegexBuilder};
 use gpui::Entity;
 use itertools::Itertools as _;
-use language::{Buffer, BufferSnapshot, CharKind};
+use language::{Buffer, BufferSnapshot, CharKind, StructuralPattern, SyntacticRegion};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
@@ -43,6 +43,7 @@ pub struct SearchInputs {
     files_to_exclude: PathMatcher,
     match_full_paths: bool,
     buffers: Option<Vec<Entity<Buffer>>>,
+    scope: SearchScope,
 }
 
 pub type LineHint = u32;
@@ -60,6 +61,85 @@ impl SearchInputs {
     pub fn buffers(&self) -> &Option<Vec<Entity<Buffer>>> {
         &self.buffers
     }
+    pub fn scope(&self) -> SearchScope {
+        self.scope
+    }
+}
+
+/// Restricts search matches to a kind of syntactic region, as classified by
+/// the highlights query of each buffer's languages.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchScope {
+    /// Matches anywhere in a buffer.
+    #[default]
+    Anywhere,
+    /// Matches outside of comments and string literals.
+    Code,
+    /// Matches within comments.
+    Comments,
+    /// Matches within string literals.
+    Strings,
+}
+
+impl SearchScope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Anywhere => "Anywhere",
+            Self::Code => "Code Only",
+            Self::Comments => "Comments Only",
+            Self::Strings => "Strings Only",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Anywhere => Self::Code,
+            Self::Code => Self::Comments,
+            Self::Comments => Self::Strings,
+            Self::Strings => Self::Anywhere,
+        }
+    }
+
+    fn from_proto(scope: proto::search_query::Scope) -> Self {
+        match scope {
+            proto::search_query::Scope::Anywhere => Self::Anywhere,
+            proto::search_query::Scope::Code => Self::Code,
+            proto::search_query::Scope::Comments => Self::Comments,
+            proto::search_query::Scope::Strings => Self::Strings,
+        }
+    }
+
+    fn to_proto(self) -> proto::search_query::Scope {
+        match self {
+            Self::Anywhere => proto::search_query::Scope::Anywhere,
+            Self::Code => proto::search_query::Scope::Code,
+            Self::Comments => proto::search_query::Scope::Comments,
+            Self::Strings => proto::search_query::Scope::Strings,
+        }
+    }
+
+    /// Whether a match at `range` lies in this scope, given the syntactic
+    /// regions around it, ordered by their start offset.
+    fn contains(&self, regions: &[(Range<usize>, SyntacticRegion)], range: &Range<usize>) -> bool {
+        // Regions may be nested through language injections, in which case
+        // the innermost one, the last to start, decides.
+        let enclosing_region = regions
+            .iter()
+            .rev()
+            .find(|(region, _)| region.start <= range.start && range.end <= region.end)
+            .map(|(_, kind)| *kind);
+        match self {
+            Self::Anywhere => true,
+            Self::Code => {
+                enclosing_region.is_none()
+                    && !regions
+                        .iter()
+                        .any(|(region, _)| region.start < range.end && range.start < region.end)
+            }
+            Self::Comments => enclosing_region == Some(SyntacticRegion::Comment),
+            Self::Strings => enclosing_region == Some(SyntacticRegion::String),
+        }
+    }
 }
 #[derive(Clone, Debug)]
 pub enum SearchQuery {
@@ -69,6 +149,10 @@ pub enum SearchQuery {
         whole_word: bool,
         case_sensitive: bool,
         include_ignored: bool,
+        /// Whether the query matches the case variants of its text, and its
+        /// replacement takes the case of each match.
+        /// See [`SearchQuery::case_preserving`].
+        preserve_case: bool,
         inner: SearchInputs,
     },
     Regex {
@@ -82,6 +166,14 @@ pub enum SearchQuery {
         inner: SearchInputs,
         escaped: bool,
     },
+    /// Matches code by its syntax, using the Tree-sitter grammar of each
+    /// buffer's languages. See [`StructuralPattern`].
+    Structural {
+        pattern: StructuralPattern,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 static WORD_MATCH_TEST: LazyLock<Regex> = LazyLock::new(|| {
@@ -131,6 +223,7 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            scope: SearchScope::default(),
         };
         Ok(Self::Text {
             search,
@@ -138,6 +231,45 @@ impl SearchQuery {
             whole_word,
             case_sensitive,
             include_ignored,
+            preserve_case: false,
+            inner,
+        })
+    }
+
+    /// Create a text query that matches the camelCase, PascalCase, snake_case,
+    /// SCREAMING_SNAKE_CASE and kebab-case variants of `query`, and whose
+    /// replacement is converted into the case of each match. Replacing
+    /// `userId` with `accountId` then also replaces `USER_ID` with `ACCOUNT_ID`.
+    ///
+    /// Case-preserving queries are always case-sensitive. Queries and matches
+    /// that aren't identifiers are replaced verbatim.
+    pub fn case_preserving(
+        query: impl ToString,
+        whole_word: bool,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+        match_full_paths: bool,
+        buffers: Option<Vec<Entity<Buffer>>>,
+    ) -> Result<Self> {
+        let mut query = query.to_string();
+        text::LineEnding::normalize(&mut query);
+        let search = AhoCorasickBuilder::new().build(IdentifierCase::variants(&query))?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            match_full_paths,
+            buffers,
+            scope: SearchScope::default(),
+        };
+        Ok(Self::Text {
+            search,
+            replacement: None,
+            whole_word,
+            case_sensitive: true,
+            include_ignored,
+            preserve_case: true,
             inner,
         })
     }
@@ -165,6 +297,7 @@ impl SearchQuery {
             files_to_exclude,
             match_full_paths,
             buffers,
+            scope: SearchScope::default(),
         };
         Self::build_regex(
             query,
@@ -200,6 +333,7 @@ impl SearchQuery {
             files_to_exclude,
             match_full_paths,
             buffers,
+            scope: SearchScope::default(),
         };
         Self::build_regex(
             regex::escape(&query),
@@ -212,6 +346,38 @@ impl SearchQuery {
         )
     }
 
+    /// Create a structural query, from either a code pattern with
+    /// metavariables such as `foo($A, $B)` or a raw Tree-sitter query.
+    ///
+    /// Structural queries are always case-sensitive, and only match buffers
+    /// whose language has a grammar.
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+        match_full_paths: bool,
+        buffers: Option<Vec<Entity<Buffer>>>,
+    ) -> Result<Self> {
+        let mut query = query.to_string();
+        text::LineEnding::normalize(&mut query);
+        let pattern = StructuralPattern::new(&query)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_include,
+            files_to_exclude,
+            match_full_paths,
+            buffers,
+            scope: SearchScope::default(),
+        };
+        Ok(Self::Structural {
+            pattern,
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     fn build_regex(
         mut pattern: String,
         whole_word: bool,
@@ -330,7 +496,27 @@ impl SearchQuery {
             message.files_to_exclude
         };
 
-        if message.regex {
+        let scope = SearchScope::from_proto(message.scope());
+        let query = if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                PathMatcher::new(files_to_include, path_style)?,
+                PathMatcher::new(files_to_exclude, path_style)?,
+                message.match_full_paths,
+                None, // search opened only don't need search remote
+            )
+        } else if message.preserve_case {
+            Self::case_preserving(
+                message.query,
+                message.whole_word,
+                message.include_ignored,
+                PathMatcher::new(files_to_include, path_style)?,
+                PathMatcher::new(files_to_exclude, path_style)?,
+                message.match_full_paths,
+                None, // search opened only don't need search remote
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -353,7 +539,8 @@ impl SearchQuery {
                 message.match_full_paths,
                 None, // search opened only don't need search remote
             )
-        }
+        }?;
+        Ok(query.with_scope(scope))
     }
 
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
@@ -365,6 +552,10 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
@@ -372,12 +563,27 @@ impl SearchQuery {
         }
     }
 
+    /// Restricts matches to the given syntactic regions of each buffer.
+    pub fn with_scope(mut self, scope: SearchScope) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. }
+            | Self::Regex { ref mut inner, .. }
+            | Self::Structural { ref mut inner, .. } => {
+                inner.scope = scope;
+                self
+            }
+        }
+    }
+
     pub fn to_proto(&self) -> proto::SearchQuery {
         let mut files_to_include = self.files_to_include().sources();
         let mut files_to_exclude = self.files_to_exclude().sources();
         proto::SearchQuery {
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
+            preserve_case: self.preserve_case(),
+            scope: self.scope().to_proto().into(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
@@ -461,19 +667,40 @@ impl SearchQuery {
                     Ok(None)
                 }
             }
+            Self::Structural { pattern, .. } => {
+                // Files are only parsed once they're opened, so open those
+                // that contain every word of the pattern.
+                let mut text = String::new();
+                reader.read_to_string(&mut text)?;
+                if pattern
+                    .required_words()
+                    .iter()
+                    .all(|word| text.contains(word.as_str()))
+                {
+                    Ok(Some(LineHint::default()))
+                } else {
+                    Ok(None)
+                }
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
+            SearchQuery::Text {
+                replacement: Some(replacement),
+                preserve_case: true,
+                ..
+            } => Some(Cow::Owned(IdentifierCase::preserve(text, replacement))),
+
             SearchQuery::Text { replacement, .. }
             | SearchQuery::Regex {
                 replacement,
@@ -504,9 +731,36 @@ impl SearchQuery {
             SearchQuery::Regex {
                 replacement: None, ..
             } => None,
+
+            // A structural replacement depends on what the match's
+            // metavariables captured, so it needs the buffer, see
+            // `structural_replacement`.
+            SearchQuery::Structural { .. } => None,
         }
     }
 
+    /// Expands the replacement template of a structural query for the match
+    /// at `range` in `buffer`.
+    pub fn structural_replacement(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        let SearchQuery::Structural {
+            pattern,
+            replacement: Some(replacement),
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let structural_match = pattern
+            .matches(buffer, range.clone())
+            .into_iter()
+            .find(|structural_match| structural_match.range == range)?;
+        Some(structural_match.expand_replacement(replacement, buffer))
+    }
+
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
@@ -519,6 +773,19 @@ impl SearchQuery {
         }
 
         let range_offset = subrange.as_ref().map(|r| r.start).unwrap_or(0);
+        if let Self::Structural { pattern, .. } = self {
+            let range = subrange.unwrap_or(0..buffer.len());
+            let matches = pattern
+                .matches(buffer, range)
+                .into_iter()
+                .map(|structural_match| {
+                    structural_match.range.start - range_offset
+                        ..structural_match.range.end - range_offset
+                })
+                .collect();
+            return self.retain_matches_in_scope(buffer, range_offset, matches);
+        }
+
         let rope = if let Some(range) = subrange {
             buffer.as_rope().slice(range)
         } else {
@@ -602,8 +869,33 @@ impl SearchQuery {
                     }
                 }
             }
+            Self::Structural { .. } => {}
         }
 
+        self.retain_matches_in_scope(buffer, range_offset, matches)
+    }
+
+    fn retain_matches_in_scope(
+        &self,
+        buffer: &BufferSnapshot,
+        range_offset: usize,
+        mut matches: Vec<Range<usize>>,
+    ) -> Vec<Range<usize>> {
+        let scope = self.scope();
+        if scope == SearchScope::Anywhere {
+            return matches;
+        }
+        let (Some(first), Some(last)) = (matches.first(), matches.last()) else {
+            return matches;
+        };
+        let regions = buffer
+            .syntactic_regions(range_offset + first.start..range_offset + last.end.max(first.end));
+        matches.retain(|range| {
+            let range = range_offset + range.start..range_offset + range.end;
+            let candidate_regions =
+                regions.partition_point(|(region, _)| region.start <= range.end);
+            scope.contains(&regions[..candidate_regions], &range)
+        });
         matches
     }
 
@@ -619,6 +911,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -626,6 +919,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
         }
     }
 
@@ -636,6 +930,9 @@ impl SearchQuery {
             } => *include_ignored,
             Self::Regex {
                 include_ignored, ..
+            }
+            | Self::Structural {
+                include_ignored, ..
             } => *include_ignored,
         }
     }
@@ -644,6 +941,30 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
+    pub fn preserve_case(&self) -> bool {
+        matches!(
+            self,
+            Self::Text {
+                preserve_case: true,
+                ..
+            }
+        )
+    }
+
+    pub fn scope(&self) -> SearchScope {
+        self.as_inner().scope()
+    }
+
+    /// Whether matching depends on each buffer's syntax tree, so buffers
+    /// need to be parsed before they are searched.
+    pub fn needs_syntax_tree(&self) -> bool {
+        self.is_structural() || self.scope() != SearchScope::Anywhere
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -687,20 +1008,22 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 
     /// Whether this search should replace only one match per line, instead of
     /// all matches.
-    /// Returns `None` for text searches, as only regex searches support this
-    /// option.
+    /// Returns `None` for text and structural searches, as only regex
+    /// searches support this option.
     pub fn one_match_per_line(&self) -> Option<bool> {
         match self {
             Self::Regex {
                 one_match_per_line, ..
             } => Some(*one_match_per_line),
-            Self::Text { .. } => None,
+            Self::Text { .. } | Self::Structural { .. } => None,
         }
     }
 
@@ -752,7 +1075,136 @@ impl SearchQuery {
                     }
                 }
             }
+            // Plain text has no syntax tree to match against.
+            Self::Structural { .. } => {}
         }
         matches
     }
 }
+
+/// A way of casing and joining the words of an identifier, which
+/// case-preserving queries map between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IdentifierCase {
+    Camel,
+    Pascal,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+}
+
+impl IdentifierCase {
+    const ALL: [Self; 5] = [
+        Self::Camel,
+        Self::Pascal,
+        Self::Snake,
+        Self::ScreamingSnake,
+        Self::Kebab,
+    ];
+
+    /// Returns `query` followed by its variants in each case.
+    fn variants(query: &str) -> Vec<String> {
+        let mut variants = vec![query.to_string()];
+        if is_identifier(query) {
+            let words = identifier_words(query);
+            for case in Self::ALL {
+                let variant = case.join(&words);
+                if !variants.contains(&variant) {
+                    variants.push(variant);
+                }
+            }
+        }
+        variants
+    }
+
+    /// Converts `replacement` into the case of `matched`.
+    fn preserve(matched: &str, replacement: &str) -> String {
+        match Self::detect(matched) {
+            Some(case) if is_identifier(replacement) => case.join(&identifier_words(replacement)),
+            _ => replacement.to_string(),
+        }
+    }
+
+    fn detect(identifier: &str) -> Option<Self> {
+        if !is_identifier(identifier) || identifier.contains(char::is_whitespace) {
+            return None;
+        }
+        let has_lowercase = identifier.chars().any(char::is_lowercase);
+        let has_uppercase = identifier.chars().any(char::is_uppercase);
+        if identifier.contains('-') {
+            (!has_uppercase).then_some(Self::Kebab)
+        } else if !has_lowercase {
+            Some(Self::ScreamingSnake)
+        } else if identifier.contains('_') {
+            (!has_uppercase).then_some(Self::Snake)
+        } else if identifier.starts_with(char::is_uppercase) {
+            Some(Self::Pascal)
+        } else {
+            Some(Self::Camel)
+        }
+    }
+
+    /// Joins lowercase words in this case.
+    fn join(self, words: &[String]) -> String {
+        match self {
+            Self::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(ix, word)| {
+                    if ix == 0 {
+                        word.clone()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+            Self::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+            Self::Snake => words.join("_"),
+            Self::ScreamingSnake => words.join("_").to_uppercase(),
+            Self::Kebab => words.join("-"),
+        }
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    text.contains(char::is_alphanumeric)
+        && text
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == ' ')
+}
+
+/// Splits an identifier into lowercase words, at separators and at case
+/// boundaries, so that `HTTPServer_config` becomes `http`, `server` and `config`.
+fn identifier_words(identifier: &str) -> Vec<String> {
+    let chars = identifier.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (ix, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let previous = chars[ix - 1];
+            let next_is_lowercase = chars.get(ix + 1).is_some_and(|c| c.is_lowercase());
+            if !previous.is_uppercase() || next_is_lowercase {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,278 @@
+use std::{path::Path, sync::Arc};
+
+use async_zip::{Compression, ZipEntryBuilder, base::write::ZipFileWriter};
+use fs::FakeFs;
+use gpui::TestAppContext;
+use language::Capability;
+use lsp::LanguageServerId;
+use project::{Project, ProjectPath, archive_store::*};
+use serde_json::json;
+use util::{path, paths::PathStyle, rel_path::rel_path};
+
+use crate::init_test;
+
+async fn build_zip(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut buffer = futures::io::Cursor::new(Vec::new());
+    let mut writer = ZipFileWriter::new(&mut buffer);
+    for (name, contents) in entries {
+        let builder = ZipEntryBuilder::new((*name).into(), Compression::Stored);
+        writer
+            .write_entry_whole(builder, contents.as_bytes())
+            .await
+            .unwrap();
+    }
+    writer.close().await.unwrap();
+    buffer.into_inner()
+}
+
+#[test]
+fn test_archive_kind_for_path() {
+    assert_eq!(
+        ArchiveKind::for_path(Path::new("libs/guava.jar")),
+        Some(ArchiveKind::Zip)
+    );
+    assert_eq!(
+        ArchiveKind::for_path(Path::new("dist/Release.TAR.GZ")),
+        Some(ArchiveKind::TarGz)
+    );
+    assert_eq!(
+        ArchiveKind::for_path(Path::new("serde-1.0.0.crate")),
+        Some(ArchiveKind::TarGz)
+    );
+    assert_eq!(
+        ArchiveKind::for_path(Path::new("backup.tar")),
+        Some(ArchiveKind::Tar)
+    );
+    assert_eq!(ArchiveKind::for_path(Path::new("src/main.rs")), None);
+    assert_eq!(ArchiveKind::for_path(Path::new("zip")), None);
+}
+
+#[test]
+fn test_parse_archive_uri() {
+    let archive_uri = lsp::Uri::from_file_path(path!("/libs/guava.jar")).unwrap();
+
+    let uri: lsp::Uri = format!("jar:{}!/com/google/Lists.java", archive_uri.as_str())
+        .parse()
+        .unwrap();
+    assert_eq!(
+        parse_archive_uri(&uri, PathStyle::local()),
+        Some((
+            Path::new(path!("/libs/guava.jar")).to_path_buf(),
+            rel_path("com/google/Lists.java").into_arc()
+        ))
+    );
+
+    let uri: lsp::Uri = format!(
+        "zipfile{}::pkg/my%20module.py",
+        archive_uri.as_str().strip_prefix("file").unwrap()
+    )
+    .parse()
+    .unwrap();
+    assert_eq!(
+        parse_archive_uri(&uri, PathStyle::local()),
+        Some((
+            Path::new(path!("/libs/guava.jar")).to_path_buf(),
+            rel_path("pkg/my module.py").into_arc()
+        ))
+    );
+
+    // Paths escaping the archive are rejected.
+    let uri: lsp::Uri = format!("jar:{}!/../secret.txt", archive_uri.as_str())
+        .parse()
+        .unwrap();
+    assert_eq!(parse_archive_uri(&uri, PathStyle::local()), None);
+
+    let uri = lsp::Uri::from_file_path(path!("/libs/guava.jar")).unwrap();
+    assert_eq!(parse_archive_uri(&uri, PathStyle::local()), None);
+}
+
+#[gpui::test]
+async fn test_open_archive(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/root"), json!({ "libs": {} })).await;
+    let archive = build_zip(&[
+        ("META-INF/MANIFEST.MF", "Manifest-Version: 1.0\n"),
+        ("com/example/Main.java", "class Main {}\n"),
+        ("../escaped.txt", "outside\n"),
+    ])
+    .await;
+    fs.insert_file(path!("/root/libs/example.jar"), archive)
+        .await;
+
+    let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+    let worktree = project
+        .update(cx, |project, cx| {
+            project.open_archive(Path::new(path!("/root/libs/example.jar")).into(), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    // Opening an archive again reuses its worktree.
+    let reopened_worktree = project
+        .update(cx, |project, cx| {
+            project.open_archive(Path::new(path!("/root/libs/example.jar")).into(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(worktree.entity_id(), reopened_worktree.entity_id());
+
+    let worktree_id = worktree.read_with(cx, |worktree, cx| {
+        assert!(!worktree.is_visible());
+        assert_eq!(worktree.root_name(), rel_path("example.jar"));
+        assert_eq!(
+            worktree
+                .paths()
+                .map(|path| path.as_unix_str())
+                .collect::<Vec<_>>(),
+            [
+                "META-INF",
+                "META-INF/MANIFEST.MF",
+                "com",
+                "com/example",
+                "com/example/Main.java",
+            ]
+        );
+        assert!(project.read(cx).is_archive_worktree(worktree.id(), cx));
+        worktree.id()
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer(
+                ProjectPath {
+                    worktree_id,
+                    path: rel_path("com/example/Main.java").into(),
+                },
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "class Main {}\n");
+        assert_eq!(buffer.capability(), Capability::ReadOnly);
+    });
+
+    fs.insert_file(path!("/root/libs/broken.zip"), b"not a zip".to_vec())
+        .await;
+    let result = project
+        .update(cx, |project, cx| {
+            project.open_archive(Path::new(path!("/root/libs/broken.zip")).into(), cx)
+        })
+        .await;
+    assert!(result.is_err());
+}
+
+#[gpui::test]
+async fn test_archive_closed_when_changed_or_deleted(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/root"), json!({ "libs": {} })).await;
+    fs.insert_file(
+        path!("/root/libs/example.zip"),
+        build_zip(&[("a.txt", "one\n")]).await,
+    )
+    .await;
+    let archive_path: Arc<Path> = Path::new(path!("/root/libs/example.zip")).into();
+
+    let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+    let worktree = project
+        .update(cx, |project, cx| {
+            project.open_archive(archive_path.clone(), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+
+    // Changing the archive removes the stale worktree, and opening it again
+    // unpacks the new contents.
+    fs.insert_file(
+        path!("/root/libs/example.zip"),
+        build_zip(&[("b.txt", "two\n")]).await,
+    )
+    .await;
+    cx.executor().run_until_parked();
+    project.read_with(cx, |project, cx| {
+        assert!(project.worktree_for_id(worktree_id, cx).is_none());
+        assert!(!project.is_archive_worktree(worktree_id, cx));
+    });
+    let worktree = project
+        .update(cx, |project, cx| {
+            project.open_archive(archive_path.clone(), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let worktree_id = worktree.read_with(cx, |worktree, _| {
+        assert_eq!(
+            worktree
+                .paths()
+                .map(|path| path.as_unix_str())
+                .collect::<Vec<_>>(),
+            ["b.txt"]
+        );
+        worktree.id()
+    });
+
+    fs.remove_file(&archive_path, Default::default())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    project.read_with(cx, |project, cx| {
+        assert!(project.worktree_for_id(worktree_id, cx).is_none());
+        assert!(!project.is_archive_worktree(worktree_id, cx));
+    });
+}
+
+#[gpui::test]
+async fn test_open_archive_uri_via_lsp(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/root"), json!({ "libs": {} })).await;
+    fs.insert_file(
+        path!("/root/libs/example.jar"),
+        build_zip(&[("com/example/Main.java", "class Main {}\n")]).await,
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+    let archive_uri = lsp::Uri::from_file_path(path!("/root/libs/example.jar")).unwrap();
+
+    // Archives are opened even when the language server isn't known to the project.
+    for uri in [
+        format!("jar:{}!/com/example/Main.java", archive_uri.as_str()),
+        format!(
+            "zipfile{}::com/example/Main.java",
+            archive_uri.as_str().strip_prefix("file").unwrap()
+        ),
+    ] {
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_via_lsp(uri.parse().unwrap(), LanguageServerId(0), cx)
+            })
+            .await
+            .unwrap();
+        buffer.read_with(cx, |buffer, cx| {
+            assert_eq!(buffer.text(), "class Main {}\n");
+            assert_eq!(buffer.capability(), Capability::ReadOnly);
+            let file = buffer.file().unwrap();
+            assert_eq!(file.path().as_ref(), rel_path("com/example/Main.java"));
+            assert!(
+                project
+                    .read(cx)
+                    .is_archive_worktree(file.worktree_id(cx), cx)
+            );
+        });
+    }
+    // Both URIs point into the same unpacked archive.
+    project.read_with(cx, |project, cx| {
+        assert_eq!(
+            project
+                .worktrees(cx)
+                .filter(|worktree| project.is_archive_worktree(worktree.read(cx).id(), cx))
+                .count(),
+            1
+        );
+    });
+}
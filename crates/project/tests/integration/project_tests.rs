@@ -1,6 +1,7 @@
 #![allow(clippy::format_collect)]
 
 mod agent_registry_store;
+mod archive_store;
 mod bookmark_store;
 mod color_extractor;
 mod context_server_store;
@@ -61,7 +62,7 @@ use pretty_assertions::{assert_eq, assert_matches};
 use project::{
     Event, TaskContexts,
     git_store::{GitStoreEvent, Repository, RepositoryEvent, StatusEntry, pending_op},
-    search::{SearchQuery, SearchResult},
+    search::{SearchQuery, SearchResult, SearchScope},
     task_store::{TaskSettingsLocation, TaskStore},
     *,
 };
@@ -9097,6 +9098,128 @@ async fn test_search_with_unicode(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "fn main() { foo(bar(1, 2), 3); foo(1); }",
+            "two.rs": "fn other() { foo(x, y) }",
+            "three.rs": "fn unrelated() {}",
+            "four.txt": "foo(a, b)",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+    let query = SearchQuery::structural(
+        "foo($A, $B)",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    );
+    assert_matches!(query, Ok(SearchQuery::Structural { .. }));
+    // Files without a grammar have nothing to match against.
+    assert_eq!(
+        search(&project, query.unwrap(), cx).await.unwrap(),
+        HashMap::from_iter([
+            (path!("dir/one.rs").to_string(), vec![12..29]),
+            (path!("dir/two.rs").to_string(), vec![13..22]),
+        ])
+    );
+}
+
+#[gpui::test]
+async fn test_search_with_scope(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "// todo: tidy\nfn todo() { let s = \"todo\"; }\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+    let query = SearchQuery::text(
+        "todo",
+        false,
+        true,
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap();
+    for (scope, expected_ranges) in [
+        (SearchScope::Anywhere, vec![3..7, 17..21, 35..39]),
+        (SearchScope::Code, vec![17..21]),
+        (SearchScope::Comments, vec![3..7]),
+        (SearchScope::Strings, vec![35..39]),
+    ] {
+        assert_eq!(
+            search(&project, query.clone().with_scope(scope), cx)
+                .await
+                .unwrap(),
+            HashMap::from_iter([(path!("dir/one.rs").to_string(), expected_ranges)]),
+            "unexpected matches for {scope:?}"
+        );
+    }
+}
+
+#[gpui::test]
+async fn test_case_preserving_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let text = "userId UserId user_id USER_ID user-id userid getUserId";
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "one.rs": text }))
+        .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    let query = SearchQuery::case_preserving(
+        "userId",
+        false,
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap()
+    .with_replacement("accountId".to_string());
+    let ranges = search(&project, query.clone(), cx)
+        .await
+        .unwrap()
+        .remove(path!("dir/one.rs"))
+        .unwrap();
+    let replaced = ranges
+        .into_iter()
+        .map(|range| query.replacement_for(&text[range]).unwrap().into_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        replaced,
+        [
+            "accountId",
+            "AccountId",
+            "account_id",
+            "ACCOUNT_ID",
+            "account-id",
+            "AccountId"
+        ]
+    );
+}
+
 #[gpui::test]
 async fn test_create_entry(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -0,0 +1,502 @@
+use std::{any::TypeId, path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::{HashMap, HashSet};
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AnyElement, App, AppContext as _, Entity, EventEmitter, FocusHandle, Focusable, PromptLevel,
+    Task, WeakEntity, Window,
+};
+use language::Buffer;
+use project::{Project, ProjectEntryId, ProjectPath, WorktreeId};
+use ui::prelude::*;
+use util::{ResultExt, paths::PathStyle, rel_path::RelPath};
+use workspace::{
+    Item, ItemNavHistory, Workspace,
+    item::{ItemEvent, SaveOptions, TabContentParams},
+};
+use worktree::CreatedEntry;
+
+use crate::{ProjectPanel, undo::Change};
+
+const MAX_PREVIEWED_OPERATIONS: usize = 10;
+
+/// Lists project entries in an editable buffer, one path per line, and
+/// applies the edited paths as renames, moves and deletions on save.
+///
+/// Lines are matched to entries by position: editing a line renames or
+/// moves its entry, while clearing it moves the entry to the trash.
+pub struct BulkRenameView {
+    editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    project: Entity<Project>,
+    project_panel: WeakEntity<ProjectPanel>,
+    entries: Vec<BulkRenameEntry>,
+    /// The root names prefixing each path, when the project has several worktrees.
+    root_names: Option<HashMap<WorktreeId, Arc<RelPath>>>,
+    path_style: PathStyle,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct BulkRenameEntry {
+    pub(crate) entry_id: ProjectEntryId,
+    pub(crate) worktree_id: WorktreeId,
+    pub(crate) path: Arc<RelPath>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum BulkRenameOperation {
+    Rename {
+        entry_id: ProjectEntryId,
+        from: ProjectPath,
+        to: ProjectPath,
+    },
+    Trash {
+        entry_id: ProjectEntryId,
+        path: ProjectPath,
+    },
+}
+
+impl BulkRenameView {
+    pub(crate) fn new(
+        entries: Vec<BulkRenameEntry>,
+        project: Entity<Project>,
+        project_panel: WeakEntity<ProjectPanel>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let (root_names, path_style) = {
+            let project = project.read(cx);
+            let worktrees = project.visible_worktrees(cx).collect::<Vec<_>>();
+            let root_names = (worktrees.len() > 1).then(|| {
+                worktrees
+                    .iter()
+                    .map(|worktree| {
+                        let worktree = worktree.read(cx);
+                        (worktree.id(), worktree.root_name().into_arc())
+                    })
+                    .collect()
+            });
+            (root_names, project.path_style(cx))
+        };
+
+        let text = entries_text(&entries, root_names.as_ref(), path_style);
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let editor =
+            cx.new(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx));
+        cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone())
+        })
+        .detach();
+        Self {
+            editor,
+            buffer,
+            project,
+            project_panel,
+            entries,
+            root_names,
+            path_style,
+        }
+    }
+
+    fn display_path(&self, worktree_id: WorktreeId, path: &RelPath) -> String {
+        display_path(self.root_names.as_ref(), self.path_style, worktree_id, path)
+    }
+
+    fn parse_path(&self, worktree_id: WorktreeId, line: &str) -> Result<Arc<RelPath>> {
+        let path = RelPath::new(Path::new(line), self.path_style)
+            .with_context(|| format!("invalid path {line:?}"))?;
+        let path = match self
+            .root_names
+            .as_ref()
+            .and_then(|root_names| root_names.get(&worktree_id))
+        {
+            Some(root_name) => path
+                .strip_prefix(root_name)
+                .map_err(|_| anyhow!("can't move {line:?} to another worktree"))?
+                .into_arc(),
+            None => path.into_arc(),
+        };
+        anyhow::ensure!(
+            !path.is_empty(),
+            "can't rename an entry to the worktree root"
+        );
+        Ok(path)
+    }
+
+    /// Matches the edited lines to the listed entries, producing the
+    /// operations to run. Trashing runs first, so that renamed entries can
+    /// take over the paths of trashed ones.
+    fn plan(&self, cx: &App) -> Result<Vec<BulkRenameOperation>> {
+        let text = self.buffer.read(cx).text();
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+        let lines = text.split('\n').collect::<Vec<_>>();
+        anyhow::ensure!(
+            lines.len() == self.entries.len(),
+            "Expected {} lines, one per entry, but found {}. Clear a line instead of removing it to trash its entry.",
+            self.entries.len(),
+            lines.len(),
+        );
+
+        let mut trashes = Vec::new();
+        let mut renames = Vec::new();
+        for (entry, line) in self.entries.iter().zip(lines) {
+            let line = line.trim();
+            let from = ProjectPath {
+                worktree_id: entry.worktree_id,
+                path: entry.path.clone(),
+            };
+            if line.is_empty() {
+                trashes.push(BulkRenameOperation::Trash {
+                    entry_id: entry.entry_id,
+                    path: from,
+                });
+                continue;
+            }
+            let to = ProjectPath {
+                worktree_id: entry.worktree_id,
+                path: self.parse_path(entry.worktree_id, line)?,
+            };
+            if to != from {
+                renames.push(BulkRenameOperation::Rename {
+                    entry_id: entry.entry_id,
+                    from,
+                    to,
+                });
+            }
+        }
+
+        let project = self.project.read(cx);
+        if !trashes.is_empty() && project.is_via_collab() {
+            anyhow::bail!("Trashing entries isn't supported in shared projects");
+        }
+        let trashed_ids = trashes
+            .iter()
+            .filter_map(|operation| match operation {
+                BulkRenameOperation::Trash { entry_id, .. } => Some(*entry_id),
+                BulkRenameOperation::Rename { .. } => None,
+            })
+            .collect::<HashSet<_>>();
+        let mut targets = HashSet::default();
+        for operation in &renames {
+            let BulkRenameOperation::Rename { entry_id, to, .. } = operation else {
+                continue;
+            };
+            let display_path = self.display_path(to.worktree_id, &to.path);
+            anyhow::ensure!(
+                targets.insert(to.clone()),
+                "Several entries would be renamed to {display_path:?}"
+            );
+            if let Some(existing_entry) = project.entry_for_path(to, cx) {
+                // Changing only the case of a name finds the entry itself on a
+                // case-insensitive file system.
+                anyhow::ensure!(
+                    existing_entry.id == *entry_id || trashed_ids.contains(&existing_entry.id),
+                    "{display_path:?} already exists"
+                );
+            }
+        }
+
+        trashes.extend(renames);
+        Ok(trashes)
+    }
+
+    fn describe(&self, operation: &BulkRenameOperation) -> String {
+        match operation {
+            BulkRenameOperation::Rename { from, to, .. } => {
+                let verb = if from.path.parent() == to.path.parent() {
+                    "Rename"
+                } else {
+                    "Move"
+                };
+                format!(
+                    "{verb} {} → {}",
+                    self.display_path(from.worktree_id, &from.path),
+                    self.display_path(to.worktree_id, &to.path)
+                )
+            }
+            BulkRenameOperation::Trash { path, .. } => {
+                format!("Trash {}", self.display_path(path.worktree_id, &path.path))
+            }
+        }
+    }
+
+    fn apply(
+        &mut self,
+        operations: Vec<BulkRenameOperation>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let project = self.project.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let mut changes = Vec::new();
+            let mut renamed_entries = HashMap::default();
+            let mut trashed_ids = HashSet::default();
+            let mut result = Ok(());
+            for operation in operations {
+                match operation {
+                    BulkRenameOperation::Trash { entry_id, path } => {
+                        let Some(task) =
+                            project.update(cx, |project, cx| project.trash_entry(entry_id, cx))
+                        else {
+                            trashed_ids.insert(entry_id);
+                            continue;
+                        };
+                        match task.await {
+                            Ok(trash_id) => {
+                                changes.push(Change::Trashed(path.worktree_id, trash_id));
+                                trashed_ids.insert(entry_id);
+                            }
+                            Err(error) => {
+                                result = Err(error.context(format!("trashing {:?}", path.path)));
+                                break;
+                            }
+                        }
+                    }
+                    BulkRenameOperation::Rename { entry_id, from, to } => {
+                        let task = project.update(cx, |project, cx| {
+                            project.rename_entry(entry_id, to.clone(), cx)
+                        });
+                        match task.await {
+                            Ok(created_entry) => {
+                                renamed_entries.insert(
+                                    entry_id,
+                                    match created_entry {
+                                        CreatedEntry::Included(entry) => Some(entry),
+                                        CreatedEntry::Excluded { .. } => None,
+                                    },
+                                );
+                                changes.push(Change::Renamed(from, to));
+                            }
+                            Err(error) => {
+                                result = Err(error
+                                    .context(format!("renaming {:?} to {:?}", from.path, to.path)));
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+
+            this.update_in(cx, |this, window, cx| {
+                this.project_panel
+                    .update(cx, |project_panel, _| {
+                        project_panel.undo_manager.record(changes).log_err();
+                    })
+                    .ok();
+                this.entries.retain_mut(|entry| {
+                    if trashed_ids.contains(&entry.entry_id) {
+                        return false;
+                    }
+                    match renamed_entries.get(&entry.entry_id) {
+                        Some(Some(renamed_entry)) => {
+                            entry.entry_id = renamed_entry.id;
+                            entry.path = renamed_entry.path.clone();
+                            true
+                        }
+                        Some(None) => false,
+                        None => true,
+                    }
+                });
+                this.reset(window, cx);
+            })?;
+            result
+        })
+    }
+
+    /// Replaces the buffer's contents with the current paths of the entries.
+    fn reset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = entries_text(&self.entries, self.root_names.as_ref(), self.path_style);
+        self.editor
+            .update(cx, |editor, cx| editor.set_text(text, window, cx));
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.did_save(buffer.version(), None, cx);
+        });
+    }
+}
+
+fn entries_text(
+    entries: &[BulkRenameEntry],
+    root_names: Option<&HashMap<WorktreeId, Arc<RelPath>>>,
+    path_style: PathStyle,
+) -> String {
+    let mut text = String::new();
+    for entry in entries {
+        text.push_str(&display_path(
+            root_names,
+            path_style,
+            entry.worktree_id,
+            &entry.path,
+        ));
+        text.push('\n');
+    }
+    text
+}
+
+fn display_path(
+    root_names: Option<&HashMap<WorktreeId, Arc<RelPath>>>,
+    path_style: PathStyle,
+    worktree_id: WorktreeId,
+    path: &RelPath,
+) -> String {
+    match root_names.and_then(|root_names| root_names.get(&worktree_id)) {
+        Some(root_name) => root_name.join(path).display(path_style).into_owned(),
+        None => path.display(path_style).into_owned(),
+    }
+}
+
+impl EventEmitter<EditorEvent> for BulkRenameView {}
+
+impl Focusable for BulkRenameView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for BulkRenameView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Pencil).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Bulk Rename".into()
+    }
+
+    fn to_item_events(event: &EditorEvent, f: &mut dyn FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Project Panel Bulk Rename Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        _: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let operations = match self.plan(cx) {
+            Ok(operations) => operations,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        if operations.is_empty() {
+            self.reset(window, cx);
+            return Task::ready(Ok(()));
+        }
+
+        let message = match operations.len() {
+            1 => "Apply 1 change to the project?".to_string(),
+            count => format!("Apply {count} changes to the project?"),
+        };
+        let mut detail = operations
+            .iter()
+            .take(MAX_PREVIEWED_OPERATIONS)
+            .map(|operation| self.describe(operation))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if operations.len() > MAX_PREVIEWED_OPERATIONS {
+            detail.push_str(&format!(
+                "\n…and {} more",
+                operations.len() - MAX_PREVIEWED_OPERATIONS
+            ));
+        }
+        let answer = window.prompt(
+            PromptLevel::Info,
+            &message,
+            Some(&detail),
+            &["Apply", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            if answer.await != Ok(0) {
+                return Ok(());
+            }
+            this.update_in(cx, |this, window, cx| this.apply(operations, window, cx))?
+                .await
+        })
+    }
+
+    fn reload(
+        &mut self,
+        _: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.reset(window, cx);
+        Task::ready(Ok(()))
+    }
+}
+
+impl Render for BulkRenameView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("BulkRename")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.editor.clone())
+    }
+}
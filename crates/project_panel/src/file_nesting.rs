@@ -0,0 +1,133 @@
+use collections::HashMap;
+
+use crate::project_panel_settings::FileNestingSettings;
+
+/// Compiled [`FileNestingSettings`], deciding which files of a directory are
+/// nested under which of their siblings.
+pub(crate) struct FileNestingRules {
+    rules: Vec<NestingRule>,
+}
+
+struct NestingRule {
+    parent: String,
+    children: Vec<String>,
+}
+
+impl FileNestingRules {
+    pub(crate) fn new(settings: &FileNestingSettings) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+
+        let mut rules = settings
+            .patterns
+            .iter()
+            .filter(|(parent, children)| {
+                !parent.is_empty() && !children.is_empty() && parent.matches('*').count() <= 1
+            })
+            .map(|(parent, children)| NestingRule {
+                parent: parent.clone(),
+                children: children.clone(),
+            })
+            .collect::<Vec<_>>();
+        if rules.is_empty() {
+            return None;
+        }
+        // Exact file names take precedence over wildcard patterns, and the rest
+        // of the order only needs to be stable.
+        rules.sort_by(|a, b| {
+            a.parent
+                .contains('*')
+                .cmp(&b.parent.contains('*'))
+                .then_with(|| a.parent.cmp(&b.parent))
+        });
+        Some(Self { rules })
+    }
+
+    /// Returns, for each of the given sibling file names, the index of the file
+    /// it is nested under. Nesting is only one level deep: a file whose parent
+    /// is itself nested ends up under the outermost parent.
+    pub(crate) fn nest(&self, file_names: &[&str]) -> Vec<Option<usize>> {
+        let index_by_name = file_names
+            .iter()
+            .enumerate()
+            .map(|(ix, name)| (*name, ix))
+            .collect::<HashMap<_, _>>();
+
+        let mut parents = vec![None; file_names.len()];
+        for (parent_ix, parent_name) in file_names.iter().enumerate() {
+            for rule in &self.rules {
+                let Some(capture) = wildcard_capture(&rule.parent, parent_name) else {
+                    continue;
+                };
+                for child in &rule.children {
+                    let child = child.replace("${capture}", capture);
+                    if child.contains('*') {
+                        for (child_ix, child_name) in file_names.iter().enumerate() {
+                            if wildcards_match(&child, child_name) {
+                                nest_under(&mut parents, parent_ix, child_ix);
+                            }
+                        }
+                    } else if let Some(&child_ix) = index_by_name.get(child.as_str()) {
+                        nest_under(&mut parents, parent_ix, child_ix);
+                    }
+                }
+            }
+        }
+
+        (0..parents.len())
+            .map(|ix| {
+                let mut parent = parents[ix]?;
+                while let Some(grandparent) = parents[parent] {
+                    parent = grandparent;
+                }
+                Some(parent)
+            })
+            .collect()
+    }
+}
+
+fn nest_under(parents: &mut [Option<usize>], parent_ix: usize, child_ix: usize) {
+    if parents[child_ix].is_some() {
+        return;
+    }
+    // Nesting a file under itself or one of its own nested files would form a cycle.
+    let mut ancestor = Some(parent_ix);
+    while let Some(ix) = ancestor {
+        if ix == child_ix {
+            return;
+        }
+        ancestor = parents[ix];
+    }
+    parents[child_ix] = Some(parent_ix);
+}
+
+/// Matches a file name against a pattern with at most one `*`, returning the
+/// text matched by the `*`.
+fn wildcard_capture<'a>(pattern: &str, name: &'a str) -> Option<&'a str> {
+    let Some((prefix, suffix)) = pattern.split_once('*') else {
+        return (pattern == name).then_some("");
+    };
+    if name.len() < prefix.len() + suffix.len() {
+        return None;
+    }
+    name.strip_prefix(prefix)?.strip_suffix(suffix)
+}
+
+fn wildcards_match(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = parts.next().and_then(|prefix| name.strip_prefix(prefix)) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((suffix, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        let Some(ix) = rest.find(part) else {
+            return false;
+        };
+        rest = &rest[ix + part.len()..];
+    }
+    rest.len() >= suffix.len() && rest.ends_with(suffix)
+}
@@ -1,3 +1,5 @@
+mod bulk_rename;
+mod file_nesting;
 pub mod project_panel_settings;
 mod undo;
 mod utils;
@@ -34,12 +36,13 @@ use markdown_preview::markdown_preview_view::MarkdownPreviewView;
 use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
 use notifications::status_toast::StatusToast;
 use project::{
-    Entry, EntryKind, Fs, GitEntry, GitEntryRef, GitTraversal, Project, ProjectEntryId,
-    ProjectPath, Worktree, WorktreeId,
+    Candidates, Entry, EntryKind, Fs, GitEntry, GitEntryRef, GitTraversal, PathMatchCandidateSet,
+    Project, ProjectEntryId, ProjectPath, Worktree, WorktreeId,
+    archive_store::ArchiveKind,
     git_store::{GitStoreEvent, RepositoryEvent, git_traversal::ChildEntriesGitIter},
     project_settings::GoToDiagnosticSeverityFilter,
 };
-use project_panel_settings::ProjectPanelSettings;
+use project_panel_settings::{FileNestingSettings, ProjectPanelSettings};
 use rayon::slice::ParallelSliceMut;
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -56,14 +59,15 @@ use std::{
     ops::Neg,
     ops::Range,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, atomic::AtomicBool},
     time::{Duration, Instant},
 };
 use theme_settings::ThemeSettings;
 use ui::{
-    ContextMenu, DecoratedIcon, IconDecoration, IconDecorationKind, IndentGuideColors,
-    IndentGuideLayout, Indicator, KeyBinding, ListItem, ListItemSpacing, ProjectEmptyState,
-    ScrollAxes, ScrollableHandle, Scrollbars, StickyCandidate, Tooltip, WithScrollbar, prelude::*,
+    ContextMenu, DecoratedIcon, HighlightedLabel, IconDecoration, IconDecorationKind,
+    IndentGuideColors, IndentGuideLayout, Indicator, KeyBinding, ListItem, ListItemSpacing,
+    ProjectEmptyState, ScrollAxes, ScrollableHandle, Scrollbars, StickyCandidate, Tooltip,
+    WithScrollbar, prelude::*,
 };
 use util::{
     ResultExt, TakeUntilExt, TryFutureExt,
@@ -79,18 +83,21 @@ use workspace::{
     focus_follows_mouse::FocusFollowsMouse as _,
     notifications::{DetachAndPromptErr, NotifyResultExt, NotifyTaskExt},
 };
-use worktree::CreatedEntry;
+use worktree::{CreatedEntry, Snapshot};
 use zed_actions::{
     project_panel::{Toggle, ToggleFocus},
     workspace::OpenWithSystem,
 };
 
 use crate::{
+    bulk_rename::{BulkRenameEntry, BulkRenameView},
+    file_nesting::FileNestingRules,
     project_panel_settings::ProjectPanelScrollbarProxy,
     undo::{Change, UndoManager},
 };
 
 const PROJECT_PANEL_KEY: &str = "ProjectPanel";
+const MAX_FILTER_MATCHES: usize = 10_000;
 const NEW_ENTRY_ID: ProjectEntryId = ProjectEntryId::MAX;
 
 struct VisibleEntriesForWorktree {
@@ -111,6 +118,21 @@ struct State {
     temporarily_unfolded_pending_state: Option<TemporaryUnfoldedPendingState>,
     unfolded_dir_ids: HashSet<ProjectEntryId>,
     expanded_dir_ids: HashMap<WorktreeId, Vec<ProjectEntryId>>,
+    /// Maps from a file to the related files nested under it, see [`FileNestingSettings`].
+    nests: HashMap<ProjectEntryId, FileNest>,
+    /// Maps from a nested file to the file it is nested under.
+    nest_parents: HashMap<ProjectEntryId, ProjectEntryId>,
+    expanded_nest_ids: HashSet<ProjectEntryId>,
+    /// The archive files whose contents are shown, see [`ProjectPanel::archives`].
+    expanded_archive_ids: HashSet<ProjectEntryId>,
+    /// Maps from an entry within an archive to the archive file it is shown under.
+    archive_parents: HashMap<ProjectEntryId, ProjectEntryId>,
+}
+
+struct FileNest {
+    children: Vec<ProjectEntryId>,
+    /// The combined git status of the nested files, shown on the parent while collapsed.
+    git_summary: GitSummary,
 }
 
 impl State {
@@ -131,8 +153,17 @@ impl State {
             temporarily_unfolded_pending_state: None,
             unfolded_dir_ids: old.unfolded_dir_ids.clone(),
             expanded_dir_ids: old.expanded_dir_ids.clone(),
+            nests: Default::default(),
+            nest_parents: Default::default(),
+            expanded_nest_ids: old.expanded_nest_ids.clone(),
+            expanded_archive_ids: old.expanded_archive_ids.clone(),
+            archive_parents: Default::default(),
         }
     }
+
+    fn is_collapsed_nest(&self, entry_id: ProjectEntryId) -> bool {
+        self.nests.contains_key(&entry_id) && !self.expanded_nest_ids.contains(&entry_id)
+    }
 }
 
 pub struct ProjectPanel {
@@ -165,9 +196,88 @@ pub struct ProjectPanel {
     last_reported_update: Instant,
     update_visible_entries_task: UpdateVisibleEntriesTask,
     undo_manager: UndoManager,
+    filter: Option<Filter>,
+    /// The archives browsed in the panel, by the entry of the archive file.
+    archives: HashMap<ProjectEntryId, ExpandedArchive>,
     state: State,
 }
 
+/// An archive unpacked into an invisible worktree, whose entries are shown
+/// under the archive file in the worktree containing it.
+struct ExpandedArchive {
+    worktree: Entity<Worktree>,
+    worktree_id: WorktreeId,
+    host_worktree_id: WorktreeId,
+}
+
+struct Filter {
+    editor: Entity<Editor>,
+    /// `None` while the query is empty, in which case no entries are filtered out.
+    matches: Option<Arc<FilterMatches>>,
+    /// The directories expanded before filtering, restored once the query is cleared.
+    previous_expanded_dir_ids: Option<HashMap<WorktreeId, Vec<ProjectEntryId>>>,
+    match_task: Task<()>,
+    _subscription: Subscription,
+}
+
+#[derive(Default)]
+struct FilterMatches {
+    /// Positions of the matched characters in the paths of the matching entries.
+    positions: HashMap<WorktreeId, HashMap<Arc<RelPath>, Vec<usize>>>,
+    /// The matching entries along with their ancestors, the only entries shown while filtering.
+    visible_paths: HashMap<WorktreeId, HashSet<Arc<RelPath>>>,
+    /// The directories containing matching entries, kept expanded while filtering.
+    ancestor_dir_ids: HashMap<WorktreeId, Vec<ProjectEntryId>>,
+    best_match: Option<(WorktreeId, ProjectEntryId)>,
+    match_count: usize,
+}
+
+impl FilterMatches {
+    fn new(
+        path_matches: Vec<fuzzy_nucleo::PathMatch>,
+        candidate_sets: &[PathMatchCandidateSet],
+    ) -> Self {
+        let mut matches = Self::default();
+        let mut ancestor_paths = HashMap::<WorktreeId, HashSet<Arc<RelPath>>>::default();
+        for path_match in path_matches {
+            let Some(snapshot) = candidate_sets
+                .iter()
+                .map(|candidate_set| &candidate_set.snapshot)
+                .find(|snapshot| snapshot.id().to_usize() == path_match.worktree_id)
+            else {
+                continue;
+            };
+            let worktree_id = snapshot.id();
+            if matches.best_match.is_none()
+                && let Some(entry) = snapshot.entry_for_path(&path_match.path)
+            {
+                matches.best_match = Some((worktree_id, entry.id));
+            }
+
+            let visible_paths = matches.visible_paths.entry(worktree_id).or_default();
+            let ancestor_paths = ancestor_paths.entry(worktree_id).or_default();
+            let ancestor_dir_ids = matches.ancestor_dir_ids.entry(worktree_id).or_default();
+            for ancestor in path_match.path.ancestors().skip(1) {
+                if !ancestor_paths.insert(ancestor.into()) {
+                    break;
+                }
+                visible_paths.insert(ancestor.into());
+                if let Some(entry) = snapshot.entry_for_path(ancestor) {
+                    ancestor_dir_ids.push(entry.id);
+                }
+            }
+            visible_paths.insert(path_match.path.clone());
+            matches
+                .positions
+                .entry(worktree_id)
+                .or_default()
+                .insert(path_match.path, path_match.positions);
+            matches.match_count += 1;
+        }
+        matches
+    }
+}
+
 struct UpdateVisibleEntriesTask {
     _visible_entries_task: Task<()>,
     focus_filename_editor: bool,
@@ -275,6 +385,8 @@ struct EntryDetails {
     kind: EntryKind,
     is_ignored: bool,
     is_expanded: bool,
+    is_nest_parent: bool,
+    is_archive: bool,
     is_selected: bool,
     is_marked: bool,
     is_editing: bool,
@@ -288,6 +400,8 @@ struct EntryDetails {
     is_private: bool,
     worktree_id: WorktreeId,
     canonical_path: Option<Arc<Path>>,
+    /// Positions of the characters in `filename` matched by the filter query.
+    filter_match_positions: Vec<usize>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -366,6 +480,8 @@ actions!(
         DownloadFromRemote,
         /// Renames the selected file or directory.
         Rename,
+        /// Opens the selected entries in a buffer to rename, move or delete them in bulk.
+        BulkRename,
         /// Opens the selected file in the editor.
         Open,
         /// Opens the selected file in a permanent tab.
@@ -412,6 +528,8 @@ actions!(
         Redo,
         /// Opens a markdown preview for the selected file.
         OpenMarkdownPreview,
+        /// Toggles filtering the project tree by a fuzzy query.
+        ToggleFilter,
     ]
 );
 
@@ -732,13 +850,23 @@ impl ProjectPanel {
                     }
                     project::Event::WorktreeRemoved(id) => {
                         this.state.expanded_dir_ids.remove(id);
+                        this.archives
+                            .retain(|_, archive| archive.worktree_id != *id);
                         this.update_visible_entries(None, false, false, window, cx);
                         cx.notify();
                     }
                     project::Event::WorktreeUpdatedEntries(_, _)
                     | project::Event::WorktreeAdded(_)
                     | project::Event::WorktreeOrderChanged => {
-                        this.update_visible_entries(None, false, false, window, cx);
+                        if this
+                            .filter
+                            .as_ref()
+                            .is_some_and(|filter| filter.matches.is_some())
+                        {
+                            this.update_filter_matches(window, cx);
+                        } else {
+                            this.update_visible_entries(None, false, false, window, cx);
+                        }
                         cx.notify();
                     }
                     project::Event::ExpandedAllForEntry(worktree_id, entry_id) => {
@@ -799,7 +927,13 @@ impl ProjectPanel {
             .detach();
 
             let mut project_panel_settings = *ProjectPanelSettings::get_global(cx);
+            let mut file_nesting_settings = FileNestingSettings::get_global(cx).clone();
             cx.observe_global_in::<SettingsStore>(window, move |this, window, cx| {
+                let new_file_nesting_settings = FileNestingSettings::get_global(cx);
+                if &file_nesting_settings != new_file_nesting_settings {
+                    file_nesting_settings = new_file_nesting_settings.clone();
+                    this.update_visible_entries(None, false, false, window, cx);
+                }
                 let new_settings = *ProjectPanelSettings::get_global(cx);
                 if project_panel_settings != new_settings {
                     if project_panel_settings.hide_gitignore != new_settings.hide_gitignore {
@@ -862,6 +996,11 @@ impl ProjectPanel {
                     ancestors: Default::default(),
                     expanded_dir_ids: Default::default(),
                     unfolded_dir_ids: Default::default(),
+                    nests: Default::default(),
+                    nest_parents: Default::default(),
+                    expanded_nest_ids: Default::default(),
+                    expanded_archive_ids: Default::default(),
+                    archive_parents: Default::default(),
                 },
                 update_visible_entries_task: Default::default(),
                 undo_manager: UndoManager::new(
@@ -870,6 +1009,8 @@ impl ProjectPanel {
                     project.read(cx).is_via_collab(),
                     &cx,
                 ),
+                filter: None,
+                archives: HashMap::default(),
             };
             this.update_visible_entries(None, false, false, window, cx);
 
@@ -924,6 +1065,10 @@ impl ProjectPanel {
                             if let Some(project_panel) = project_panel.upgrade() {
                                 // Always select and mark the entry, regardless of whether it is opened or not.
                                 project_panel.update(cx, |project_panel, _| {
+                                    // Entries within archives are selected in the worktree showing them.
+                                    let worktree_id = project_panel
+                                        .archive_host(worktree_id)
+                                        .map_or(worktree_id, |(host_worktree_id, _)| host_worktree_id);
                                     let entry = SelectedEntry { worktree_id, entry_id };
                                     project_panel.marked_entries.clear();
                                     project_panel.marked_entries.push(entry);
@@ -1077,7 +1222,7 @@ impl ProjectPanel {
             let is_dir = entry.is_dir();
             let is_foldable = auto_fold_dirs && self.is_foldable(entry, worktree);
             let is_unfoldable = auto_fold_dirs && self.is_unfoldable(entry, worktree);
-            let is_read_only = project.is_read_only(cx);
+            let is_read_only = project.is_read_only(cx) || self.is_archive_member(entry_id);
             let is_remote = project.is_remote();
             let is_collab = project.is_via_collab();
             let is_local = project.is_local() || project.is_via_wsl_with_host_interop(cx);
@@ -1196,6 +1341,9 @@ impl ProjectPanel {
                             .when(!should_hide_rename, |menu| {
                                 menu.separator().action("Rename", Box::new(Rename))
                             })
+                            .when(self.marked_entries.len() > 1, |menu| {
+                                menu.action("Bulk Rename…", Box::new(BulkRename))
+                            })
                             .when(!is_root && !is_collab, |menu| {
                                 menu.action("Trash", Box::new(Trash { skip_prompt: false }))
                             })
@@ -1288,6 +1436,24 @@ impl ProjectPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if let Some((worktree, entry)) = self.selected_archive_entry(cx) {
+            if entry.is_dir() {
+                let entry_id = entry.id;
+                let is_expanded = self
+                    .state
+                    .expanded_dir_ids
+                    .get(&worktree.read(cx).id())
+                    .is_some_and(|expanded_dir_ids| {
+                        expanded_dir_ids.binary_search(&entry_id).is_ok()
+                    });
+                if is_expanded {
+                    self.select_next(&SelectNext, window, cx);
+                } else {
+                    self.toggle_expanded(entry_id, window, cx);
+                }
+            }
+            return;
+        }
         if let Some((worktree, entry)) = self.selected_entry(cx) {
             if let Some(folded_ancestors) = self.state.ancestors.get_mut(&entry.id)
                 && folded_ancestors.current_ancestor_depth > 0
@@ -1319,6 +1485,19 @@ impl ProjectPanel {
                         cx.notify();
                     }
                 }
+            } else if self.state.nests.contains_key(&entry.id) {
+                if self.state.expanded_nest_ids.insert(entry.id) {
+                    self.update_visible_entries(None, false, false, window, cx);
+                    cx.notify();
+                } else {
+                    self.select_next(&SelectNext, window, cx);
+                }
+            } else if self.is_archive(entry, cx) {
+                if self.state.expanded_archive_ids.contains(&entry.id) {
+                    self.select_next(&SelectNext, window, cx);
+                } else {
+                    self.toggle_archive_expanded(entry.id, window, cx);
+                }
             }
         }
     }
@@ -1329,6 +1508,33 @@ impl ProjectPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if let Some((worktree, entry)) = self.selected_archive_entry(cx) {
+            let entry = entry.clone();
+            let Some(&archive_id) = self.state.archive_parents.get(&entry.id) else {
+                return;
+            };
+            // Collapse the innermost expanded directory containing the entry, and
+            // the archive itself once there are none left.
+            let snapshot = worktree.read(cx);
+            let expanded_dir_ids = self
+                .state
+                .expanded_dir_ids
+                .get(&snapshot.id())
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
+            let is_in_expanded_dir = entry
+                .path
+                .ancestors()
+                .filter(|path| !path.is_empty())
+                .filter_map(|path| snapshot.entry_for_path(path))
+                .any(|entry| expanded_dir_ids.binary_search(&entry.id).is_ok());
+            if is_in_expanded_dir {
+                self.collapse_entry(entry, worktree, window, cx);
+            } else {
+                self.toggle_archive_expanded(archive_id, window, cx);
+            }
+            return;
+        }
         let Some((worktree, entry)) = self.selected_entry_handle(cx) else {
             return;
         };
@@ -1342,6 +1548,10 @@ impl ProjectPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if self.state.expanded_archive_ids.contains(&entry.id) {
+            self.toggle_archive_expanded(entry.id, window, cx);
+            return;
+        }
         let worktree = worktree.read(cx);
         if let Some(folded_ancestors) = self.state.ancestors.get_mut(&entry.id)
             && folded_ancestors.current_ancestor_depth + 1 < folded_ancestors.max_ancestor_depth()
@@ -1351,6 +1561,19 @@ impl ProjectPanel {
             return;
         }
         let worktree_id = worktree.id();
+        // Collapsing a nested file collapses the file it is nested under.
+        let nest_id = if self.state.expanded_nest_ids.contains(&entry.id) {
+            Some(entry.id)
+        } else {
+            self.state.nest_parents.get(&entry.id).copied()
+        };
+        if let Some(nest_id) = nest_id
+            && self.state.expanded_nest_ids.remove(&nest_id)
+        {
+            self.update_visible_entries(Some((worktree_id, nest_id)), false, false, window, cx);
+            cx.notify();
+            return;
+        }
         let expanded_dir_ids =
             if let Some(expanded_dir_ids) = self.state.expanded_dir_ids.get_mut(&worktree_id) {
                 expanded_dir_ids
@@ -1467,6 +1690,7 @@ impl ProjectPanel {
         cx: &mut Context<Self>,
     ) {
         let roots = self.all_worktree_roots(cx);
+        self.state.expanded_nest_ids.clear();
         self.collapse_worktree_roots(roots, window, cx);
     }
 
@@ -1728,6 +1952,10 @@ impl ProjectPanel {
     }
 
     fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.is_filter_focused(window, cx) {
+            self.open_internal(false, true, None, window, cx);
+            return;
+        }
         if let Some(task) = self.confirm_edit(true, window, cx) {
             task.detach_and_notify_err(self.workspace.clone(), window, cx);
         }
@@ -1803,8 +2031,14 @@ impl ProjectPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if let Some((_, entry)) = self.selected_entry(cx) {
-            if entry.is_file() {
+        let selected_entry = self
+            .selected_archive_entry(cx)
+            .map(|(_, entry)| entry)
+            .or_else(|| Some(self.selected_entry(cx)?.1));
+        if let Some(entry) = selected_entry {
+            if self.is_archive(entry, cx) {
+                self.toggle_archive_expanded(entry.id, window, cx);
+            } else if entry.is_file() {
                 if split_direction.is_some() {
                     self.split_entry(entry.id, allow_preview, split_direction, cx);
                 } else {
@@ -2139,6 +2373,10 @@ impl ProjectPanel {
             self.clear_drag_state(cx);
             return;
         }
+        if self.is_filter_focused(window, cx) {
+            self.dismiss_filter(window, cx);
+            return;
+        }
         self.marked_entries.clear();
         cx.notify();
         self.discard_edit_state(window, cx);
@@ -2206,6 +2444,9 @@ impl ProjectPanel {
         else {
             return;
         };
+        if self.is_archive_member(entry_id) {
+            return;
+        }
 
         let directory_id;
         let new_entry_id = self.resolve_entry(entry_id);
@@ -2295,6 +2536,7 @@ impl ProjectPanel {
             worktree_id,
             entry_id,
         }) = self.selection
+            && !self.is_archive_member(entry_id)
             && let Some(worktree) = self.project.read(cx).worktree_for_id(worktree_id, cx)
         {
             let sub_entry_id = self.unflatten_entry_id(entry_id);
@@ -2566,8 +2808,13 @@ impl ProjectPanel {
         cx: &mut Context<ProjectPanel>,
     ) {
         maybe!({
-            let items_to_delete = self.disjoint_effective_entries_excluding_roots(cx);
-            if items_to_delete.is_empty() {
+            let items_to_delete = self
+                .with_collapsed_nested_files(self.disjoint_effective_entries_excluding_roots(cx));
+            if items_to_delete.is_empty()
+                || items_to_delete
+                    .iter()
+                    .any(|selection| self.is_archive_member(selection.entry_id))
+            {
                 return None;
             }
             let project = self.project.read(cx);
@@ -3289,8 +3536,13 @@ impl ProjectPanel {
     }
 
     fn cut(&mut self, _: &Cut, _: &mut Window, cx: &mut Context<Self>) {
-        let entries = self.disjoint_effective_entries_excluding_roots(cx);
-        if !entries.is_empty() {
+        let entries =
+            self.with_collapsed_nested_files(self.disjoint_effective_entries_excluding_roots(cx));
+        if !entries.is_empty()
+            && !entries
+                .iter()
+                .any(|selection| self.is_archive_member(selection.entry_id))
+        {
             self.write_entries_to_system_clipboard(&entries, cx);
             self.clipboard = Some(ClipboardEntry::Cut(entries));
             cx.notify();
@@ -3298,7 +3550,8 @@ impl ProjectPanel {
     }
 
     fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
-        let entries = self.disjoint_effective_entries_excluding_roots(cx);
+        let entries =
+            self.with_collapsed_nested_files(self.disjoint_effective_entries_excluding_roots(cx));
         if !entries.is_empty() {
             self.write_entries_to_system_clipboard(&entries, cx);
             self.clipboard = Some(ClipboardEntry::Copied(entries));
@@ -3371,6 +3624,12 @@ impl ProjectPanel {
     }
 
     fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
+        if self
+            .selection
+            .is_some_and(|selection| self.is_archive_member(selection.entry_id))
+        {
+            return;
+        }
         if let Some(external_paths) = self.external_paths_from_system_clipboard(cx) {
             let target_entry_id = self
                 .selection
@@ -3816,6 +4075,52 @@ impl ProjectPanel {
         }
     }
 
+    fn bulk_rename(&mut self, _: &BulkRename, window: &mut Window, cx: &mut Context<Self>) {
+        let project = self.project.read(cx);
+        let worktree_ids = project
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).id())
+            .collect::<Vec<_>>();
+        let mut entries = self
+            .disjoint_effective_entries_excluding_roots(cx)
+            .into_iter()
+            .filter_map(|selection| {
+                let worktree = project.worktree_for_id(selection.worktree_id, cx)?;
+                let entry = worktree.read(cx).entry_for_id(selection.entry_id)?.clone();
+                let worktree_ix = worktree_ids
+                    .iter()
+                    .position(|id| *id == selection.worktree_id)?;
+                Some((worktree_ix, entry))
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return;
+        }
+        let settings = ProjectPanelSettings::get_global(cx);
+        entries.sort_by(|(a_ix, a), (b_ix, b)| {
+            a_ix.cmp(b_ix)
+                .then_with(|| cmp_worktree_entries(a, b, &settings.sort_mode, &settings.sort_order))
+        });
+        let entries = entries
+            .into_iter()
+            .map(|(worktree_ix, entry)| BulkRenameEntry {
+                entry_id: entry.id,
+                worktree_id: worktree_ids[worktree_ix],
+                path: entry.path,
+            })
+            .collect();
+
+        let project = self.project.clone();
+        let project_panel = cx.weak_entity();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let bulk_rename =
+                    cx.new(|cx| BulkRenameView::new(entries, project, project_panel, window, cx));
+                workspace.add_item_to_active_pane(Box::new(bulk_rename), None, true, window, cx);
+            })
+            .ok();
+    }
+
     fn open_system(&mut self, _: &OpenWithSystem, _: &mut Window, cx: &mut Context<Self>) {
         if let Some((worktree, entry)) = self.selected_entry(cx) {
             let abs_path = worktree.absolutize(&entry.path);
@@ -4240,6 +4545,11 @@ impl ProjectPanel {
         let hide_gitignore = settings.hide_gitignore;
         let sort_mode = settings.sort_mode;
         let sort_order = settings.sort_order;
+        let file_nesting = FileNestingRules::new(FileNestingSettings::get_global(cx));
+        let filter_matches = self
+            .filter
+            .as_ref()
+            .and_then(|filter| filter.matches.clone());
         let project = self.project.read(cx);
         let repo_snapshots = project.git_store().read(cx).display_repo_snapshots(cx);
 
@@ -4259,6 +4569,16 @@ impl ProjectPanel {
             .collect();
         let hide_root = settings.hide_root && visible_worktrees.len() == 1;
         let hide_hidden = settings.hide_hidden;
+        // Archive contents aren't searched, so they are hidden while filtering.
+        let archive_snapshots = if filter_matches.is_none() {
+            self.archives
+                .iter()
+                .filter(|(entry_id, _)| self.state.expanded_archive_ids.contains(entry_id))
+                .map(|(entry_id, archive)| (*entry_id, archive.worktree.read(cx).snapshot()))
+                .collect::<HashMap<_, _>>()
+        } else {
+            HashMap::default()
+        };
 
         let visible_entries_task = cx.spawn_in(window, async move |this, cx| {
             let new_state = cx
@@ -4285,7 +4605,22 @@ impl ProjectPanel {
                             GitTraversal::new(&repo_snapshots, worktree_snapshot.entries(true, 0));
                         let mut auto_folded_ancestors = vec![];
                         let worktree_abs_path = worktree_snapshot.abs_path();
+                        let empty_paths = HashSet::default();
+                        let filtered_paths = filter_matches.as_ref().map(|matches| {
+                            matches
+                                .visible_paths
+                                .get(&worktree_id)
+                                .unwrap_or(&empty_paths)
+                        });
                         while let Some(entry) = entry_iter.entry() {
+                            if let Some(filtered_paths) = filtered_paths
+                                && !filtered_paths.contains(entry.path.as_ref())
+                            {
+                                if !entry_iter.advance_to_sibling() {
+                                    entry_iter.advance();
+                                }
+                                continue;
+                            }
                             if hide_root && Some(entry.entry) == worktree_snapshot.root_entry() {
                                 if new_entry_parent_id == Some(entry.id) {
                                     visible_worktree_entries.push(Self::create_new_git_entry(
@@ -4478,6 +4813,22 @@ impl ProjectPanel {
                             sort_mode,
                             sort_order,
                         );
+                        if let Some(file_nesting) = &file_nesting {
+                            Self::nest_files(
+                                &mut visible_worktree_entries,
+                                file_nesting,
+                                &mut new_state,
+                            );
+                        }
+                        if !archive_snapshots.is_empty() {
+                            Self::insert_archive_entries(
+                                &mut visible_worktree_entries,
+                                &archive_snapshots,
+                                &mut new_state,
+                                sort_mode,
+                                sort_order,
+                            );
+                        }
                         new_state.visible_entries.push(VisibleEntriesForWorktree {
                             worktree_id,
                             entries: visible_worktree_entries,
@@ -4510,6 +4861,9 @@ impl ProjectPanel {
             this.update_in(cx, |this, window, cx| {
                 this.state = new_state;
                 if let Some((worktree_id, entry_id)) = new_selected_entry {
+                    let worktree_id = this
+                        .archive_host(worktree_id)
+                        .map_or(worktree_id, |(host_worktree_id, _)| host_worktree_id);
                     this.selection = Some(SelectedEntry {
                         worktree_id,
                         entry_id,
@@ -4551,12 +4905,435 @@ impl ProjectPanel {
         };
     }
 
+    /// Moves the files nested under another file right after it, or hides
+    /// them if that file is collapsed.
+    fn nest_files(entries: &mut Vec<GitEntry>, file_nesting: &FileNestingRules, state: &mut State) {
+        let mut siblings: HashMap<&RelPath, Vec<usize>> = HashMap::default();
+        for (ix, entry) in entries.iter().enumerate() {
+            if entry.is_file()
+                && entry.id != NEW_ENTRY_ID
+                && let Some(parent) = entry.path.parent()
+            {
+                siblings.entry(parent).or_default().push(ix);
+            }
+        }
+
+        let mut nested_under = vec![None; entries.len()];
+        for file_ixs in siblings.values() {
+            let file_names = file_ixs
+                .iter()
+                .map(|ix| entries[*ix].path.file_name().unwrap_or_default())
+                .collect::<Vec<_>>();
+            for (file_ix, parent_ix) in file_ixs.iter().zip(file_nesting.nest(&file_names)) {
+                nested_under[*file_ix] = parent_ix.map(|parent_ix| file_ixs[parent_ix]);
+            }
+        }
+        if nested_under.iter().all(Option::is_none) {
+            return;
+        }
+
+        let mut nested_children: HashMap<usize, Vec<usize>> = HashMap::default();
+        for (ix, parent_ix) in nested_under.iter().enumerate() {
+            if let Some(parent_ix) = parent_ix {
+                nested_children.entry(*parent_ix).or_default().push(ix);
+            }
+        }
+
+        let mut unordered_entries = std::mem::take(entries)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        for ix in 0..unordered_entries.len() {
+            if nested_under[ix].is_some() {
+                continue;
+            }
+            let Some(entry) = unordered_entries[ix].take() else {
+                continue;
+            };
+            let parent_id = entry.id;
+            entries.push(entry);
+
+            let Some(child_ixs) = nested_children.get(&ix) else {
+                continue;
+            };
+            let is_expanded = state.expanded_nest_ids.contains(&parent_id);
+            let mut nest = FileNest {
+                children: Vec::with_capacity(child_ixs.len()),
+                git_summary: GitSummary::default(),
+            };
+            for child_ix in child_ixs {
+                let Some(child) = unordered_entries[*child_ix].take() else {
+                    continue;
+                };
+                nest.children.push(child.id);
+                nest.git_summary += child.git_summary;
+                state.nest_parents.insert(child.id, parent_id);
+                if is_expanded {
+                    entries.push(child);
+                }
+            }
+            state.nests.insert(parent_id, nest);
+        }
+    }
+
+    fn toggle_nest_expanded(
+        &mut self,
+        entry_id: ProjectEntryId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(worktree_id) = self.project.read(cx).worktree_id_for_entry(entry_id, cx) else {
+            return;
+        };
+        if !self.state.expanded_nest_ids.remove(&entry_id) {
+            self.state.expanded_nest_ids.insert(entry_id);
+        }
+        self.update_visible_entries(Some((worktree_id, entry_id)), false, false, window, cx);
+        window.focus(&self.focus_handle, cx);
+        cx.notify();
+    }
+
+    /// Adds the files nested under collapsed entries, so that they stay with
+    /// their parent when it is moved, copied, cut or removed.
+    fn with_collapsed_nested_files(
+        &self,
+        entries: BTreeSet<SelectedEntry>,
+    ) -> BTreeSet<SelectedEntry> {
+        let mut result = entries.clone();
+        for entry in entries {
+            if !self.state.is_collapsed_nest(entry.entry_id) {
+                continue;
+            }
+            if let Some(nest) = self.state.nests.get(&entry.entry_id) {
+                result.extend(nest.children.iter().map(|entry_id| SelectedEntry {
+                    worktree_id: entry.worktree_id,
+                    entry_id: *entry_id,
+                }));
+            }
+        }
+        result
+    }
+
+    /// Inserts the entries of the expanded archives right after the archive
+    /// files, with their paths rebased onto the path of the archive.
+    fn insert_archive_entries(
+        entries: &mut Vec<GitEntry>,
+        archive_snapshots: &HashMap<ProjectEntryId, Snapshot>,
+        state: &mut State,
+        sort_mode: settings::ProjectPanelSortMode,
+        sort_order: settings::ProjectPanelSortOrder,
+    ) {
+        let mut ix = 0;
+        while ix < entries.len() {
+            let archive_entry = &entries[ix];
+            ix += 1;
+            let Some(snapshot) = archive_snapshots.get(&archive_entry.id) else {
+                continue;
+            };
+            let archive_id = archive_entry.id;
+            let archive_path = archive_entry.path.clone();
+            let expanded_dir_ids = state
+                .expanded_dir_ids
+                .get(&snapshot.id())
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
+
+            let mut archive_entries = Vec::new();
+            let mut entry_iter = snapshot.entries(true, 0);
+            while let Some(entry) = entry_iter.entry() {
+                // The root of the archive is the archive file itself.
+                if entry.path.is_empty() {
+                    entry_iter.advance();
+                    continue;
+                }
+                let mut member = entry.clone();
+                member.path = archive_path.join(&entry.path).into();
+                archive_entries.push(GitEntry {
+                    entry: member,
+                    git_summary: GitSummary::default(),
+                });
+                if entry.is_dir()
+                    && expanded_dir_ids.binary_search(&entry.id).is_err()
+                    && entry_iter.advance_to_sibling()
+                {
+                    continue;
+                }
+                entry_iter.advance();
+            }
+
+            par_sort_worktree_entries(&mut archive_entries, sort_mode, sort_order);
+            state
+                .archive_parents
+                .extend(archive_entries.iter().map(|member| (member.id, archive_id)));
+            let archive_entries_len = archive_entries.len();
+            entries.splice(ix..ix, archive_entries);
+            ix += archive_entries_len;
+        }
+    }
+
+    /// Whether the entry is shown within an archive, whose contents are read-only.
+    fn is_archive_member(&self, entry_id: ProjectEntryId) -> bool {
+        self.state.archive_parents.contains_key(&entry_id)
+    }
+
+    fn is_archive(&self, entry: &Entry, cx: &App) -> bool {
+        entry.is_file()
+            && !self.state.archive_parents.contains_key(&entry.id)
+            && !self.state.nests.contains_key(&entry.id)
+            && self.project.read(cx).is_local()
+            && ArchiveKind::for_path(entry.path.as_std_path()).is_some()
+    }
+
+    /// Returns the worktree showing the given archive worktree, along with the
+    /// entry of the archive file.
+    fn archive_host(&self, worktree_id: WorktreeId) -> Option<(WorktreeId, ProjectEntryId)> {
+        self.archives
+            .iter()
+            .find(|(_, archive)| archive.worktree_id == worktree_id)
+            .map(|(entry_id, archive)| (archive.host_worktree_id, *entry_id))
+    }
+
+    /// Returns the selected entry if it is shown within an archive, along with
+    /// the worktree the archive was unpacked into.
+    fn selected_archive_entry<'a>(
+        &self,
+        cx: &'a App,
+    ) -> Option<(Entity<Worktree>, &'a project::Entry)> {
+        let entry_id = self.selection?.entry_id;
+        let archive_id = self.state.archive_parents.get(&entry_id)?;
+        let worktree = self.archives.get(archive_id)?.worktree.clone();
+        let entry = worktree.read(cx).entry_for_id(entry_id)?;
+        Some((worktree, entry))
+    }
+
+    fn toggle_archive_expanded(
+        &mut self,
+        entry_id: ProjectEntryId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(worktree) = self.project.read(cx).worktree_for_entry(entry_id, cx) else {
+            return;
+        };
+        let worktree_id = worktree.read(cx).id();
+        if self.archives.contains_key(&entry_id) {
+            if !self.state.expanded_archive_ids.remove(&entry_id) {
+                self.state.expanded_archive_ids.insert(entry_id);
+            }
+            self.update_visible_entries(Some((worktree_id, entry_id)), false, false, window, cx);
+            window.focus(&self.focus_handle, cx);
+            cx.notify();
+            return;
+        }
+
+        let Some(abs_path) = worktree
+            .read(cx)
+            .entry_for_id(entry_id)
+            .map(|entry| worktree.read(cx).absolutize(&entry.path))
+        else {
+            return;
+        };
+        let open_archive = self
+            .project
+            .update(cx, |project, cx| project.open_archive(abs_path.into(), cx));
+        cx.spawn_in(window, async move |this, cx| {
+            let archive_worktree = open_archive.await?;
+            this.update_in(cx, |this, window, cx| {
+                let archive_worktree_id = archive_worktree.read(cx).id();
+                this.state
+                    .expanded_dir_ids
+                    .entry(archive_worktree_id)
+                    .or_default();
+                this.archives.insert(
+                    entry_id,
+                    ExpandedArchive {
+                        worktree: archive_worktree,
+                        worktree_id: archive_worktree_id,
+                        host_worktree_id: worktree_id,
+                    },
+                );
+                this.state.expanded_archive_ids.insert(entry_id);
+                this.update_visible_entries(
+                    Some((worktree_id, entry_id)),
+                    false,
+                    false,
+                    window,
+                    cx,
+                );
+                cx.notify();
+            })
+        })
+        .detach_and_prompt_err("Failed to open archive", window, cx, |_, _, _| None);
+    }
+
+    fn toggle_filter(&mut self, _: &ToggleFilter, window: &mut Window, cx: &mut Context<Self>) {
+        if self.filter.is_some() {
+            self.dismiss_filter(window, cx);
+            return;
+        }
+
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter files…", window, cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(&editor, window, |this, _, event, window, cx| {
+            if let EditorEvent::BufferEdited = event {
+                this.update_filter_matches(window, cx);
+            }
+        });
+        window.focus(&editor.focus_handle(cx), cx);
+        self.filter = Some(Filter {
+            editor,
+            matches: None,
+            previous_expanded_dir_ids: None,
+            match_task: Task::ready(()),
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    fn dismiss_filter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.clear_filter_matches(window, cx);
+        self.filter = None;
+        window.focus(&self.focus_handle, cx);
+        cx.notify();
+    }
+
+    fn update_filter_matches(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(filter) = self.filter.as_mut() else {
+            return;
+        };
+        let query = filter.editor.read(cx).text(cx);
+        if query.trim().is_empty() {
+            self.clear_filter_matches(window, cx);
+            return;
+        }
+        if filter.previous_expanded_dir_ids.is_none() {
+            filter.previous_expanded_dir_ids = Some(self.state.expanded_dir_ids.clone());
+        }
+
+        let include_ignored = !ProjectPanelSettings::get_global(cx).hide_gitignore;
+        let candidate_sets = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| PathMatchCandidateSet {
+                snapshot: worktree.read(cx).snapshot(),
+                include_ignored,
+                include_root_name: false,
+                candidates: Candidates::Entries,
+            })
+            .collect::<Vec<_>>();
+        let executor = cx.background_executor().clone();
+        filter.match_task = cx.spawn_in(window, async move |this, cx| {
+            let matches = cx
+                .background_spawn(async move {
+                    let path_matches = fuzzy_nucleo::match_path_sets(
+                        &candidate_sets,
+                        &query,
+                        &None,
+                        fuzzy_nucleo::Case::smart_if_uppercase_in(&query),
+                        MAX_FILTER_MATCHES,
+                        &AtomicBool::new(false),
+                        executor,
+                    )
+                    .await;
+                    FilterMatches::new(path_matches, &candidate_sets)
+                })
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                let Some(filter) = this.filter.as_mut() else {
+                    return;
+                };
+                let mut expanded_dir_ids = filter
+                    .previous_expanded_dir_ids
+                    .clone()
+                    .unwrap_or_else(|| this.state.expanded_dir_ids.clone());
+                for (worktree_id, ancestor_dir_ids) in &matches.ancestor_dir_ids {
+                    let expanded_dir_ids = expanded_dir_ids.entry(*worktree_id).or_default();
+                    expanded_dir_ids.extend(ancestor_dir_ids);
+                    expanded_dir_ids.sort_unstable();
+                    expanded_dir_ids.dedup();
+                }
+                this.state.expanded_dir_ids = expanded_dir_ids;
+                let best_match = matches.best_match;
+                filter.matches = Some(Arc::new(matches));
+                this.update_visible_entries(best_match, false, true, window, cx);
+            })
+            .ok();
+        });
+    }
+
+    /// Shows all entries again, restoring the directories expanded before
+    /// filtering while keeping the selected entry in view.
+    fn clear_filter_matches(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(filter) = self.filter.as_mut() else {
+            return;
+        };
+        filter.match_task = Task::ready(());
+        filter.matches = None;
+        let Some(expanded_dir_ids) = filter.previous_expanded_dir_ids.take() else {
+            return;
+        };
+        self.state.expanded_dir_ids = expanded_dir_ids;
+        if let Some(selection) = self.selection {
+            self.expand_entry(selection.worktree_id, selection.entry_id, cx);
+        }
+        self.update_visible_entries(None, false, true, window, cx);
+    }
+
+    fn render_filter_bar(&self, cx: &Context<Self>) -> Option<AnyElement> {
+        let filter = self.filter.as_ref()?;
+        let match_count = filter.matches.as_ref().map(|matches| matches.match_count);
+        Some(
+            h_flex()
+                .id("project-panel-filter")
+                .flex_none()
+                .h_8()
+                .pl_2()
+                .pr_1()
+                .gap_1()
+                .border_b_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Icon::new(IconName::MagnifyingGlass)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(div().flex_1().child(filter.editor.clone()))
+                .when_some(match_count, |this, match_count| {
+                    this.child(
+                        Label::new(match match_count {
+                            0 => "No matches".to_string(),
+                            1 => "1 match".to_string(),
+                            count => format!("{count} matches"),
+                        })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                })
+                .child(
+                    IconButton::new("dismiss-project-panel-filter", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Clear Filter"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.dismiss_filter(window, cx);
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+
     fn expand_entry(
         &mut self,
         worktree_id: WorktreeId,
         entry_id: ProjectEntryId,
         cx: &mut Context<Self>,
     ) {
+        if let Some(parent_id) = self.state.nest_parents.get(&entry_id) {
+            self.state.expanded_nest_ids.insert(*parent_id);
+        }
         self.project.update(cx, |project, cx| {
             if let Some((worktree, expanded_dir_ids)) = project
                 .worktree_for_id(worktree_id, cx)
@@ -4795,6 +5572,14 @@ impl ProjectPanel {
                 worktree_id: entry.worktree_id,
             })
             .collect::<BTreeSet<SelectedEntry>>();
+        if self.is_archive_member(target_entry_id)
+            || resolved_selections
+                .iter()
+                .any(|selection| self.is_archive_member(selection.entry_id))
+        {
+            return;
+        }
+        let resolved_selections = self.with_collapsed_nested_files(resolved_selections);
         let entries = self.disjoint_entries(resolved_selections, cx);
 
         let root_entries: Vec<ProjectEntryId> = {
@@ -5168,9 +5953,15 @@ impl ProjectPanel {
                     .index
                     .get_or_init(|| visible.entries.iter().map(|e| e.path.clone()).collect());
                 for entry in visible.entries[entry_range].iter() {
-                    let status = git_status_setting
+                    let mut status = git_status_setting
                         .then_some(entry.git_summary)
                         .unwrap_or_default();
+                    if git_status_setting
+                        && self.state.is_collapsed_nest(entry.id)
+                        && let Some(nest) = self.state.nests.get(&entry.id)
+                    {
+                        status += nest.git_summary;
+                    }
 
                     let mut details = self.details_for_entry(
                         entry,
@@ -5634,6 +6425,7 @@ impl ProjectPanel {
 
         let kind = details.kind;
         let is_sticky = details.sticky.is_some();
+        let is_archive_member = self.is_archive_member(entry_id);
         let sticky_index = details.sticky.as_ref().map(|this| this.sticky_index);
         let settings = ProjectPanelSettings::get_global(cx);
         let show_editor = details.is_editing && !details.is_processing;
@@ -5669,6 +6461,9 @@ impl ProjectPanel {
 
         let depth = details.depth;
         let worktree_id = details.worktree_id;
+        let nest_toggle = details.is_nest_parent.then_some(details.is_expanded);
+        let archive_toggle = details.is_archive.then_some(details.is_expanded);
+        let filter_match_positions = details.filter_match_positions;
 
         let bg_color = if is_marked {
             item_colors.marked
@@ -5775,7 +6570,7 @@ impl ProjectPanel {
                             .bg(item_colors.drag_over)
                     },
                 )
-                .when(settings.drag_and_drop, |this| {
+                .when(settings.drag_and_drop && !is_archive_member, |this| {
                     let path_for_external_paths = path.clone();
                     let path_for_dragged_selection = path.clone();
                     let dragged_selection = DraggedSelection {
@@ -6085,6 +6880,9 @@ impl ProjectPanel {
                         } else {
                             project_panel.toggle_expanded(entry_id, window, cx);
                         }
+                    } else if archive_toggle.is_some() {
+                        project_panel.marked_entries.clear();
+                        project_panel.toggle_archive_expanded(entry_id, window, cx);
                     } else {
                         let preview_tabs_enabled =
                             PreviewTabsSettings::get_global(cx).enable_preview_from_project_panel;
@@ -6109,6 +6907,22 @@ impl ProjectPanel {
                 ListItem::new(id)
                     .indent_level(depth)
                     .indent_step_size(px(settings.indent_size))
+                    .when_some(nest_toggle, |this, is_expanded| {
+                        this.toggle(is_expanded)
+                            .always_show_disclosure_icon(true)
+                            .on_toggle(cx.listener(move |this, _, window, cx| {
+                                cx.stop_propagation();
+                                this.toggle_nest_expanded(entry_id, window, cx);
+                            }))
+                    })
+                    .when_some(archive_toggle, |this, is_expanded| {
+                        this.toggle(is_expanded)
+                            .always_show_disclosure_icon(true)
+                            .on_toggle(cx.listener(move |this, _, window, cx| {
+                                cx.stop_propagation();
+                                this.toggle_archive_expanded(entry_id, window, cx);
+                            }))
+                    })
                     .spacing(match settings.entry_spacing {
                         ProjectPanelEntrySpacing::Comfortable => ListItemSpacing::Dense,
                         ProjectPanelEntrySpacing::Standard => ListItemSpacing::ExtraDense,
@@ -6253,6 +7067,16 @@ impl ProjectPanel {
                                     ))
                                 }
 
+                                None if !filter_match_positions.is_empty() => this.child(
+                                    HighlightedLabel::new(file_name, filter_match_positions)
+                                        .single_line()
+                                        .color(filename_text_color)
+                                        .when(
+                                            settings.bold_folder_labels && kind.is_dir(),
+                                            |this| this.weight(FontWeight::SEMIBOLD),
+                                        )
+                                        .into_any_element(),
+                                ),
                                 None => this.child(
                                     Label::new(file_name)
                                         .single_line()
@@ -6528,13 +7352,28 @@ impl ProjectPanel {
             (settings.file_icons, settings.folder_icons)
         };
 
+        // Entries within archives are expanded in the worktree the archive was unpacked into.
+        let expanded_dirs_worktree_id = self
+            .state
+            .archive_parents
+            .get(&entry.id)
+            .and_then(|archive_id| self.archives.get(archive_id))
+            .map_or(worktree_id, |archive| archive.worktree_id);
         let expanded_entry_ids = self
             .state
             .expanded_dir_ids
-            .get(&worktree_id)
+            .get(&expanded_dirs_worktree_id)
             .map(Vec::as_slice)
             .unwrap_or(&[]);
-        let is_expanded = expanded_entry_ids.binary_search(&entry.id).is_ok();
+        let is_nest_parent = self.state.nests.contains_key(&entry.id);
+        let is_archive = self.is_archive(entry, cx);
+        let is_expanded = if is_nest_parent {
+            self.state.expanded_nest_ids.contains(&entry.id)
+        } else if is_archive {
+            self.state.expanded_archive_ids.contains(&entry.id)
+        } else {
+            expanded_entry_ids.binary_search(&entry.id).is_ok()
+        };
 
         let icon = match entry.kind {
             EntryKind::File => {
@@ -6556,6 +7395,7 @@ impl ProjectPanel {
         let path_style = self.project.read(cx).path_style(cx);
         let (depth, difference) =
             ProjectPanel::calculate_depth_and_difference(entry, entries_paths);
+        let depth = depth + usize::from(self.state.nest_parents.contains_key(&entry.id));
 
         let filename = if difference > 1 {
             entry
@@ -6597,6 +7437,29 @@ impl ProjectPanel {
             .as_ref()
             .is_some_and(|e| e.is_cut() && e.items().contains(&selection));
 
+        let filter_match_positions = self
+            .filter
+            .as_ref()
+            .and_then(|filter| filter.matches.as_ref())
+            .and_then(|matches| {
+                matches
+                    .positions
+                    .get(&worktree_id)?
+                    .get(entry.path.as_ref())
+            })
+            .and_then(|positions| {
+                // The positions are within the entry's path, of which the file name is a suffix.
+                let path = entry.path.as_unix_str();
+                let offset = path.strip_suffix(filename.as_str())?.len();
+                Some(
+                    positions
+                        .iter()
+                        .filter_map(|position| position.checked_sub(offset))
+                        .collect(),
+                )
+            })
+            .unwrap_or_default();
+
         EntryDetails {
             filename,
             icon,
@@ -6605,6 +7468,8 @@ impl ProjectPanel {
             kind: entry.kind,
             is_ignored: entry.is_ignored,
             is_expanded,
+            is_nest_parent,
+            is_archive,
             is_selected,
             is_marked,
             is_editing: false,
@@ -6618,6 +7483,7 @@ impl ProjectPanel {
             is_private: entry.is_private,
             worktree_id,
             canonical_path: entry.canonical_path.clone(),
+            filter_match_positions,
         }
     }
 
@@ -6626,16 +7492,27 @@ impl ProjectPanel {
         dispatch_context.add("ProjectPanel");
         dispatch_context.add("menu");
 
-        let identifier = if self.filename_editor.focus_handle(cx).is_focused(window) {
+        let is_filtering = self.is_filter_focused(window, cx);
+        let identifier = if is_filtering || self.filename_editor.focus_handle(cx).is_focused(window)
+        {
             "editing"
         } else {
             "not_editing"
         };
 
         dispatch_context.add(identifier);
+        if is_filtering {
+            dispatch_context.add("filtering");
+        }
         dispatch_context
     }
 
+    fn is_filter_focused(&self, window: &Window, cx: &App) -> bool {
+        self.filter
+            .as_ref()
+            .is_some_and(|filter| filter.editor.focus_handle(cx).is_focused(window))
+    }
+
     fn reveal_entry(
         &mut self,
         project: Entity<Project>,
@@ -6653,18 +7530,25 @@ impl ProjectPanel {
         let is_ignored = worktree
             .entry_for_id(entry_id)
             .is_none_or(|entry| entry.is_ignored && !entry.is_always_included);
+        // Entries within archives are shown in the worktree containing the archive.
+        let archive_host = self.archive_host(worktree_id);
+        let selected_worktree_id =
+            archive_host.map_or(worktree_id, |(host_worktree_id, _)| host_worktree_id);
         if skip_ignored && is_ignored {
-            if self.index_for_entry(entry_id, worktree_id).is_none() {
+            if self
+                .index_for_entry(entry_id, selected_worktree_id)
+                .is_none()
+            {
                 anyhow::bail!("can't reveal an ignored entry in the project panel");
             }
 
             self.selection = Some(SelectedEntry {
-                worktree_id,
+                worktree_id: selected_worktree_id,
                 entry_id,
             });
             self.marked_entries.clear();
             self.marked_entries.push(SelectedEntry {
-                worktree_id,
+                worktree_id: selected_worktree_id,
                 entry_id,
             });
             self.autoscroll(cx);
@@ -6681,11 +7565,15 @@ impl ProjectPanel {
             return Ok(());
         }
 
+        if let Some((host_worktree_id, archive_id)) = archive_host {
+            self.state.expanded_archive_ids.insert(archive_id);
+            self.expand_entry(host_worktree_id, archive_id, cx);
+        }
         self.expand_entry(worktree_id, entry_id, cx);
         self.update_visible_entries(Some((worktree_id, entry_id)), false, true, window, cx);
         self.marked_entries.clear();
         self.marked_entries.push(SelectedEntry {
-            worktree_id,
+            worktree_id: selected_worktree_id,
             entry_id,
         });
         cx.notify();
@@ -6935,6 +7823,7 @@ impl Render for ProjectPanel {
         let supports_undo = cx.has_flag::<ProjectPanelUndoRedoFeatureFlag>();
 
         if has_worktree {
+            let filter_bar = self.render_filter_bar(cx);
             let item_count = self
                 .state
                 .visible_entries
@@ -7064,10 +7953,12 @@ impl Render for ProjectPanel {
                 .on_action(cx.listener(Self::fold_directory))
                 .on_action(cx.listener(Self::remove_from_project))
                 .on_action(cx.listener(Self::compare_marked_files))
+                .on_action(cx.listener(Self::toggle_filter))
                 .when(!project.is_read_only(cx), |el| {
                     el.on_action(cx.listener(Self::new_file))
                         .on_action(cx.listener(Self::new_directory))
                         .on_action(cx.listener(Self::rename))
+                        .on_action(cx.listener(Self::bulk_rename))
                         .on_action(cx.listener(Self::delete))
                         .on_action(cx.listener(Self::cut))
                         .on_action(cx.listener(Self::copy))
@@ -7097,6 +7988,7 @@ impl Render for ProjectPanel {
                 .track_focus(&self.focus_handle(cx))
                 .child(
                     v_flex()
+                        .children(filter_bar)
                         .child(
                             uniform_list("entries", item_count, {
                                 cx.processor(|this, range: Range<usize>, window, cx| {
@@ -7132,6 +8024,7 @@ impl Render for ProjectPanel {
                                         |this, range, window, cx| {
                                             let mut items =
                                                 SmallVec::with_capacity(range.end - range.start);
+                                            let nest_parents = &this.state.nest_parents;
                                             this.iter_visible_entries(
                                                 range,
                                                 window,
@@ -7141,7 +8034,9 @@ impl Render for ProjectPanel {
                                                         Self::calculate_depth_and_difference(
                                                             entry, entries,
                                                         );
-                                                    items.push(depth);
+                                                    let is_nested =
+                                                        nest_parents.contains_key(&entry.id);
+                                                    items.push(depth + usize::from(is_nested));
                                                 },
                                             );
                                             items
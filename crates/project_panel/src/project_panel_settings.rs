@@ -1,3 +1,4 @@
+use collections::HashMap;
 use editor::{EditorSettings, ui_scrollbar_settings_from_raw};
 use gpui::Pixels;
 use schemars::JsonSchema;
@@ -40,6 +41,14 @@ pub struct ProjectPanelSettings {
     pub git_status_indicator: bool,
 }
 
+/// Rules for nesting related files under a parent file, kept apart from
+/// [`ProjectPanelSettings`] so that those stay `Copy`.
+#[derive(Clone, Debug, PartialEq, RegisterSetting)]
+pub struct FileNestingSettings {
+    pub enabled: bool,
+    pub patterns: HashMap<String, Vec<String>>,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct IndentGuidesSettings {
     pub show: ShowIndentGuides,
@@ -148,3 +157,19 @@ impl Settings for ProjectPanelSettings {
         }
     }
 }
+
+impl Settings for FileNestingSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let file_nesting = content
+            .project_panel
+            .as_ref()
+            .unwrap()
+            .file_nesting
+            .clone()
+            .unwrap();
+        Self {
+            enabled: file_nesting.enabled.unwrap(),
+            patterns: file_nesting.patterns.unwrap(),
+        }
+    }
+}
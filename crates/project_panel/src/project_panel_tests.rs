@@ -17,7 +17,7 @@ use std::path::{Path, PathBuf};
 use util::{path, paths::PathStyle, rel_path::rel_path};
 use workspace::{
     AppState, ItemHandle, MultiWorkspace, Pane, Workspace,
-    item::{Item, ProjectItem, test::TestItem},
+    item::{Item, ProjectItem, SaveOptions, test::TestItem},
     register_project_item,
 };
 
@@ -7757,6 +7757,717 @@ async fn test_selection_fallback_to_next_highest_worktree(cx: &mut gpui::TestApp
     );
 }
 
+#[gpui::test]
+async fn test_file_nesting(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings
+                    .project_panel
+                    .get_or_insert_default()
+                    .file_nesting
+                    .get_or_insert_default()
+                    .enabled = Some(true);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "src": {
+                "main.ts": "",
+                "main.js": "",
+                "main.js.map": "",
+                "main.d.ts": "",
+                "util.ts": "",
+            },
+            "Cargo.toml": "",
+            "Cargo.lock": "",
+            "README.md": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+    let window = cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+    let workspace = window
+        .read_with(cx, |mw, _| mw.workspace().clone())
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(window.into(), cx);
+    let panel = workspace.update_in(cx, ProjectPanel::new);
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &["v root", "    > src", "    > Cargo.toml", "      README.md",]
+    );
+
+    toggle_expand_nest(&panel, "root/Cargo.toml", cx);
+    toggle_expand_dir(&panel, "root/src", cx);
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    v src  <== selected",
+            "        > main.ts",
+            "          util.ts",
+            "    v Cargo.toml",
+            "          Cargo.lock",
+            "      README.md",
+        ]
+    );
+
+    // Files nested under a file that is nested itself end up under the outermost one.
+    toggle_expand_nest(&panel, "root/src/main.ts", cx);
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    v src",
+            "        v main.ts  <== selected",
+            "              main.js",
+            "              main.d.ts",
+            "              main.js.map",
+            "          util.ts",
+            "    v Cargo.toml",
+            "          Cargo.lock",
+            "      README.md",
+        ]
+    );
+
+    // Collapsing a nested file collapses and selects the file it is nested under.
+    select_path(&panel, "root/src/main.js", cx);
+    panel.update_in(cx, |panel, window, cx| {
+        panel.collapse_selected_entry(&CollapseSelectedEntry, window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    v src",
+            "        > main.ts  <== selected",
+            "          util.ts",
+            "    v Cargo.toml",
+            "          Cargo.lock",
+            "      README.md",
+        ]
+    );
+
+    // Revealing a nested file expands the file it is nested under.
+    let main_js = find_project_entry(&panel, "root/src/main.js", cx).unwrap();
+    panel.update_in(cx, |panel, window, cx| {
+        panel
+            .reveal_entry(project.clone(), main_js, false, window, cx)
+            .unwrap();
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    v src",
+            "        v main.ts",
+            "              main.js  <== selected  <== marked",
+            "              main.d.ts",
+            "              main.js.map",
+            "          util.ts",
+            "    v Cargo.toml",
+            "          Cargo.lock",
+            "      README.md",
+        ]
+    );
+
+    cx.update(|_, cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings
+                    .project_panel
+                    .get_or_insert_default()
+                    .file_nesting
+                    .get_or_insert_default()
+                    .enabled = Some(false);
+            });
+        });
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    v src",
+            "          main.js  <== selected  <== marked",
+            "          main.ts",
+            "          main.d.ts",
+            "          main.js.map",
+            "          util.ts",
+            "      Cargo.lock",
+            "      Cargo.toml",
+            "      README.md",
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_filter(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "docs": {
+                "readme.md": "",
+            },
+            "src": {
+                "main.rs": "",
+                "lib.rs": "",
+                "utils": {
+                    "helpers.rs": "",
+                },
+            },
+            "Cargo.toml": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+    let window = cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+    let workspace = window
+        .read_with(cx, |mw, _| mw.workspace().clone())
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(window.into(), cx);
+    let panel = workspace.update_in(cx, ProjectPanel::new);
+    cx.run_until_parked();
+    let unfiltered_entries = &["v root", "    > docs", "    > src", "      Cargo.toml"];
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        unfiltered_entries
+    );
+
+    panel.update_in(cx, |panel, window, cx| {
+        panel.toggle_filter(&ToggleFilter, window, cx);
+    });
+    set_filter_query(&panel, "zzz", cx);
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        Vec::<String>::new()
+    );
+
+    // Clearing the query restores the directories expanded before filtering.
+    set_filter_query(&panel, "", cx);
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        unfiltered_entries
+    );
+
+    // Ancestors of the matches are expanded, and the best match is selected.
+    set_filter_query(&panel, "help", cx);
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    v src",
+            "        v utils",
+            "              helpers.rs  <== selected",
+        ]
+    );
+    panel.update_in(cx, |panel, window, cx| {
+        let mut match_positions = Vec::new();
+        panel.for_each_visible_entry(0..50, window, cx, &mut |_, details, _, _| {
+            match_positions.push((details.filename, details.filter_match_positions));
+        });
+        assert_eq!(
+            match_positions,
+            &[
+                ("root".to_string(), vec![]),
+                ("src".to_string(), vec![]),
+                ("utils".to_string(), vec![]),
+                ("helpers.rs".to_string(), vec![0, 1, 2, 3]),
+            ]
+        );
+    });
+
+    // Dismissing the filter keeps the selected entry in view.
+    panel.update_in(cx, |panel, window, cx| {
+        panel.dismiss_filter(window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    > docs",
+            "    v src",
+            "        v utils",
+            "              helpers.rs  <== selected",
+            "          lib.rs",
+            "          main.rs",
+            "      Cargo.toml",
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_bulk_rename(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "a.txt": "",
+            "b.txt": "",
+            "c.txt": "",
+            "dir": {},
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+    let window = cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+    let workspace = window
+        .read_with(cx, |mw, _| mw.workspace().clone())
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(window.into(), cx);
+    let panel = workspace.update_in(cx, ProjectPanel::new);
+    cx.run_until_parked();
+
+    select_path_with_mark(&panel, "root/c.txt", cx);
+    select_path_with_mark(&panel, "root/a.txt", cx);
+    select_path_with_mark(&panel, "root/b.txt", cx);
+    panel.update_in(cx, |panel, window, cx| {
+        panel.bulk_rename(&BulkRename, window, cx);
+    });
+    cx.run_until_parked();
+
+    let (bulk_rename, editor) = workspace.update_in(cx, |workspace, _, cx| {
+        let item = workspace.active_item(cx).unwrap();
+        (
+            item.downcast::<BulkRenameView>()
+                .expect("Active item should be the bulk rename view"),
+            item.act_as::<Editor>(cx).unwrap(),
+        )
+    });
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.text(cx)),
+        "a.txt\nb.txt\nc.txt\n"
+    );
+
+    // Lines are matched to entries by position, so removing one is rejected.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.set_text("a.txt\nc.txt\n", window, cx)
+    });
+    let save = bulk_rename.update_in(cx, |bulk_rename, window, cx| {
+        bulk_rename.save(SaveOptions::default(), project.clone(), window, cx)
+    });
+    assert!(save.await.is_err());
+    assert!(!cx.has_pending_prompt());
+
+    // Clearing a line trashes its entry, and the other changes are applied after
+    // confirming the preview.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.set_text("renamed.txt\n\ndir/c.txt\n", window, cx)
+    });
+    let save = bulk_rename.update_in(cx, |bulk_rename, window, cx| {
+        bulk_rename.save(SaveOptions::default(), project.clone(), window, cx)
+    });
+    assert!(cx.has_pending_prompt());
+    cx.simulate_prompt_answer("Apply");
+    save.await.unwrap();
+    cx.run_until_parked();
+
+    assert!(fs.is_file(Path::new("/root/renamed.txt")).await);
+    assert!(fs.is_file(Path::new("/root/dir/c.txt")).await);
+    assert!(!fs.is_file(Path::new("/root/a.txt")).await);
+    assert!(!fs.is_file(Path::new("/root/b.txt")).await);
+    assert!(!fs.is_file(Path::new("/root/c.txt")).await);
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.text(cx)),
+        "renamed.txt\ndir/c.txt\n"
+    );
+    assert!(!bulk_rename.read_with(cx, |bulk_rename, cx| bulk_rename.is_dirty(cx)));
+
+    // The whole bulk rename is undone at once.
+    panel.update_in(cx, |panel, window, cx| {
+        panel.undo(&Undo, window, cx);
+    });
+    cx.run_until_parked();
+    assert!(fs.is_file(Path::new("/root/a.txt")).await);
+    assert!(fs.is_file(Path::new("/root/b.txt")).await);
+    assert!(fs.is_file(Path::new("/root/c.txt")).await);
+}
+
+#[gpui::test]
+async fn test_expand_archive(cx: &mut gpui::TestAppContext) {
+    init_test_with_editor(cx);
+
+    let mut archive = futures::io::Cursor::new(Vec::new());
+    let mut writer = async_zip::base::write::ZipFileWriter::new(&mut archive);
+    for (name, contents) in [("docs/guide.md", "# Guide"), ("README.md", "# Readme")] {
+        let builder = async_zip::ZipEntryBuilder::new(name.into(), async_zip::Compression::Stored);
+        writer
+            .write_entry_whole(builder, contents.as_bytes())
+            .await
+            .unwrap();
+    }
+    writer.close().await.unwrap();
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "libs": {},
+            "main.rs": "",
+        }),
+    )
+    .await;
+    fs.insert_file("/root/libs/example.zip", archive.into_inner())
+        .await;
+
+    let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+    let window = cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+    let workspace = window
+        .read_with(cx, |mw, _| mw.workspace().clone())
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(window.into(), cx);
+    let panel = workspace.update_in(cx, ProjectPanel::new);
+    cx.run_until_parked();
+
+    toggle_expand_dir(&panel, "root/libs", cx);
+    select_path(&panel, "root/libs/example.zip", cx);
+    panel.update_in(cx, |panel, window, cx| {
+        panel.expand_selected_entry(&ExpandSelectedEntry, window, cx)
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    v libs",
+            "        v example.zip  <== selected",
+            "            > docs",
+            "              README.md",
+            "      main.rs",
+        ]
+    );
+
+    panel.update_in(cx, |panel, window, cx| {
+        panel.select_next(&SelectNext, window, cx);
+        panel.expand_selected_entry(&ExpandSelectedEntry, window, cx);
+    });
+    cx.run_until_parked();
+    panel.update_in(cx, |panel, window, cx| {
+        panel.select_next(&SelectNext, window, cx);
+        panel.open(&Open, window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    v libs",
+            "        v example.zip",
+            "            v docs",
+            "                  guide.md  <== selected  <== marked",
+            "              README.md",
+            "      main.rs",
+        ]
+    );
+    let editor = workspace.update_in(cx, |workspace, _, cx| {
+        workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+            .expect("the archive entry should be opened in an editor")
+    });
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "# Guide");
+        assert!(editor.read_only(cx));
+    });
+
+    // The contents of archives can't be renamed, trashed, cut or dragged out.
+    panel.update_in(cx, |panel, window, cx| {
+        panel.rename(&Rename, window, cx);
+        assert!(panel.state.edit_state.is_none());
+        panel.trash(&Trash { skip_prompt: true }, window, cx);
+        panel.cut(&Cut, window, cx);
+        assert!(panel.clipboard.is_none());
+
+        let drag = DraggedSelection {
+            active_selection: *panel.selection.as_ref().unwrap(),
+            marked_selections: Arc::new([*panel.selection.as_ref().unwrap()]),
+        };
+        let target_entry = panel
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .unwrap()
+            .read(cx)
+            .root_entry()
+            .unwrap()
+            .id;
+        panel.drag_onto(&drag, target_entry, false, window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    v libs",
+            "        v example.zip",
+            "            v docs",
+            "                  guide.md  <== selected  <== marked",
+            "              README.md",
+            "      main.rs",
+        ]
+    );
+    assert!(!fs.is_file(Path::new("/root/guide.md")).await);
+
+    // Collapsing the entries of an archive eventually collapses the archive itself.
+    for _ in 0..2 {
+        panel.update_in(cx, |panel, window, cx| {
+            panel.collapse_selected_entry(&CollapseSelectedEntry, window, cx)
+        });
+        cx.run_until_parked();
+    }
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &[
+            "v root",
+            "    v libs",
+            "        > example.zip  <== selected",
+            "      main.rs",
+        ]
+    );
+}
+
+fn set_filter_query(panel: &Entity<ProjectPanel>, query: &str, cx: &mut VisualTestContext) {
+    panel.update_in(cx, |panel, window, cx| {
+        let editor = panel.filter.as_ref().unwrap().editor.clone();
+        editor.update(cx, |editor, cx| editor.set_text(query, window, cx));
+    });
+    cx.run_until_parked();
+}
+
+#[gpui::test]
+async fn test_drag_file_nest(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    set_file_nesting_enabled(true, cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "config": {},
+            "vendor": {},
+            "Cargo.toml": "",
+            "Cargo.lock": "",
+            "package.json": "",
+            "package-lock.json": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+    let window = cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+    let workspace = window
+        .read_with(cx, |mw, _| mw.workspace().clone())
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(window.into(), cx);
+    let panel = workspace.update_in(cx, ProjectPanel::new);
+    cx.run_until_parked();
+
+    // Dragging a collapsed nest moves the files nested under it along with it.
+    select_path(&panel, "root/Cargo.toml", cx);
+    drag_selection_to(&panel, "root/config", false, cx);
+    assert!(find_project_entry(&panel, "root/config/Cargo.toml", cx).is_some());
+    assert!(find_project_entry(&panel, "root/config/Cargo.lock", cx).is_some());
+    assert_eq!(find_project_entry(&panel, "root/Cargo.lock", cx), None);
+
+    // The nested files are visible in an expanded nest, so only the selected file moves.
+    toggle_expand_nest(&panel, "root/package.json", cx);
+    select_path(&panel, "root/package.json", cx);
+    drag_selection_to(&panel, "root/vendor", false, cx);
+    assert!(find_project_entry(&panel, "root/vendor/package.json", cx).is_some());
+    assert_eq!(
+        find_project_entry(&panel, "root/vendor/package-lock.json", cx),
+        None
+    );
+    assert!(find_project_entry(&panel, "root/package-lock.json", cx).is_some());
+}
+
+#[gpui::test]
+async fn test_copy_and_trash_file_nest(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    set_file_nesting_enabled(true, cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "config": {},
+            "Cargo.toml": "",
+            "Cargo.lock": "",
+            "package.json": "",
+            "package-lock.json": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+    let window = cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+    let workspace = window
+        .read_with(cx, |mw, _| mw.workspace().clone())
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(window.into(), cx);
+    let panel = workspace.update_in(cx, ProjectPanel::new);
+    cx.run_until_parked();
+
+    // Copying a collapsed nest copies the files nested under it along with it.
+    select_path(&panel, "root/Cargo.toml", cx);
+    panel.update_in(cx, |panel, window, cx| {
+        panel.copy(&Default::default(), window, cx)
+    });
+    select_path(&panel, "root/config", cx);
+    panel.update_in(cx, |panel, window, cx| {
+        panel.paste(&Default::default(), window, cx)
+    });
+    cx.run_until_parked();
+    assert!(find_project_entry(&panel, "root/config/Cargo.toml", cx).is_some());
+    assert!(find_project_entry(&panel, "root/config/Cargo.lock", cx).is_some());
+
+    // Trashing a collapsed nest trashes the nested files too.
+    select_path(&panel, "root/Cargo.toml", cx);
+    panel.update_in(cx, |panel, window, cx| {
+        panel.trash(&Trash { skip_prompt: true }, window, cx)
+    });
+    cx.run_until_parked();
+    assert_eq!(find_project_entry(&panel, "root/Cargo.toml", cx), None);
+    assert_eq!(find_project_entry(&panel, "root/Cargo.lock", cx), None);
+    let mut trashed_paths = fs.trashed_paths();
+    trashed_paths.sort();
+    assert_eq!(
+        trashed_paths,
+        [
+            PathBuf::from("/root/Cargo.lock"),
+            PathBuf::from("/root/Cargo.toml")
+        ]
+    );
+
+    // The nested files are visible in an expanded nest, so only the selected file is trashed.
+    toggle_expand_nest(&panel, "root/package.json", cx);
+    select_path(&panel, "root/package.json", cx);
+    panel.update_in(cx, |panel, window, cx| {
+        panel.trash(&Trash { skip_prompt: true }, window, cx)
+    });
+    cx.run_until_parked();
+    assert_eq!(find_project_entry(&panel, "root/package.json", cx), None);
+    assert!(find_project_entry(&panel, "root/package-lock.json", cx).is_some());
+}
+
+#[gpui::test]
+async fn test_collapsed_file_nest_git_status(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    set_file_nesting_enabled(true, cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/root"),
+        json!({
+            ".git": {},
+            "Cargo.toml": "[package]",
+            "Cargo.lock": "modified",
+        }),
+    )
+    .await;
+    fs.set_head_and_index_for_repo(
+        path!("/root/.git").as_ref(),
+        &[
+            ("Cargo.toml", "[package]".into()),
+            ("Cargo.lock", "original".into()),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+    let window = cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+    let workspace = window
+        .read_with(cx, |mw, _| mw.workspace().clone())
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(window.into(), cx);
+    let panel = workspace.update_in(cx, ProjectPanel::new);
+    cx.run_until_parked();
+
+    // A collapsed nest shows the status of the files nested under it.
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..50, cx),
+        &["v root", "    > Cargo.toml"]
+    );
+    assert_eq!(
+        visible_git_status(&panel, "root/Cargo.toml", cx)
+            .worktree
+            .modified,
+        1
+    );
+
+    // Once expanded, each file shows its own status.
+    toggle_expand_nest(&panel, "root/Cargo.toml", cx);
+    assert_eq!(
+        visible_git_status(&panel, "root/Cargo.toml", cx)
+            .worktree
+            .modified,
+        0
+    );
+    assert_eq!(
+        visible_git_status(&panel, "root/Cargo.lock", cx)
+            .worktree
+            .modified,
+        1
+    );
+}
+
+fn set_file_nesting_enabled(enabled: bool, cx: &mut gpui::TestAppContext) {
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings
+                    .project_panel
+                    .get_or_insert_default()
+                    .file_nesting
+                    .get_or_insert_default()
+                    .enabled = Some(enabled);
+            });
+        });
+    });
+}
+
+fn visible_git_status(
+    panel: &Entity<ProjectPanel>,
+    path: &str,
+    cx: &mut VisualTestContext,
+) -> GitSummary {
+    let entry_id = find_project_entry(panel, path, cx).unwrap();
+    let mut git_status = None;
+    panel.update_in(cx, |panel, window, cx| {
+        panel.for_each_visible_entry(0..50, window, cx, &mut |project_entry, details, _, _| {
+            if project_entry == entry_id {
+                git_status = Some(details.git_status);
+            }
+        });
+    });
+    git_status.unwrap_or_else(|| panic!("{path:?} is not visible"))
+}
+
+fn toggle_expand_nest(panel: &Entity<ProjectPanel>, path: &str, cx: &mut VisualTestContext) {
+    let entry_id = find_project_entry(panel, path, cx).unwrap();
+    panel.update_in(cx, |panel, window, cx| {
+        panel.toggle_nest_expanded(entry_id, window, cx);
+    });
+    cx.run_until_parked();
+}
+
 pub(crate) fn toggle_expand_dir(
     panel: &Entity<ProjectPanel>,
     path: &str,
@@ -10544,7 +11255,7 @@ fn visible_entries_as_strings(
             }
 
             let indent = "    ".repeat(details.depth);
-            let icon = if details.kind.is_dir() {
+            let icon = if details.kind.is_dir() || details.is_nest_parent || details.is_archive {
                 if details.is_expanded { "v " } else { "> " }
             } else {
                 "  "
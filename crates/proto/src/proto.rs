@@ -122,6 +122,8 @@ messages!(
     (GetProjectSymbolsResponse, Background),
     (GetReferences, Background),
     (GetReferencesResponse, Background),
+    (GetSelectionRanges, Background),
+    (GetSelectionRangesResponse, Background),
     (GetSignatureHelp, Background),
     (GetSignatureHelpResponse, Background),
     (GetTypeDefinition, Background),
@@ -358,6 +360,8 @@ messages!(
     (GetTreeDiffResponse, Background),
     (GetBlobContent, Background),
     (GetBlobContentResponse, Background),
+    (GetBlobBytes, Background),
+    (GetBlobBytesResponse, Background),
     (LoadCommitTemplate, Background),
     (LoadCommitTemplateResponse, Background),
     (GitClone, Background),
@@ -449,6 +453,7 @@ request_messages!(
     (GetNotifications, GetNotificationsResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
     (GetReferences, GetReferencesResponse),
+    (GetSelectionRanges, GetSelectionRangesResponse),
     (GetSignatureHelp, GetSignatureHelpResponse),
     (OpenUnstagedDiff, OpenUnstagedDiffResponse),
     (OpenUncommittedDiff, OpenUncommittedDiffResponse),
@@ -603,6 +608,7 @@ request_messages!(
     (PullWorkspaceDiagnostics, Ack),
     (GetDefaultBranch, GetDefaultBranchResponse),
     (GetBlobContent, GetBlobContentResponse),
+    (GetBlobBytes, GetBlobBytesResponse),
     (LoadCommitTemplate, LoadCommitTemplateResponse),
     (GetTreeDiff, GetTreeDiffResponse),
     (GitClone, GitCloneResponse),
@@ -704,6 +710,7 @@ entity_messages!(
     GetHover,
     GetProjectSymbols,
     GetReferences,
+    GetSelectionRanges,
     GetSignatureHelp,
     OpenUnstagedDiff,
     OpenUncommittedDiff,
@@ -827,6 +834,7 @@ entity_messages!(
     GetDefaultBranch,
     GetTreeDiff,
     GetBlobContent,
+    GetBlobBytes,
     LoadCommitTemplate,
     GitClone,
     GetAgentServerCommand,
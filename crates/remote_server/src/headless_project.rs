@@ -17,6 +17,7 @@ use project::{
     AgentRegistryStore, LspStore, LspStoreEvent, ManifestTree, PrettierStore, ProjectEnvironment,
     ProjectPath, ToolchainStore, WorktreeId,
     agent_server_store::AgentServerStore,
+    archive_store::ArchiveStore,
     buffer_store::{BufferStore, BufferStoreEvent},
     context_server_store::ContextServerStore,
     debugger::{breakpoint_store::BreakpointStore, dap_store::DapStore},
@@ -210,10 +211,13 @@ impl HeadlessProject {
             observer
         });
 
+        let archive_store = cx.new(|cx| ArchiveStore::new(fs.clone(), worktree_store.clone(), cx));
+
         let lsp_store = cx.new(|cx| {
             let mut lsp_store = LspStore::new_local(
                 buffer_store.clone(),
                 worktree_store.clone(),
+                archive_store,
                 prettier_store.clone(),
                 toolchain_store
                     .read(cx)
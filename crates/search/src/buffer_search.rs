@@ -1,10 +1,12 @@
 mod registrar;
 
 use crate::{
-    FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOption,
-    SearchOptions, SearchSource, SelectAllMatches, SelectNextMatch, SelectPreviousMatch,
-    ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection, ToggleWholeWord,
+    CycleSearchScope, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
+    SearchOption, SearchOptions, SearchSource, SelectAllMatches, SelectNextMatch,
+    SelectPreviousMatch, ToggleCaseSensitive, TogglePreserveCase, ToggleRegex, ToggleReplace,
+    ToggleSelection, ToggleStructural, ToggleWholeWord,
     buffer_search::registrar::WithResultsOrExternalQuery,
+    render_search_scope_button,
     search_bar::{
         ActionButtonState, HistoryNavigationDirection, alignment_element,
         filter_search_results_input, input_base_styles, render_action_button, render_text_input,
@@ -26,7 +28,7 @@ use gpui::{
 };
 use language::{Language, LanguageRegistry};
 use project::{
-    search::SearchQuery,
+    search::{SearchQuery, SearchScope},
     search_history::{SearchHistory, SearchHistoryCursor},
 };
 
@@ -82,6 +84,7 @@ pub struct BufferSearchBar {
         HashMap<Box<dyn WeakSearchableItemHandle>, (AnyVec<dyn Send>, SearchToken)>,
     pending_search: Option<Task<()>>,
     search_options: SearchOptions,
+    search_scope: SearchScope,
     default_options: SearchOptions,
     configured_options: SearchOptions,
     query_error: Option<String>,
@@ -169,6 +172,8 @@ impl Render for BufferSearchBar {
             case,
             word,
             regex,
+            structural,
+            scopes,
             replacement,
             selection,
             select_all,
@@ -259,6 +264,27 @@ impl Render for BufferSearchBar {
                             SearchSource::Buffer,
                             focus_handle.clone(),
                         ))
+                    })
+                    .when(structural, |div| {
+                        div.child(SearchOption::Structural.as_button(
+                            self.search_options,
+                            SearchSource::Buffer,
+                            focus_handle.clone(),
+                        ))
+                    })
+                    .when(replacement, |div| {
+                        div.child(SearchOption::PreserveCase.as_button(
+                            self.search_options,
+                            SearchSource::Buffer,
+                            focus_handle.clone(),
+                        ))
+                    })
+                    .when(scopes, |div| {
+                        div.child(render_search_scope_button(
+                            "buffer-search-bar-search-scope",
+                            self.search_scope,
+                            focus_handle.clone(),
+                        ))
                     }),
             );
 
@@ -650,6 +676,27 @@ impl BufferSearchBar {
                 this.toggle_regex(action, window, cx);
             }
         }));
+        registrar.register_handler(ForDeployed(
+            |this, action: &ToggleStructural, window, cx| {
+                if this.supported_options(cx).structural {
+                    this.toggle_structural(action, window, cx);
+                }
+            },
+        ));
+        registrar.register_handler(ForDeployed(
+            |this, action: &TogglePreserveCase, window, cx| {
+                if this.supported_options(cx).replacement {
+                    this.toggle_preserve_case(action, window, cx);
+                }
+            },
+        ));
+        registrar.register_handler(ForDeployed(
+            |this, action: &CycleSearchScope, window, cx| {
+                if this.supported_options(cx).scopes {
+                    this.cycle_search_scope(action, window, cx);
+                }
+            },
+        ));
         registrar.register_handler(ForDeployed(|this, action: &ToggleSelection, window, cx| {
             if this.supported_options(cx).selection {
                 this.toggle_selection(action, window, cx);
@@ -797,6 +844,7 @@ impl BufferSearchBar {
             default_options: search_options,
             configured_options: search_options,
             search_options,
+            search_scope: SearchScope::default(),
             pending_search: None,
             query_error: None,
             dismissed: true,
@@ -1468,6 +1516,46 @@ impl BufferSearchBar {
         self.toggle_search_option(SearchOptions::REGEX, window, cx)
     }
 
+    fn toggle_structural(
+        &mut self,
+        _: &ToggleStructural,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_search_option(SearchOptions::STRUCTURAL, window, cx)
+    }
+
+    fn toggle_preserve_case(
+        &mut self,
+        _: &TogglePreserveCase,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_search_option(SearchOptions::PRESERVE_CASE, window, cx)
+    }
+
+    fn cycle_search_scope(
+        &mut self,
+        _: &CycleSearchScope,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_search_scope(self.search_scope.next(), window, cx);
+    }
+
+    /// Restricts matches to the given syntactic regions of the searched
+    /// buffers, when the active item supports it.
+    pub fn set_search_scope(
+        &mut self,
+        search_scope: SearchScope,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.search_scope = search_scope;
+        drop(self.update_matches(false, false, window, cx));
+        cx.notify();
+    }
+
     fn clear_active_searchable_item_matches(&mut self, window: &mut Window, cx: &mut App) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.active_match_index = None;
@@ -1526,7 +1614,31 @@ impl BufferSearchBar {
                 } else {
                     // Value doesn't matter, we only construct empty matchers with it
 
-                    if self.search_options.contains(SearchOptions::REGEX) {
+                    let search_scope = if self.supported_options(cx).scopes {
+                        self.search_scope
+                    } else {
+                        SearchScope::Anywhere
+                    };
+                    if self.search_options.contains(SearchOptions::STRUCTURAL)
+                        && self.supported_options(cx).structural
+                    {
+                        match SearchQuery::structural(
+                            query,
+                            false,
+                            PathMatcher::default(),
+                            PathMatcher::default(),
+                            false,
+                            None,
+                        ) {
+                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Err(e) => {
+                                self.query_error = Some(e.to_string());
+                                self.clear_active_searchable_item_matches(window, cx);
+                                cx.notify();
+                                return done_rx;
+                            }
+                        }
+                    } else if self.search_options.contains(SearchOptions::REGEX) {
                         match SearchQuery::regex(
                             query,
                             self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1547,6 +1659,26 @@ impl BufferSearchBar {
                                 return done_rx;
                             }
                         }
+                    } else if self.search_options.contains(SearchOptions::PRESERVE_CASE)
+                        && self.supported_options(cx).replacement
+                    {
+                        match SearchQuery::case_preserving(
+                            query,
+                            self.search_options.contains(SearchOptions::WHOLE_WORD),
+                            false,
+                            PathMatcher::default(),
+                            PathMatcher::default(),
+                            false,
+                            None,
+                        ) {
+                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Err(e) => {
+                                self.query_error = Some(e.to_string());
+                                self.clear_active_searchable_item_matches(window, cx);
+                                cx.notify();
+                                return done_rx;
+                            }
+                        }
                     } else {
                         match SearchQuery::text(
                             query,
@@ -1567,6 +1699,7 @@ impl BufferSearchBar {
                             }
                         }
                     }
+                    .with_scope(search_scope)
                     .into()
                 };
 
@@ -2898,6 +3031,44 @@ mod tests {
         });
     }
 
+    #[perf]
+    #[gpui::test]
+    async fn test_replace_structural(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+        editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+            buffer.update(cx, |buffer, cx| {
+                buffer.set_text("fn main() { foo(a, bar(b, c)); foo(d); }", cx);
+                buffer.set_language(Some(language::rust_lang()), cx);
+            });
+        });
+        cx.run_until_parked();
+
+        search_bar
+            .update_in(cx, |search_bar, window, cx| {
+                search_bar.search(
+                    "foo($A, $B)",
+                    Some(SearchOptions::STRUCTURAL),
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        search_bar.update_in(cx, |search_bar, window, cx| {
+            assert_eq!(search_bar.active_match_index, Some(0));
+            search_bar.replacement_editor.update(cx, |editor, cx| {
+                editor.set_text("foo($B, $A)", window, cx);
+            });
+            search_bar.replace_all(&ReplaceAll, window, cx)
+        });
+        assert_eq!(
+            editor.read_with(cx, |this, cx| this.text(cx)),
+            "fn main() { foo(bar(b, c), a); foo(d); }"
+        );
+    }
+
     #[perf]
     #[gpui::test]
     async fn test_replace_simple(cx: &mut TestAppContext) {
@@ -3015,6 +3186,38 @@ mod tests {
         );
     }
 
+    #[perf]
+    #[gpui::test]
+    async fn test_replace_all_preserving_case(cx: &mut TestAppContext) {
+        let (editor, search_bar, cx) = init_test(cx);
+        editor.update_in(cx, |editor, window, cx| {
+            editor.set_text("let user_id = USER_ID + userId;", window, cx);
+        });
+
+        search_bar
+            .update_in(cx, |search_bar, window, cx| {
+                search_bar.search(
+                    "user_id",
+                    Some(SearchOptions::PRESERVE_CASE),
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        search_bar.update_in(cx, |search_bar, window, cx| {
+            search_bar.replacement_editor.update(cx, |editor, cx| {
+                editor.set_text("account_id", window, cx);
+            });
+            search_bar.replace_all(&ReplaceAll, window, cx)
+        });
+        assert_eq!(
+            editor.read_with(cx, |this, cx| this.text(cx)),
+            "let account_id = ACCOUNT_ID + accountId;"
+        );
+    }
+
     #[gpui::test]
     async fn test_replace_focus(cx: &mut TestAppContext) {
         let (editor, search_bar, cx) = init_test(cx);
@@ -1,9 +1,11 @@
 use crate::{
-    BufferSearchBar, EXCLUDE_PLACEHOLDER, FocusSearch, HighlightKey, INCLUDE_PLACEHOLDER,
-    NextHistoryQuery, PreviousHistoryQuery, REPLACE_PLACEHOLDER, ReplaceAll, ReplaceNext,
-    SearchOption, SearchOptions, SearchSource, SelectNextMatch, SelectPreviousMatch,
-    ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleWholeWord,
+    BufferSearchBar, CycleSearchScope, EXCLUDE_PLACEHOLDER, FocusSearch, HighlightKey,
+    INCLUDE_PLACEHOLDER, NextHistoryQuery, PreviousHistoryQuery, REPLACE_PLACEHOLDER, ReplaceAll,
+    ReplaceNext, SearchOption, SearchOptions, SearchSource, SelectNextMatch, SelectPreviousMatch,
+    ToggleCaseSensitive, ToggleIncludeIgnored, TogglePreserveCase, ToggleRegex, ToggleReplace,
+    ToggleSemantic, ToggleStructural, ToggleWholeWord,
     buffer_search::Deploy,
+    render_search_scope_button,
     search_bar::{
         ActionButtonState, HistoryNavigationDirection, alignment_element, input_base_styles,
         render_action_button, render_text_input, should_navigate_history,
@@ -32,10 +34,11 @@ use language::{Buffer, Language};
 use menu::Confirm;
 use multi_buffer;
 use project::{
-    Project, ProjectPath, SearchResults,
-    search::{SearchInputKind, SearchQuery, SearchResult},
+    Project, ProjectPath, SearchResults, WorktreeId,
+    search::{SearchInputKind, SearchQuery, SearchResult, SearchScope},
     search_history::SearchHistoryCursor,
 };
+use semantic_index::{PathFilter, ProjectIndex, SemanticIndex};
 use settings::Settings;
 use std::{
     any::{Any, TypeId},
@@ -51,7 +54,7 @@ use ui::{
     CommonAnimationExt, IconButtonShape, KeyBinding, Toggleable, Tooltip, prelude::*,
     utils::SearchInputWidth,
 };
-use util::{ResultExt as _, paths::PathMatcher};
+use util::{ResultExt as _, paths::PathMatcher, rel_path::RelPath};
 use workspace::{
     DeploySearch, ItemNavHistory, NewSearch, ToolbarItemEvent, ToolbarItemLocation,
     ToolbarItemView, Workspace, WorkspaceId,
@@ -138,6 +141,30 @@ pub fn init(cx: &mut App) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, window, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, window, cx);
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleStructural, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::STRUCTURAL, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &TogglePreserveCase, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::PRESERVE_CASE, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &CycleSearchScope, window, cx| {
+                search_bar.cycle_search_scope(window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleSemantic, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::SEMANTIC, window, cx);
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &ToggleReplace, window, cx| {
@@ -242,6 +269,9 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+/// How many of the chunks most similar to a semantic query are shown.
+const SEMANTIC_SEARCH_LIMIT: usize = 50;
+
 fn contains_uppercase(str: &str) -> bool {
     str.chars().any(|c| c.is_uppercase())
 }
@@ -309,6 +339,7 @@ pub struct ProjectSearchView {
     replacement_editor: Entity<Editor>,
     results_editor: Entity<Editor>,
     pub(crate) search_options: SearchOptions,
+    pub(crate) search_scope: SearchScope,
     panels_with_errors: HashMap<InputPanel, String>,
     active_match_index: Option<usize>,
     search_id: usize,
@@ -325,6 +356,7 @@ pub struct ProjectSearchView {
 #[derive(Debug, Clone)]
 pub struct ProjectSearchSettings {
     search_options: SearchOptions,
+    search_scope: SearchScope,
     filters_enabled: bool,
 }
 
@@ -441,10 +473,8 @@ impl ProjectSearch {
         }
     }
 
-    fn search(&mut self, query: SearchQuery, cx: &mut Context<Self>) {
-        let project_search_turning_into_text_finder =
-            Arc::clone(&self.project_search_turning_into_text_finder);
-        let search = self.project.update(cx, |project, cx| {
+    fn add_to_search_history(&mut self, query: &SearchQuery, cx: &mut Context<Self>) {
+        self.project.update(cx, |project, _| {
             project
                 .search_history_mut(SearchInputKind::Query)
                 .add(&mut self.search_history_cursor, query.as_str().to_string());
@@ -460,8 +490,110 @@ impl ProjectSearch {
                     .search_history_mut(SearchInputKind::Exclude)
                     .add(&mut self.search_excluded_history_cursor, excluded);
             }
-            project.search(query.clone(), cx)
         });
+    }
+
+    fn search(&mut self, query: SearchQuery, cx: &mut Context<Self>) {
+        self.add_to_search_history(&query, cx);
+        let search = self
+            .project
+            .update(cx, |project, cx| project.search(query.clone(), cx));
+        self.start_search(query, search, cx);
+    }
+
+    /// Searches the project's semantic index for code similar to the query,
+    /// showing each matching chunk as a match. Only the query text and the
+    /// path filters are used.
+    fn semantic_search(
+        &mut self,
+        query: SearchQuery,
+        project_index: Entity<ProjectIndex>,
+        cx: &mut Context<Self>,
+    ) {
+        self.add_to_search_history(&query, cx);
+        // Paths are filtered by the index, so that excluded files don't use
+        // up the result limit.
+        let path_filter = query.filters_path().then(|| {
+            let root_names = self
+                .project
+                .read(cx)
+                .worktrees(cx)
+                .map(|worktree| {
+                    let worktree = worktree.read(cx);
+                    (worktree.id(), worktree.root_name().to_owned())
+                })
+                .collect::<HashMap<_, _>>();
+            let path_query = query.clone();
+            Arc::new(move |worktree_id: WorktreeId, path: &RelPath| {
+                if path_query.match_full_paths() {
+                    root_names.get(&worktree_id).is_some_and(|root_name| {
+                        let mut full_path = root_name.clone();
+                        full_path.push(path);
+                        path_query.match_path(&full_path)
+                    })
+                } else {
+                    path_query.match_path(path)
+                }
+            }) as PathFilter
+        });
+        let search = project_index.read(cx).search(
+            query.as_str().to_string(),
+            SEMANTIC_SEARCH_LIMIT,
+            path_filter,
+            cx,
+        );
+        let project = self.project.clone();
+        let (tx, rx) = smol::channel::unbounded();
+        let task_handle = cx.spawn(async move |_, cx| {
+            let Some(results) = search.await.log_err() else {
+                return;
+            };
+            // Chunks are ranked individually, but a buffer's matches must be
+            // reported together.
+            let mut buffers_with_ranges = Vec::<(Entity<Buffer>, Vec<Range<text::Anchor>>)>::new();
+            for result in results {
+                let open_buffer = project.update(cx, |project, cx| {
+                    project.open_buffer(result.project_path.clone(), cx)
+                });
+                // The file may have been deleted since it was indexed.
+                let Ok(buffer) = open_buffer.await else {
+                    continue;
+                };
+                let range = buffer.read_with(cx, |buffer, _| {
+                    let snapshot = buffer.snapshot();
+                    let max_row = snapshot.max_point().row;
+                    let start_row = result.rows.start.min(max_row);
+                    let end_row = result.rows.end.saturating_sub(1).clamp(start_row, max_row);
+                    snapshot.anchor_before(language::Point::new(start_row, 0))
+                        ..snapshot
+                            .anchor_after(language::Point::new(end_row, snapshot.line_len(end_row)))
+                });
+                match buffers_with_ranges
+                    .iter_mut()
+                    .find(|(existing, _)| *existing == buffer)
+                {
+                    Some((_, ranges)) => ranges.push(range),
+                    None => buffers_with_ranges.push((buffer, vec![range])),
+                }
+            }
+            for (buffer, ranges) in buffers_with_ranges {
+                let result = SearchResult::Buffer { buffer, ranges };
+                if tx.send(result).await.is_err() {
+                    break;
+                }
+            }
+        });
+        self.start_search(query, SearchResults { task_handle, rx }, cx);
+    }
+
+    fn start_search(
+        &mut self,
+        query: SearchQuery,
+        search: SearchResults<SearchResult>,
+        cx: &mut Context<Self>,
+    ) {
+        let project_search_turning_into_text_finder =
+            Arc::clone(&self.project_search_turning_into_text_finder);
         self.last_search_query_text = Some(query.as_str().to_string());
         self.search_id += 1;
         self.active_query = Some(query);
@@ -898,6 +1030,7 @@ impl ProjectSearchView {
     fn current_settings(&self) -> ProjectSearchSettings {
         ProjectSearchSettings {
             search_options: self.search_options,
+            search_scope: self.search_scope,
             filters_enabled: self.filters_enabled,
         }
     }
@@ -924,6 +1057,16 @@ impl ProjectSearchView {
         self.adjust_query_regex_language(cx);
     }
 
+    fn cycle_search_scope(&mut self, cx: &mut Context<Self>) {
+        self.search_scope = self.search_scope.next();
+        ActiveSettings::update_global(cx, |settings, cx| {
+            settings.0.insert(
+                self.entity.read(cx).project.downgrade(),
+                self.current_settings(),
+            );
+        });
+    }
+
     fn toggle_opened_only(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
         self.included_opened_only = !self.included_opened_only;
     }
@@ -988,7 +1131,82 @@ impl ProjectSearchView {
             return;
         };
         let query = query.clone().with_replacement(self.replacement(cx));
+        if query.preserve_case() {
+            self.confirm_case_preserving_replace_all(query, window, cx);
+        } else {
+            self.replace_all_matches(&query, window, cx);
+        }
+    }
+
+    /// Case-preserving replacements differ from match to match, so each
+    /// distinct one is previewed before they're applied.
+    fn confirm_case_preserving_replace_all(
+        &mut self,
+        query: SearchQuery,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.results_editor.read(cx).buffer().read(cx).snapshot(cx);
+        let match_ranges = &self.entity.read(cx).match_ranges;
+        let mut replacements: Vec<(String, String, usize)> = Vec::new();
+        for range in match_ranges {
+            let text = snapshot.text_for_range(range.clone()).collect::<String>();
+            let Some(replacement) = query
+                .replacement_for(&text)
+                .map(|replacement| replacement.into_owned())
+            else {
+                continue;
+            };
+            if let Some((_, _, count)) = replacements
+                .iter_mut()
+                .find(|(from, to, _)| *from == text && *to == replacement)
+            {
+                *count += 1;
+            } else {
+                replacements.push((text, replacement, 1));
+            }
+        }
+        if replacements.is_empty() {
+            return;
+        }
 
+        let message = format!(
+            "Replace {} {}?",
+            match_ranges.len(),
+            if match_ranges.len() == 1 {
+                "match"
+            } else {
+                "matches"
+            }
+        );
+        let detail = replacements
+            .iter()
+            .map(|(from, to, count)| format!("{from} → {to} ({count})"))
+            .join("\n");
+        let answer = window.prompt(
+            gpui::PromptLevel::Info,
+            &message,
+            Some(&detail),
+            &["Replace All", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            if matches!(answer.await, Ok(0)) {
+                this.update_in(cx, |this, window, cx| {
+                    this.replace_all_matches(&query, window, cx)
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn replace_all_matches(
+        &mut self,
+        query: &SearchQuery,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         let match_ranges = self
             .entity
             .update(cx, |model, _| mem::take(&mut model.match_ranges));
@@ -999,7 +1217,7 @@ impl ProjectSearchView {
         self.results_editor.update(cx, |editor, cx| {
             editor.replace_all(
                 &mut match_ranges.iter(),
-                &query,
+                query,
                 SearchToken::default(),
                 window,
                 cx,
@@ -1046,12 +1264,16 @@ impl ProjectSearchView {
         let mut subscriptions = Vec::new();
 
         // Read in settings if available
-        let (mut options, filters_enabled) = if let Some(settings) = settings {
-            (settings.search_options, settings.filters_enabled)
+        let (mut options, mut search_scope, filters_enabled) = if let Some(settings) = settings {
+            (
+                settings.search_options,
+                settings.search_scope,
+                settings.filters_enabled,
+            )
         } else {
             let search_options =
                 SearchOptions::from_settings(&EditorSettings::get_global(cx).search);
-            (search_options, false)
+            (search_options, SearchScope::default(), false)
         };
 
         {
@@ -1062,6 +1284,7 @@ impl ProjectSearchView {
                 query_text = active_query.as_str().to_string();
                 replacement_text = active_query.replacement().map(ToOwned::to_owned);
                 options = SearchOptions::from_query(active_query);
+                search_scope = active_query.scope();
             }
         }
         subscriptions.push(cx.observe_in(&entity, window, |this, _, window, cx| {
@@ -1188,6 +1411,7 @@ impl ProjectSearchView {
             query_editor,
             results_editor,
             search_options: options,
+            search_scope,
             panels_with_errors: HashMap::default(),
             active_match_index: None,
             included_files_editor,
@@ -1265,6 +1489,7 @@ impl ProjectSearchView {
                         editor.set_text(old_query.as_str(), window, cx);
                     });
                     search_view.search_options = SearchOptions::from_query(&old_query);
+                    search_view.search_scope = old_query.scope();
                     search_view.adjust_query_regex_language(cx);
                 }
                 new_query
@@ -1484,10 +1709,24 @@ impl ProjectSearchView {
             None
         };
         if let Some(query) = self.build_search_query(cx, open_buffers) {
-            self.entity.update(cx, |model, cx| model.search(query, cx));
+            if self.search_options.contains(SearchOptions::SEMANTIC)
+                && let Some(project_index) = self.project_index(cx)
+            {
+                self.entity.update(cx, |model, cx| {
+                    model.semantic_search(query, project_index, cx)
+                });
+            } else {
+                self.entity.update(cx, |model, cx| model.search(query, cx));
+            }
         }
     }
 
+    /// The project's semantic index, when `semantic_index` is enabled.
+    fn project_index(&self, cx: &App) -> Option<Entity<ProjectIndex>> {
+        let project = &self.entity.read(cx).project;
+        SemanticIndex::global(cx)?.read(cx).project_index(project)
+    }
+
     pub fn search_query_text(&self, cx: &App) -> String {
         self.query_editor.read(cx).text(cx)
     }
@@ -1563,13 +1802,25 @@ impl ProjectSearchView {
             .count()
             > 1;
 
-        let query = match self.search_options.build_query(
-            text,
-            included_files,
-            excluded_files,
-            match_full_paths,
-            open_buffers,
-        ) {
+        // Semantic queries are descriptions rather than patterns.
+        let search_options = if self.search_options.contains(SearchOptions::SEMANTIC) {
+            self.search_options
+                - SearchOptions::REGEX
+                - SearchOptions::STRUCTURAL
+                - SearchOptions::PRESERVE_CASE
+        } else {
+            self.search_options
+        };
+        let query = match search_options
+            .build_query(
+                text,
+                included_files,
+                excluded_files,
+                match_full_paths,
+                open_buffers,
+            )
+            .map(|query| query.with_scope(self.search_scope))
+        {
             Ok(query) => {
                 let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
                 if should_unmark_error.is_some() {
@@ -2054,6 +2305,25 @@ impl ProjectSearchBar {
         option: SearchOptions,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) -> bool {
+        self.update_search_view_and_search(window, cx, move |search_view, cx| {
+            search_view.toggle_search_option(option, cx)
+        })
+    }
+
+    fn cycle_search_scope(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        self.update_search_view_and_search(window, cx, |search_view, cx| {
+            search_view.cycle_search_scope(cx)
+        })
+    }
+
+    /// Applies `update` to the active search view, then reruns its search if
+    /// one was already made.
+    fn update_search_view_and_search(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut ProjectSearchView, &mut Context<ProjectSearchView>) + 'static,
     ) -> bool {
         if self.active_project_search.is_none() {
             return false;
@@ -2063,7 +2333,7 @@ impl ProjectSearchBar {
             let task = this.update_in(cx, |this, window, cx| {
                 let search_view = this.active_project_search.as_ref()?;
                 search_view.update(cx, |search_view, cx| {
-                    search_view.toggle_search_option(option, cx);
+                    update(search_view, cx);
                     search_view
                         .entity
                         .read(cx)
@@ -2401,7 +2671,29 @@ impl Render for ProjectSearchBar {
                         search.search_options,
                         SearchSource::Project(cx),
                         focus_handle.clone(),
-                    )),
+                    ))
+                    .child(SearchOption::Structural.as_button(
+                        search.search_options,
+                        SearchSource::Project(cx),
+                        focus_handle.clone(),
+                    ))
+                    .child(SearchOption::PreserveCase.as_button(
+                        search.search_options,
+                        SearchSource::Project(cx),
+                        focus_handle.clone(),
+                    ))
+                    .child(render_search_scope_button(
+                        "project-search-scope",
+                        search.search_scope,
+                        focus_handle.clone(),
+                    ))
+                    .when(search.project_index(cx).is_some(), |this| {
+                        this.child(SearchOption::Semantic.as_button(
+                            search.search_options,
+                            SearchSource::Project(cx),
+                            focus_handle.clone(),
+                        ))
+                    }),
             );
 
         let matches_column = h_flex()
@@ -5874,6 +6166,127 @@ pub mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_replace_all_preserving_case(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "one.rs": "let user_id = USER_ID + userId;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let window =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = window
+            .read_with(cx, |mw, _| mw.workspace().clone())
+            .unwrap();
+        let search = cx.new(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|window, cx| {
+            ProjectSearchView::new(workspace.downgrade(), search.clone(), window, cx, None)
+        });
+
+        search_view
+            .update(cx, |search_view, _, _| {
+                search_view.search_options = SearchOptions::PRESERVE_CASE;
+            })
+            .unwrap();
+        perform_search(search_view, "user_id", cx);
+
+        search_view
+            .update(cx, |search_view, window, cx| {
+                assert_eq!(search_view.entity.read(cx).match_ranges.len(), 3);
+                search_view.replacement_editor.update(cx, |editor, cx| {
+                    editor.set_text("account_id", window, cx);
+                });
+                search_view.replace_all(&ReplaceAll, window, cx);
+            })
+            .unwrap();
+        cx.run_until_parked();
+        cx.simulate_prompt_answer("Replace All");
+        cx.run_until_parked();
+
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_buffer((worktree_id, rel_path("one.rs")), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "let account_id = ACCOUNT_ID + accountId;"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_semantic_search_filters_paths_before_limiting(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.semantic_index.get_or_insert_default().enabled = Some(true);
+                });
+            });
+            semantic_index::init_with_provider(
+                Arc::new(semantic_index::FakeEmbeddingProvider::default()),
+                cx,
+            );
+        });
+
+        // More rendering files than the result limit, all ranking above the
+        // one file that survives the exclusion.
+        let render_files = (0..SEMANTIC_SEARCH_LIMIT + 5)
+            .map(|ix| (format!("render_{ix}.rs"), json!("fn render_pixels() {}\n")))
+            .collect::<serde_json::Map<_, _>>();
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "render": render_files,
+                "parse.rs": "fn parse_tokens() {}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        cx.run_until_parked();
+        let window =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = window
+            .read_with(cx, |mw, _| mw.workspace().clone())
+            .unwrap();
+        let search = cx.new(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|window, cx| {
+            ProjectSearchView::new(workspace.downgrade(), search.clone(), window, cx, None)
+        });
+
+        search_view
+            .update(cx, |search_view, window, cx| {
+                search_view.search_options = SearchOptions::SEMANTIC;
+                search_view.filters_enabled = true;
+                search_view.excluded_files_editor.update(cx, |editor, cx| {
+                    editor.set_text("render/**", window, cx);
+                });
+            })
+            .unwrap();
+        perform_search(search_view, "render pixels", cx);
+
+        let paths = search.read_with(cx, |search, cx| {
+            search
+                .excerpts
+                .read(cx)
+                .all_buffers_iter()
+                .filter_map(|buffer| Some(buffer.read(cx).file()?.path().as_unix_str().to_string()))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(paths, ["parse.rs"]);
+    }
+
     #[gpui::test]
     async fn test_smartcase_overrides_explicit_case_sensitive(cx: &mut TestAppContext) {
         init_test(cx);
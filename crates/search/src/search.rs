@@ -3,7 +3,7 @@ pub use buffer_search::BufferSearchBar;
 pub use editor::HighlightKey;
 use editor::SearchSettings;
 use gpui::{Action, App, ClickEvent, Entity, FocusHandle, IntoElement, actions};
-use project::search::SearchQuery;
+use project::search::{SearchQuery, SearchScope};
 pub use project_search::ProjectSearchView;
 use ui::{IconButtonShape, Tooltip, prelude::*};
 use util::paths::PathMatcher;
@@ -37,6 +37,16 @@ actions!(
         ToggleWholeWord,
         /// Toggles regular expression mode.
         ToggleRegex,
+        /// Toggles structural mode, which matches code by its syntax using patterns
+        /// like `foo($A, $B)` or raw tree-sitter queries.
+        ToggleStructural,
+        /// Toggles searching the project's code by meaning, using the semantic index.
+        ToggleSemantic,
+        /// Toggles matching the camelCase, PascalCase, snake_case, SCREAMING_SNAKE_CASE
+        /// and kebab-case variants of the query, replacing each in its own case.
+        TogglePreserveCase,
+        /// Cycles through restricting matches to code, comments or strings.
+        CycleSearchScope,
         /// Toggles the replace interface.
         ToggleReplace,
         /// Toggles searching within selection only.
@@ -58,7 +68,7 @@ actions!(
 
 bitflags! {
     #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
-    pub struct SearchOptions: u8 {
+    pub struct SearchOptions: u16 {
         const NONE = 0;
         const WHOLE_WORD = 1 << SearchOption::WholeWord as u8;
         const CASE_SENSITIVE = 1 << SearchOption::CaseSensitive as u8;
@@ -67,6 +77,11 @@ bitflags! {
         const ONE_MATCH_PER_LINE = 1 << SearchOption::OneMatchPerLine as u8;
         /// If set, reverse direction when finding the active match
         const BACKWARDS = 1 << SearchOption::Backwards as u8;
+        /// If set, project search ranks code by similarity to the query
+        /// instead of matching its text
+        const SEMANTIC = 1 << SearchOption::Semantic as u8;
+        const STRUCTURAL = 1 << SearchOption::Structural as u8;
+        const PRESERVE_CASE = 1 << SearchOption::PreserveCase as u8;
     }
 }
 
@@ -79,6 +94,9 @@ pub enum SearchOption {
     Regex,
     OneMatchPerLine,
     Backwards,
+    Semantic,
+    Structural,
+    PreserveCase,
 }
 
 const REPLACE_PLACEHOLDER: &str = "Replace in project…";
@@ -103,6 +121,9 @@ impl SearchOption {
             SearchOption::Regex => "Use Regular Expressions",
             SearchOption::OneMatchPerLine => "One Match Per Line",
             SearchOption::Backwards => "Search Backwards",
+            SearchOption::Semantic => "Search by Meaning",
+            SearchOption::Structural => "Match Code Structure",
+            SearchOption::PreserveCase => "Preserve Case",
         }
     }
 
@@ -112,6 +133,9 @@ impl SearchOption {
             SearchOption::CaseSensitive => IconName::CaseSensitive,
             SearchOption::IncludeIgnored => IconName::FileIgnored,
             SearchOption::Regex => IconName::Regex,
+            SearchOption::Semantic => IconName::Sparkle,
+            SearchOption::Structural => IconName::Code,
+            SearchOption::PreserveCase => IconName::Font,
             _ => panic!("{self:?} is not a named SearchOption"),
         }
     }
@@ -122,6 +146,9 @@ impl SearchOption {
             SearchOption::CaseSensitive => &ToggleCaseSensitive,
             SearchOption::IncludeIgnored => &ToggleIncludeIgnored,
             SearchOption::Regex => &ToggleRegex,
+            SearchOption::Semantic => &ToggleSemantic,
+            SearchOption::Structural => &ToggleStructural,
+            SearchOption::PreserveCase => &TogglePreserveCase,
             _ => panic!("{self:?} is not a toggle action"),
         }
     }
@@ -162,6 +189,31 @@ impl SearchOption {
     }
 }
 
+/// Renders the button that cycles through the syntactic regions a search is
+/// restricted to, see [`SearchScope`].
+pub(crate) fn render_search_scope_button(
+    id: &'static str,
+    scope: SearchScope,
+    focus_handle: FocusHandle,
+) -> impl IntoElement {
+    let tooltip = SharedString::from(format!("Search Scope: {}", scope.label()));
+    IconButton::new(id, IconName::Filter)
+        .shape(IconButtonShape::Square)
+        .toggle_state(scope != SearchScope::Anywhere)
+        .on_click({
+            let focus_handle = focus_handle.clone();
+            move |_: &ClickEvent, window, cx| {
+                if !focus_handle.is_focused(window) {
+                    window.focus(&focus_handle, cx);
+                }
+                window.dispatch_action(CycleSearchScope.boxed_clone(), cx);
+            }
+        })
+        .tooltip(move |_window, cx| {
+            Tooltip::for_action_in(tooltip.clone(), &CycleSearchScope, &focus_handle, cx)
+        })
+}
+
 impl SearchOptions {
     pub fn none() -> SearchOptions {
         SearchOptions::NONE
@@ -173,6 +225,8 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
+        options.set(SearchOptions::PRESERVE_CASE, query.preserve_case());
         options
     }
 
@@ -185,9 +239,10 @@ impl SearchOptions {
         options
     }
 
-    /// Build a [`SearchQuery`] from these options, selecting the regex or text
-    /// constructor based on [`SearchOptions::REGEX`]. Inverse of
-    /// [`SearchOptions::from_query`].
+    /// Build a [`SearchQuery`] from these options, selecting the structural,
+    /// regex, case-preserving or text constructor based on
+    /// [`SearchOptions::STRUCTURAL`], [`SearchOptions::REGEX`] and
+    /// [`SearchOptions::PRESERVE_CASE`]. Inverse of [`SearchOptions::from_query`].
     pub fn build_query(
         &self,
         query: impl ToString,
@@ -196,7 +251,16 @@ impl SearchOptions {
         match_full_paths: bool,
         buffers: Option<Vec<Entity<language::Buffer>>>,
     ) -> anyhow::Result<SearchQuery> {
-        if self.contains(SearchOptions::REGEX) {
+        if self.contains(SearchOptions::STRUCTURAL) {
+            SearchQuery::structural(
+                query,
+                self.contains(SearchOptions::INCLUDE_IGNORED),
+                files_to_include,
+                files_to_exclude,
+                match_full_paths,
+                buffers,
+            )
+        } else if self.contains(SearchOptions::REGEX) {
             SearchQuery::regex(
                 query,
                 self.contains(SearchOptions::WHOLE_WORD),
@@ -208,6 +272,16 @@ impl SearchOptions {
                 match_full_paths,
                 buffers,
             )
+        } else if self.contains(SearchOptions::PRESERVE_CASE) {
+            SearchQuery::case_preserving(
+                query,
+                self.contains(SearchOptions::WHOLE_WORD),
+                self.contains(SearchOptions::INCLUDE_IGNORED),
+                files_to_include,
+                files_to_exclude,
+                match_full_paths,
+                buffers,
+            )
         } else {
             SearchQuery::text(
                 query,
@@ -111,7 +111,7 @@ fn load_last_search(workspace_id: Option<WorkspaceId>, cx: &App) -> Option<Searc
     }
     Some(SearchSeed {
         query,
-        options: Some(SearchOptions::from_bits_truncate(search_options as u8)),
+        options: Some(SearchOptions::from_bits_truncate(search_options as u16)),
     })
 }
 
@@ -0,0 +1,273 @@
+use language::{BufferSnapshot, ToOffset as _};
+use sha2::{Digest as _, Sha256};
+use std::ops::Range;
+
+/// Chunks larger than this are split along the outline items they contain,
+/// or else into runs of whole lines.
+const CHUNK_MAX_BYTES: usize = 1_500;
+/// Titles of nested outline items are joined with this separator.
+const TITLE_SEPARATOR: &str = " > ";
+
+/// A run of whole lines of a file, embedded as one unit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chunk {
+    pub range: Range<usize>,
+    /// The outline items containing this chunk, such as `impl Parser > fn parse`.
+    pub title: Option<String>,
+}
+
+pub type ChunkDigest = [u8; 32];
+
+/// An outline item's range, in bytes, with its nesting depth and the text
+/// shown for it in the outline.
+#[derive(Clone, Debug)]
+pub struct OutlineSpan {
+    pub range: Range<usize>,
+    pub depth: usize,
+    pub title: String,
+}
+
+/// The outline items of a parsed file. Files without a grammar have none, so
+/// they're chunked by lines.
+pub fn outline_spans(snapshot: &BufferSnapshot) -> Vec<OutlineSpan> {
+    snapshot
+        .outline_items_containing(0..snapshot.len(), false, None)
+        .into_iter()
+        .map(|item| OutlineSpan {
+            range: item.range.start.to_offset(snapshot)..item.range.end.to_offset(snapshot),
+            depth: item.depth,
+            title: item.text.to_string(),
+        })
+        .collect()
+}
+
+/// Splits a file into chunks, keeping each outline item that fits within
+/// [`CHUNK_MAX_BYTES`] whole. Larger items are split along their children,
+/// and the text between items is chunked by lines.
+pub fn chunk_text(text: &str, spans: &[OutlineSpan]) -> Vec<Chunk> {
+    let mut spans = spans
+        .iter()
+        .map(|span| OutlineSpan {
+            range: expand_to_lines(text, span.range.clone()),
+            depth: span.depth,
+            title: span.title.clone(),
+        })
+        .collect::<Vec<_>>();
+    spans.sort_by_key(|span| (span.range.start, span.depth));
+
+    let mut chunks = Vec::new();
+    chunk_range(text, 0..text.len(), &spans, None, &mut chunks);
+    chunks
+}
+
+fn chunk_range(
+    text: &str,
+    range: Range<usize>,
+    spans: &[OutlineSpan],
+    title: Option<&str>,
+    chunks: &mut Vec<Chunk>,
+) {
+    let mut position = range.start;
+    let mut ix = 0;
+    while ix < spans.len() {
+        let span = &spans[ix];
+        // The span's descendants are the spans that follow it within its range.
+        // Items sharing a line, as in minified files, can overlap once expanded
+        // to whole lines, so a span is only a descendant if it ends within it.
+        let descendants_end = spans[ix + 1..]
+            .iter()
+            .position(|child| child.range.end > span.range.end)
+            .map_or(spans.len(), |end| ix + 1 + end);
+        if span.range.start < position {
+            ix += 1;
+            continue;
+        }
+
+        chunk_lines(text, position..span.range.start, title, chunks);
+        let span_title = match title {
+            Some(title) => format!("{title}{TITLE_SEPARATOR}{}", span.title),
+            None => span.title.clone(),
+        };
+        if span.range.len() <= CHUNK_MAX_BYTES || descendants_end == ix + 1 {
+            chunk_lines(text, span.range.clone(), Some(&span_title), chunks);
+        } else {
+            chunk_range(
+                text,
+                span.range.clone(),
+                &spans[ix + 1..descendants_end],
+                Some(&span_title),
+                chunks,
+            );
+        }
+        position = span.range.end.min(range.end);
+        ix = descendants_end;
+    }
+    chunk_lines(text, position..range.end, title, chunks);
+}
+
+/// Chunks a range of whole lines, grouping lines up to [`CHUNK_MAX_BYTES`].
+/// Ranges of only whitespace are skipped.
+fn chunk_lines(text: &str, range: Range<usize>, title: Option<&str>, chunks: &mut Vec<Chunk>) {
+    if text[range.clone()].trim().is_empty() {
+        return;
+    }
+
+    let mut start = range.start;
+    while start < range.end {
+        let mut end = start;
+        for line in text[start..range.end].split_inclusive('\n') {
+            if end > start && end + line.len() - start > CHUNK_MAX_BYTES {
+                break;
+            }
+            end += line.len();
+        }
+        if !text[start..end].trim().is_empty() {
+            chunks.push(Chunk {
+                range: start..end,
+                title: title.map(ToString::to_string),
+            });
+        }
+        start = end;
+    }
+}
+
+fn expand_to_lines(text: &str, range: Range<usize>) -> Range<usize> {
+    let start = text[..range.start].rfind('\n').map_or(0, |ix| ix + 1);
+    let end = text[range.end..]
+        .find('\n')
+        .map_or(text.len(), |ix| range.end + ix + 1);
+    start..end
+}
+
+/// The text embedded for a chunk. It includes the chunk's path and title, so
+/// that queries can match on where code lives as well as what it says.
+pub fn embedding_input(path: &str, chunk: &Chunk, text: &str) -> String {
+    match &chunk.title {
+        Some(title) => format!("{path}\n{title}\n{}", &text[chunk.range.clone()]),
+        None => format!("{path}\n{}", &text[chunk.range.clone()]),
+    }
+}
+
+pub fn digest(embedding_input: &str) -> ChunkDigest {
+    Sha256::digest(embedding_input.as_bytes()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn span(text: &str, item: &str, depth: usize, title: &str) -> OutlineSpan {
+        let start = text.find(item).unwrap();
+        OutlineSpan {
+            range: start..start + item.len(),
+            depth,
+            title: title.to_string(),
+        }
+    }
+
+    fn chunk_texts(text: &str, spans: &[OutlineSpan]) -> Vec<(Option<String>, String)> {
+        chunk_text(text, spans)
+            .into_iter()
+            .map(|chunk| (chunk.title, text[chunk.range].to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_chunk_small_items() {
+        let text = "use std::fmt;\n\nfn one() {\n    1\n}\n\nfn two() {\n    2\n}\n";
+        let spans = [
+            span(text, "fn one() {\n    1\n}", 0, "fn one"),
+            span(text, "fn two() {\n    2\n}", 0, "fn two"),
+        ];
+        assert_eq!(
+            chunk_texts(text, &spans),
+            vec![
+                (None, "use std::fmt;\n\n".to_string()),
+                (Some("fn one".into()), "fn one() {\n    1\n}\n".to_string()),
+                (Some("fn two".into()), "fn two() {\n    2\n}\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunk_large_item_along_children() {
+        let body = "    // padding\n".repeat(150);
+        let text =
+            format!("impl Parser {{\n    fn parse() {{\n{body}    }}\n\n    fn other() {{}}\n}}\n");
+        let parse = format!("fn parse() {{\n{body}    }}");
+        let spans = [
+            span(&text, &text[..text.len() - 1], 0, "impl Parser"),
+            span(&text, &parse, 1, "fn parse"),
+            span(&text, "fn other() {}", 1, "fn other"),
+        ];
+        let chunks = chunk_texts(&text, &spans);
+
+        assert_eq!(
+            chunks[0],
+            (Some("impl Parser".into()), "impl Parser {\n".into())
+        );
+        // `parse` is too large to embed whole, and has no children to split
+        // along, so it's split into runs of lines.
+        let parse_chunks = chunks
+            .iter()
+            .filter(|(title, _)| title.as_deref() == Some("impl Parser > fn parse"))
+            .collect::<Vec<_>>();
+        assert!(parse_chunks.len() > 1);
+        assert!(
+            parse_chunks
+                .iter()
+                .all(|(_, text)| text.len() <= CHUNK_MAX_BYTES)
+        );
+        assert_eq!(
+            &chunks[chunks.len() - 2..],
+            &[
+                (
+                    Some("impl Parser > fn other".into()),
+                    "    fn other() {}\n".into()
+                ),
+                (Some("impl Parser".into()), "}\n".into()),
+            ]
+        );
+        // Only the blank line between the methods is left out.
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|(_, text)| text.as_str())
+                .collect::<String>(),
+            text.replacen("    }\n\n", "    }\n", 1)
+        );
+    }
+
+    #[test]
+    fn test_chunk_overlapping_items_on_one_line() {
+        let padding = "x".repeat(CHUNK_MAX_BYTES);
+        let text = format!("a{{{padding}\n}} b{{\n}}\n");
+        // `b` starts on the line `a` ends on, so once expanded to whole lines
+        // the two overlap without either containing the other.
+        let spans = [
+            span(&text, &format!("a{{{padding}\n}}"), 0, "a"),
+            span(&text, "b{\n}", 0, "b"),
+        ];
+        assert_eq!(
+            chunk_texts(&text, &spans),
+            vec![
+                (Some("a".into()), format!("a{{{padding}\n")),
+                (Some("a".into()), "} b{\n".into()),
+                (None, "}\n".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunk_without_outline() {
+        let text = "line\n".repeat(1_000);
+        let chunks = chunk_text(&text, &[]);
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.first().unwrap().range.start, 0);
+        assert_eq!(chunks.last().unwrap().range.end, text.len());
+        assert!(chunks.iter().all(|chunk| chunk.title.is_none()
+            && chunk.range.len() <= CHUNK_MAX_BYTES
+            && text[..chunk.range.end].ends_with('\n')));
+    }
+}
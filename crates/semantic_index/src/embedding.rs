@@ -0,0 +1,227 @@
+use anyhow::Result;
+#[cfg(any(test, feature = "test-support"))]
+use anyhow::anyhow;
+use futures::{FutureExt as _, future::BoxFuture};
+use http_client::{CustomHeaders, HttpClient};
+#[cfg(any(test, feature = "test-support"))]
+use parking_lot::Mutex;
+use std::sync::Arc;
+#[cfg(any(test, feature = "test-support"))]
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::{EmbeddingProviderKind, SemanticIndexSettings};
+
+/// A unit-length vector, so the dot product of two embeddings is their
+/// cosine similarity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Embedding(Vec<f32>);
+
+impl Embedding {
+    pub fn new(mut values: Vec<f32>) -> Self {
+        let norm = values.iter().map(|value| value * value).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for value in &mut values {
+                *value /= norm;
+            }
+        }
+        Self(values)
+    }
+
+    pub fn similarity(&self, other: &Embedding) -> f32 {
+        self.0.iter().zip(&other.0).map(|(a, b)| a * b).sum()
+    }
+
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        self.0
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Self {
+        Self(
+            bytes
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect(),
+        )
+    }
+}
+
+/// Computes embeddings for chunks of code and for search queries.
+pub trait EmbeddingProvider: Send + Sync {
+    /// Identifies the model, so that embeddings from different models are
+    /// never compared with each other.
+    fn model_id(&self) -> String;
+
+    /// How many texts to send in a single request.
+    fn batch_size(&self) -> usize;
+
+    /// Embeds each text, in order.
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Embedding>>>;
+}
+
+pub(crate) fn embedding_provider(
+    settings: &SemanticIndexSettings,
+    http_client: Arc<dyn HttpClient>,
+) -> Arc<dyn EmbeddingProvider> {
+    match settings.provider {
+        EmbeddingProviderKind::Ollama => Arc::new(OllamaEmbeddingProvider {
+            http_client,
+            api_url: settings
+                .api_url
+                .clone()
+                .unwrap_or_else(|| ollama::OLLAMA_API_URL.to_string()),
+            model: settings.model.clone(),
+        }),
+        EmbeddingProviderKind::LlamaCpp => Arc::new(LlamaCppEmbeddingProvider {
+            http_client,
+            api_url: settings
+                .api_url
+                .clone()
+                .unwrap_or_else(|| llama_cpp::LLAMA_CPP_API_URL.to_string()),
+            model: settings.model.clone(),
+        }),
+    }
+}
+
+pub struct OllamaEmbeddingProvider {
+    http_client: Arc<dyn HttpClient>,
+    api_url: String,
+    model: String,
+}
+
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    fn model_id(&self) -> String {
+        format!("ollama/{}", self.model)
+    }
+
+    fn batch_size(&self) -> usize {
+        32
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        async move {
+            let embeddings = ollama::embed(
+                self.http_client.as_ref(),
+                &self.api_url,
+                None,
+                ollama::EmbedRequest {
+                    model: &self.model,
+                    input: texts,
+                    keep_alive: None,
+                },
+                &CustomHeaders::default(),
+            )
+            .await?;
+            Ok(embeddings.into_iter().map(Embedding::new).collect())
+        }
+        .boxed()
+    }
+}
+
+pub struct LlamaCppEmbeddingProvider {
+    http_client: Arc<dyn HttpClient>,
+    api_url: String,
+    model: String,
+}
+
+impl EmbeddingProvider for LlamaCppEmbeddingProvider {
+    fn model_id(&self) -> String {
+        format!("llama_cpp/{}", self.model)
+    }
+
+    fn batch_size(&self) -> usize {
+        16
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        async move {
+            let embeddings = llama_cpp::embed(
+                self.http_client.as_ref(),
+                &self.api_url,
+                None,
+                llama_cpp::EmbeddingsRequest {
+                    model: Some(&self.model),
+                    input: texts,
+                },
+                &CustomHeaders::default(),
+            )
+            .await?;
+            Ok(embeddings.into_iter().map(Embedding::new).collect())
+        }
+        .boxed()
+    }
+}
+
+/// Embeds texts by counting the letters in them, which is enough to tell
+/// different texts apart in tests. Its requests fail while it's marked as
+/// unavailable, like a provider that isn't running.
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Default)]
+pub struct FakeEmbeddingProvider {
+    unavailable: AtomicBool,
+    failed_requests: AtomicUsize,
+    embedded_texts: Mutex<Vec<String>>,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl FakeEmbeddingProvider {
+    pub fn set_available(&self, available: bool) {
+        self.unavailable.store(!available, Ordering::SeqCst);
+    }
+
+    /// How many requests failed because the provider was unavailable.
+    pub fn failed_requests(&self) -> usize {
+        self.failed_requests.load(Ordering::SeqCst)
+    }
+
+    /// The texts embedded since the last call, in order.
+    pub fn take_embedded_texts(&self) -> Vec<String> {
+        std::mem::take(&mut *self.embedded_texts.lock())
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl EmbeddingProvider for FakeEmbeddingProvider {
+    fn model_id(&self) -> String {
+        "fake".to_string()
+    }
+
+    fn batch_size(&self) -> usize {
+        4
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+        if self.unavailable.load(Ordering::SeqCst) {
+            self.failed_requests.fetch_add(1, Ordering::SeqCst);
+            return futures::future::ready(Err(anyhow!("connection refused"))).boxed();
+        }
+        self.embedded_texts.lock().extend(texts.iter().cloned());
+        let embeddings = texts
+            .iter()
+            .map(|text| {
+                let mut counts = vec![0.0; 26];
+                for char in text.chars().filter(char::is_ascii_alphabetic) {
+                    counts[(char.to_ascii_lowercase() as u8 - b'a') as usize] += 1.0;
+                }
+                Embedding::new(counts)
+            })
+            .collect();
+        futures::future::ready(Ok(embeddings)).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_similarity() {
+        let a = Embedding::new(vec![3.0, 4.0]);
+        assert_eq!(a, Embedding::new(vec![0.6, 0.8]));
+        assert!((a.similarity(&a) - 1.0).abs() < 1e-6);
+        assert!(a.similarity(&Embedding::new(vec![-4.0, 3.0])).abs() < 1e-6);
+        assert_eq!(Embedding::from_bytes(&a.to_bytes()), a);
+    }
+}
@@ -0,0 +1,438 @@
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use fs::MTime;
+use futures::{FutureExt as _, future::Shared};
+use gpui::{App, BackgroundExecutor, Global, Task};
+use indoc::indoc;
+use parking_lot::Mutex;
+use sqlez::connection::Connection;
+use std::{ops::Range, path::Path, sync::Arc};
+use zed_env_vars::ZED_STATELESS;
+
+use crate::{
+    chunking::{Chunk, ChunkDigest},
+    embedding::Embedding,
+};
+
+/// A chunk as stored in the index, located by both its byte and row range at
+/// the time it was indexed.
+#[derive(Clone, Debug)]
+pub struct IndexedChunk {
+    pub chunk: Chunk,
+    pub rows: Range<u32>,
+    pub digest: ChunkDigest,
+}
+
+/// A stored chunk whose embedding is similar to a query's.
+#[derive(Clone, Debug)]
+pub struct ChunkMatch {
+    pub path: String,
+    pub rows: Range<u32>,
+    pub title: Option<String>,
+    pub score: f32,
+}
+
+/// The on-disk index: the chunks of every indexed file, keyed by worktree
+/// path, and the embeddings of those chunks, keyed by model and content
+/// digest so that unchanged code is never embedded twice.
+pub(crate) struct IndexDatabase {
+    executor: BackgroundExecutor,
+    connection: Arc<Mutex<Connection>>,
+}
+
+struct GlobalIndexDatabase(Shared<Task<Result<Arc<IndexDatabase>, Arc<anyhow::Error>>>>);
+
+impl Global for GlobalIndexDatabase {}
+
+impl IndexDatabase {
+    pub fn connect(cx: &mut App) -> Shared<Task<Result<Arc<IndexDatabase>, Arc<anyhow::Error>>>> {
+        if cx.has_global::<GlobalIndexDatabase>() {
+            return cx.global::<GlobalIndexDatabase>().0.clone();
+        }
+        let executor = cx.background_executor().clone();
+        let task = executor
+            .spawn({
+                let executor = executor.clone();
+                async move {
+                    match IndexDatabase::new(executor) {
+                        Ok(db) => Ok(Arc::new(db)),
+                        Err(err) => Err(Arc::new(err)),
+                    }
+                }
+            })
+            .shared();
+
+        cx.set_global(GlobalIndexDatabase(task.clone()));
+        task
+    }
+
+    pub fn new(executor: BackgroundExecutor) -> Result<Self> {
+        let connection = if *ZED_STATELESS || cfg!(test) {
+            Connection::open_memory(None)
+        } else {
+            let index_dir = paths::data_dir().join("semantic_index");
+            std::fs::create_dir_all(&index_dir)?;
+            let sqlite_path = index_dir.join("index.db");
+            Connection::open_file(&sqlite_path.to_string_lossy())
+        };
+
+        connection.exec(indoc! {"
+            CREATE TABLE IF NOT EXISTS files (
+                worktree_path TEXT NOT NULL,
+                path TEXT NOT NULL,
+                model TEXT NOT NULL,
+                mtime_seconds INTEGER NOT NULL,
+                mtime_nanos INTEGER NOT NULL,
+                PRIMARY KEY (worktree_path, path)
+            );
+            CREATE TABLE IF NOT EXISTS chunks (
+                worktree_path TEXT NOT NULL,
+                path TEXT NOT NULL,
+                start_offset INTEGER NOT NULL,
+                end_offset INTEGER NOT NULL,
+                start_row INTEGER NOT NULL,
+                end_row INTEGER NOT NULL,
+                title TEXT,
+                digest BLOB NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS chunks_by_file ON chunks (worktree_path, path);
+            CREATE TABLE IF NOT EXISTS embeddings (
+                model TEXT NOT NULL,
+                digest BLOB NOT NULL,
+                embedding BLOB NOT NULL,
+                PRIMARY KEY (model, digest)
+            );
+        "})?()
+        .map_err(|e| e.context("Failed to create semantic index tables"))?;
+
+        Ok(Self {
+            executor,
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// When each file of a worktree was last indexed with the given model.
+    pub fn indexed_files(
+        &self,
+        worktree_path: Arc<Path>,
+        model: String,
+    ) -> Task<Result<HashMap<String, MTime>>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut select =
+                connection.select_bound::<(&str, &str), (String, u64, u32)>(indoc! {"
+                SELECT path, mtime_seconds, mtime_nanos
+                FROM files
+                WHERE worktree_path = ? AND model = ?
+            "})?;
+            Ok(
+                select((worktree_path.to_string_lossy().as_ref(), model.as_str()))?
+                    .into_iter()
+                    .map(|(path, seconds, nanos)| {
+                        (path, MTime::from_seconds_and_nanos(seconds, nanos))
+                    })
+                    .collect(),
+            )
+        })
+    }
+
+    /// Which of the given digests already have an embedding from the model.
+    pub fn embedded_digests(
+        &self,
+        model: String,
+        digests: Vec<ChunkDigest>,
+    ) -> Task<Result<HashSet<ChunkDigest>>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut select = connection.select_row_bound::<(&str, &[u8]), bool>(indoc! {"
+                SELECT 1 FROM embeddings WHERE model = ? AND digest = ?
+            "})?;
+            let mut embedded = HashSet::default();
+            for digest in &digests {
+                if select((model.as_str(), digest.as_slice()))?.is_some() {
+                    embedded.insert(*digest);
+                }
+            }
+            Ok(embedded)
+        })
+    }
+
+    /// Replaces a file's chunks, storing embeddings for the ones that had none.
+    pub fn save_file(
+        &self,
+        worktree_path: Arc<Path>,
+        path: String,
+        model: String,
+        mtime: MTime,
+        chunks: Vec<IndexedChunk>,
+        embeddings: Vec<(ChunkDigest, Embedding)>,
+    ) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let worktree_path = worktree_path.to_string_lossy().into_owned();
+            let (mtime_seconds, mtime_nanos) = mtime
+                .to_seconds_and_nanos_for_persistence()
+                .unwrap_or((0, 0));
+            connection.with_savepoint("semantic_index_save_file", || {
+                delete_file_chunks(&connection, &worktree_path, &path)?;
+
+                let mut insert_embedding =
+                    connection.exec_bound::<(&str, &[u8], Vec<u8>)>(indoc! {"
+                        INSERT OR REPLACE INTO embeddings (model, digest, embedding)
+                        VALUES (?, ?, ?)
+                    "})?;
+                for (digest, embedding) in &embeddings {
+                    insert_embedding((model.as_str(), digest.as_slice(), embedding.to_bytes()))?;
+                }
+
+                let mut insert_chunk =
+                    connection
+                        .exec_bound::<(&str, &str, usize, usize, u32, u32, Option<&str>, &[u8])>(
+                            indoc! {"
+                            INSERT INTO chunks (
+                                worktree_path, path, start_offset, end_offset,
+                                start_row, end_row, title, digest
+                            )
+                            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                        "},
+                        )?;
+                for chunk in &chunks {
+                    insert_chunk((
+                        worktree_path.as_str(),
+                        path.as_str(),
+                        chunk.chunk.range.start,
+                        chunk.chunk.range.end,
+                        chunk.rows.start,
+                        chunk.rows.end,
+                        chunk.chunk.title.as_deref(),
+                        chunk.digest.as_slice(),
+                    ))?;
+                }
+
+                connection.exec_bound::<(&str, &str, &str, u64, u32)>(indoc! {"
+                    INSERT OR REPLACE INTO files (
+                        worktree_path, path, model, mtime_seconds, mtime_nanos
+                    )
+                    VALUES (?, ?, ?, ?, ?)
+                "})?((
+                    worktree_path.as_str(),
+                    path.as_str(),
+                    model.as_str(),
+                    mtime_seconds,
+                    mtime_nanos,
+                ))?;
+                Ok(())
+            })?;
+            Ok(())
+        })
+    }
+
+    pub fn delete_file(&self, worktree_path: Arc<Path>, path: String) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let worktree_path = worktree_path.to_string_lossy().into_owned();
+            connection.with_savepoint("semantic_index_delete_file", || {
+                delete_file_chunks(&connection, &worktree_path, &path)?;
+                connection.exec_bound::<(&str, &str)>(indoc! {"
+                    DELETE FROM files WHERE worktree_path = ? AND path = ?
+                "})?((worktree_path.as_str(), path.as_str()))?;
+                Ok(())
+            })?;
+            Ok(())
+        })
+    }
+
+    /// Drops the embeddings no chunk refers to any more.
+    pub fn prune_embeddings(&self) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            connection.exec(indoc! {"
+                DELETE FROM embeddings
+                WHERE digest NOT IN (SELECT DISTINCT digest FROM chunks)
+            "})?()?;
+            Ok(())
+        })
+    }
+
+    /// The chunks of a worktree most similar to the query, best first. Only
+    /// the chunks of files whose path is accepted by `path_filter` count
+    /// towards the limit.
+    pub fn search(
+        &self,
+        worktree_path: Arc<Path>,
+        model: String,
+        query: Embedding,
+        limit: usize,
+        path_filter: impl Fn(&str) -> bool + Send + 'static,
+    ) -> Task<Result<Vec<ChunkMatch>>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut select = connection
+                .select_bound::<(&str, &str), (String, u32, u32, Option<String>, Vec<u8>)>(
+                    indoc! {"
+                        SELECT chunks.path, chunks.start_row, chunks.end_row, chunks.title,
+                            embeddings.embedding
+                        FROM chunks
+                        JOIN embeddings
+                            ON embeddings.digest = chunks.digest AND embeddings.model = ?
+                        WHERE chunks.worktree_path = ?
+                    "},
+                )?;
+            let mut matches = select((model.as_str(), worktree_path.to_string_lossy().as_ref()))?
+                .into_iter()
+                .filter(|(path, ..)| path_filter(path))
+                .map(|(path, start_row, end_row, title, embedding)| ChunkMatch {
+                    path,
+                    rows: start_row..end_row,
+                    title,
+                    score: query.similarity(&Embedding::from_bytes(&embedding)),
+                })
+                .collect::<Vec<_>>();
+            matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+            matches.truncate(limit);
+            Ok(matches)
+        })
+    }
+}
+
+fn delete_file_chunks(connection: &Connection, worktree_path: &str, path: &str) -> Result<()> {
+    connection.exec_bound::<(&str, &str)>(indoc! {"
+        DELETE FROM chunks WHERE worktree_path = ? AND path = ?
+    "})?((worktree_path, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chunking,
+        embedding::{EmbeddingProvider as _, FakeEmbeddingProvider},
+    };
+    use gpui::TestAppContext;
+    use pretty_assertions::assert_eq;
+
+    fn indexed_chunks(text: &str, titles: &[&str]) -> Vec<(IndexedChunk, String)> {
+        let mut offset = 0;
+        text.split_inclusive('\n')
+            .zip(titles)
+            .enumerate()
+            .map(|(row, (line, title))| {
+                let chunk = Chunk {
+                    range: offset..offset + line.len(),
+                    title: Some(title.to_string()),
+                };
+                offset += line.len();
+                let input = chunking::embedding_input("src/lib.rs", &chunk, text);
+                let indexed_chunk = IndexedChunk {
+                    rows: row as u32..row as u32 + 1,
+                    digest: chunking::digest(&input),
+                    chunk,
+                };
+                (indexed_chunk, input)
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_save_search_and_delete_file(cx: &mut TestAppContext) {
+        let db = IndexDatabase::new(cx.executor()).unwrap();
+        let provider = FakeEmbeddingProvider::default();
+        let worktree_path: Arc<Path> = Path::new("/project").into();
+        let model = provider.model_id();
+        let mtime = MTime::from_seconds_and_nanos(100, 5);
+
+        let text = "parse tokens\nrender pixels\n";
+        let (chunks, inputs): (Vec<_>, Vec<_>) = indexed_chunks(text, &["fn parse", "fn render"])
+            .into_iter()
+            .unzip();
+        let digests = chunks.iter().map(|chunk| chunk.digest).collect::<Vec<_>>();
+        assert!(
+            db.embedded_digests(model.clone(), digests.clone())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let embeddings = provider.embed(&inputs).await.unwrap();
+        db.save_file(
+            worktree_path.clone(),
+            "src/lib.rs".into(),
+            model.clone(),
+            mtime,
+            chunks,
+            digests.iter().copied().zip(embeddings).collect(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            db.embedded_digests(model.clone(), digests.clone())
+                .await
+                .unwrap(),
+            digests.iter().copied().collect()
+        );
+        assert_eq!(
+            db.indexed_files(worktree_path.clone(), model.clone())
+                .await
+                .unwrap(),
+            HashMap::from_iter([("src/lib.rs".to_string(), mtime)])
+        );
+
+        let query = provider
+            .embed(&["render pixels".to_string()])
+            .await
+            .unwrap()
+            .remove(0);
+        let matches = db
+            .search(
+                worktree_path.clone(),
+                model.clone(),
+                query.clone(),
+                10,
+                |_| true,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            matches
+                .iter()
+                .map(|chunk_match| (chunk_match.title.as_deref(), chunk_match.rows.clone()))
+                .collect::<Vec<_>>(),
+            vec![(Some("fn render"), 1..2), (Some("fn parse"), 0..1)]
+        );
+        assert!(
+            db.search(
+                worktree_path.clone(),
+                model.clone(),
+                query.clone(),
+                10,
+                |path| { path != "src/lib.rs" }
+            )
+            .await
+            .unwrap()
+            .is_empty()
+        );
+
+        db.delete_file(worktree_path.clone(), "src/lib.rs".into())
+            .await
+            .unwrap();
+        assert!(
+            db.search(worktree_path.clone(), model.clone(), query, 10, |_| true)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        db.prune_embeddings().await.unwrap();
+        assert!(
+            db.embedded_digests(model, digests)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,515 @@
+//! A local, on-disk semantic index of project code.
+//!
+//! Each local worktree is split into chunks along its files' outline items
+//! and the chunks are embedded with a model served by a local provider, such
+//! as Ollama or llama.cpp. Embeddings are stored by content digest, so
+//! re-indexing a file only embeds the chunks that changed. The index follows
+//! the worktree's file events, and is queried by the agent's
+//! `semantic_search` tool and the semantic mode of project search.
+
+mod chunking;
+mod embedding;
+mod index_db;
+mod worktree_index;
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use futures::future::Shared;
+use gpui::{App, AppContext as _, Context, Entity, EntityId, Global, Task, WeakEntity};
+use project::{Project, ProjectPath};
+use settings::{EmbeddingProviderContent, RegisterSetting, Settings, SettingsStore};
+use std::{ops::Range, path::Path, sync::Arc};
+use util::{paths::PathStyle, rel_path::RelPath};
+use worktree::WorktreeId;
+
+#[cfg(any(test, feature = "test-support"))]
+pub use embedding::FakeEmbeddingProvider;
+pub use embedding::{Embedding, EmbeddingProvider};
+use index_db::IndexDatabase;
+use worktree_index::WorktreeIndex;
+
+#[derive(Clone, Debug, PartialEq, RegisterSetting)]
+pub struct SemanticIndexSettings {
+    pub enabled: bool,
+    pub provider: EmbeddingProviderKind,
+    pub model: String,
+    pub api_url: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmbeddingProviderKind {
+    Ollama,
+    LlamaCpp,
+}
+
+impl Settings for SemanticIndexSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let semantic_index = content.semantic_index.clone().unwrap();
+        Self {
+            enabled: semantic_index.enabled.unwrap(),
+            provider: match semantic_index.provider.unwrap() {
+                EmbeddingProviderContent::Ollama => EmbeddingProviderKind::Ollama,
+                EmbeddingProviderContent::LlamaCpp => EmbeddingProviderKind::LlamaCpp,
+            },
+            model: semantic_index.model.unwrap(),
+            api_url: semantic_index
+                .api_url
+                .filter(|api_url| !api_url.trim().is_empty()),
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let semantic_index = cx.new(|cx| SemanticIndex::new(None, cx));
+    register_semantic_index(semantic_index, cx);
+}
+
+/// Like [`init`], but computes embeddings with the given provider instead of
+/// the one configured in the settings.
+#[cfg(any(test, feature = "test-support"))]
+pub fn init_with_provider(provider: Arc<dyn EmbeddingProvider>, cx: &mut App) {
+    let semantic_index = cx.new(|cx| SemanticIndex::new(Some(provider), cx));
+    register_semantic_index(semantic_index, cx);
+}
+
+fn register_semantic_index(semantic_index: Entity<SemanticIndex>, cx: &mut App) {
+    cx.set_global(GlobalSemanticIndex(semantic_index.clone()));
+
+    cx.observe_new(move |_: &mut Project, _, cx| {
+        let project = cx.entity();
+        let semantic_index = semantic_index.clone();
+        cx.defer(move |cx| {
+            semantic_index.update(cx, |semantic_index, cx| {
+                semantic_index.add_project(project, cx)
+            });
+        });
+    })
+    .detach();
+}
+
+struct GlobalSemanticIndex(Entity<SemanticIndex>);
+
+impl Global for GlobalSemanticIndex {}
+
+/// Owns the index of every open project, rebuilding them when the
+/// `semantic_index` settings change.
+pub struct SemanticIndex {
+    settings: SemanticIndexSettings,
+    provider_override: Option<Arc<dyn EmbeddingProvider>>,
+    projects: HashMap<EntityId, IndexedProject>,
+    _settings_subscription: gpui::Subscription,
+}
+
+struct IndexedProject {
+    project: WeakEntity<Project>,
+    index: Option<Entity<ProjectIndex>>,
+}
+
+impl SemanticIndex {
+    fn new(provider_override: Option<Arc<dyn EmbeddingProvider>>, cx: &mut Context<Self>) -> Self {
+        let settings_subscription = cx.observe_global::<SettingsStore>(|this, cx| {
+            let settings = SemanticIndexSettings::get_global(cx).clone();
+            if settings != this.settings {
+                this.settings = settings;
+                for indexed_project in this.projects.values_mut() {
+                    indexed_project.index = indexed_project.project.upgrade().and_then(|project| {
+                        ProjectIndex::new(
+                            project,
+                            &this.settings,
+                            this.provider_override.clone(),
+                            cx,
+                        )
+                    });
+                }
+                cx.notify();
+            }
+        });
+        Self {
+            settings: SemanticIndexSettings::get_global(cx).clone(),
+            provider_override,
+            projects: HashMap::default(),
+            _settings_subscription: settings_subscription,
+        }
+    }
+
+    pub fn global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalSemanticIndex>()
+            .map(|semantic_index| semantic_index.0.clone())
+    }
+
+    /// The index of a project, if semantic indexing is enabled and the
+    /// project is local.
+    pub fn project_index(&self, project: &Entity<Project>) -> Option<Entity<ProjectIndex>> {
+        self.projects
+            .get(&project.entity_id())
+            .and_then(|indexed_project| indexed_project.index.clone())
+    }
+
+    fn add_project(&mut self, project: Entity<Project>, cx: &mut Context<Self>) {
+        let project_id = project.entity_id();
+        cx.observe_release(&project, move |this, _, _| {
+            this.projects.remove(&project_id);
+        })
+        .detach();
+        let index = ProjectIndex::new(
+            project.clone(),
+            &self.settings,
+            self.provider_override.clone(),
+            cx,
+        );
+        self.projects.insert(
+            project_id,
+            IndexedProject {
+                project: project.downgrade(),
+                index,
+            },
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexStatus {
+    /// The index is still being loaded.
+    Loading,
+    /// Files are being embedded.
+    Indexing {
+        remaining_files: usize,
+    },
+    Idle,
+    /// The index couldn't be opened.
+    Failed,
+}
+
+/// Decides which files a search may return, so that results are filtered by
+/// path before they are limited.
+pub type PathFilter = Arc<dyn Fn(WorktreeId, &RelPath) -> bool + Send + Sync>;
+
+/// A chunk of project code similar to a search query.
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub project_path: ProjectPath,
+    /// The chunk's rows when it was indexed, end-exclusive.
+    pub rows: Range<u32>,
+    /// The outline items containing the chunk, such as `impl Parser > fn parse`.
+    pub title: Option<String>,
+    /// The cosine similarity of the chunk and the query.
+    pub score: f32,
+}
+
+/// The semantic index of one project's local worktrees.
+pub struct ProjectIndex {
+    project: WeakEntity<Project>,
+    provider: Arc<dyn EmbeddingProvider>,
+    db: Shared<Task<Result<Arc<IndexDatabase>, Arc<anyhow::Error>>>>,
+    worktree_indices: HashMap<WorktreeId, WorktreeIndex>,
+    status: IndexStatus,
+    _subscription: gpui::Subscription,
+    _load_db: Task<()>,
+}
+
+impl ProjectIndex {
+    fn new(
+        project: Entity<Project>,
+        settings: &SemanticIndexSettings,
+        provider_override: Option<Arc<dyn EmbeddingProvider>>,
+        cx: &mut App,
+    ) -> Option<Entity<Self>> {
+        if !settings.enabled || !project.read(cx).is_local() {
+            return None;
+        }
+        let provider = provider_override
+            .unwrap_or_else(|| embedding::embedding_provider(settings, cx.http_client()));
+        let db = IndexDatabase::connect(cx);
+        Some(cx.new(|cx| {
+            let subscription = cx.subscribe(&project, |this, project, event, cx| match event {
+                project::Event::WorktreeAdded(_) | project::Event::WorktreeRemoved(_) => {
+                    this.update_worktrees(&project, cx);
+                }
+                _ => {}
+            });
+            let load_db = cx.spawn({
+                let db = db.clone();
+                let project = project.downgrade();
+                async move |this, cx| {
+                    let result = db.await;
+                    this.update(cx, |this, cx| match result {
+                        Ok(_) => {
+                            if let Some(project) = project.upgrade() {
+                                this.update_worktrees(&project, cx);
+                            }
+                        }
+                        Err(error) => {
+                            log::error!("failed to open semantic index: {error:#}");
+                            this.status = IndexStatus::Failed;
+                            cx.notify();
+                        }
+                    })
+                    .ok();
+                }
+            });
+            Self {
+                project: project.downgrade(),
+                provider,
+                db,
+                worktree_indices: HashMap::default(),
+                status: IndexStatus::Loading,
+                _subscription: subscription,
+                _load_db: load_db,
+            }
+        }))
+    }
+
+    fn update_worktrees(&mut self, project: &Entity<Project>, cx: &mut Context<Self>) {
+        let Some(Ok(db)) = self.db.peek().cloned() else {
+            return;
+        };
+        let (worktrees, language_registry, fs) = {
+            let project = project.read(cx);
+            (
+                project
+                    .visible_worktrees(cx)
+                    .filter(|worktree| worktree.read(cx).is_local())
+                    .collect::<Vec<_>>(),
+                project.languages().clone(),
+                project.fs().clone(),
+            )
+        };
+
+        self.worktree_indices.retain(|worktree_id, _| {
+            worktrees
+                .iter()
+                .any(|worktree| worktree.read(cx).id() == *worktree_id)
+        });
+        for worktree in worktrees {
+            let worktree_id = worktree.read(cx).id();
+            if !self.worktree_indices.contains_key(&worktree_id) {
+                let index = WorktreeIndex::new(
+                    worktree,
+                    db.clone(),
+                    self.provider.clone(),
+                    language_registry.clone(),
+                    fs.clone(),
+                    cx,
+                );
+                self.worktree_indices.insert(worktree_id, index);
+            }
+        }
+        self.update_status(cx);
+    }
+
+    fn update_status(&mut self, cx: &mut Context<Self>) {
+        if self.status == IndexStatus::Failed {
+            return;
+        }
+        let remaining_files = self
+            .worktree_indices
+            .values()
+            .map(|index| index.pending_files)
+            .sum();
+        self.status = if remaining_files > 0 {
+            IndexStatus::Indexing { remaining_files }
+        } else {
+            IndexStatus::Idle
+        };
+        cx.notify();
+    }
+
+    pub fn status(&self) -> IndexStatus {
+        self.status
+    }
+
+    /// The chunks of code most similar to the query, across all of the
+    /// project's indexed worktrees, best first. When a `path_filter` is
+    /// given, only chunks of the files it accepts are returned.
+    pub fn search(
+        &self,
+        query: String,
+        limit: usize,
+        path_filter: Option<PathFilter>,
+        cx: &App,
+    ) -> Task<Result<Vec<SearchResult>>> {
+        let db = self.db.clone();
+        let provider = self.provider.clone();
+        let worktrees = self
+            .worktree_indices
+            .iter()
+            .map(|(worktree_id, index)| (*worktree_id, index.abs_path.clone()))
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            let db = db.await.map_err(|error| anyhow!(error))?;
+            let query = provider
+                .embed(std::slice::from_ref(&query))
+                .await?
+                .pop()
+                .context("embedding provider returned no embedding for the query")?;
+
+            let mut results = Vec::new();
+            for (worktree_id, abs_path) in worktrees {
+                let path_filter = path_filter.clone();
+                let matches = db
+                    .search(
+                        abs_path,
+                        provider.model_id(),
+                        query.clone(),
+                        limit,
+                        move |path| {
+                            let Some(path_filter) = &path_filter else {
+                                return true;
+                            };
+                            RelPath::new(Path::new(path), PathStyle::Unix)
+                                .is_ok_and(|path| path_filter(worktree_id, path.as_ref()))
+                        },
+                    )
+                    .await?;
+                results.extend(matches.into_iter().filter_map(|chunk_match| {
+                    let path = RelPath::new(Path::new(&chunk_match.path), PathStyle::Unix).ok()?;
+                    Some(SearchResult {
+                        project_path: ProjectPath {
+                            worktree_id,
+                            path: path.into_arc(),
+                        },
+                        rows: chunk_match.rows,
+                        title: chunk_match.title,
+                        score: chunk_match.score,
+                    })
+                }));
+            }
+            results.sort_by(|a, b| b.score.total_cmp(&a.score));
+            results.truncate(limit);
+            Ok(results)
+        })
+    }
+
+    pub fn project(&self) -> Option<Entity<Project>> {
+        self.project.upgrade()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use pretty_assertions::assert_eq;
+    use project::FakeFs;
+    use serde_json::json;
+    use std::collections::BTreeSet;
+    use util::{path, rel_path::rel_path};
+
+    pub(crate) fn init_test(cx: &mut TestAppContext) -> Arc<FakeEmbeddingProvider> {
+        let provider = Arc::new(FakeEmbeddingProvider::default());
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.semantic_index.get_or_insert_default().enabled = Some(true);
+                });
+            });
+            init_with_provider(provider.clone(), cx);
+        });
+        provider
+    }
+
+    pub(crate) fn project_index(
+        project: &Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> Entity<ProjectIndex> {
+        cx.update(|cx| {
+            SemanticIndex::global(cx)
+                .unwrap()
+                .read(cx)
+                .project_index(project)
+                .expect("the project should be indexed")
+        })
+    }
+
+    /// The paths of the matching chunks, best first.
+    pub(crate) async fn search_paths(
+        project: &Entity<Project>,
+        query: &str,
+        limit: usize,
+        path_filter: Option<PathFilter>,
+        cx: &mut TestAppContext,
+    ) -> Vec<String> {
+        let search = project_index(project, cx).read_with(cx, |index, cx| {
+            index.search(query.to_string(), limit, path_filter, cx)
+        });
+        search
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| result.project_path.path.as_unix_str().to_string())
+            .collect()
+    }
+
+    /// The files whose chunks were embedded since the last call.
+    pub(crate) fn embedded_paths(provider: &FakeEmbeddingProvider) -> Vec<String> {
+        provider
+            .take_embedded_texts()
+            .iter()
+            .filter_map(|text| Some(text.split_once('\n')?.0.to_string()))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_search_filters_paths_before_limiting(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "src": {
+                    "parse.rs": "fn parse_tokens() {}\n",
+                    "render.rs": "fn render_pixels() {}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        assert_eq!(
+            project_index(&project, cx).read_with(cx, |index, _| index.status()),
+            IndexStatus::Idle
+        );
+
+        assert_eq!(
+            search_paths(&project, "render pixels", 1, None, cx).await,
+            ["src/render.rs"]
+        );
+        let path_filter: PathFilter =
+            Arc::new(|_, path: &RelPath| path != rel_path("src/render.rs"));
+        assert_eq!(
+            search_paths(&project, "render pixels", 1, Some(path_filter), cx).await,
+            ["src/parse.rs"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_index_follows_settings(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "lib.rs": "fn main() {}\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        project_index(&project, cx);
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.semantic_index.get_or_insert_default().enabled = Some(false);
+                });
+            });
+        });
+        cx.run_until_parked();
+        let index = cx.update(|cx| {
+            SemanticIndex::global(cx)
+                .unwrap()
+                .read(cx)
+                .project_index(&project)
+        });
+        assert!(index.is_none());
+    }
+}
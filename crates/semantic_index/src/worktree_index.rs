@@ -0,0 +1,467 @@
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use fs::{Fs, MTime};
+use futures::{
+    StreamExt as _,
+    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+};
+use gpui::{AsyncApp, Context, Entity, Subscription, Task, WeakEntity};
+use language::{Buffer, LanguageRegistry};
+use std::{fmt, mem, path::Path, sync::Arc};
+use util::{ResultExt as _, rel_path::RelPath};
+use worktree::{Entry, PathChange, Worktree, WorktreeId};
+
+use crate::{
+    ProjectIndex,
+    chunking::{self, ChunkDigest},
+    embedding::{Embedding, EmbeddingProvider},
+    index_db::{IndexDatabase, IndexedChunk},
+};
+
+/// Files larger than this are usually generated or vendored, and not worth
+/// embedding.
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+
+/// Keeps one worktree's files indexed: every changed file once the worktree
+/// has been scanned, then each file the worktree reports as updated.
+pub(crate) struct WorktreeIndex {
+    pub(crate) abs_path: Arc<Path>,
+    updates: UnboundedSender<FileUpdate>,
+    pub(crate) pending_files: usize,
+    _subscription: Subscription,
+    _index_files: Task<()>,
+}
+
+#[derive(Debug)]
+enum FileUpdate {
+    Changed { path: Arc<RelPath>, mtime: MTime },
+    Deleted { path: Arc<RelPath> },
+}
+
+impl FileUpdate {
+    fn path(&self) -> &Arc<RelPath> {
+        match self {
+            FileUpdate::Changed { path, .. } | FileUpdate::Deleted { path } => path,
+        }
+    }
+}
+
+/// Marks failed requests to the embedding provider. These usually mean that
+/// the provider isn't running, so the remaining files would fail the same way.
+#[derive(Debug)]
+struct EmbeddingFailed;
+
+impl fmt::Display for EmbeddingFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to compute embeddings")
+    }
+}
+
+/// What indexing a file needs, shared by all of a worktree's files.
+#[derive(Clone)]
+struct Indexer {
+    worktree_id: WorktreeId,
+    abs_path: Arc<Path>,
+    db: Arc<IndexDatabase>,
+    provider: Arc<dyn EmbeddingProvider>,
+    language_registry: Arc<LanguageRegistry>,
+    fs: Arc<dyn Fs>,
+}
+
+impl WorktreeIndex {
+    pub(crate) fn new(
+        worktree: Entity<Worktree>,
+        db: Arc<IndexDatabase>,
+        provider: Arc<dyn EmbeddingProvider>,
+        language_registry: Arc<LanguageRegistry>,
+        fs: Arc<dyn Fs>,
+        cx: &mut Context<ProjectIndex>,
+    ) -> Self {
+        let (worktree_id, abs_path) = {
+            let worktree = worktree.read(cx);
+            (worktree.id(), worktree.abs_path())
+        };
+        let (updates_tx, updates_rx) = mpsc::unbounded();
+        let indexer = Indexer {
+            worktree_id,
+            abs_path: abs_path.clone(),
+            db,
+            provider,
+            language_registry,
+            fs,
+        };
+
+        let subscription = cx.subscribe(&worktree, move |this, worktree, event, cx| {
+            if let worktree::Event::UpdatedEntries(changes) = event
+                && let Some(index) = this.worktree_indices.get(&worktree_id)
+            {
+                let snapshot = worktree.read(cx).snapshot();
+                for (path, _, change) in changes.iter() {
+                    let update = match change {
+                        // Reported for the initial scan, which is compared
+                        // with the index as a whole instead.
+                        PathChange::Loaded => continue,
+                        PathChange::Removed => FileUpdate::Deleted { path: path.clone() },
+                        PathChange::Added | PathChange::Updated | PathChange::AddedOrUpdated => {
+                            let Some(entry) = snapshot.entry_for_path(path) else {
+                                continue;
+                            };
+                            match indexable_mtime(entry) {
+                                Some(mtime) => FileUpdate::Changed {
+                                    path: path.clone(),
+                                    mtime,
+                                },
+                                None if entry.is_file() => {
+                                    FileUpdate::Deleted { path: path.clone() }
+                                }
+                                None => continue,
+                            }
+                        }
+                    };
+                    index.updates.unbounded_send(update).ok();
+                }
+            }
+        });
+
+        let scan_complete = worktree
+            .read(cx)
+            .as_local()
+            .map(|local| local.scan_complete());
+        let index_files = cx.spawn({
+            let updates_tx = updates_tx.clone();
+            async move |this, cx| {
+                if let Some(scan_complete) = scan_complete {
+                    scan_complete.await;
+                }
+                let initial_updates = initial_updates(&worktree, &indexer, cx).await;
+                match initial_updates {
+                    Ok(updates) => {
+                        for update in updates {
+                            updates_tx.unbounded_send(update).ok();
+                        }
+                    }
+                    Err(error) => {
+                        log::error!("failed to compare worktree with semantic index: {error:#}")
+                    }
+                }
+                drop(worktree);
+                index_updates(this, indexer, updates_rx, cx).await;
+            }
+        });
+
+        Self {
+            abs_path,
+            updates: updates_tx,
+            pending_files: 0,
+            _subscription: subscription,
+            _index_files: index_files,
+        }
+    }
+}
+
+/// The modification time of a file that should be indexed, or `None` for
+/// entries that shouldn't be.
+fn indexable_mtime(entry: &Entry) -> Option<MTime> {
+    let indexable = entry.is_file()
+        && !entry.is_ignored
+        && !entry.is_external
+        && !entry.is_private
+        && !entry.is_fifo
+        && entry.size <= MAX_FILE_BYTES;
+    entry.mtime.filter(|_| indexable)
+}
+
+/// The files that changed since the worktree was last indexed, and the
+/// indexed files that no longer exist.
+async fn initial_updates(
+    worktree: &Entity<Worktree>,
+    indexer: &Indexer,
+    cx: &mut AsyncApp,
+) -> Result<Vec<FileUpdate>> {
+    let snapshot = worktree.read_with(cx, |worktree, _| worktree.snapshot());
+    let mut indexed_files = indexer
+        .db
+        .indexed_files(indexer.abs_path.clone(), indexer.provider.model_id())
+        .await?;
+
+    cx.background_spawn(async move {
+        let mut updates = Vec::new();
+        for entry in snapshot.files(false, 0) {
+            let Some(mtime) = indexable_mtime(entry) else {
+                continue;
+            };
+            if indexed_files.remove(entry.path.as_unix_str()) != Some(mtime) {
+                updates.push(FileUpdate::Changed {
+                    path: entry.path.clone(),
+                    mtime,
+                });
+            }
+        }
+        for path in indexed_files.into_keys() {
+            if let Ok(path) = RelPath::new(Path::new(&path), util::paths::PathStyle::Unix) {
+                updates.push(FileUpdate::Deleted {
+                    path: path.into_arc(),
+                });
+            }
+        }
+        Ok(updates)
+    })
+    .await
+}
+
+async fn index_updates(
+    this: WeakEntity<ProjectIndex>,
+    indexer: Indexer,
+    mut updates_rx: UnboundedReceiver<FileUpdate>,
+    cx: &mut AsyncApp,
+) {
+    let mut is_initial_batch = true;
+    // When the embedding provider fails, the rest of the batch is kept here
+    // and retried with the next update, rather than failing file by file.
+    let mut deferred_updates = HashMap::default();
+    while let Some(update) = updates_rx.next().await {
+        // Coalesce the updates that are already queued, keeping the latest
+        // update for each path.
+        let mut updates = mem::take(&mut deferred_updates);
+        updates.insert(update.path().clone(), update);
+        while let Ok(Some(update)) = updates_rx.try_next() {
+            updates.insert(update.path().clone(), update);
+        }
+
+        let mut pending_files = updates.len();
+        set_pending_files(&this, indexer.worktree_id, pending_files, cx);
+        let mut updates = updates.into_iter();
+        for (path, update) in &mut updates {
+            let result = match &update {
+                FileUpdate::Changed { path, mtime } => index_file(&indexer, path, *mtime, cx).await,
+                FileUpdate::Deleted { path } => {
+                    indexer
+                        .db
+                        .delete_file(indexer.abs_path.clone(), path.as_unix_str().to_string())
+                        .await
+                }
+            };
+            if let Err(error) = &result
+                && error.downcast_ref::<EmbeddingFailed>().is_some()
+            {
+                log::error!(
+                    "pausing semantic indexing of {} until the next file change: {error:#}",
+                    indexer.abs_path.display()
+                );
+                deferred_updates.insert(path, update);
+                break;
+            }
+            result.log_err();
+            pending_files -= 1;
+            set_pending_files(&this, indexer.worktree_id, pending_files, cx);
+        }
+        if !deferred_updates.is_empty() {
+            deferred_updates.extend(updates);
+            set_pending_files(&this, indexer.worktree_id, 0, cx);
+        }
+
+        // Edits leave behind embeddings of code that no longer exists. These
+        // are dropped once per session, after catching up with the worktree.
+        if is_initial_batch && deferred_updates.is_empty() {
+            is_initial_batch = false;
+            indexer.db.prune_embeddings().await.log_err();
+        }
+    }
+}
+
+fn set_pending_files(
+    this: &WeakEntity<ProjectIndex>,
+    worktree_id: WorktreeId,
+    pending_files: usize,
+    cx: &mut AsyncApp,
+) {
+    this.update(cx, |this, cx| {
+        if let Some(index) = this.worktree_indices.get_mut(&worktree_id) {
+            index.pending_files = pending_files;
+            this.update_status(cx);
+        }
+    })
+    .ok();
+}
+
+async fn index_file(
+    indexer: &Indexer,
+    path: &Arc<RelPath>,
+    mtime: MTime,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let abs_path = indexer.abs_path.join(path.as_std_path());
+    let text = indexer.fs.load(&abs_path).await?;
+    if text.contains('\0') {
+        return Ok(());
+    }
+    let language = indexer
+        .language_registry
+        .load_language_for_file_path(&abs_path)
+        .await
+        .ok();
+    let snapshot = cx
+        .update(|cx| {
+            Buffer::build_snapshot(
+                text.as_str().into(),
+                language,
+                Some(indexer.language_registry.clone()),
+                None,
+                cx,
+            )
+        })
+        .await;
+
+    let path_text = path.as_unix_str().to_string();
+    let (chunks, inputs) = cx
+        .background_spawn(async move {
+            let spans = chunking::outline_spans(&snapshot);
+            let mut chunks = Vec::new();
+            let mut inputs = Vec::new();
+            for chunk in chunking::chunk_text(&text, &spans) {
+                let input = chunking::embedding_input(&path_text, &chunk, &text);
+                let last_offset = chunk.range.end.saturating_sub(1).max(chunk.range.start);
+                chunks.push(IndexedChunk {
+                    rows: snapshot.offset_to_point(chunk.range.start).row
+                        ..snapshot.offset_to_point(last_offset).row + 1,
+                    digest: chunking::digest(&input),
+                    chunk,
+                });
+                inputs.push(input);
+            }
+            (chunks, inputs)
+        })
+        .await;
+
+    let model = indexer.provider.model_id();
+    let embedded = indexer
+        .db
+        .embedded_digests(
+            model.clone(),
+            chunks.iter().map(|chunk| chunk.digest).collect(),
+        )
+        .await?;
+    let mut missing = HashMap::<ChunkDigest, String>::default();
+    for (chunk, input) in chunks.iter().zip(inputs) {
+        if !embedded.contains(&chunk.digest) {
+            missing.insert(chunk.digest, input);
+        }
+    }
+    let (digests, inputs): (Vec<_>, Vec<_>) = missing.into_iter().unzip();
+    let mut embeddings = Vec::<(ChunkDigest, Embedding)>::with_capacity(inputs.len());
+    for (digests, inputs) in digests
+        .chunks(indexer.provider.batch_size())
+        .zip(inputs.chunks(indexer.provider.batch_size()))
+    {
+        let batch = indexer
+            .provider
+            .embed(inputs)
+            .await
+            .context(EmbeddingFailed)?;
+        embeddings.extend(digests.iter().copied().zip(batch));
+    }
+
+    indexer
+        .db
+        .save_file(
+            indexer.abs_path.clone(),
+            path.as_unix_str().to_string(),
+            model,
+            mtime,
+            chunks,
+            embeddings,
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IndexStatus,
+        tests::{embedded_paths, init_test, project_index, search_paths},
+    };
+    use fs::{Fs as _, RemoveOptions};
+    use gpui::TestAppContext;
+    use pretty_assertions::assert_eq;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use std::path::Path;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_reindexes_changed_and_deleted_files(cx: &mut TestAppContext) {
+        let provider = init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "src": {
+                    "parse.rs": "fn parse_tokens() {}\n",
+                    "render.rs": "fn render_pixels() {}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+        assert_eq!(embedded_paths(&provider), ["src/parse.rs", "src/render.rs"]);
+
+        // Only the changed file is embedded again.
+        fs.insert_file(
+            path!("/project/src/parse.rs"),
+            b"fn compile_shaders() {}\n".to_vec(),
+        )
+        .await;
+        cx.run_until_parked();
+        assert_eq!(embedded_paths(&provider), ["src/parse.rs"]);
+        assert_eq!(
+            search_paths(&project, "compile shaders", 1, None, cx).await,
+            ["src/parse.rs"]
+        );
+
+        fs.remove_file(
+            Path::new(path!("/project/src/render.rs")),
+            RemoveOptions::default(),
+        )
+        .await
+        .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            search_paths(&project, "render pixels", 2, None, cx).await,
+            ["src/parse.rs"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_embedding_failure_pauses_indexing(cx: &mut TestAppContext) {
+        let provider = init_test(cx);
+        provider.set_available(false);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "a.rs": "fn alpha() {}\n",
+                "b.rs": "fn beta() {}\n",
+                "c.rs": "fn gamma() {}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.run_until_parked();
+
+        // The first failure pauses indexing instead of trying every file.
+        assert_eq!(provider.failed_requests(), 1);
+        assert_eq!(
+            project_index(&project, cx).read_with(cx, |index, _| index.status()),
+            IndexStatus::Idle
+        );
+
+        // The paused files are indexed along with the next change.
+        provider.set_available(true);
+        fs.insert_file(path!("/project/a.rs"), b"fn alpha_beta() {}\n".to_vec())
+            .await;
+        cx.run_until_parked();
+        assert_eq!(embedded_paths(&provider), ["a.rs", "b.rs", "c.rs"]);
+        assert_eq!(provider.failed_requests(), 1);
+    }
+}
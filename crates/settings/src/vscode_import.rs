@@ -213,6 +213,7 @@ impl VsCodeSettings {
             }),
             remote: RemoteSettingsContent::default(),
             repl: None,
+            semantic_index: None,
             server_url: None,
             session: None,
             status_bar: self.status_bar_settings_content(),
@@ -796,6 +797,7 @@ impl VsCodeSettings {
                 .read_str("workbench.editor.editorActionsLocation")
                 .and_then(|str| if str == "hidden" { Some(false) } else { None }),
             show_pinned_tabs_in_separate_row: None,
+            groups: None,
         })
     }
 
@@ -853,6 +855,7 @@ impl VsCodeSettings {
             starts_open: None,
             sticky_scroll: None,
             auto_open: None,
+            file_nesting: self.project_panel_file_nesting_settings_content(),
             diagnostic_badges: None,
             git_status_indicator: None,
         };
@@ -868,6 +871,35 @@ impl VsCodeSettings {
         skip_default(project_panel_settings)
     }
 
+    fn project_panel_file_nesting_settings_content(
+        &self,
+    ) -> Option<ProjectPanelFileNestingSettingsContent> {
+        // VS Code lists the nested files of each pattern as a comma-separated string.
+        let patterns = self
+            .read_value("explorer.fileNesting.patterns")
+            .and_then(Value::as_object)
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .filter_map(|(parent, children)| {
+                        let children = children
+                            .as_str()?
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|child| !child.is_empty())
+                            .map(ToOwned::to_owned)
+                            .collect();
+                        Some((parent.clone(), children))
+                    })
+                    .collect()
+            });
+
+        skip_default(ProjectPanelFileNestingSettingsContent {
+            enabled: self.read_bool("explorer.fileNesting.enabled"),
+            patterns,
+        })
+    }
+
     fn telemetry_settings_content(&self) -> Option<TelemetrySettingsContent> {
         self.read_enum("telemetry.telemetryLevel", |level| {
             let (metrics, diagnostics) = match level {
@@ -360,6 +360,19 @@ pub struct AgentSettingsContent {
     /// These are populated when choosing "Allow always" from a sandbox
     /// escalation prompt.
     pub sandbox_permissions: Option<SandboxPermissionsContent>,
+
+    /// Commands to run around the agent's actions, such as running a
+    /// formatter after each edit or enforcing repository policies before a
+    /// tool call. Each hook receives a JSON description of the event on
+    /// stdin and runs inside the same sandbox as agent terminal commands.
+    pub hooks: Option<AgentHooksContent>,
+
+    /// Limits on how many tokens, or how much money, the agent may spend per
+    /// thread, per day, or per profile. The agent warns as it approaches a
+    /// limit and stops before a request would start past one. A request that
+    /// started under a limit still finishes, so spend can overshoot a limit by
+    /// up to one request per running thread.
+    pub budgets: Option<AgentBudgetsContent>,
 }
 
 impl AgentSettingsContent {
@@ -959,6 +972,89 @@ pub struct SandboxPermissionsContent {
     pub warn_ntfs_grants: Option<bool>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHooksContent {
+    /// Hooks to run before the agent calls a tool. A hook that exits with
+    /// status 2 blocks the call, and its stderr is returned to the model as
+    /// the reason.
+    /// Default: []
+    pub before_tool_call: Option<Vec<AgentHookContent>>,
+
+    /// Hooks to run after a tool edits a file. Their output is appended to
+    /// the tool's result so the model sees it.
+    /// Default: []
+    pub after_file_edit: Option<Vec<AgentHookContent>>,
+
+    /// Hooks to run when the agent is about to end its turn. A hook that
+    /// exits with status 2 keeps the turn going, with its stderr sent to the
+    /// model as a follow-up message.
+    /// Default: []
+    pub turn_end: Option<Vec<AgentHookContent>>,
+
+    /// Hooks to run before the first turn of a thread in this session. Their
+    /// output is added to the thread as context for the model.
+    /// Default: []
+    pub session_start: Option<Vec<AgentHookContent>>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHookContent {
+    /// The program to run.
+    #[serde(default)]
+    pub command: String,
+
+    /// Arguments passed to the program.
+    /// Default: []
+    pub args: Option<Vec<String>>,
+
+    /// Names of the tools this hook applies to, for the tool events. When
+    /// empty, the hook runs for every tool.
+    /// Default: []
+    pub tools: Option<Vec<String>>,
+
+    /// How long to wait for the hook to finish, in seconds, before it is
+    /// killed and ignored.
+    /// Default: 60
+    pub timeout: Option<u64>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentBudgetsContent {
+    /// Limits for each thread.
+    /// Default: no limits
+    pub thread: Option<AgentBudgetLimitsContent>,
+
+    /// Limits across all threads over a calendar day, in local time.
+    /// Default: no limits
+    pub daily: Option<AgentBudgetLimitsContent>,
+
+    /// Limits across all threads using the given profile over a calendar day,
+    /// in local time. They apply on top of the `thread` and `daily` limits.
+    /// Default: {}
+    pub profiles: Option<IndexMap<Arc<str>, AgentBudgetLimitsContent>>,
+
+    /// The fraction of a limit at which the agent starts warning that it's
+    /// close, between 0 and 1.
+    /// Default: 0.8
+    pub warning_threshold: Option<f32>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentBudgetLimitsContent {
+    /// The most tokens (input, output and cached) that may be used.
+    /// Default: no limit
+    pub max_tokens: Option<u64>,
+
+    /// The most that may be spent, in US dollars. Only models that report
+    /// per-token prices count towards this.
+    /// Default: no limit
+    pub max_cost: Option<f64>,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct ToolPermissionsContent {
@@ -249,6 +249,9 @@ pub struct SettingsContent {
     /// Default: off
     pub reduce_motion: Option<ReduceMotionMode>,
 
+    /// Settings for the local semantic index of project code.
+    pub semantic_index: Option<SemanticIndexSettingsContent>,
+
     /// The URL of the Zed server to connect to.
     pub server_url: Option<String>,
 
@@ -1084,6 +1087,38 @@ pub enum HourFormat {
     Hour24,
 }
 
+/// Settings for the local semantic index of project code.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct SemanticIndexSettingsContent {
+    /// Whether to index projects for semantic search. Indexing runs locally,
+    /// using an embedding model served by the configured provider.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The local server that computes embeddings.
+    ///
+    /// Default: ollama
+    pub provider: Option<EmbeddingProviderContent>,
+    /// The embedding model to request from the provider.
+    ///
+    /// Default: "nomic-embed-text"
+    pub model: Option<String>,
+    /// The URL of the provider's API. When unset, the provider's default
+    /// local address is used.
+    pub api_url: Option<String>,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderContent {
+    #[default]
+    Ollama,
+    LlamaCpp,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
 pub struct OutlinePanelSettingsContent {
@@ -1221,6 +1256,12 @@ pub struct ImageViewerSettingsContent {
     ///
     /// Default: "binary"
     pub unit: Option<ImageFileSizeUnit>,
+    /// The quality, from 1 to 100, used when saving edited images as JPEG.
+    /// WebP images are always saved losslessly, so there's no quality setting
+    /// for them.
+    ///
+    /// Default: 90
+    pub jpeg_quality: Option<u32>,
 }
 
 #[with_fallible_options]
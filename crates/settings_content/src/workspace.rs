@@ -495,6 +495,54 @@ pub struct TabBarSettingsContent {
     ///
     /// Default: false
     pub show_pinned_tabs_in_separate_row: Option<bool>,
+    /// Tab groups that tabs are added to automatically when their file's path
+    /// matches one of the group's patterns.
+    ///
+    /// Default: []
+    pub groups: Option<Vec<TabGroupRuleContent>>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq, Eq)]
+pub struct TabGroupRuleContent {
+    /// The name of the tab group.
+    pub name: String,
+    /// The color of the tab group's label.
+    ///
+    /// Default: blue
+    pub color: Option<TabGroupColor>,
+    /// Glob patterns, relative to the worktree root, of the files whose tabs
+    /// are added to the group.
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+/// The color of a tab group's label in the tab bar.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TabGroupColor {
+    #[default]
+    Blue,
+    Green,
+    Yellow,
+    Orange,
+    Red,
+    Purple,
+    Gray,
 }
 
 #[with_fallible_options]
@@ -724,6 +772,23 @@ pub struct ProjectPanelAutoOpenSettings {
     pub on_drop: Option<bool>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug)]
+pub struct ProjectPanelFileNestingSettingsContent {
+    /// Whether to nest related files under a parent file in the project panel.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Maps a parent file name pattern to the patterns of the files nested under it.
+    ///
+    /// A parent pattern may contain a single `*`, whose match is substituted for
+    /// `${capture}` in the child patterns. Child patterns may also contain `*`
+    /// wildcards of their own. Set a pattern to an empty list to disable it.
+    ///
+    /// Default: rules for common companion files, such as `Cargo.toml` → `Cargo.lock`
+    pub patterns: Option<HashMap<String, Vec<String>>>,
+}
+
 #[with_fallible_options]
 #[derive(Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug)]
 pub struct ProjectPanelSettingsContent {
@@ -810,6 +875,8 @@ pub struct ProjectPanelSettingsContent {
     pub drag_and_drop: Option<bool>,
     /// Settings for automatically opening files.
     pub auto_open: Option<ProjectPanelAutoOpenSettings>,
+    /// Settings for nesting related files under a parent file.
+    pub file_nesting: Option<ProjectPanelFileNestingSettingsContent>,
     /// How to order sibling entries in the project panel.
     ///
     /// Default: directories_first
@@ -4372,7 +4372,7 @@ fn window_and_layout_page() -> SettingsPage {
         ]
     }
 
-    fn tab_bar_section() -> [SettingsPageItem; 9] {
+    fn tab_bar_section() -> [SettingsPageItem; 10] {
         [
             SettingsPageItem::SectionHeader("Tab Bar"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -4521,6 +4521,23 @@ fn window_and_layout_page() -> SettingsPage {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Tab Groups",
+                description: "Tab groups that tabs join automatically when their file matches one of the group's path patterns.",
+                field: Box::new(
+                    SettingField {
+                        organization_override: None,
+                        json_path: Some("tab_bar.groups"),
+                        pick: |settings_content| settings_content.tab_bar.as_ref()?.groups.as_ref(),
+                        write: |settings_content, value, _| {
+                            settings_content.tab_bar.get_or_insert_default().groups = value;
+                        },
+                    }
+                    .unimplemented(),
+                ),
+                metadata: None,
+                files: USER,
+            }),
         ]
     }
 
@@ -38,6 +38,6 @@ pub(crate) use tool_permissions_setup::render_tool_permissions_setup_page;
 pub use tool_permissions_setup::{
     render_copy_path_tool_config, render_create_directory_tool_config,
     render_delete_path_tool_config, render_edit_file_tool_config, render_fetch_tool_config,
-    render_move_path_tool_config, render_skill_tool_config, render_terminal_tool_config,
-    render_web_search_tool_config, render_write_file_tool_config,
+    render_move_path_tool_config, render_run_task_tool_config, render_skill_tool_config,
+    render_terminal_tool_config, render_web_search_tool_config, render_write_file_tool_config,
 };
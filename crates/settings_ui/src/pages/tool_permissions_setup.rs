@@ -26,6 +26,12 @@ const TOOLS: &[ToolInfo] = &[
         description: "Commands executed in the terminal",
         regex_explanation: "Patterns are matched against each command in the input. Commands chained with &&, ||, ;, or pipes are split and checked individually.",
     },
+    ToolInfo {
+        id: "run_task",
+        name: "Run Task",
+        description: "Project tasks and runnables",
+        regex_explanation: "Patterns are matched against the label of the task being run.",
+    },
     ToolInfo {
         id: "edit_file",
         name: "Edit File",
@@ -303,6 +309,7 @@ fn get_tool_render_fn(
 ) -> fn(&SettingsWindow, &ScrollHandle, &mut Window, &mut Context<SettingsWindow>) -> AnyElement {
     match tool_id {
         "terminal" => render_terminal_tool_config,
+        "run_task" => render_run_task_tool_config,
         "edit_file" => render_edit_file_tool_config,
         "write_file" => render_write_file_tool_config,
         "delete_path" => render_delete_path_tool_config,
@@ -1381,6 +1388,7 @@ macro_rules! tool_config_page_fn {
 }
 
 tool_config_page_fn!(render_terminal_tool_config, "terminal");
+tool_config_page_fn!(render_run_task_tool_config, "run_task");
 tool_config_page_fn!(render_edit_file_tool_config, "edit_file");
 tool_config_page_fn!(render_write_file_tool_config, "write_file");
 tool_config_page_fn!(render_delete_path_tool_config, "delete_path");
@@ -1415,6 +1423,7 @@ mod tests {
             "open",
             "read_file",
             "rename_symbol",
+            "semantic_search",
             "thinking",
             // streaming_edit_file uses "edit_file" for permission lookups,
             // so its rules are configured under the edit_file entry.
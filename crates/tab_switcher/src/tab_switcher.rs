@@ -23,9 +23,10 @@ use ui::{
 };
 use util::ResultExt;
 use workspace::{
-    Event as WorkspaceEvent, ModalView, Pane, SaveIntent, Workspace,
+    Event as WorkspaceEvent, ModalView, Pane, SaveIntent, SplitDirection, Workspace,
     item::{ItemHandle, ItemSettings, ShowDiagnostics, TabContentParams},
     pane::{render_item_indicator, tab_details},
+    tab_group::{TabGroup, tab_group_color},
 };
 
 const PANEL_WIDTH_REMS: f32 = 28.;
@@ -48,6 +49,9 @@ actions!(
         /// Toggles the tab switcher showing all tabs across all panes, deduplicated by path.
         /// Opens selected items in the active pane.
         OpenInActivePane,
+        /// Moves the selected tab or tab group to the pane on the right, splitting
+        /// the pane if there's none.
+        MoveSelectedToSplit,
     ]
 );
 
@@ -213,6 +217,19 @@ impl TabSwitcher {
                 .close_item_at(picker.delegate.selected_index(), window, cx)
         });
     }
+
+    fn handle_move_selected_to_split(
+        &mut self,
+        _: &MoveSelectedToSplit,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .move_to_split_at(picker.delegate.selected_index(), window, cx)
+        });
+    }
 }
 
 impl EventEmitter<DismissEvent> for TabSwitcher {}
@@ -230,6 +247,7 @@ impl Render for TabSwitcher {
             .w(rems(PANEL_WIDTH_REMS))
             .on_modifiers_changed(cx.listener(Self::handle_modifiers_changed))
             .on_action(cx.listener(Self::handle_close_selected_item))
+            .on_action(cx.listener(Self::handle_move_selected_to_split))
             .child(self.picker.clone())
     }
 }
@@ -243,6 +261,14 @@ struct TabMatch {
     preview: bool,
 }
 
+/// A tab group listed after the tabs, to close or move its tabs as a unit.
+#[derive(Clone)]
+struct TabGroupMatch {
+    pane: WeakEntity<Pane>,
+    group: TabGroup,
+    item_count: usize,
+}
+
 pub struct TabSwitcherDelegate {
     select_last: bool,
     tab_switcher: WeakEntity<TabSwitcher>,
@@ -251,6 +277,7 @@ pub struct TabSwitcherDelegate {
     workspace: WeakEntity<Workspace>,
     project: Entity<Project>,
     matches: Vec<TabMatch>,
+    group_matches: Vec<TabGroupMatch>,
     original_items: Vec<(Entity<Pane>, usize)>,
     is_all_panes: bool,
     open_in_active_pane: bool,
@@ -353,6 +380,7 @@ impl TabSwitcherDelegate {
             workspace,
             project,
             matches: Vec::new(),
+            group_matches: Vec::new(),
             is_all_panes,
             open_in_active_pane,
             original_items,
@@ -467,6 +495,9 @@ impl TabSwitcherDelegate {
             });
         }
 
+        let panes = workspace.read(cx).panes().to_vec();
+        self.update_group_matches(&panes, &query, cx);
+
         let selected_item_id = self.selected_item_id();
         self.matches = matches;
         self.selected_index = if query.is_empty() {
@@ -532,6 +563,10 @@ impl TabSwitcherDelegate {
             a_score.cmp(&b_score)
         });
 
+        if let Some(pane) = self.pane.upgrade() {
+            self.update_group_matches(&[pane], &query, cx);
+        }
+
         self.selected_index = if query.is_empty() {
             self.compute_selected_index(selected_item_id, window, cx)
         } else {
@@ -539,6 +574,52 @@ impl TabSwitcherDelegate {
         }
     }
 
+    fn update_group_matches(&mut self, panes: &[Entity<Pane>], query: &str, cx: &App) {
+        let all_groups = panes
+            .iter()
+            .flat_map(|pane_handle| {
+                let pane = pane_handle.read(cx);
+                pane.tab_groups().iter().map(|group| TabGroupMatch {
+                    pane: pane_handle.downgrade(),
+                    group: group.clone(),
+                    item_count: pane.tab_group_item_ids(group.id).len(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        self.group_matches = if query.is_empty() {
+            all_groups
+        } else {
+            let candidates = all_groups
+                .iter()
+                .enumerate()
+                .map(|(ix, group_match)| StringMatchCandidate::new(ix, &group_match.group.name))
+                .collect::<Vec<_>>();
+            fuzzy_nucleo::match_strings(
+                &candidates,
+                query,
+                fuzzy_nucleo::Case::Smart,
+                fuzzy_nucleo::LengthPenalty::On,
+                10000,
+            )
+            .into_iter()
+            .map(|m| all_groups[m.candidate_id].clone())
+            .collect()
+        };
+    }
+
+    fn group_match_at(&self, ix: usize) -> Option<&TabGroupMatch> {
+        self.group_matches.get(ix.checked_sub(self.matches.len())?)
+    }
+
+    fn restore_original_items(&mut self, window: &mut Window, cx: &mut App) {
+        for (pane, index) in self.original_items.iter() {
+            pane.update(cx, |this, cx| {
+                this.activate_item(*index, false, false, window, cx);
+            })
+        }
+    }
+
     fn selected_item_id(&self) -> Option<EntityId> {
         self.matches
             .get(self.selected_index())
@@ -590,6 +671,17 @@ impl TabSwitcherDelegate {
         window: &mut Window,
         cx: &mut Context<Picker<TabSwitcherDelegate>>,
     ) {
+        if let Some(group_match) = self.group_match_at(ix) {
+            let group_id = group_match.group.id;
+            group_match
+                .pane
+                .update(cx, |pane, cx| {
+                    pane.close_tab_group(group_id, SaveIntent::Close, window, cx)
+                        .detach_and_log_err(cx);
+                })
+                .ok();
+            return;
+        }
         let Some(tab_match) = self.matches.get(ix) else {
             return;
         };
@@ -620,6 +712,98 @@ impl TabSwitcherDelegate {
         }
     }
 
+    fn move_to_split_at(
+        &mut self,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Picker<TabSwitcherDelegate>>,
+    ) {
+        let (source_pane, item_ids) = if let Some(group_match) = self.group_match_at(ix) {
+            let Some(pane) = group_match.pane.upgrade() else {
+                return;
+            };
+            let item_ids = pane.read(cx).tab_group_item_ids(group_match.group.id);
+            (pane, item_ids)
+        } else if let Some(tab_match) = self.matches.get(ix) {
+            let Some(pane) = tab_match.pane.upgrade() else {
+                return;
+            };
+            (pane, vec![tab_match.item.item_id()])
+        } else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        self.restored_items = true;
+        self.restore_original_items(window, cx);
+        workspace.update(cx, |workspace, cx| {
+            workspace.move_items_to_pane_in_direction(
+                &source_pane,
+                &item_ids,
+                SplitDirection::Right,
+                window,
+                cx,
+            );
+        });
+        self.tab_switcher
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_group_match(
+        &self,
+        ix: usize,
+        group_match: &TabGroupMatch,
+        selected: bool,
+        cx: &mut Context<Picker<Self>>,
+    ) -> ListItem {
+        let item_count = group_match.item_count;
+        let close_button = IconButton::new("close_tab_group", IconName::Close)
+            .icon_size(IconSize::Small)
+            .icon_color(Color::Muted)
+            .tooltip(Tooltip::for_action_title("Close Group", &CloseSelectedItem))
+            .on_click(cx.listener(move |picker, _, window, cx| {
+                cx.stop_propagation();
+                picker.delegate.close_item_at(ix, window, cx);
+            }));
+
+        ListItem::new(ix)
+            .spacing(ListItemSpacing::Sparse)
+            .inset(true)
+            .toggle_state(selected)
+            .start_slot(
+                div()
+                    .size_2()
+                    .rounded_full()
+                    .bg(tab_group_color(group_match.group.color)),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .min_w_0()
+                    .gap_2()
+                    .child(Label::new(group_match.group.name.clone()).truncate())
+                    .child(
+                        Label::new(if item_count == 1 {
+                            "1 tab".to_string()
+                        } else {
+                            format!("{item_count} tabs")
+                        })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .map(|el| {
+                if selected {
+                    el.end_slot(close_button)
+                } else {
+                    el.end_slot_on_hover(close_button)
+                }
+            })
+    }
+
     /// Updates the selected index to ensure it matches the pane's active item,
     /// as the pane's active item can be indirectly updated and this method
     /// ensures that the picker can react to those changes.
@@ -733,7 +917,7 @@ impl PickerDelegate for TabSwitcherDelegate {
     }
 
     fn match_count(&self) -> usize {
-        self.matches.len()
+        self.matches.len() + self.group_matches.len()
     }
 
     fn selected_index(&self) -> usize {
@@ -748,10 +932,9 @@ impl PickerDelegate for TabSwitcherDelegate {
     ) {
         self.selected_index = ix;
 
-        if !self.open_in_active_pane {
-            let Some(selected_match) = self.matches.get(self.selected_index()) else {
-                return;
-            };
+        if !self.open_in_active_pane
+            && let Some(selected_match) = self.matches.get(self.selected_index())
+        {
             selected_match
                 .pane
                 .update(cx, |pane, cx| {
@@ -765,7 +948,11 @@ impl PickerDelegate for TabSwitcherDelegate {
     }
 
     fn separators_after_indices(&self) -> Vec<usize> {
-        Vec::new()
+        if self.matches.is_empty() || self.group_matches.is_empty() {
+            Vec::new()
+        } else {
+            vec![self.matches.len() - 1]
+        }
     }
 
     fn update_matches(
@@ -784,16 +971,31 @@ impl PickerDelegate for TabSwitcherDelegate {
         window: &mut Window,
         cx: &mut Context<Picker<TabSwitcherDelegate>>,
     ) {
+        if let Some(group_match) = self.group_match_at(self.selected_index()).cloned() {
+            self.restored_items = true;
+            self.restore_original_items(window, cx);
+            let group_id = group_match.group.id;
+            group_match
+                .pane
+                .update(cx, |pane, cx| {
+                    pane.set_tab_group_collapsed(group_id, false, cx);
+                    let first_item_id = pane.tab_group_item_ids(group_id).first().copied();
+                    if let Some(index) = pane
+                        .items()
+                        .position(|item| Some(item.item_id()) == first_item_id)
+                    {
+                        pane.activate_item(index, true, true, window, cx);
+                    }
+                })
+                .ok();
+            return;
+        }
         let Some(selected_match) = self.matches.get(self.selected_index()).cloned() else {
             return;
         };
 
         self.restored_items = true;
-        for (pane, index) in self.original_items.iter() {
-            pane.update(cx, |this, cx| {
-                this.activate_item(*index, false, false, window, cx);
-            })
-        }
+        self.restore_original_items(window, cx);
 
         if self.open_in_active_pane {
             self.confirm_open_in_active_pane(selected_match, window, cx);
@@ -811,11 +1013,7 @@ impl PickerDelegate for TabSwitcherDelegate {
 
     fn dismissed(&mut self, window: &mut Window, cx: &mut Context<Picker<TabSwitcherDelegate>>) {
         if !self.restored_items {
-            for (pane, index) in self.original_items.iter() {
-                pane.update(cx, |this, cx| {
-                    this.activate_item(*index, false, false, window, cx);
-                })
-            }
+            self.restore_original_items(window, cx);
         }
 
         self.tab_switcher
@@ -830,6 +1028,9 @@ impl PickerDelegate for TabSwitcherDelegate {
         window: &mut Window,
         cx: &mut Context<Picker<Self>>,
     ) -> Option<Self::ListItem> {
+        if let Some(group_match) = self.group_match_at(ix) {
+            return Some(self.render_group_match(ix, group_match, selected, cx));
+        }
         let tab_match = self.matches.get(ix)?;
 
         let params = TabContentParams {
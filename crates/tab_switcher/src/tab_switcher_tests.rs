@@ -5,7 +5,10 @@ use menu::SelectPrevious;
 use project::{Project, ProjectPath};
 use serde_json::json;
 use util::{path, rel_path::rel_path};
-use workspace::{ActivatePreviousItem, AppState, MultiWorkspace, Workspace, item::test::TestItem};
+use workspace::{
+    ActivatePreviousItem, AppState, MultiWorkspace, Workspace, item::test::TestItem,
+    tab_group::TabGroupColor,
+};
 
 #[ctor::ctor(unsafe)]
 fn init_logger() {
@@ -255,6 +258,138 @@ async fn test_close_selected_item(cx: &mut gpui::TestAppContext) {
     assert_tab_switcher_is_closed(workspace, cx);
 }
 
+#[gpui::test]
+async fn test_close_selected_tab_group(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (multi_workspace, cx) =
+        cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+    let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+    let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+    pane.update(cx, |pane, cx| {
+        pane.create_tab_group(
+            "Drafts",
+            TabGroupColor::Purple,
+            &[tab_1.item_id(), tab_2.item_id()],
+            cx,
+        );
+    });
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 3);
+        assert_eq!(tab_switcher.delegate.group_matches.len(), 1);
+        assert_eq!(tab_switcher.delegate.group_matches[0].group.name, "Drafts");
+        assert_eq!(tab_switcher.delegate.group_matches[0].item_count, 2);
+        tab_switcher.delegate.selected_index = 3;
+    });
+
+    cx.dispatch_action(CloseSelectedItem);
+    cx.run_until_parked();
+
+    pane.read_with(cx, |pane, _| {
+        assert_eq!(
+            pane.items().map(|item| item.item_id()).collect::<Vec<_>>(),
+            [tab_3.item_id()]
+        );
+        assert!(pane.tab_groups().is_empty());
+    });
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 1);
+        assert!(tab_switcher.delegate.group_matches.is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_move_selected_tab_group_to_split(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (multi_workspace, cx) =
+        cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+    let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+    let source_pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+    source_pane.update(cx, |pane, cx| {
+        let group_id = pane.create_tab_group(
+            "Drafts",
+            TabGroupColor::Purple,
+            &[tab_1.item_id(), tab_3.item_id()],
+            cx,
+        );
+        pane.set_tab_group_collapsed(group_id, true, cx);
+    });
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.group_matches.len(), 1);
+        tab_switcher.delegate.selected_index = tab_switcher.delegate.matches.len();
+    });
+
+    cx.dispatch_action(MoveSelectedToSplit);
+    cx.run_until_parked();
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+
+    let panes = workspace.read_with(cx, |workspace, _| workspace.panes().to_vec());
+    assert_eq!(panes.len(), 2);
+    assert_eq!(panes[0], source_pane);
+    source_pane.read_with(cx, |pane, _| {
+        assert_eq!(
+            pane.items().map(|item| item.item_id()).collect::<Vec<_>>(),
+            [tab_2.item_id()]
+        );
+        assert!(pane.tab_groups().is_empty());
+    });
+    panes[1].read_with(cx, |pane, _| {
+        assert_eq!(
+            pane.items().map(|item| item.item_id()).collect::<Vec<_>>(),
+            [tab_1.item_id(), tab_3.item_id()]
+        );
+        let [group] = pane.tab_groups() else {
+            panic!("expected a single tab group, got {:?}", pane.tab_groups());
+        };
+        assert_eq!(group.name, "Drafts");
+        assert_eq!(group.color, TabGroupColor::Purple);
+        assert!(group.collapsed);
+        assert_eq!(
+            pane.tab_group_item_ids(group.id),
+            [tab_1.item_id(), tab_3.item_id()]
+        );
+    });
+}
+
 fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
     cx.update(|cx| {
         let state = AppState::test(cx);
@@ -1959,6 +1959,8 @@ impl SearchableItem for TerminalView {
             case: false,
             word: false,
             regex: true,
+            structural: false,
+            scopes: false,
             replacement: false,
             selection: false,
             select_all: false,
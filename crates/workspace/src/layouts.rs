@@ -0,0 +1,497 @@
+//! Named workspace layouts.
+//!
+//! A layout captures the splits of the center pane group, the files open in
+//! each pane and the state of the docks. Layouts are stored by name in the
+//! project's `.zed/layouts.json`, so they can be checked in and shared.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::{Context as _, Result};
+use async_recursion::async_recursion;
+use fs::Fs;
+use gpui::{Action, App, AsyncWindowContext, Axis, Context, Entity, Task, WeakEntity, Window, px};
+use project::{Project, ProjectPath};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use util::{ResultExt as _, rel_path::RelPath};
+
+use crate::{
+    DockData, DockStructure, Member, Pane, PaneAxis, PaneGroup, SaveIntent, Workspace, dock::Dock,
+    pane::CloseAllItems,
+};
+
+/// Saves the current pane splits, open files and docks as a named layout.
+#[derive(Clone, PartialEq, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = workspace)]
+#[serde(deny_unknown_fields)]
+pub struct SaveLayout {
+    pub name: String,
+}
+
+/// Restores a layout previously saved with `workspace::SaveLayout`.
+#[derive(Clone, PartialEq, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = workspace)]
+#[serde(deny_unknown_fields)]
+pub struct RestoreLayout {
+    pub name: String,
+}
+
+/// Deletes a saved layout.
+#[derive(Clone, PartialEq, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = workspace)]
+#[serde(deny_unknown_fields)]
+pub struct DeleteLayout {
+    pub name: String,
+}
+
+/// The contents of `.zed/layouts.json`, keyed by layout name.
+pub type WorkspaceLayouts = BTreeMap<String, WorkspaceLayout>;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceLayout {
+    pub center: LayoutPaneGroup,
+    #[serde(default)]
+    pub docks: LayoutDocks,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutPaneGroup {
+    Group {
+        axis: Axis,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        flexes: Option<Vec<f32>>,
+        children: Vec<LayoutPaneGroup>,
+    },
+    Pane(LayoutPane),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayoutPane {
+    /// Paths of the files open in the pane. Paths in the first folder of the
+    /// project are relative to it, paths in other folders start with the
+    /// folder's name.
+    #[serde(default)]
+    pub items: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_item: Option<usize>,
+    #[serde(default)]
+    pub active: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayoutDocks {
+    #[serde(default)]
+    pub left: LayoutDock,
+    #[serde(default)]
+    pub right: LayoutDock,
+    #[serde(default)]
+    pub bottom: LayoutDock,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayoutDock {
+    #[serde(default)]
+    pub visible: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_panel: Option<String>,
+    #[serde(default)]
+    pub zoom: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flex: Option<f32>,
+}
+
+impl LayoutDock {
+    fn new(data: DockData, dock: &Entity<Dock>, cx: &App) -> Self {
+        let size_state = dock.read(cx).active_panel_size();
+        Self {
+            visible: data.visible,
+            active_panel: data.active_panel,
+            zoom: data.zoom,
+            size: size_state
+                .and_then(|state| state.size)
+                .map(|size| size.as_f32()),
+            flex: size_state.and_then(|state| state.flex),
+        }
+    }
+
+    fn dock_data(&self) -> DockData {
+        DockData {
+            visible: self.visible,
+            active_panel: self.active_panel.clone(),
+            zoom: self.zoom,
+        }
+    }
+}
+
+impl LayoutPaneGroup {
+    #[async_recursion(?Send)]
+    async fn open(
+        self,
+        project: &Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut AsyncWindowContext,
+    ) -> Option<(Member, Option<Entity<Pane>>)> {
+        match self {
+            LayoutPaneGroup::Group {
+                axis,
+                flexes,
+                children,
+            } => {
+                let mut current_active_pane = None;
+                let mut members = Vec::new();
+                for child in children {
+                    if let Some((member, active_pane)) =
+                        child.open(project, workspace.clone(), cx).await
+                    {
+                        members.push(member);
+                        current_active_pane = current_active_pane.or(active_pane);
+                    }
+                }
+
+                match members.len() {
+                    0 => None,
+                    1 => Some((members.remove(0), current_active_pane)),
+                    _ => Some((
+                        Member::Axis(PaneAxis::load(axis, members, flexes)),
+                        current_active_pane,
+                    )),
+                }
+            }
+            LayoutPaneGroup::Pane(layout_pane) => {
+                let pane = workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.add_pane(window, cx).downgrade()
+                    })
+                    .log_err()?;
+                let project_paths = project.read_with(cx, |project, cx| {
+                    layout_pane
+                        .items
+                        .iter()
+                        .map(|path| resolve_layout_item_path(project, path, cx))
+                        .collect::<Vec<_>>()
+                });
+
+                let mut active_item = None;
+                for (index, project_path) in project_paths.into_iter().enumerate() {
+                    let Some(project_path) = project_path else {
+                        continue;
+                    };
+                    let open_task = workspace
+                        .update_in(cx, |workspace, window, cx| {
+                            workspace.open_path(project_path, Some(pane.clone()), false, window, cx)
+                        })
+                        .log_err()?;
+                    let item = open_task.await.log_err();
+                    if layout_pane.active_item == Some(index) {
+                        active_item = item;
+                    }
+                }
+
+                if let Some(active_item) = active_item {
+                    pane.update_in(cx, |pane, window, cx| {
+                        if let Some(index) = pane.index_for_item(active_item.as_ref()) {
+                            pane.activate_item(index, false, false, window, cx);
+                        }
+                    })
+                    .log_err()?;
+                }
+
+                let pane = pane.upgrade()?;
+                if pane.read_with(cx, |pane, _| pane.items_len() != 0) {
+                    Some((
+                        Member::Pane(pane.clone()),
+                        layout_pane.active.then_some(pane),
+                    ))
+                } else {
+                    workspace
+                        .update_in(cx, |workspace, window, cx| {
+                            workspace.force_remove_pane(&pane, &None, window, cx)
+                        })
+                        .log_err()?;
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Resolves a path stored in a layout against the project's visible worktrees.
+fn resolve_layout_item_path(project: &Project, path: &str, cx: &App) -> Option<ProjectPath> {
+    let path = RelPath::from_unix_str(path).ok()?;
+    let mut worktrees = project.visible_worktrees(cx);
+    let first_worktree = worktrees.next()?.read(cx);
+    if first_worktree.entry_for_path(path).is_some() {
+        return Some(ProjectPath {
+            worktree_id: first_worktree.id(),
+            path: path.into_arc(),
+        });
+    }
+    worktrees.find_map(|worktree| {
+        let worktree = worktree.read(cx);
+        let relative_path = path.strip_prefix(worktree.root_name()).ok()?;
+        Some(ProjectPath {
+            worktree_id: worktree.id(),
+            path: relative_path.into_arc(),
+        })
+    })
+}
+
+async fn load_layouts(fs: &dyn Fs, path: &PathBuf) -> Result<WorkspaceLayouts> {
+    if !fs.is_file(path).await {
+        return Ok(WorkspaceLayouts::default());
+    }
+    let contents = fs.load(path).await?;
+    serde_json::from_str(&contents).with_context(|| format!("parsing {}", path.display()))
+}
+
+impl Workspace {
+    /// Captures the current pane splits, open files and docks.
+    pub fn capture_layout(&self, window: &Window, cx: &App) -> WorkspaceLayout {
+        let docks = self.capture_dock_state(window, cx);
+        WorkspaceLayout {
+            center: self.capture_layout_pane_group(&self.center.root, cx),
+            docks: LayoutDocks {
+                left: LayoutDock::new(docks.left, &self.left_dock, cx),
+                right: LayoutDock::new(docks.right, &self.right_dock, cx),
+                bottom: LayoutDock::new(docks.bottom, &self.bottom_dock, cx),
+            },
+        }
+    }
+
+    fn capture_layout_pane_group(&self, member: &Member, cx: &App) -> LayoutPaneGroup {
+        match member {
+            Member::Axis(PaneAxis {
+                axis,
+                members,
+                flexes,
+                bounding_boxes: _,
+            }) => LayoutPaneGroup::Group {
+                axis: *axis,
+                flexes: Some(flexes.lock().clone()),
+                children: members
+                    .iter()
+                    .map(|member| self.capture_layout_pane_group(member, cx))
+                    .collect(),
+            },
+            Member::Pane(pane) => {
+                let pane_ref = pane.read(cx);
+                let active_item_id = pane_ref.active_item().map(|item| item.item_id());
+                let mut layout_pane = LayoutPane {
+                    active: pane == &self.active_pane,
+                    ..LayoutPane::default()
+                };
+                for item in pane_ref.items() {
+                    let Some(path) = item
+                        .project_path(cx)
+                        .and_then(|project_path| self.layout_item_path(&project_path, cx))
+                    else {
+                        continue;
+                    };
+                    if Some(item.item_id()) == active_item_id {
+                        layout_pane.active_item = Some(layout_pane.items.len());
+                    }
+                    layout_pane.items.push(path);
+                }
+                LayoutPaneGroup::Pane(layout_pane)
+            }
+        }
+    }
+
+    fn layout_item_path(&self, project_path: &ProjectPath, cx: &App) -> Option<String> {
+        let project = self.project.read(cx);
+        let first_worktree = project.visible_worktrees(cx).next()?;
+        if first_worktree.read(cx).id() == project_path.worktree_id {
+            return Some(project_path.path.as_unix_str().to_string());
+        }
+        let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+        let worktree = worktree.read(cx);
+        worktree.is_visible().then(|| {
+            worktree
+                .root_name()
+                .join(&project_path.path)
+                .as_unix_str()
+                .to_string()
+        })
+    }
+
+    fn layouts_file_path(&self, cx: &App) -> Result<PathBuf> {
+        let project = self.project.read(cx);
+        anyhow::ensure!(
+            project.is_local(),
+            "Layouts can only be saved in local projects"
+        );
+        let root_dir = project
+            .visible_worktrees(cx)
+            .find_map(|worktree| worktree.read(cx).root_dir())
+            .context("Layouts require a project folder")?;
+        Ok(root_dir.join(paths::local_layouts_file_relative_path().as_std_path()))
+    }
+
+    /// Loads the layouts saved for this project.
+    pub fn workspace_layouts(&self, cx: &App) -> Task<Result<WorkspaceLayouts>> {
+        let path = match self.layouts_file_path(cx) {
+            Ok(path) => path,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let fs = self.app_state.fs.clone();
+        cx.background_spawn(async move { load_layouts(fs.as_ref(), &path).await })
+    }
+
+    fn update_workspace_layouts(
+        &self,
+        cx: &App,
+        update: impl FnOnce(&mut WorkspaceLayouts) + Send + 'static,
+    ) -> Task<Result<()>> {
+        let path = match self.layouts_file_path(cx) {
+            Ok(path) => path,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let fs = self.app_state.fs.clone();
+        cx.background_spawn(async move {
+            let mut layouts = load_layouts(fs.as_ref(), &path).await?;
+            update(&mut layouts);
+            if let Some(dir) = path.parent()
+                && !fs.is_dir(dir).await
+            {
+                fs.create_dir(dir).await?;
+            }
+            let contents = serde_json::to_string_pretty(&layouts)?;
+            fs.write(&path, contents.as_bytes()).await
+        })
+    }
+
+    /// Saves the current layout under the given name, replacing any layout
+    /// with the same name.
+    pub fn save_layout(
+        &mut self,
+        name: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let layout = self.capture_layout(window, cx);
+        self.update_workspace_layouts(cx, move |layouts| {
+            layouts.insert(name, layout);
+        })
+    }
+
+    pub fn delete_layout(&mut self, name: String, cx: &mut Context<Self>) -> Task<Result<()>> {
+        self.update_workspace_layouts(cx, move |layouts| {
+            layouts.remove(&name);
+        })
+    }
+
+    /// Restores the layout saved under the given name.
+    pub fn restore_layout(
+        &mut self,
+        name: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let layouts = self.workspace_layouts(cx);
+        cx.spawn_in(window, async move |workspace, cx| {
+            let layout = layouts
+                .await?
+                .remove(&name)
+                .with_context(|| format!("No layout named {name:?}"))?;
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.apply_layout(layout, window, cx)
+                })?
+                .await
+        })
+    }
+
+    /// Closes every item in the center, prompting to save unsaved changes,
+    /// and replaces the panes and docks with the given layout.
+    pub fn apply_layout(
+        &mut self,
+        layout: WorkspaceLayout,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let close_tasks = self
+            .panes
+            .iter()
+            .map(|pane| {
+                pane.update(cx, |pane, cx| {
+                    pane.close_all_items(
+                        &CloseAllItems {
+                            save_intent: Some(SaveIntent::Close),
+                            close_pinned: true,
+                        },
+                        window,
+                        cx,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            for task in close_tasks {
+                task.await?;
+            }
+            let (project, has_open_items) = workspace.read_with(cx, |workspace, cx| {
+                let has_open_items = workspace
+                    .panes
+                    .iter()
+                    .any(|pane| pane.read(cx).items_len() != 0);
+                (workspace.project.clone(), has_open_items)
+            })?;
+            anyhow::ensure!(
+                !has_open_items,
+                "Not all items were closed, the layout was not restored"
+            );
+
+            let center_group = layout.center.open(&project, workspace.clone(), cx).await;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some((center_group, active_pane)) = center_group {
+                    workspace.remove_panes(workspace.center.root.clone(), window, cx);
+
+                    workspace.center = PaneGroup::with_root(center_group);
+                    workspace.center.set_is_center(true);
+                    workspace.center.mark_positions(cx);
+
+                    let active_pane = active_pane.unwrap_or_else(|| workspace.center.first_pane());
+                    workspace.set_active_pane(&active_pane, window, cx);
+                    cx.focus_self(window);
+                }
+
+                let docks = layout.docks;
+                workspace.set_dock_structure(
+                    DockStructure {
+                        left: docks.left.dock_data(),
+                        right: docks.right.dock_data(),
+                        bottom: docks.bottom.dock_data(),
+                    },
+                    window,
+                    cx,
+                );
+                for (dock, layout_dock) in [
+                    (&workspace.left_dock, &docks.left),
+                    (&workspace.right_dock, &docks.right),
+                    (&workspace.bottom_dock, &docks.bottom),
+                ] {
+                    if layout_dock.visible
+                        && (layout_dock.size.is_some() || layout_dock.flex.is_some())
+                    {
+                        dock.update(cx, |dock, cx| {
+                            dock.resize_active_panel(
+                                layout_dock.size.map(px),
+                                layout_dock.flex,
+                                window,
+                                cx,
+                            );
+                        });
+                    }
+                }
+
+                cx.notify();
+                workspace.serialize_workspace(window, cx);
+            })
+        })
+    }
+}
@@ -11,6 +11,7 @@ use crate::{
     },
     move_item,
     notifications::NotifyResultExt,
+    tab_group::{TabGroup, TabGroupColor, TabGroupId, TabGroupNameModal, tab_group_color},
     toolbar::Toolbar,
     workspace_settings::{AutosaveSetting, FocusFollowsMouse, TabBarSettings, WorkspaceSettings},
 };
@@ -43,6 +44,7 @@ use std::{
     },
     time::Duration,
 };
+use strum::{VariantArray, VariantNames};
 use theme_settings::ThemeSettings;
 use ui::{
     ContextMenu, ContextMenuEntry, ContextMenuItem, DecoratedIcon, IconButtonShape, IconDecoration,
@@ -316,6 +318,16 @@ actions!(
         TogglePinTab,
         /// Unpins all tabs in the pane.
         UnpinAllTabs,
+        /// Adds the current tab to a new tab group.
+        AddToNewTabGroup,
+        /// Removes the current tab from its tab group.
+        RemoveFromTabGroup,
+        /// Collapses or expands the current tab's group.
+        ToggleTabGroupCollapsed,
+        /// Closes all tabs in the current tab's group.
+        CloseTabGroup,
+        /// Renames the current tab's group.
+        RenameTabGroup,
     ]
 );
 
@@ -341,6 +353,7 @@ pub enum Event {
     },
     ItemPinned,
     ItemUnpinned,
+    TabGroupsChanged,
     JoinAll,
     JoinIntoNext,
     ChangeItemTitle,
@@ -387,6 +400,7 @@ impl fmt::Debug for Event {
                 .finish(),
             Event::ItemPinned => f.write_str("ItemPinned"),
             Event::ItemUnpinned => f.write_str("ItemUnpinned"),
+            Event::TabGroupsChanged => f.write_str("TabGroupsChanged"),
         }
     }
 }
@@ -445,6 +459,9 @@ pub struct Pane {
     pub new_item_context_menu_handle: PopoverMenuHandle<ContextMenu>,
     pub split_item_context_menu_handle: PopoverMenuHandle<ContextMenu>,
     pinned_tab_count: usize,
+    tab_groups: Vec<TabGroup>,
+    tab_group_by_item: HashMap<EntityId, TabGroupId>,
+    next_tab_group_id: usize,
     diagnostics: HashMap<ProjectPath, DiagnosticSeverity>,
     zoom_out_on_close: bool,
     focus_follows_mouse: FocusFollowsMouse,
@@ -618,6 +635,9 @@ impl Pane {
             split_item_context_menu_handle: Default::default(),
             new_item_context_menu_handle: Default::default(),
             pinned_tab_count: 0,
+            tab_groups: Vec::new(),
+            tab_group_by_item: HashMap::default(),
+            next_tab_group_id: 0,
             diagnostics: Default::default(),
             zoom_out_on_close: true,
             focus_follows_mouse: WorkspaceSettings::get_global(cx).focus_follows_mouse,
@@ -1338,6 +1358,7 @@ impl Pane {
 
                 self.activate_item(insertion_index, activate_pane, focus_item, window, cx);
             }
+            self.apply_tab_group_rules(item.as_ref(), cx);
         }
 
         cx.emit(Event::AddItem { item });
@@ -1510,10 +1531,32 @@ impl Pane {
         if !self.is_tab_pinned(index) {
             self.suppress_scroll = false;
             self.tab_bar_scroll_handle
-                .scroll_to_item(index - self.pinned_tab_count);
+                .scroll_to_item(self.scrollable_tab_bar_child_index(index));
         }
     }
 
+    /// The position of the tab at `index` among the scrollable children of the tab
+    /// bar, which also contain the headers of tab groups and omit the hidden tabs of
+    /// collapsed groups. Mirrors the layout built in `render_tab_bar`.
+    fn scrollable_tab_bar_child_index(&self, index: usize) -> usize {
+        let mut rendered_tab_groups = HashSet::default();
+        let mut child_index = 0;
+        for (ix, item) in self.items.iter().enumerate().take(index + 1) {
+            let is_scrollable = !self.is_tab_pinned(ix);
+            let mut is_hidden = false;
+            if let Some(group) = self.tab_group_for_item(item.item_id()) {
+                if rendered_tab_groups.insert(group.id) && is_scrollable {
+                    child_index += 1;
+                }
+                is_hidden = group.collapsed && ix != self.active_item_index;
+            }
+            if ix < index && is_scrollable && !is_hidden {
+                child_index += 1;
+            }
+        }
+        child_index
+    }
+
     fn update_history(&mut self, index: usize) {
         if let Some(newly_active_item) = self.items.get(index) {
             self.activation_history
@@ -2190,6 +2233,9 @@ impl Pane {
         }
 
         let item = self.items.remove(item_index);
+        if self.tab_group_by_item.remove(&item.item_id()).is_some() {
+            self.remove_empty_tab_groups();
+        }
 
         cx.emit(Event::RemovedItem { item: item.clone() });
         if self.items.is_empty() {
@@ -2778,6 +2824,310 @@ impl Pane {
         self.activate_item(index, true, true, window, cx);
     }
 
+    pub fn tab_groups(&self) -> &[TabGroup] {
+        &self.tab_groups
+    }
+
+    pub fn tab_group(&self, group_id: TabGroupId) -> Option<&TabGroup> {
+        self.tab_groups.iter().find(|group| group.id == group_id)
+    }
+
+    pub fn tab_group_for_item(&self, item_id: EntityId) -> Option<&TabGroup> {
+        self.tab_group(*self.tab_group_by_item.get(&item_id)?)
+    }
+
+    /// Returns the IDs of the items in the given tab group, in tab order.
+    pub fn tab_group_item_ids(&self, group_id: TabGroupId) -> Vec<EntityId> {
+        self.items
+            .iter()
+            .map(|item| item.item_id())
+            .filter(|item_id| self.tab_group_by_item.get(item_id) == Some(&group_id))
+            .collect()
+    }
+
+    pub fn create_tab_group(
+        &mut self,
+        name: impl Into<SharedString>,
+        color: TabGroupColor,
+        item_ids: &[EntityId],
+        cx: &mut Context<Self>,
+    ) -> TabGroupId {
+        let id = TabGroupId(self.next_tab_group_id);
+        self.next_tab_group_id += 1;
+        self.tab_groups.push(TabGroup {
+            id,
+            name: name.into(),
+            color,
+            collapsed: false,
+        });
+        for item_id in item_ids {
+            self.add_to_tab_group(*item_id, id, cx);
+        }
+        cx.emit(Event::TabGroupsChanged);
+        cx.notify();
+        id
+    }
+
+    /// Adds the item to the tab group, moving it next to the group's other tabs
+    /// unless that would move it across the pinned tabs boundary.
+    pub fn add_to_tab_group(
+        &mut self,
+        item_id: EntityId,
+        group_id: TabGroupId,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self.index_for_item_id(item_id) else {
+            return;
+        };
+        if self.tab_group(group_id).is_none() {
+            return;
+        }
+
+        if let Some(last_member_ix) = self.items.iter().rposition(|item| {
+            item.item_id() != item_id
+                && self.tab_group_by_item.get(&item.item_id()) == Some(&group_id)
+        }) && self.is_tab_pinned(ix) == self.is_tab_pinned(last_member_ix)
+        {
+            let destination_ix = if ix < last_member_ix {
+                last_member_ix
+            } else {
+                last_member_ix + 1
+            };
+            self.move_item_within_pane(ix, destination_ix);
+        }
+
+        self.tab_group_by_item.insert(item_id, group_id);
+        self.remove_empty_tab_groups();
+        cx.emit(Event::TabGroupsChanged);
+        cx.notify();
+    }
+
+    pub fn remove_from_tab_group(&mut self, item_id: EntityId, cx: &mut Context<Self>) {
+        if self.tab_group_by_item.remove(&item_id).is_some() {
+            self.remove_empty_tab_groups();
+            cx.emit(Event::TabGroupsChanged);
+            cx.notify();
+        }
+    }
+
+    pub fn rename_tab_group(
+        &mut self,
+        group_id: TabGroupId,
+        name: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(group) = self
+            .tab_groups
+            .iter_mut()
+            .find(|group| group.id == group_id)
+        {
+            group.name = name.into();
+            cx.emit(Event::TabGroupsChanged);
+            cx.notify();
+        }
+    }
+
+    pub fn set_tab_group_color(
+        &mut self,
+        group_id: TabGroupId,
+        color: TabGroupColor,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(group) = self
+            .tab_groups
+            .iter_mut()
+            .find(|group| group.id == group_id)
+        {
+            group.color = color;
+            cx.emit(Event::TabGroupsChanged);
+            cx.notify();
+        }
+    }
+
+    pub fn set_tab_group_collapsed(
+        &mut self,
+        group_id: TabGroupId,
+        collapsed: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(group) = self
+            .tab_groups
+            .iter_mut()
+            .find(|group| group.id == group_id)
+            && group.collapsed != collapsed
+        {
+            group.collapsed = collapsed;
+            cx.emit(Event::TabGroupsChanged);
+            cx.notify();
+        }
+    }
+
+    pub fn toggle_tab_group_collapsed(&mut self, group_id: TabGroupId, cx: &mut Context<Self>) {
+        if let Some(group) = self.tab_group(group_id) {
+            self.set_tab_group_collapsed(group_id, !group.collapsed, cx);
+        }
+    }
+
+    /// Removes the tab group, keeping its tabs open.
+    pub fn ungroup_tabs(&mut self, group_id: TabGroupId, cx: &mut Context<Self>) {
+        self.tab_group_by_item.retain(|_, id| *id != group_id);
+        self.tab_groups.retain(|group| group.id != group_id);
+        cx.emit(Event::TabGroupsChanged);
+        cx.notify();
+    }
+
+    pub fn close_tab_group(
+        &mut self,
+        group_id: TabGroupId,
+        save_intent: SaveIntent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let item_ids = self.tab_group_item_ids(group_id);
+        self.close_items(window, cx, save_intent, &move |item_id| {
+            item_ids.contains(&item_id)
+        })
+    }
+
+    pub fn rename_tab_group_interactively(
+        &mut self,
+        group_id: TabGroupId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(group) = self.tab_group(group_id) else {
+            return;
+        };
+        let name = group.name.clone();
+        let pane = cx.weak_entity();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    TabGroupNameModal::new(pane, group_id, &name, window, cx)
+                });
+            })
+            .ok();
+    }
+
+    /// Adds a newly opened item to the first tab group from the `tab_bar.groups`
+    /// setting whose patterns match its path.
+    fn apply_tab_group_rules(&mut self, item: &dyn ItemHandle, cx: &mut Context<Self>) {
+        if self.tab_group_by_item.contains_key(&item.item_id()) {
+            return;
+        }
+        let Some(project_path) = item.project_path(cx) else {
+            return;
+        };
+        let Some(rule) = TabBarSettings::get_global(cx)
+            .groups
+            .iter()
+            .find(|rule| rule.matches(&project_path.path))
+            .cloned()
+        else {
+            return;
+        };
+
+        let group_id = match self.tab_groups.iter().find(|group| group.name == rule.name) {
+            Some(group) => group.id,
+            None => self.create_tab_group(rule.name, rule.color, &[], cx),
+        };
+        self.add_to_tab_group(item.item_id(), group_id, cx);
+    }
+
+    fn remove_empty_tab_groups(&mut self) {
+        let tab_group_by_item = &self.tab_group_by_item;
+        self.tab_groups
+            .retain(|group| tab_group_by_item.values().any(|id| *id == group.id));
+    }
+
+    fn move_item_within_pane(&mut self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        let active_item_id = self.active_item().map(|item| item.item_id());
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+        if let Some(ix) = active_item_id.and_then(|item_id| self.index_for_item_id(item_id)) {
+            self.active_item_index = ix;
+        }
+    }
+
+    fn add_to_new_tab_group(
+        &mut self,
+        _: &AddToNewTabGroup,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(item) = self.active_item() else {
+            return;
+        };
+        self.add_item_to_new_tab_group(item.item_id(), cx);
+    }
+
+    fn add_item_to_new_tab_group(&mut self, item_id: EntityId, cx: &mut Context<Self>) {
+        let name = format!("Group {}", self.next_tab_group_id + 1);
+        let color = TabGroupColor::VARIANTS
+            .iter()
+            .copied()
+            .find(|color| !self.tab_groups.iter().any(|group| group.color == *color))
+            .unwrap_or_default();
+        self.create_tab_group(name, color, &[item_id], cx);
+    }
+
+    fn remove_from_tab_group_action(
+        &mut self,
+        _: &RemoveFromTabGroup,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(item) = self.active_item() {
+            self.remove_from_tab_group(item.item_id(), cx);
+        }
+    }
+
+    fn toggle_active_tab_group_collapsed(
+        &mut self,
+        _: &ToggleTabGroupCollapsed,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(item) = self.active_item() else {
+            return;
+        };
+        if let Some(group_id) = self.tab_group_by_item.get(&item.item_id()).copied() {
+            self.toggle_tab_group_collapsed(group_id, cx);
+        }
+    }
+
+    fn close_active_tab_group(
+        &mut self,
+        _: &CloseTabGroup,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(item) = self.active_item() else {
+            return;
+        };
+        if let Some(group_id) = self.tab_group_by_item.get(&item.item_id()).copied() {
+            self.close_tab_group(group_id, SaveIntent::Close, window, cx)
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn rename_active_tab_group(
+        &mut self,
+        _: &RenameTabGroup,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(item) = self.active_item() else {
+            return;
+        };
+        if let Some(group_id) = self.tab_group_by_item.get(&item.item_id()).copied() {
+            self.rename_tab_group_interactively(group_id, window, cx);
+        }
+    }
+
     fn tab_icon_element(
         &self,
         item: &dyn ItemHandle,
@@ -2866,6 +3216,9 @@ impl Pane {
         let is_last_item = ix == self.items.len() - 1;
         let is_pinned = self.is_tab_pinned(ix);
         let position_relative_to_active_item = ix.cmp(&self.active_item_index);
+        let group_color = self
+            .tab_group_for_item(item_id)
+            .map(|group| tab_group_color(group.color));
 
         let read_only_toggle = |toggleable: bool| {
             IconButton::new("toggle_read_only", IconName::FileLock)
@@ -3045,6 +3398,9 @@ impl Pane {
                 h_flex()
                     .id(("pane-tab-content", ix))
                     .gap_1()
+                    .when_some(group_color, |this, color| {
+                        this.child(div().w_0p5().h_3().rounded_sm().bg(color))
+                    })
                     .children(if let Some(icon) = icon {
                         Some(icon)
                     } else if !capability.editable() {
@@ -3087,6 +3443,13 @@ impl Pane {
         let has_items_to_right = ix < total_items - 1;
         let has_clean_items = self.items.iter().any(|item| !item.is_dirty(cx));
         let is_pinned = self.is_tab_pinned(ix);
+        let tab_group_id = self.tab_group_by_item.get(&item_id).copied();
+        let other_tab_groups = self
+            .tab_groups
+            .iter()
+            .filter(|group| Some(group.id) != tab_group_id)
+            .map(|group| (group.id, group.name.clone()))
+            .collect::<Vec<_>>();
 
         let pane = cx.entity().downgrade();
         let menu_context = item.item_focus_handle(cx);
@@ -3096,6 +3459,7 @@ impl Pane {
             .trigger(|_, _, _| tab)
             .menu(move |window, cx| {
                 let pane = pane.clone();
+                let other_tab_groups = other_tab_groups.clone();
                 let menu_context = menu_context.clone();
                 let extra_actions = item_handle.tab_extra_context_menu_actions(window, cx);
                 ContextMenu::build(window, cx, move |mut menu, window, cx| {
@@ -3240,6 +3604,34 @@ impl Pane {
                             })
                         };
 
+                        let tab_group_entries = |menu: ContextMenu| {
+                            let mut menu = menu.entry(
+                                "Add to New Group",
+                                Some(AddToNewTabGroup.boxed_clone()),
+                                window.handler_for(&pane, move |pane, _, cx| {
+                                    pane.add_item_to_new_tab_group(item_id, cx);
+                                }),
+                            );
+                            for (group_id, name) in other_tab_groups.iter().cloned() {
+                                menu = menu.entry(
+                                    format!("Add to Group “{name}”"),
+                                    None,
+                                    window.handler_for(&pane, move |pane, _, cx| {
+                                        pane.add_to_tab_group(item_id, group_id, cx);
+                                    }),
+                                );
+                            }
+                            menu.when(tab_group_id.is_some(), |menu| {
+                                menu.entry(
+                                    "Remove from Group",
+                                    Some(RemoveFromTabGroup.boxed_clone()),
+                                    window.handler_for(&pane, move |pane, _, cx| {
+                                        pane.remove_from_tab_group(item_id, cx);
+                                    }),
+                                )
+                            })
+                        };
+
                         if capability != Capability::ReadOnly {
                             let read_only_label = if capability.editable() {
                                 "Make Tab Read-Only"
@@ -3348,6 +3740,7 @@ impl Pane {
                                     })
                                 })
                                 .map(pin_tab_entries)
+                                .map(tab_group_entries)
                                 .when(visible_in_project_panel, |menu| {
                                     menu.entry(
                                         "Reveal In Project Panel",
@@ -3380,7 +3773,7 @@ impl Pane {
                                     )
                                 });
                         } else {
-                            menu = menu.map(pin_tab_entries);
+                            menu = menu.map(pin_tab_entries).map(tab_group_entries);
                         }
                     };
 
@@ -3450,17 +3843,7 @@ impl Pane {
                 }
             });
 
-        let mut tab_items = self
-            .items
-            .iter()
-            .enumerate()
-            .zip(tab_details(&self.items, window, cx))
-            .map(|((ix, item), detail)| {
-                self.render_tab(ix, &**item, detail, &focus_handle, window, cx)
-                    .into_any_element()
-            })
-            .collect::<Vec<_>>();
-        let tab_count = tab_items.len();
+        let tab_count = self.items.len();
         if self.is_tab_pinned(tab_count) {
             log::warn!(
                 "Pinned tab count ({}) exceeds actual tab count ({}). \
@@ -3471,8 +3854,36 @@ impl Pane {
             );
             self.pinned_tab_count = tab_count;
         }
-        let unpinned_tabs = tab_items.split_off(self.pinned_tab_count);
-        let pinned_tabs = tab_items;
+
+        let mut pinned_tabs = Vec::new();
+        let mut unpinned_tabs = Vec::new();
+        let mut rendered_tab_groups = HashSet::default();
+        for ((ix, item), detail) in
+            self.items
+                .iter()
+                .enumerate()
+                .zip(tab_details(&self.items, window, cx))
+        {
+            let tabs = if self.is_tab_pinned(ix) {
+                &mut pinned_tabs
+            } else {
+                &mut unpinned_tabs
+            };
+            // Groups are labeled by a header in front of their first tab. The tabs of
+            // a collapsed group are hidden, except for the active one.
+            if let Some(group) = self.tab_group_for_item(item.item_id()) {
+                if rendered_tab_groups.insert(group.id) {
+                    tabs.push(self.render_tab_group_header(group, cx).into_any_element());
+                }
+                if group.collapsed && ix != self.active_item_index {
+                    continue;
+                }
+            }
+            tabs.push(
+                self.render_tab(ix, &**item, detail, &focus_handle, window, cx)
+                    .into_any_element(),
+            );
+        }
 
         let tab_bar_settings = TabBarSettings::get_global(cx);
         let use_separate_rows = tab_bar_settings.show_pinned_tabs_in_separate_row;
@@ -3500,6 +3911,165 @@ impl Pane {
         }
     }
 
+    fn render_tab_group_header(
+        &self,
+        group: &TabGroup,
+        cx: &mut Context<Pane>,
+    ) -> impl IntoElement + use<> {
+        let group_id = group.id;
+        let color = tab_group_color(group.color);
+        let collapsed = group.collapsed;
+        let current_color = group.color;
+        let member_count = self.tab_group_item_ids(group_id).len();
+
+        let header = h_flex()
+            .id(("tab-group-header", group_id.0))
+            .flex_none()
+            .h(Tab::container_height(cx))
+            .px_1p5()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .bg(color.opacity(0.12))
+            .hover(|style| style.bg(color.opacity(0.24)))
+            .cursor_pointer()
+            .child(div().size_2().rounded_full().bg(color))
+            .child(Label::new(group.name.clone()).size(LabelSize::Small))
+            .when(collapsed, |this| {
+                this.child(
+                    Label::new(member_count.to_string())
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
+            .child(
+                Icon::new(if collapsed {
+                    IconName::ChevronRight
+                } else {
+                    IconName::ChevronDown
+                })
+                .size(IconSize::XSmall)
+                .color(Color::Muted),
+            )
+            .tooltip(Tooltip::text(if collapsed {
+                "Expand Group"
+            } else {
+                "Collapse Group"
+            }))
+            .on_click(cx.listener(move |pane, _, _, cx| {
+                pane.toggle_tab_group_collapsed(group_id, cx);
+            }))
+            .drag_over::<DraggedTab>(|header, _, _, cx| {
+                header.bg(cx.theme().colors().drop_target_background)
+            })
+            .on_drop(
+                cx.listener(move |pane, dragged_tab: &DraggedTab, window, cx| {
+                    pane.drag_split_direction = None;
+                    pane.handle_tab_group_drop(dragged_tab, group_id, window, cx);
+                }),
+            );
+
+        let pane = cx.entity().downgrade();
+        right_click_menu(("tab-group-menu", group_id.0))
+            .trigger(|_, _, _| header)
+            .menu(move |window, cx| {
+                let pane = pane.clone();
+                ContextMenu::build(window, cx, move |mut menu, window, _| {
+                    let Some(pane) = pane.upgrade() else {
+                        return menu;
+                    };
+                    menu = menu
+                        .entry(
+                            "Rename Group…",
+                            None,
+                            window.handler_for(&pane, move |pane, window, cx| {
+                                pane.rename_tab_group_interactively(group_id, window, cx);
+                            }),
+                        )
+                        .entry(
+                            if collapsed {
+                                "Expand Group"
+                            } else {
+                                "Collapse Group"
+                            },
+                            None,
+                            window.handler_for(&pane, move |pane, _, cx| {
+                                pane.toggle_tab_group_collapsed(group_id, cx);
+                            }),
+                        )
+                        .separator()
+                        .header("Color");
+                    for (color, name) in TabGroupColor::VARIANTS
+                        .iter()
+                        .copied()
+                        .zip(TabGroupColor::VARIANT_NAMES)
+                    {
+                        menu = menu.toggleable_entry(
+                            *name,
+                            color == current_color,
+                            IconPosition::Start,
+                            None,
+                            window.handler_for(&pane, move |pane, _, cx| {
+                                pane.set_tab_group_color(group_id, color, cx);
+                            }),
+                        );
+                    }
+                    menu.separator()
+                        .entry(
+                            "Ungroup",
+                            None,
+                            window.handler_for(&pane, move |pane, _, cx| {
+                                pane.ungroup_tabs(group_id, cx);
+                            }),
+                        )
+                        .entry(
+                            "Close Group",
+                            None,
+                            window.handler_for(&pane, move |pane, window, cx| {
+                                pane.close_tab_group(group_id, SaveIntent::Close, window, cx)
+                                    .detach_and_log_err(cx);
+                            }),
+                        )
+                })
+            })
+    }
+
+    fn handle_tab_group_drop(
+        &mut self,
+        dragged_tab: &DraggedTab,
+        group_id: TabGroupId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let item_id = dragged_tab.item.item_id();
+        let to_pane = cx.entity();
+        if dragged_tab.pane == to_pane {
+            self.add_to_tab_group(item_id, group_id, cx);
+            return;
+        }
+
+        let from_pane = dragged_tab.pane.clone();
+        let destination_index = self.items.len();
+        self.workspace
+            .update(cx, |_, cx| {
+                cx.defer_in(window, move |_, window, cx| {
+                    move_item(
+                        &from_pane,
+                        &to_pane,
+                        item_id,
+                        destination_index,
+                        true,
+                        window,
+                        cx,
+                    );
+                    to_pane.update(cx, |pane, cx| {
+                        pane.add_to_tab_group(item_id, group_id, cx);
+                    });
+                });
+            })
+            .ok();
+    }
+
     fn configure_tab_bar_start(
         &mut self,
         tab_bar: TabBar,
@@ -4430,6 +5000,11 @@ impl Render for Pane {
             .on_action(cx.listener(Self::swap_item_right))
             .on_action(cx.listener(Self::toggle_pin_tab))
             .on_action(cx.listener(Self::unpin_all_tabs))
+            .on_action(cx.listener(Self::add_to_new_tab_group))
+            .on_action(cx.listener(Self::remove_from_tab_group_action))
+            .on_action(cx.listener(Self::toggle_active_tab_group_collapsed))
+            .on_action(cx.listener(Self::close_active_tab_group))
+            .on_action(cx.listener(Self::rename_active_tab_group))
             .when(PreviewTabsSettings::get_global(cx).enabled, |this| {
                 this.on_action(
                     cx.listener(|pane: &mut Pane, _: &TogglePreviewTab, window, cx| {
@@ -5478,6 +6053,198 @@ mod tests {
         assert_item_labels(&pane, ["B*", "A", "C"], cx);
     }
 
+    #[gpui::test]
+    async fn test_tab_groups(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+
+        let [item_a, _item_b, item_c, item_d] = set_labeled_items(&pane, ["A", "B", "C", "D*"], cx);
+
+        // Grouping tabs moves them next to each other.
+        let group_id = pane.update(cx, |pane, cx| {
+            pane.create_tab_group(
+                "Group",
+                TabGroupColor::Green,
+                &[item_a.item_id(), item_c.item_id()],
+                cx,
+            )
+        });
+        assert_item_labels(&pane, ["A", "C", "B", "D*"], cx);
+
+        pane.update(cx, |pane, cx| {
+            pane.add_to_tab_group(item_d.item_id(), group_id, cx);
+            assert_eq!(
+                pane.tab_group_item_ids(group_id),
+                [item_a.item_id(), item_c.item_id(), item_d.item_id()]
+            );
+        });
+        assert_item_labels(&pane, ["A", "C", "D*", "B"], cx);
+
+        // Collapsing the group of the active tab.
+        pane.update_in(cx, |pane, window, cx| {
+            pane.toggle_active_tab_group_collapsed(&ToggleTabGroupCollapsed, window, cx);
+            assert!(pane.tab_group(group_id).unwrap().collapsed);
+            pane.toggle_active_tab_group_collapsed(&ToggleTabGroupCollapsed, window, cx);
+            assert!(!pane.tab_group(group_id).unwrap().collapsed);
+        });
+
+        pane.update(cx, |pane, cx| {
+            pane.remove_from_tab_group(item_a.item_id(), cx);
+            assert!(pane.tab_group_for_item(item_a.item_id()).is_none());
+            assert_eq!(
+                pane.tab_group_item_ids(group_id),
+                [item_c.item_id(), item_d.item_id()]
+            );
+        });
+
+        // Closing the group closes its tabs and removes the group.
+        pane.update_in(cx, |pane, window, cx| {
+            pane.close_tab_group(group_id, SaveIntent::Close, window, cx)
+        })
+        .await
+        .unwrap();
+        assert_item_labels(&pane, ["A", "B*"], cx);
+        pane.read_with(cx, |pane, _| assert!(pane.tab_groups().is_empty()));
+    }
+
+    #[gpui::test]
+    async fn test_tab_groups_removed_when_empty(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+
+        let [item_a, item_b] = set_labeled_items(&pane, ["A", "B*"], cx);
+        pane.update(cx, |pane, cx| {
+            pane.create_tab_group("Group", TabGroupColor::Blue, &[item_b.item_id()], cx);
+        });
+
+        pane.update_in(cx, |pane, window, cx| {
+            pane.close_item_by_id(item_b.item_id(), SaveIntent::Skip, window, cx)
+        })
+        .await
+        .unwrap();
+        assert_item_labels(&pane, ["A*"], cx);
+        pane.read_with(cx, |pane, _| {
+            assert!(pane.tab_groups().is_empty());
+            assert!(pane.tab_group_for_item(item_a.item_id()).is_none());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_drop_tab_onto_tab_group(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let pane_a = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+
+        let [item_a, _item_b, item_c] = set_labeled_items(&pane_a, ["A*", "B", "C"], cx);
+        let group_id = pane_a.update(cx, |pane, cx| {
+            pane.create_tab_group("Group", TabGroupColor::Orange, &[item_a.item_id()], cx)
+        });
+
+        // Dropping a tab of the same pane onto the group's header.
+        pane_a.update_in(cx, |pane, window, cx| {
+            let dragged_tab = DraggedTab {
+                pane: pane_a.clone(),
+                item: item_c.boxed_clone(),
+                ix: 2,
+                detail: 0,
+                is_active: false,
+            };
+            pane.handle_tab_group_drop(&dragged_tab, group_id, window, cx);
+        });
+        assert_item_labels(&pane_a, ["A*", "C", "B"], cx);
+
+        // Dropping a tab from another pane onto the group's header.
+        let pane_b = workspace.update_in(cx, |workspace, window, cx| {
+            workspace.split_pane(pane_a.clone(), SplitDirection::Right, window, cx)
+        });
+        let item_d = add_labeled_item(&pane_b, "D", false, cx);
+        add_labeled_item(&pane_b, "E", false, cx);
+        pane_a.update_in(cx, |pane, window, cx| {
+            let dragged_tab = DraggedTab {
+                pane: pane_b.clone(),
+                item: item_d.boxed_clone(),
+                ix: 0,
+                detail: 0,
+                is_active: false,
+            };
+            pane.handle_tab_group_drop(&dragged_tab, group_id, window, cx);
+        });
+        cx.run_until_parked();
+
+        assert_item_labels(&pane_a, ["A", "C", "D*", "B"], cx);
+        assert_item_labels(&pane_b, ["E*"], cx);
+        pane_a.read_with(cx, |pane, _| {
+            assert_eq!(
+                pane.tab_group_item_ids(group_id),
+                [item_a.item_id(), item_c.item_id(), item_d.item_id()]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_tab_group_rules(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.tab_bar.get_or_insert_default().groups =
+                    Some(vec![settings::TabGroupRuleContent {
+                        name: "Tests".to_string(),
+                        color: Some(TabGroupColor::Yellow),
+                        paths: vec!["tests/**".to_string()],
+                    }]);
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+
+        let add_item = |id, path: &'static str, cx: &mut VisualTestContext| {
+            pane.update_in(cx, |pane, window, cx| {
+                let item = Box::new(cx.new(|cx| {
+                    TestItem::new(cx)
+                        .with_buffer_kind(ItemBufferKind::Singleton)
+                        .with_label(path)
+                        .with_project_items(&[TestProjectItem::new(id, path, cx)])
+                }));
+                pane.add_item(item.clone(), false, false, None, window, cx);
+                item
+            })
+        };
+        let test_a = add_item(1, "tests/a.rs", cx);
+        let lib = add_item(2, "src/lib.rs", cx);
+        let test_b = add_item(3, "tests/b.rs", cx);
+        assert_item_labels(&pane, ["tests/a.rs", "tests/b.rs*", "src/lib.rs"], cx);
+
+        pane.read_with(cx, |pane, _| {
+            assert_eq!(pane.tab_groups().len(), 1);
+            let group = &pane.tab_groups()[0];
+            assert_eq!(group.name, "Tests");
+            assert_eq!(group.color, TabGroupColor::Yellow);
+            assert_eq!(
+                pane.tab_group_item_ids(group.id),
+                [test_a.item_id(), test_b.item_id()]
+            );
+            assert!(pane.tab_group_for_item(lib.item_id()).is_none());
+        });
+    }
+
     #[gpui::test]
     async fn test_unpin_all_tabs(cx: &mut TestAppContext) {
         init_test(cx);
@@ -8524,6 +9291,70 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_tab_groups_scroll_to_item_uses_correct_index(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+
+        cx.simulate_resize(size(px(400.), px(300.)));
+
+        let items = ["A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K"]
+            .map(|label| add_labeled_item(&pane, label, false, cx));
+
+        // Every group adds a header in front of its tabs, and the tabs of the
+        // collapsed group are hidden, so tab and child indices diverge.
+        pane.update(cx, |pane, cx| {
+            for item in &items[..5] {
+                pane.create_tab_group("Open", TabGroupColor::Blue, &[item.item_id()], cx);
+            }
+            let collapsed_group = pane.create_tab_group(
+                "Collapsed",
+                TabGroupColor::Red,
+                &items[5..9]
+                    .iter()
+                    .map(|item| item.item_id())
+                    .collect::<Vec<_>>(),
+                cx,
+            );
+            pane.set_tab_group_collapsed(collapsed_group, true, cx);
+        });
+        cx.run_until_parked();
+
+        let scroll_handle =
+            pane.update_in(cx, |pane, _window, _cx| pane.tab_bar_scroll_handle.clone());
+        assert!(
+            scroll_handle.max_offset().x > px(0.),
+            "Test requires tab overflow to verify scrolling. Increase tab count or reduce window width."
+        );
+
+        pane.update_in(cx, |pane, window, cx| {
+            assert_eq!(pane.scrollable_tab_bar_child_index(4), 9);
+            assert_eq!(pane.scrollable_tab_bar_child_index(10), 12);
+            pane.activate_item(0, true, true, window, cx);
+        });
+        cx.run_until_parked();
+
+        for (ix, selector) in [(10, "TAB-10"), (4, "TAB-4")] {
+            pane.update_in(cx, |pane, window, cx| {
+                pane.activate_item(ix, true, true, window, cx);
+            });
+            cx.run_until_parked();
+
+            let scroll_bounds = scroll_handle.bounds();
+            let tab_bounds = cx.debug_bounds(selector).unwrap();
+            assert!(
+                tab_bounds.left() >= scroll_bounds.left()
+                    && tab_bounds.right() <= scroll_bounds.right(),
+                "Active tab {ix} should be scrolled into view"
+            );
+        }
+    }
+
     #[gpui::test]
     async fn test_close_all_items_including_pinned(cx: &mut TestAppContext) {
         init_test(cx);
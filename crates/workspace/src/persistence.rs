@@ -51,7 +51,7 @@ use crate::{
 
 use model::{
     GroupId, ItemId, PaneId, RemoteConnectionId, SerializedItem, SerializedPane,
-    SerializedPaneGroup, SerializedWorkspace,
+    SerializedPaneGroup, SerializedTabGroup, SerializedWorkspace,
 };
 
 use self::model::{DockStructure, SerializedWorkspaceLocation, SessionWorkspace};
@@ -1051,6 +1051,9 @@ impl Domain for WorkspaceDb {
         sql!(
             ALTER TABLE bookmarks ADD COLUMN label TEXT NOT NULL DEFAULT "";
         ),
+        sql!(
+            ALTER TABLE panes ADD COLUMN tab_groups TEXT;
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -2234,6 +2237,7 @@ impl WorkspaceDb {
                     active: true,
                     children: vec![],
                     pinned_count: 0,
+                    tab_groups: Vec::new(),
                 })
             }))
     }
@@ -2251,9 +2255,10 @@ impl WorkspaceDb {
             Option<bool>,
             Option<usize>,
             Option<String>,
+            Option<String>,
         );
         self.select_bound::<GroupKey, GroupOrPane>(sql!(
-            SELECT group_id, axis, pane_id, active, pinned_count, flexes
+            SELECT group_id, axis, pane_id, active, pinned_count, flexes, tab_groups
                 FROM (SELECT
                         group_id,
                         axis,
@@ -2263,7 +2268,8 @@ impl WorkspaceDb {
                         position,
                         parent_group_id,
                         workspace_id,
-                        flexes
+                        flexes,
+                        NULL as tab_groups
                       FROM pane_groups
                     UNION
                       SELECT
@@ -2275,35 +2281,43 @@ impl WorkspaceDb {
                         position,
                         parent_group_id,
                         panes.workspace_id as workspace_id,
-                        NULL
+                        NULL,
+                        panes.tab_groups
                       FROM center_panes
                       JOIN panes ON center_panes.pane_id = panes.pane_id)
                 WHERE parent_group_id IS ? AND workspace_id = ?
                 ORDER BY position
         ))?((group_id, workspace_id))?
         .into_iter()
-        .map(|(group_id, axis, pane_id, active, pinned_count, flexes)| {
-            let maybe_pane = maybe!({ Some((pane_id?, active?, pinned_count?)) });
-            if let Some((group_id, axis)) = group_id.zip(axis) {
-                let flexes = flexes
-                    .map(|flexes: String| serde_json::from_str::<Vec<f32>>(&flexes))
-                    .transpose()?;
-
-                Ok(SerializedPaneGroup::Group {
-                    axis,
-                    children: self.get_pane_group(workspace_id, Some(group_id))?,
-                    flexes,
-                })
-            } else if let Some((pane_id, active, pinned_count)) = maybe_pane {
-                Ok(SerializedPaneGroup::Pane(SerializedPane::new(
-                    self.get_items(pane_id)?,
-                    active,
-                    pinned_count,
-                )))
-            } else {
-                bail!("Pane Group Child was neither a pane group or a pane");
-            }
-        })
+        .map(
+            |(group_id, axis, pane_id, active, pinned_count, flexes, tab_groups)| {
+                let maybe_pane = maybe!({ Some((pane_id?, active?, pinned_count?)) });
+                if let Some((group_id, axis)) = group_id.zip(axis) {
+                    let flexes = flexes
+                        .map(|flexes: String| serde_json::from_str::<Vec<f32>>(&flexes))
+                        .transpose()?;
+
+                    Ok(SerializedPaneGroup::Group {
+                        axis,
+                        children: self.get_pane_group(workspace_id, Some(group_id))?,
+                        flexes,
+                    })
+                } else if let Some((pane_id, active, pinned_count)) = maybe_pane {
+                    let tab_groups = tab_groups
+                        .map(|tab_groups: String| {
+                            serde_json::from_str::<Vec<SerializedTabGroup>>(&tab_groups)
+                        })
+                        .transpose()?
+                        .unwrap_or_default();
+                    Ok(SerializedPaneGroup::Pane(
+                        SerializedPane::new(self.get_items(pane_id)?, active, pinned_count)
+                            .with_tab_groups(tab_groups),
+                    ))
+                } else {
+                    bail!("Pane Group Child was neither a pane group or a pane");
+                }
+            },
+        )
         // Filter out panes and pane groups which don't have any children or items
         .filter(|pane_group| match pane_group {
             Ok(SerializedPaneGroup::Group { children, .. }) => !children.is_empty(),
@@ -2372,11 +2386,16 @@ impl WorkspaceDb {
         pane: &SerializedPane,
         parent: Option<(GroupId, usize)>,
     ) -> Result<PaneId> {
+        let tab_groups = if pane.tab_groups.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&pane.tab_groups)?)
+        };
         let pane_id = conn.select_row_bound::<_, i64>(sql!(
-            INSERT INTO panes(workspace_id, active, pinned_count)
-            VALUES (?, ?, ?)
+            INSERT INTO panes(workspace_id, active, pinned_count, tab_groups)
+            VALUES (?, ?, ?, ?)
             RETURNING pane_id
-        ))?((workspace_id, pane.active, pane.pinned_count))?
+        ))?((workspace_id, pane.active, pane.pinned_count, tab_groups))?
         .context("Could not retrieve inserted pane_id")?;
 
         let (parent_id, order) = parent.unzip();
@@ -2776,6 +2795,7 @@ mod tests {
             },
             read_multi_workspace_state,
         },
+        tab_group::TabGroupColor,
     };
     use gpui::TaskExt;
 
@@ -4329,6 +4349,47 @@ mod tests {
         assert_eq!(workspace.center_group, new_workspace.center_group);
     }
 
+    #[gpui::test]
+    async fn test_tab_groups_serialization() {
+        zlog::init_test();
+
+        let db = WorkspaceDb::open_test_db("test_tab_groups_serialization").await;
+
+        let center_pane = group(
+            Axis::Horizontal,
+            vec![
+                SerializedPaneGroup::Pane(
+                    SerializedPane::new(
+                        vec![
+                            SerializedItem::new("Terminal", 1, false, false),
+                            SerializedItem::new("Terminal", 2, true, false),
+                            SerializedItem::new("Terminal", 3, false, false),
+                        ],
+                        true,
+                        0,
+                    )
+                    .with_tab_groups(vec![SerializedTabGroup {
+                        name: "Tests".to_string(),
+                        color: TabGroupColor::Green,
+                        collapsed: true,
+                        items: vec![0, 2],
+                    }]),
+                ),
+                SerializedPaneGroup::Pane(SerializedPane::new(
+                    vec![SerializedItem::new("Terminal", 4, true, false)],
+                    false,
+                    0,
+                )),
+            ],
+        );
+
+        let workspace = default_workspace(&["/tmp"], &center_pane);
+        db.save_workspace(workspace.clone()).await;
+
+        let new_workspace = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(workspace.center_group, new_workspace.center_group);
+    }
+
     #[gpui::test]
     async fn test_cleanup_panes() {
         zlog::init_test();
@@ -1,7 +1,7 @@
 use super::{SerializedAxis, SerializedWindowBounds};
 use crate::{
     Member, Pane, PaneAxis, SerializableItemRegistry, Workspace, WorkspaceId, item::ItemHandle,
-    multi_workspace::SerializedProjectGroupState, path_list::PathList,
+    multi_workspace::SerializedProjectGroupState, path_list::PathList, tab_group::TabGroupColor,
 };
 use anyhow::{Context, Result};
 use async_recursion::async_recursion;
@@ -247,6 +247,7 @@ impl Default for SerializedPaneGroup {
             children: vec![SerializedItem::default()],
             active: false,
             pinned_count: 0,
+            tab_groups: Vec::new(),
         })
     }
 }
@@ -340,6 +341,17 @@ pub struct SerializedPane {
     pub(crate) active: bool,
     pub(crate) children: Vec<SerializedItem>,
     pub(crate) pinned_count: usize,
+    pub(crate) tab_groups: Vec<SerializedTabGroup>,
+}
+
+/// A tab group of a [`SerializedPane`], referring to its tabs by their index in
+/// the pane's `children`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct SerializedTabGroup {
+    pub(crate) name: String,
+    pub(crate) color: TabGroupColor,
+    pub(crate) collapsed: bool,
+    pub(crate) items: Vec<usize>,
 }
 
 impl SerializedPane {
@@ -348,9 +360,15 @@ impl SerializedPane {
             children,
             active,
             pinned_count,
+            tab_groups: Vec::new(),
         }
     }
 
+    pub fn with_tab_groups(mut self, tab_groups: Vec<SerializedTabGroup>) -> Self {
+        self.tab_groups = tab_groups;
+        self
+    }
+
     pub async fn deserialize_to(
         &self,
         project: &Entity<Project>,
@@ -408,8 +426,21 @@ impl SerializedPane {
                 }
             })?;
         }
-        pane.update(cx, |pane, _| {
+        pane.update(cx, |pane, cx| {
             pane.set_pinned_count(self.pinned_count.min(items.len()));
+            for tab_group in &self.tab_groups {
+                let item_ids = tab_group
+                    .items
+                    .iter()
+                    .filter_map(|ix| Some(items.get(*ix)?.as_ref()?.item_id()))
+                    .collect::<Vec<_>>();
+                if item_ids.is_empty() {
+                    continue;
+                }
+                let group_id =
+                    pane.create_tab_group(tab_group.name.clone(), tab_group.color, &item_ids, cx);
+                pane.set_tab_group_collapsed(group_id, tab_group.collapsed, cx);
+            }
         })?;
 
         anyhow::Ok(items)
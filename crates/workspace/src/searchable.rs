@@ -53,6 +53,12 @@ pub struct SearchOptions {
     pub case: bool,
     pub word: bool,
     pub regex: bool,
+    /// Whether the item can be searched with structural patterns, which
+    /// match its syntax tree.
+    pub structural: bool,
+    /// Whether matches in the item can be restricted to its code, comments
+    /// or strings.
+    pub scopes: bool,
     /// Specifies whether the  supports search & replace.
     pub replacement: bool,
     pub selection: bool,
@@ -78,6 +84,8 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
             case: true,
             word: true,
             regex: true,
+            structural: false,
+            scopes: false,
             replacement: true,
             selection: true,
             select_all: true,
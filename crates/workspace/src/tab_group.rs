@@ -0,0 +1,141 @@
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Hsla, WeakEntity, hsla,
+};
+pub use settings::TabGroupColor;
+use settings::TabGroupRuleContent;
+use ui::prelude::*;
+use ui_input::InputField;
+use util::{
+    ResultExt,
+    paths::{PathMatcher, PathStyle},
+    rel_path::RelPath,
+};
+
+use crate::{ModalView, Pane};
+
+/// Identifies a tab group within its pane.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TabGroupId(pub(crate) usize);
+
+/// A named, colored set of tabs within a pane that can be collapsed in the
+/// tab bar and closed or moved as a unit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TabGroup {
+    pub id: TabGroupId,
+    pub name: SharedString,
+    pub color: TabGroupColor,
+    pub collapsed: bool,
+}
+
+/// The color used to render a tab group's label and its members' markers.
+pub fn tab_group_color(color: TabGroupColor) -> Hsla {
+    let hue = match color {
+        TabGroupColor::Blue => 212.,
+        TabGroupColor::Green => 140.,
+        TabGroupColor::Yellow => 48.,
+        TabGroupColor::Orange => 28.,
+        TabGroupColor::Red => 0.,
+        TabGroupColor::Purple => 270.,
+        TabGroupColor::Gray => return hsla(0., 0., 0.55, 1.),
+    };
+    hsla(hue / 360., 0.7, 0.55, 1.)
+}
+
+/// A tab group from the `tab_bar.groups` setting, which tabs join when they're
+/// opened for a file matching one of its patterns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TabGroupRule {
+    pub name: SharedString,
+    pub color: TabGroupColor,
+    pub paths: PathMatcher,
+}
+
+impl TabGroupRule {
+    pub(crate) fn from_content(content: TabGroupRuleContent) -> Option<Self> {
+        let paths = PathMatcher::new(&content.paths, PathStyle::Unix).log_err()?;
+        Some(Self {
+            name: content.name.into(),
+            color: content.color.unwrap_or_default(),
+            paths,
+        })
+    }
+
+    pub fn matches(&self, path: &RelPath) -> bool {
+        self.paths.is_match(path)
+    }
+}
+
+/// A small modal for renaming a pane's tab group.
+pub struct TabGroupNameModal {
+    pane: WeakEntity<Pane>,
+    group_id: TabGroupId,
+    name_input: Entity<InputField>,
+}
+
+impl TabGroupNameModal {
+    pub fn new(
+        pane: WeakEntity<Pane>,
+        group_id: TabGroupId,
+        name: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_input = cx.new(|cx| InputField::new(window, cx, "Group name"));
+        name_input.update(cx, |input, cx| {
+            input.set_text(name, window, cx);
+            input.editor().select_all(window, cx);
+        });
+        Self {
+            pane,
+            group_id,
+            name_input,
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_input.read(cx).text(cx);
+        let name = name.trim();
+        if !name.is_empty() {
+            let group_id = self.group_id;
+            self.pane
+                .update(cx, |pane, cx| {
+                    pane.rename_tab_group(group_id, name.to_string(), cx)
+                })
+                .ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for TabGroupNameModal {}
+
+impl ModalView for TabGroupNameModal {}
+
+impl Focusable for TabGroupNameModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_input.focus_handle(cx)
+    }
+}
+
+impl Render for TabGroupNameModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TabGroupNameModal")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_3(cx)
+            .w(rems(24.))
+            .p_2()
+            .gap_1()
+            .child(
+                Label::new("Rename Tab Group")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(self.name_input.clone())
+    }
+}
@@ -3,6 +3,7 @@ pub mod dock;
 pub mod history_manager;
 pub mod invalid_item_view;
 pub mod item;
+pub mod layouts;
 mod modal_layer;
 mod multi_workspace;
 #[cfg(test)]
@@ -18,6 +19,7 @@ mod persistence;
 pub mod searchable;
 pub mod security_modal;
 pub mod shared_screen;
+pub mod tab_group;
 pub use shared_screen::SharedScreen;
 pub mod focus_follows_mouse;
 mod status_bar;
@@ -165,7 +167,7 @@ use crate::{dock::PanelSizeState, item::ItemBufferKind, notifications::Notificat
 use crate::{
     persistence::{
         SerializedAxis,
-        model::{SerializedItem, SerializedPane, SerializedPaneGroup},
+        model::{SerializedItem, SerializedPane, SerializedPaneGroup, SerializedTabGroup},
     },
     security_modal::SecurityModal,
 };
@@ -5455,6 +5457,62 @@ impl Workspace {
         );
     }
 
+    /// Moves the given items of `source_pane` into the pane next to it in the given
+    /// direction, splitting `source_pane` when there's none. Items that form a tab
+    /// group in `source_pane` are grouped the same way in the destination.
+    pub fn move_items_to_pane_in_direction(
+        &mut self,
+        source_pane: &Entity<Pane>,
+        item_ids: &[EntityId],
+        direction: SplitDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Pane>> {
+        let first_item_id = *item_ids.first()?;
+        let tab_group = {
+            let source_pane = source_pane.read(cx);
+            source_pane
+                .tab_group_for_item(first_item_id)
+                .filter(|group| {
+                    item_ids.iter().all(|item_id| {
+                        source_pane
+                            .tab_group_for_item(*item_id)
+                            .is_some_and(|item_group| item_group.id == group.id)
+                    })
+                })
+                .cloned()
+        };
+
+        let destination = match self
+            .center
+            .find_pane_in_direction(source_pane, direction, cx)
+            .cloned()
+        {
+            Some(destination) => destination,
+            None => self.split_pane(source_pane.clone(), direction, window, cx),
+        };
+        for item_id in item_ids {
+            let destination_index = destination.read(cx).items_len();
+            move_item(
+                source_pane,
+                &destination,
+                *item_id,
+                destination_index,
+                true,
+                window,
+                cx,
+            );
+        }
+
+        if let Some(tab_group) = tab_group {
+            destination.update(cx, |pane, cx| {
+                let group_id = pane.create_tab_group(tab_group.name, tab_group.color, item_ids, cx);
+                pane.set_tab_group_collapsed(group_id, tab_group.collapsed, cx);
+            });
+        }
+        Some(destination)
+    }
+
     pub fn bounding_box_for_pane(&self, pane: &Entity<Pane>) -> Option<Bounds<Pixels>> {
         self.center.bounding_box_for_pane(pane)
     }
@@ -5720,7 +5778,8 @@ impl Workspace {
                 }
                 cx.notify();
             }
-            pane::Event::ItemPinned | pane::Event::ItemUnpinned => {}
+            pane::Event::ItemPinned | pane::Event::ItemUnpinned | pane::Event::TabGroupsChanged => {
+            }
         }
 
         if serialize_workspace {
@@ -7082,28 +7141,52 @@ impl Workspace {
             window: &mut Window,
             cx: &mut App,
         ) -> SerializedPane {
-            let (items, active, pinned_count) = {
+            let (items, active, pinned_count, tab_groups) = {
                 let pane = pane_handle.read(cx);
                 let active_item_id = pane.active_item().map(|item| item.item_id());
-                (
-                    pane.items()
-                        .filter_map(|handle| {
-                            let handle = handle.to_serializable_item_handle(cx)?;
-
-                            Some(SerializedItem {
-                                kind: Arc::from(handle.serialized_item_kind()),
-                                item_id: handle.item_id().as_u64(),
-                                active: Some(handle.item_id()) == active_item_id,
-                                preview: pane.is_active_preview_item(handle.item_id()),
+                let mut serialized_item_ids = Vec::new();
+                let items = pane
+                    .items()
+                    .filter_map(|handle| {
+                        let handle = handle.to_serializable_item_handle(cx)?;
+                        serialized_item_ids.push(handle.item_id());
+
+                        Some(SerializedItem {
+                            kind: Arc::from(handle.serialized_item_kind()),
+                            item_id: handle.item_id().as_u64(),
+                            active: Some(handle.item_id()) == active_item_id,
+                            preview: pane.is_active_preview_item(handle.item_id()),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let tab_groups = pane
+                    .tab_groups()
+                    .iter()
+                    .filter_map(|group| {
+                        let items = pane
+                            .tab_group_item_ids(group.id)
+                            .into_iter()
+                            .filter_map(|item_id| {
+                                serialized_item_ids.iter().position(|id| *id == item_id)
                             })
+                            .collect::<Vec<_>>();
+                        (!items.is_empty()).then(|| SerializedTabGroup {
+                            name: group.name.to_string(),
+                            color: group.color,
+                            collapsed: group.collapsed,
+                            items,
                         })
-                        .collect::<Vec<_>>(),
+                    })
+                    .collect::<Vec<_>>();
+                (
+                    items,
                     pane.has_focus(window, cx),
                     pane.pinned_count(),
+                    tab_groups,
                 )
             };
 
-            SerializedPane::new(items, active, pinned_count)
+            SerializedPane::new(items, active, pinned_count).with_tab_groups(tab_groups)
         }
 
         fn build_serialized_pane_group(
@@ -7731,6 +7814,34 @@ impl Workspace {
                     workspace.reset_pane_sizes(cx);
                 },
             ))
+            .on_action(cx.listener(
+                |workspace: &mut Workspace, action: &layouts::SaveLayout, window, cx| {
+                    workspace
+                        .save_layout(action.name.clone(), window, cx)
+                        .detach_and_prompt_err("Failed to save layout", window, cx, |_, _, _| None);
+                },
+            ))
+            .on_action(cx.listener(
+                |workspace: &mut Workspace, action: &layouts::RestoreLayout, window, cx| {
+                    workspace
+                        .restore_layout(action.name.clone(), window, cx)
+                        .detach_and_prompt_err(
+                            "Failed to restore layout",
+                            window,
+                            cx,
+                            |_, _, _| None,
+                        );
+                },
+            ))
+            .on_action(cx.listener(
+                |workspace: &mut Workspace, action: &layouts::DeleteLayout, window, cx| {
+                    workspace
+                        .delete_layout(action.name.clone(), cx)
+                        .detach_and_prompt_err("Failed to delete layout", window, cx, |_, _, _| {
+                            None
+                        });
+                },
+            ))
             .on_action(cx.listener(
                 |workspace: &mut Workspace, act: &IncreaseActiveDockSize, window, cx| {
                     adjust_active_dock_size_by_px(
@@ -16654,6 +16765,125 @@ mod tests {
             assert!(handle.is_err());
         }
 
+        #[gpui::test]
+        async fn test_save_and_restore_layout(cx: &mut TestAppContext) {
+            use crate::layouts::{LayoutPane, LayoutPaneGroup};
+
+            init_test(cx);
+            cx.update(register_project_item::<TestPngItemView>);
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree(
+                path!("/root"),
+                json!({ "a.png": "", "b.png": "", "c.png": "" }),
+            )
+            .await;
+            let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+            let (workspace, cx) =
+                cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
+
+            let add_item =
+                |pane: &Entity<Pane>, id, path, cx: &mut VisualTestContext| {
+                    let item =
+                        cx.new(|cx| {
+                            TestItem::new(cx).with_project_items(&[
+                                TestProjectItem::new_in_worktree(id, path, worktree_id, cx),
+                            ])
+                        });
+                    pane.update_in(cx, |pane, window, cx| {
+                        pane.add_item(Box::new(item), true, true, None, window, cx);
+                    });
+                };
+            let left_pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+            add_item(&left_pane, 1, "a.png", cx);
+            let right_pane = workspace.update_in(cx, |workspace, window, cx| {
+                workspace.split_pane(left_pane.clone(), SplitDirection::Right, window, cx)
+            });
+            add_item(&right_pane, 2, "b.png", cx);
+            add_item(&right_pane, 3, "c.png", cx);
+            right_pane.update_in(cx, |pane, window, cx| {
+                pane.activate_item(0, true, true, window, cx);
+            });
+
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.save_layout("review".to_string(), window, cx)
+                })
+                .await
+                .unwrap();
+            assert!(
+                fs.is_file(Path::new(path!("/root/.zed/layouts.json")))
+                    .await
+            );
+
+            let layouts = workspace
+                .update(cx, |workspace, cx| workspace.workspace_layouts(cx))
+                .await
+                .unwrap();
+            let LayoutPaneGroup::Group { axis, children, .. } = &layouts["review"].center else {
+                panic!("expected a split layout");
+            };
+            assert_eq!(*axis, Axis::Horizontal);
+            assert_eq!(
+                children,
+                &[
+                    LayoutPaneGroup::Pane(LayoutPane {
+                        items: vec!["a.png".to_string()],
+                        active_item: Some(0),
+                        active: false,
+                    }),
+                    LayoutPaneGroup::Pane(LayoutPane {
+                        items: vec!["b.png".to_string(), "c.png".to_string()],
+                        active_item: Some(0),
+                        active: true,
+                    }),
+                ]
+            );
+
+            // Collapse the workspace to a single pane, then restore the layout.
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.close_all_items_and_panes(&CloseAllItemsAndPanes::default(), window, cx)
+            });
+            cx.run_until_parked();
+            workspace.read_with(cx, |workspace, _| {
+                assert_eq!(workspace.center.panes().len(), 1);
+            });
+
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.restore_layout("review".to_string(), window, cx)
+                })
+                .await
+                .unwrap();
+            workspace.read_with(cx, |workspace, cx| {
+                let panes = workspace.center.panes();
+                assert_eq!(
+                    panes
+                        .iter()
+                        .map(|pane| pane.read(cx).items_len())
+                        .collect::<Vec<_>>(),
+                    [1, 2]
+                );
+                assert_eq!(panes[1].read(cx).active_item_index(), 0);
+                assert_eq!(workspace.active_pane(), panes[1]);
+            });
+
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.delete_layout("review".to_string(), cx)
+                })
+                .await
+                .unwrap();
+            let layouts = workspace
+                .update(cx, |workspace, cx| workspace.workspace_layouts(cx))
+                .await
+                .unwrap();
+            assert!(layouts.is_empty());
+        }
+
         #[gpui::test]
         async fn test_open_url_or_file_resolves_remote_base_path(cx: &mut TestAppContext) {
             init_test(cx);
@@ -1,6 +1,6 @@
 use std::{num::NonZeroUsize, time::Duration};
 
-use crate::DockPosition;
+use crate::{DockPosition, tab_group::TabGroupRule};
 use collections::HashMap;
 use gpui::{App, Subscription};
 use serde::Deserialize;
@@ -74,6 +74,8 @@ pub struct TabBarSettings {
     pub show_nav_history_buttons: bool,
     pub show_tab_bar_buttons: bool,
     pub show_pinned_tabs_in_separate_row: bool,
+    #[serde(skip)]
+    pub groups: Vec<TabGroupRule>,
 }
 
 impl Settings for WorkspaceSettings {
@@ -187,6 +189,12 @@ impl Settings for TabBarSettings {
             show_nav_history_buttons: tab_bar.show_nav_history_buttons.unwrap(),
             show_tab_bar_buttons: tab_bar.show_tab_bar_buttons.unwrap(),
             show_pinned_tabs_in_separate_row: tab_bar.show_pinned_tabs_in_separate_row.unwrap(),
+            groups: tab_bar
+                .groups
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(TabGroupRule::from_content)
+                .collect(),
         }
     }
 }
@@ -5797,6 +5797,7 @@ mod tests {
                 "keymap_editor",
                 "keystroke_input",
                 "language_selector",
+                "layout_selector",
                 "welcome",
                 "line_ending_selector",
                 "lsp_tool",